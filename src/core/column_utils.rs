@@ -1,4 +1,10 @@
-use crate::{core::state_manager::RedisFdwState, tables::types::RedisTableType};
+use crate::{
+    core::state_manager::RedisFdwState,
+    tables::{
+        implementations::geo::GeoExtra,
+        types::{bytes_to_hex, ColumnEncodings, RedisTableType, RowValue, ValueEncoding},
+    },
+};
 use pgrx::prelude::*;
 
 #[inline]
@@ -40,6 +46,31 @@ pub(crate) unsafe fn extract_column_names(tupdesc: pg_sys::TupleDesc) -> Vec<Str
     names
 }
 
+/// Like [`extract_column_names`], but a column's `field` or `path` option,
+/// when set, replaces its name: the Redis field (hash row, stream) or
+/// JSONPath (json) the column maps to.
+pub(crate) unsafe fn extract_field_names(
+    relid: pg_sys::Oid,
+    tupdesc: pg_sys::TupleDesc,
+) -> Vec<String> {
+    use crate::utils::helpers::{get_foreign_column_options, tuple_desc_attr};
+    let natts = (*tupdesc).natts as usize;
+    let mut names = Vec::with_capacity(natts);
+    for i in 0..natts {
+        let attr = tuple_desc_attr(tupdesc, i);
+        if (*attr).attisdropped {
+            continue;
+        }
+        let mut options = get_foreign_column_options(relid, (*attr).attnum);
+        let name = match options.remove("field").or_else(|| options.remove("path")) {
+            Some(field) => field,
+            None => pgrx::name_data_to_str(&(*attr).attname).to_string(),
+        };
+        names.push(name);
+    }
+    names
+}
+
 /// Compute the raw attribute index of the first data column for pushdown.
 ///
 /// The "first data column" is the column that HSCAN MATCH / ZSCAN MATCH / XRANGE should target. It accounts for:
@@ -83,6 +114,11 @@ pub(crate) fn adjust_column_for_ttl_strip(col: usize, ttl_idx: Option<usize>) ->
 pub(crate) unsafe fn datum_to_text_string(datum: pg_sys::Datum, typoid: pg_sys::Oid) -> String {
     if typoid == pg_sys::TEXTOID || typoid == pg_sys::VARCHAROID || typoid == pg_sys::BPCHAROID {
         String::from_datum(datum, false).unwrap_or_default()
+    } else if typoid == pg_sys::BYTEAOID {
+        // Always hex, whatever bytea_output says; ValueEncoding::encode turns it back into bytes.
+        <&[u8]>::from_datum(datum, false)
+            .map(bytes_to_hex)
+            .unwrap_or_default()
     } else {
         let mut out_func_oid: pg_sys::Oid = pg_sys::InvalidOid;
         let mut is_varlena = false;
@@ -105,6 +141,62 @@ pub(crate) unsafe fn datum_to_text_string(datum: pg_sys::Datum, typoid: pg_sys::
     }
 }
 
+/// Each non-dropped column of `slot` as the text Redis stores, through the
+/// column type's output function; "NULL" for SQL NULL.
+pub(crate) unsafe fn slot_to_text_values(slot: *mut pg_sys::TupleTableSlot) -> Vec<String> {
+    slot_values(slot, |value| match value {
+        Some((datum, typoid)) => stored_text(datum, typoid),
+        None => "NULL".to_string(),
+    })
+}
+
+/// Each non-dropped column of `slot` as the bytes Redis stores, bytea as is;
+/// `None` for SQL NULL.
+pub(crate) unsafe fn slot_to_row_values(slot: *mut pg_sys::TupleTableSlot) -> Vec<RowValue> {
+    slot_values(slot, |value| {
+        value.map(|(datum, typoid)| match typoid {
+            pg_sys::BYTEAOID => <&[u8]>::from_datum(datum, false)
+                .unwrap_or_default()
+                .to_vec(),
+            _ => stored_text(datum, typoid).into_bytes(),
+        })
+    })
+}
+
+/// Map each non-dropped column of `slot`, `None` for SQL NULL.
+unsafe fn slot_values<T>(
+    slot: *mut pg_sys::TupleTableSlot,
+    map: impl Fn(Option<(pg_sys::Datum, pg_sys::Oid)>) -> T,
+) -> Vec<T> {
+    use crate::utils::helpers::tuple_desc_attr;
+    let tupdesc = (*slot).tts_tupleDescriptor;
+    let natts = (*tupdesc).natts as usize;
+    if natts > (*slot).tts_nvalid as usize {
+        pg_sys::slot_getsomeattrs(slot, natts as i32);
+    }
+    let mut values = Vec::with_capacity(natts);
+    for i in 0..natts {
+        let attr = tuple_desc_attr(tupdesc, i);
+        if (*attr).attisdropped {
+            continue;
+        }
+        let value =
+            (!*(*slot).tts_isnull.add(i)).then(|| (*(*slot).tts_values.add(i), (*attr).atttypid));
+        values.push(map(value));
+    }
+    values
+}
+
+unsafe fn stored_text(datum: pg_sys::Datum, typoid: pg_sys::Oid) -> String {
+    match typoid {
+        // boolout writes t/f; keep the spelling Redis clients already read.
+        pg_sys::BOOLOID => bool::from_datum(datum, false)
+            .unwrap_or_default()
+            .to_string(),
+        typoid => datum_to_text_string(datum, typoid),
+    }
+}
+
 pub(crate) fn validate_column_count(
     table_type: &RedisTableType,
     column_count: usize,
@@ -123,12 +215,66 @@ pub(crate) fn validate_column_count(
         }
         RedisTableType::Set(_) => (1 + extra, 1 + extra, "set", "member"),
         RedisTableType::ZSet(_) => (2 + extra, 2 + extra, "zset", "member, score"),
-        RedisTableType::Stream(_) => {
+        RedisTableType::Stream(_) => (
+            2 + extra,
+            usize::MAX,
+            "stream",
             if is_multi_key {
-                pgrx::error!("redis_fdw: multi-key mode is not supported for stream tables");
-            }
-            (2, usize::MAX, "stream", "stream_id, field1[, ...]")
-        }
+                "key, stream_id, field1[, ...]"
+            } else {
+                "stream_id, field1[, ...]"
+            },
+        ),
+        RedisTableType::HashRow(_) => (
+            1 + extra,
+            usize::MAX,
+            "hash_row",
+            if is_multi_key {
+                "key, field1[, ...]"
+            } else {
+                "field1[, ...]"
+            },
+        ),
+        RedisTableType::Json(_) => (
+            1 + extra,
+            usize::MAX,
+            "json",
+            if is_multi_key {
+                "key, path1[, ...]"
+            } else {
+                "path1[, ...]"
+            },
+        ),
+        RedisTableType::Geo(_) => (
+            3 + extra,
+            5 + extra,
+            "geo",
+            if is_multi_key {
+                "key, member, longitude, latitude[, distance][, geohash]"
+            } else {
+                "member, longitude, latitude[, distance][, geohash]"
+            },
+        ),
+        RedisTableType::Bitmap(_) => (2 + extra, 2 + extra, "bitmap", "offset, bit"),
+        RedisTableType::HyperLogLog(_) => (
+            1 + extra,
+            2 + extra,
+            "hyperloglog",
+            if is_multi_key {
+                "key, approx_count[, element]"
+            } else {
+                "approx_count[, element]"
+            },
+        ),
+        RedisTableType::PubSub(t) if t.channel.is_some() => (1, 1, "pubsub", "message"),
+        RedisTableType::PubSub(_) => (2, 2, "pubsub", "channel, message"),
+        RedisTableType::Keyspace(_) => (
+            2,
+            7,
+            "keyspace",
+            "key, redis_type[, ttl_ms[, memory_bytes[, encoding[, idle_seconds[, length]]]]]",
+        ),
+        RedisTableType::Function(_) => (1, usize::MAX, "function", "column1[, ...]"),
         RedisTableType::None => return,
     };
 
@@ -173,6 +319,50 @@ pub(crate) fn validate_column_count(
     }
 }
 
+/// Index of the first column after `after` that is neither dropped nor the
+/// TTL column.
+pub(crate) unsafe fn next_data_column(
+    tupdesc: pg_sys::TupleDesc,
+    after: usize,
+    ttl_column_index: Option<usize>,
+) -> usize {
+    use crate::utils::helpers::tuple_desc_attr;
+    let natts = (*tupdesc).natts as usize;
+    let mut idx = after + 1;
+    while idx < natts
+        && ((*tuple_desc_attr(tupdesc, idx)).attisdropped || Some(idx) == ttl_column_index)
+    {
+        idx += 1;
+    }
+    idx
+}
+
+/// Configure `table_type` for the columns of `tupdesc`: the zset score
+/// column and the geo longitude/latitude columns are the data columns
+/// following the pushdown column, and the geo virtual columns follow those.
+pub(crate) unsafe fn configure_table_columns(
+    table_type: &mut RedisTableType,
+    column_names: &[String],
+    tupdesc: pg_sys::TupleDesc,
+    pushdown_idx: usize,
+    ttl_column_index: Option<usize>,
+) {
+    let score_column_index = matches!(table_type, RedisTableType::ZSet(_) | RedisTableType::Geo(_))
+        .then(|| next_data_column(tupdesc, pushdown_idx, ttl_column_index));
+    table_type.configure(column_names, pushdown_idx, score_column_index);
+    if let (RedisTableType::Geo(geo), Some(longitude)) = (table_type, score_column_index) {
+        geo.latitude_column_index = next_data_column(tupdesc, longitude, ttl_column_index);
+        let mut column = geo.latitude_column_index;
+        for extra in &geo.extras {
+            column = next_data_column(tupdesc, column, ttl_column_index);
+            if *extra == GeoExtra::Distance {
+                geo.distance_column_index = Some(column);
+                break;
+            }
+        }
+    }
+}
+
 pub(crate) fn transform_insert_data(
     table_type: &RedisTableType,
     column_names: &[String],
@@ -180,7 +370,8 @@ pub(crate) fn transform_insert_data(
 ) -> Vec<String> {
     match table_type {
         RedisTableType::List(_) if column_names.len() >= 2 => data.into_iter().skip(1).collect(),
-        RedisTableType::Stream(_) if column_names.len() > 1 => {
+        // Multi-key rows keep their values in column order.
+        RedisTableType::Stream(s) if !s.key_column && column_names.len() > 1 => {
             let mut stream_data = Vec::with_capacity(1 + (data.len() - 1) * 2);
             stream_data.push(data[0].clone());
             for (i, val) in data[1..].iter().enumerate() {
@@ -201,6 +392,16 @@ pub(crate) unsafe fn extract_delete_key(
 ) -> Result<String, &'static str> {
     use crate::utils::helpers::exec_get_junk_attribute;
 
+    // A single-key hash_row or json table is one row, identified by the key
+    // itself; its first column may well be NULL.
+    if matches!(
+        state.table_type,
+        RedisTableType::HashRow(_) | RedisTableType::Json(_)
+    ) && !state.is_multi_key
+    {
+        return Ok(state.table_key_prefix.clone());
+    }
+
     if state.key_attno <= 0 {
         return Err("Invalid key attribute number");
     }
@@ -212,27 +413,49 @@ pub(crate) unsafe fn extract_delete_key(
         return Err("Delete key is NULL");
     }
 
-    if let Some(key_string) = String::from_datum(datum, false) {
-        if key_string.is_empty() {
-            return Err("Delete key is empty");
-        }
-        return Ok(key_string);
-    }
-
-    // Fallback for non-text types: use PG output function
+    // Convert through the column type when the slot describes it, so
+    // bytea keys are not read as text.
     let tupdesc = (*plan_slot).tts_tupleDescriptor;
     let attidx = (state.key_attno - 1) as usize;
-    if !tupdesc.is_null() && attidx < (*tupdesc).natts as usize {
+    let key_string = if !tupdesc.is_null() && attidx < (*tupdesc).natts as usize {
         let attr = crate::utils::helpers::tuple_desc_attr(tupdesc, attidx);
-        let typoid = (*attr).atttypid;
-        let key_string = datum_to_text_string(datum, typoid);
-        if key_string.is_empty() {
-            return Err("Delete key is empty");
-        }
-        return Ok(key_string);
+        datum_to_text_string(datum, (*attr).atttypid)
+    } else {
+        String::from_datum(datum, false).ok_or("Failed to convert datum to string")?
+    };
+
+    if key_string.is_empty() {
+        return Err("Delete key is empty");
     }
+    Ok(key_string)
+}
 
-    Err("Failed to convert datum to string")
+/// Encoding of each data column of `tupdesc` in row order: dropped columns,
+/// the TTL column and, in multi-key mode, the key column are skipped.
+pub(crate) unsafe fn column_encodings(
+    tupdesc: pg_sys::TupleDesc,
+    ttl_column_index: Option<usize>,
+    is_multi_key: bool,
+) -> ColumnEncodings {
+    use crate::utils::helpers::tuple_desc_attr;
+    let natts = (*tupdesc).natts as usize;
+    let encodings = (0..natts)
+        .filter(|&i| Some(i) != ttl_column_index && !(*tuple_desc_attr(tupdesc, i)).attisdropped)
+        .skip(usize::from(is_multi_key))
+        .map(|i| match (*tuple_desc_attr(tupdesc, i)).atttypid {
+            pg_sys::BYTEAOID => ValueEncoding::Binary,
+            pg_sys::JSONOID
+            | pg_sys::JSONBOID
+            | pg_sys::BOOLOID
+            | pg_sys::INT2OID
+            | pg_sys::INT4OID
+            | pg_sys::INT8OID
+            | pg_sys::FLOAT4OID
+            | pg_sys::FLOAT8OID
+            | pg_sys::NUMERICOID => ValueEncoding::Json,
+            _ => ValueEncoding::Text,
+        });
+    ColumnEncodings::new(encodings)
 }
 
 #[cfg(test)]
@@ -1,12 +1,16 @@
 use crate::{
     auth::RedisAuthConfig,
-    core::pool_manager::{get_pooled_connection, PoolConfig, PooledConnection},
+    core::pool_manager::{
+        get_pooled_connection, get_sentinel_connection, PoolConfig, PooledConnection,
+        SentinelConfig,
+    },
 };
 /// Redis connection factory module
 ///
 /// This module provides a clean interface for creating Redis connections
 /// with proper error handling, configuration validation, and retry logic.
-/// It supports both single-node and cluster Redis deployments with authentication.
+/// It supports single-node, cluster and Sentinel-managed Redis deployments with
+/// authentication.
 ///
 /// The factory now uses a global connection pool manager for efficient connection
 /// reuse across queries, significantly improving performance under concurrent workloads.
@@ -31,6 +35,9 @@ pub enum ConnectionFactoryError {
     #[error("Missing required configuration: {0}")]
     MissingConfiguration(String),
 
+    #[error("Invalid Sentinel configuration: {0}")]
+    InvalidSentinel(String),
+
     #[error("Connection pool error: {0}")]
     PoolError(#[from] r2d2::Error),
 }
@@ -40,21 +47,33 @@ pub type ConnectionFactoryResult<T> = Result<T, ConnectionFactoryError>;
 /// Configuration for Redis connection creation
 #[derive(Debug, Clone)]
 pub struct RedisConnectionConfig {
+    /// host_port option, or how a Sentinel-managed server is named
     pub host_port: String,
     pub database: i64,
     pub retry_attempts: Option<u32>,
     pub auth_config: RedisAuthConfig,
     pub pool_config: PoolConfig,
     pub cluster_mode: bool,
+    pub sentinel: Option<SentinelConfig>,
+    /// Only reads go through the connection, so with Sentinel replica reads
+    /// it may come from a replica
+    pub read_only: bool,
 }
 
 impl RedisConnectionConfig {
     /// Create a new configuration from options map
     pub fn from_options(opts: &HashMap<String, String>) -> ConnectionFactoryResult<Self> {
-        let host_port = opts
-            .get("host_port")
-            .ok_or_else(|| ConnectionFactoryError::MissingConfiguration("host_port".to_string()))?
-            .clone();
+        let sentinel =
+            SentinelConfig::from_options(opts).map_err(ConnectionFactoryError::InvalidSentinel)?;
+        let host_port = match (opts.get("host_port"), &sentinel) {
+            (Some(host_port), _) => host_port.clone(),
+            (None, Some(sentinel)) => sentinel.server_label(),
+            (None, None) => {
+                return Err(ConnectionFactoryError::MissingConfiguration(
+                    "host_port".to_string(),
+                ))
+            }
+        };
 
         let database = opts
             .get("database")
@@ -77,6 +96,8 @@ impl RedisConnectionConfig {
             auth_config: RedisAuthConfig::from_user_mapping_options(opts),
             pool_config: PoolConfig::from_options(opts),
             cluster_mode,
+            sentinel,
+            read_only: false,
         };
 
         config.validate()?;
@@ -109,13 +130,22 @@ impl RedisConnectionFactory {
     pub fn create_pooled_connection(
         config: &RedisConnectionConfig,
     ) -> ConnectionFactoryResult<PooledConnection> {
-        get_pooled_connection(
-            &config.host_port,
-            config.database,
-            &config.auth_config,
-            &config.pool_config,
-            config.cluster_mode,
-        )
+        match &config.sentinel {
+            Some(sentinel) => get_sentinel_connection(
+                sentinel,
+                config.read_only && sentinel.replica_reads,
+                config.database,
+                &config.auth_config,
+                &config.pool_config,
+            ),
+            None => get_pooled_connection(
+                &config.host_port,
+                config.database,
+                &config.auth_config,
+                &config.pool_config,
+                config.cluster_mode,
+            ),
+        }
         .map_err(|e| ConnectionFactoryError::ConnectionFailed(e.to_string()))
     }
 
@@ -239,6 +269,30 @@ mod tests {
         assert_eq!(config.database, 5);
     }
 
+    #[test]
+    fn test_config_from_sentinel_options() {
+        let mut opts = HashMap::new();
+        opts.insert(
+            "sentinel_nodes".to_string(),
+            "10.0.0.1:26379,10.0.0.2:26379".to_string(),
+        );
+        opts.insert("sentinel_master_name".to_string(), "mymaster".to_string());
+
+        let config = RedisConnectionConfig::from_options(&opts).unwrap();
+        assert_eq!(
+            config.host_port,
+            "mymaster via sentinel 10.0.0.1:26379,10.0.0.2:26379"
+        );
+        assert_eq!(config.sentinel.unwrap().master_name, "mymaster");
+        assert!(!config.cluster_mode);
+
+        opts.remove("sentinel_master_name");
+        assert!(matches!(
+            RedisConnectionConfig::from_options(&opts).unwrap_err(),
+            ConnectionFactoryError::InvalidSentinel(_)
+        ));
+    }
+
     #[test]
     fn test_config_database_boundary_values() {
         for db in 0..=15 {
@@ -97,9 +97,13 @@ unsafe fn extract_validation_inputs(rel_id: pg_sys::Oid) -> Option<(RedisTableTy
     let opts = get_foreign_table_options(rel_id);
 
     let table_type_str = opts.get("table_type")?;
-    let table_type = RedisTableType::from_str(table_type_str);
-    if matches!(table_type, RedisTableType::None) {
-        return None;
+    let mut table_type = RedisTableType::from_str(table_type_str);
+    match table_type {
+        RedisTableType::None => return None,
+        // A fixed channel leaves only the message column. Its name is not
+        // needed, and an empty String does not allocate.
+        RedisTableType::PubSub(ref mut t) => t.channel = opts.get("channel").map(|_| String::new()),
+        _ => {}
     }
 
     let is_multi_key = opts
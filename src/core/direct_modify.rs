@@ -0,0 +1,1373 @@
+//! Direct modify: UPDATE/DELETE whose WHERE clause names the targeted keys,
+//! fields or members outright, or a score/member/ID/index range Redis can remove
+//! natively, runs as one Redis command batch instead of a foreign scan
+//! followed by one write per row. `SET col = col + n` and `col || 'x'`
+//! become atomic increments and APPENDs.
+
+use crate::{
+    core::{
+        column_utils::{
+            compute_key_column_index, compute_pushdown_column_index, extract_field_names,
+            state_from_ptr,
+        },
+        state_manager::RedisFdwState,
+    },
+    query::{
+        pushdown::WhereClausePushdown,
+        pushdown_types::{ComparisonOperator, PushableCondition},
+        scan_ops::glob_match,
+    },
+    tables::{
+        implementations::{RedisHashTable, RedisStringTable},
+        interface::RedisTableOperations,
+        types::{InsertMode, RedisTableType},
+    },
+    utils::helpers::*,
+};
+use pgrx::{
+    pg_sys::{ExecClearTuple, Index, ModifyTable, PlannerInfo},
+    prelude::*,
+    PgMemoryContexts,
+};
+use std::ptr;
+
+/// Delete the `ARGV[1]..ARGV[2]` complement of a list with LTRIM, replying
+/// with the number of elements removed.
+const LTRIM_COUNT_SCRIPT: &str = "local before = redis.call('LLEN', KEYS[1]) \
+     redis.call('LTRIM', KEYS[1], ARGV[1], ARGV[2]) \
+     return before - redis.call('LLEN', KEYS[1])";
+
+/// ZREMRANGEBYLEX `ARGV[1] ARGV[2]` when every member has the same score,
+/// the only case where Redis keeps members in lex order; nil otherwise.
+const ZREMRANGEBYLEX_SAME_SCORE_SCRIPT: &str =
+    "local first = redis.call('ZRANGE', KEYS[1], 0, 0, 'WITHSCORES') \
+     local last = redis.call('ZRANGE', KEYS[1], -1, -1, 'WITHSCORES') \
+     if first[2] ~= last[2] then return false end \
+     return redis.call('ZREMRANGEBYLEX', KEYS[1], ARGV[1], ARGV[2])";
+
+/// Run `ARGV[1] KEYS[1] ARGV[2] ARGV[3]` (HINCRBY/HINCRBYFLOAT) only when the
+/// field exists, replying 1 when it ran.
+const HASH_FIELD_XX_SCRIPT: &str = "if redis.call('HEXISTS', KEYS[1], ARGV[2]) == 1 then \
+     redis.call(ARGV[1], KEYS[1], ARGV[2], ARGV[3]) return 1 end return 0";
+
+/// Run `ARGV[1] KEYS[1] ARGV[2]` (INCRBY/INCRBYFLOAT/APPEND) only when the
+/// key exists, replying 1 when it ran.
+const STRING_KEY_XX_SCRIPT: &str = "if redis.call('EXISTS', KEYS[1]) == 1 then \
+     redis.call(ARGV[1], KEYS[1], ARGV[2]) return 1 end return 0";
+
+/// JSON.NUMINCRBY path `ARGV[1]` of `KEYS[1]` by `ARGV[2]` only when the
+/// key exists, replying 1 when it ran.
+const JSON_NUMINCRBY_XX_SCRIPT: &str = "if redis.call('EXISTS', KEYS[1]) == 1 then \
+     redis.call('JSON.NUMINCRBY', KEYS[1], ARGV[1], ARGV[2]) return 1 end return 0";
+
+/// ZINCRBY `KEYS[1]` by `ARGV[1]` for member `ARGV[2]` only when it is
+/// already in the set, replying 1 when it ran.
+const ZINCRBY_XX_SCRIPT: &str = "if redis.call('ZSCORE', KEYS[1], ARGV[2]) then \
+     redis.call('ZINCRBY', KEYS[1], ARGV[1], ARGV[2]) return 1 end return 0";
+
+/// Right-hand side of an UPDATE's single SET that Redis can apply itself.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Assignment {
+    /// `col = <constant>`
+    Set(String),
+    /// `col = col + <constant>` / `col - <constant>`; `delta` is already
+    /// negated for `-`, `float` selects the *FLOAT command variant
+    Increment { delta: String, float: bool },
+    /// `col = col || <constant>`
+    Append(String),
+}
+
+impl Assignment {
+    /// Build from `col <op> <constant>` with the column on the left unless
+    /// the operator commutes. `integer` tells whether the expression's type
+    /// is an integer type.
+    pub fn from_operator(
+        opname: &str,
+        column_first: bool,
+        constant: String,
+        integer: bool,
+    ) -> Option<Self> {
+        match (opname, column_first) {
+            ("+", _) => Self::increment(constant, integer),
+            ("-", true) => {
+                let negated = match constant.strip_prefix('-') {
+                    Some(abs) => abs.to_string(),
+                    None => format!("-{}", constant),
+                };
+                Self::increment(negated, integer)
+            }
+            ("||", true) => Some(Assignment::Append(constant)),
+            _ => None,
+        }
+    }
+
+    fn increment(delta: String, integer: bool) -> Option<Self> {
+        let delta = if integer {
+            // Redis rejects "-0" and other non-canonical integers.
+            delta.parse::<i64>().ok()?.to_string()
+        } else {
+            delta.parse::<f64>().ok().filter(|d| d.is_finite())?;
+            delta
+        };
+        Some(Assignment::Increment {
+            delta,
+            float: !integer,
+        })
+    }
+}
+
+/// Redis side of a direct UPDATE/DELETE.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DirectModifyOp {
+    /// DELETE on a multi-key table: UNLINK the targeted keys
+    Unlink,
+    /// DELETE on a single-key hash/set/zset/geo: HDEL, SREM or ZREM
+    Remove(&'static str),
+    /// UPDATE of a single-key hash value: HSET on fields that already exist
+    SetHashField(String),
+    /// UPDATE of a multi-key string value: SET XX on each key
+    SetStringKey(String),
+    /// `value = value + n` on a single-key hash: HINCRBY(FLOAT) on existing
+    /// fields
+    IncrementHashField { delta: String, float: bool },
+    /// `value = value + n` on a multi-key string: INCRBY(FLOAT) on existing
+    /// keys
+    IncrementStringKey { delta: String, float: bool },
+    /// `value = value || s` on a multi-key string: APPEND to existing keys
+    AppendStringKey(String),
+    /// `score = score + n` on a single-key zset: ZINCRBY on existing members
+    IncrementScore(String),
+    /// `col = col + n` on a multi-key json table: JSON.NUMINCRBY of the
+    /// column's path on existing keys
+    IncrementJsonPath { path: String, delta: String },
+    /// DELETE on a zset score range: ZREMRANGEBYSCORE with Redis bounds
+    RemoveScoreRange { min: String, max: String },
+    /// DELETE on a zset member range: ZREMRANGEBYLEX with Redis lex bounds,
+    /// or ZSCAN then ZREM in batches when the scores differ
+    RemoveMemberRange { min: String, max: String },
+    /// DELETE of every stream entry below an ID: XTRIM MINID
+    TrimStream { min_id: String },
+    /// DELETE of a stream ID range with a lower bound: XRANGE then XDEL in
+    /// batches, with XRANGE bounds
+    DeleteStreamRange { start: String, end: String },
+    /// DELETE on a list index range: LTRIM to the kept `start..=stop`
+    TrimList { start: i64, stop: i64 },
+}
+
+impl DirectModifyOp {
+    /// Command name shown by EXPLAIN.
+    pub fn command_name(&self) -> &'static str {
+        match self {
+            DirectModifyOp::Unlink => "UNLINK",
+            DirectModifyOp::Remove(name) => name,
+            DirectModifyOp::SetHashField(_) => "HSET",
+            DirectModifyOp::SetStringKey(_) => "SET",
+            DirectModifyOp::IncrementHashField { float: false, .. } => "HINCRBY",
+            DirectModifyOp::IncrementHashField { float: true, .. } => "HINCRBYFLOAT",
+            DirectModifyOp::IncrementStringKey { float: false, .. } => "INCRBY",
+            DirectModifyOp::IncrementStringKey { float: true, .. } => "INCRBYFLOAT",
+            DirectModifyOp::AppendStringKey(_) => "APPEND",
+            DirectModifyOp::IncrementScore(_) => "ZINCRBY",
+            DirectModifyOp::IncrementJsonPath { .. } => "JSON.NUMINCRBY",
+            DirectModifyOp::RemoveScoreRange { .. } => "ZREMRANGEBYSCORE",
+            DirectModifyOp::RemoveMemberRange { .. } => "ZREMRANGEBYLEX",
+            DirectModifyOp::TrimStream { .. } => "XTRIM MINID",
+            DirectModifyOp::DeleteStreamRange { .. } => "XDEL",
+            DirectModifyOp::TrimList { .. } => "LTRIM",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirectModify {
+    pub op: DirectModifyOp,
+    /// Keys (multi-key) or fields/members (single-key) named by the WHERE
+    /// clause; empty for range deletes
+    pub targets: Vec<String>,
+}
+
+/// How PostgreSQL orders the values of a column, as far as Redis ranges
+/// are concerned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnOrder {
+    /// Integer, floating point or numeric
+    Numeric,
+    /// Text under the C/POSIX collation: byte by byte, like Redis lex ranges
+    Bytewise,
+    Other,
+}
+
+/// One side of a range condition: value and whether it is inclusive.
+type Bound<'a> = Option<(&'a str, bool)>;
+
+/// Split range conditions on one column into at most one lower and one
+/// upper bound. `None` when a condition is not a range or a side repeats.
+fn range_bounds(conditions: &[PushableCondition]) -> Option<(Bound<'_>, Bound<'_>)> {
+    let (mut lower, mut upper) = (None, None);
+    for c in conditions {
+        let (side, inclusive) = match c.operator {
+            ComparisonOperator::GreaterThan => (&mut lower, false),
+            ComparisonOperator::GreaterThanOrEqual => (&mut lower, true),
+            ComparisonOperator::LessThan => (&mut upper, false),
+            ComparisonOperator::LessThanOrEqual => (&mut upper, true),
+            _ => return None,
+        };
+        if side.replace((c.value.as_str(), inclusive)).is_some() {
+            return None;
+        }
+    }
+    Some((lower, upper))
+}
+
+/// Redis range argument for a bound: `(v` when exclusive.
+fn redis_bound(bound: Bound<'_>, unbounded: &str) -> String {
+    match bound {
+        Some((v, true)) => v.to_string(),
+        Some((v, false)) => format!("({}", v),
+        None => unbounded.to_string(),
+    }
+}
+
+/// ZREMRANGEBYLEX argument for a bound: `[v` or `(v`.
+fn lex_bound(bound: Bound<'_>, unbounded: &str) -> String {
+    match bound {
+        Some((v, true)) => format!("[{}", v),
+        Some((v, false)) => format!("({}", v),
+        None => unbounded.to_string(),
+    }
+}
+
+/// Whether `member` lies within the ZREMRANGEBYLEX bounds `min`..`max`.
+fn in_lex_range(member: &[u8], min: &str, max: &str) -> bool {
+    let above_min = match min.split_at(1) {
+        ("[", v) => member >= v.as_bytes(),
+        ("(", v) => member > v.as_bytes(),
+        _ => true,
+    };
+    let below_max = match max.split_at(1) {
+        ("[", v) => member <= v.as_bytes(),
+        ("(", v) => member < v.as_bytes(),
+        _ => true,
+    };
+    above_min && below_max
+}
+
+/// Parse a stream ID bound (`ms` or `ms-seq`).
+fn parse_stream_id(value: &str) -> Option<(u64, Option<u64>)> {
+    let mut parts = value.splitn(2, '-');
+    let ms = parts.next()?.parse::<u64>().ok()?;
+    let seq = match parts.next() {
+        Some(seq) => Some(seq.parse::<u64>().ok()?),
+        None => None,
+    };
+    Some((ms, seq))
+}
+
+/// XTRIM MINID argument for an upper bound: entries below it are removed.
+fn stream_min_id(value: &str, inclusive: bool) -> Option<String> {
+    let (ms, seq) = parse_stream_id(value)?;
+    if !inclusive {
+        return Some(value.to_string());
+    }
+    // `<= ms` covers every sequence number within that millisecond.
+    match seq.and_then(|s| s.checked_add(1)) {
+        Some(next) => Some(format!("{}-{}", ms, next)),
+        None => Some(format!("{}-0", ms.checked_add(1)?)),
+    }
+}
+
+/// LTRIM bounds that keep everything outside a one-sided index range.
+fn list_kept_range(lower: Bound<'_>, upper: Bound<'_>) -> Option<(i64, i64)> {
+    // Any start past the stop empties the list.
+    const EMPTY: (i64, i64) = (1, 0);
+    match (lower, upper) {
+        (None, Some((v, inclusive))) => {
+            let first_kept = v.parse::<i64>().ok()?.checked_add(inclusive as i64)?;
+            Some((first_kept.max(0), -1))
+        }
+        (Some((v, inclusive)), None) => {
+            let last_kept = v.parse::<i64>().ok()?.checked_sub(inclusive as i64)?;
+            Some(if last_kept < 0 { EMPTY } else { (0, last_kept) })
+        }
+        _ => None,
+    }
+}
+
+impl DirectModify {
+    /// Plan a direct modify for a statement whose WHERE clauses are
+    /// `conditions`. `assignment` is the column and right-hand side of an
+    /// UPDATE's single SET, `None` for DELETE; `column_order` tells how
+    /// PostgreSQL compares a column's values. Returns `None` when the
+    /// statement needs the regular scan-then-modify path.
+    pub fn plan(
+        table_type: &RedisTableType,
+        table_key_prefix: &str,
+        is_multi_key: bool,
+        ttl_column_index: Option<usize>,
+        conditions: &[PushableCondition],
+        assignment: Option<(usize, Assignment)>,
+        column_order: &dyn Fn(usize) -> ColumnOrder,
+    ) -> Option<Self> {
+        let identity_column = if is_multi_key {
+            compute_key_column_index(ttl_column_index)
+        } else {
+            compute_pushdown_column_index(ttl_column_index, false)
+        };
+        // The column right after the identity one: hash value / zset score
+        // (single-key) or string value (multi-key).
+        let value_column = compute_pushdown_column_index(ttl_column_index, true);
+
+        let column = conditions.first()?.column_index;
+        if conditions.iter().any(|c| c.column_index != column) {
+            return None;
+        }
+
+        if let [condition] = conditions {
+            if matches!(
+                condition.operator,
+                ComparisonOperator::Equal | ComparisonOperator::In
+            ) {
+                if column != identity_column {
+                    return None;
+                }
+                return Self::plan_targets(
+                    table_type,
+                    table_key_prefix,
+                    is_multi_key,
+                    ttl_column_index,
+                    condition,
+                    assignment,
+                    value_column,
+                );
+            }
+        }
+
+        // Range deletes on a single key.
+        if assignment.is_some() || is_multi_key {
+            return None;
+        }
+        let (lower, upper) = range_bounds(conditions)?;
+        let op = match table_type {
+            RedisTableType::ZSet(_)
+                if column == value_column && column_order(column) == ColumnOrder::Numeric =>
+            {
+                DirectModifyOp::RemoveScoreRange {
+                    min: redis_bound(lower, "-inf"),
+                    max: redis_bound(upper, "+inf"),
+                }
+            }
+            RedisTableType::ZSet(_)
+                if column == identity_column && column_order(column) == ColumnOrder::Bytewise =>
+            {
+                DirectModifyOp::RemoveMemberRange {
+                    min: lex_bound(lower, "-"),
+                    max: lex_bound(upper, "+"),
+                }
+            }
+            RedisTableType::Stream(_) if column == identity_column => match (lower, upper) {
+                (None, Some((v, inclusive))) => DirectModifyOp::TrimStream {
+                    min_id: stream_min_id(v, inclusive)?,
+                },
+                _ => {
+                    for (v, _) in lower.iter().chain(upper.iter()) {
+                        parse_stream_id(v)?;
+                    }
+                    DirectModifyOp::DeleteStreamRange {
+                        start: redis_bound(lower, "-"),
+                        end: redis_bound(upper, "+"),
+                    }
+                }
+            },
+            RedisTableType::List(l)
+                if l.include_index
+                    && column == identity_column
+                    && column_order(column) == ColumnOrder::Numeric =>
+            {
+                let (start, stop) = list_kept_range(lower, upper)?;
+                DirectModifyOp::TrimList { start, stop }
+            }
+            _ => return None,
+        };
+        Some(DirectModify {
+            op,
+            targets: Vec::new(),
+        })
+    }
+
+    /// Plan for an `=`/`IN` condition on the identity column.
+    fn plan_targets(
+        table_type: &RedisTableType,
+        table_key_prefix: &str,
+        is_multi_key: bool,
+        ttl_column_index: Option<usize>,
+        condition: &PushableCondition,
+        assignment: Option<(usize, Assignment)>,
+        value_column: usize,
+    ) -> Option<Self> {
+        let mut targets: Vec<String> = match condition.operator {
+            ComparisonOperator::Equal => vec![condition.value.clone()],
+            _ => condition
+                .value
+                .split(',')
+                .filter(|s| !s.is_empty())
+                .map(|s| s.to_string())
+                .collect(),
+        };
+        targets.sort_unstable();
+        targets.dedup();
+        if is_multi_key {
+            targets.retain(|k| glob_match(table_key_prefix, k));
+        }
+
+        let op = match (assignment, table_type) {
+            (None, RedisTableType::HashRow(_)) => return None,
+            (None, _) if is_multi_key => DirectModifyOp::Unlink,
+            (None, RedisTableType::Hash(_)) => DirectModifyOp::Remove("HDEL"),
+            (None, RedisTableType::Set(_)) => DirectModifyOp::Remove("SREM"),
+            (None, RedisTableType::ZSet(_) | RedisTableType::Geo(_)) => {
+                DirectModifyOp::Remove("ZREM")
+            }
+            (Some((col, Assignment::Increment { delta, .. })), RedisTableType::Json(t))
+                if is_multi_key =>
+            {
+                if col == compute_key_column_index(ttl_column_index)
+                    || Some(col) == ttl_column_index
+                {
+                    return None;
+                }
+                // Data columns follow the key column, skipping TTL.
+                let data_column =
+                    col - 1 - usize::from(ttl_column_index.is_some_and(|ttl| ttl < col));
+                DirectModifyOp::IncrementJsonPath {
+                    path: t.paths.get(data_column)?.clone(),
+                    delta,
+                }
+            }
+            (Some((col, assignment)), table_type) if col == value_column => {
+                match (assignment, table_type, is_multi_key) {
+                    (Assignment::Set(v), RedisTableType::Hash(_), false) => {
+                        DirectModifyOp::SetHashField(v)
+                    }
+                    (Assignment::Set(v), RedisTableType::String(_), true) => {
+                        DirectModifyOp::SetStringKey(v)
+                    }
+                    (Assignment::Increment { delta, float }, RedisTableType::Hash(_), false) => {
+                        DirectModifyOp::IncrementHashField { delta, float }
+                    }
+                    (Assignment::Increment { delta, float }, RedisTableType::String(_), true) => {
+                        DirectModifyOp::IncrementStringKey { delta, float }
+                    }
+                    (Assignment::Increment { delta, .. }, RedisTableType::ZSet(_), false) => {
+                        DirectModifyOp::IncrementScore(delta)
+                    }
+                    (Assignment::Append(v), RedisTableType::String(_), true) => {
+                        DirectModifyOp::AppendStringKey(v)
+                    }
+                    _ => return None,
+                }
+            }
+            _ => return None,
+        };
+        Some(DirectModify { op, targets })
+    }
+
+    /// Commands to send for the single key `key_prefix` (ignored for
+    /// multi-key tables). `per_key` splits UNLINK into one command per key so
+    /// each stays within one cluster slot. Stream and member range deletes
+    /// need reads first and are driven by [`DirectModify::execute`] instead.
+    pub fn commands(&self, key_prefix: &str, per_key: bool) -> Vec<redis::Cmd> {
+        let mut cmd;
+        match &self.op {
+            DirectModifyOp::Unlink if self.targets.is_empty() => return Vec::new(),
+            DirectModifyOp::Unlink if per_key => {
+                return self
+                    .targets
+                    .iter()
+                    .map(|key| {
+                        let mut cmd = redis::cmd("UNLINK");
+                        cmd.arg(key);
+                        cmd
+                    })
+                    .collect();
+            }
+            DirectModifyOp::Unlink => {
+                cmd = redis::cmd("UNLINK");
+                cmd.arg(&self.targets);
+            }
+            DirectModifyOp::Remove(_) if self.targets.is_empty() => return Vec::new(),
+            DirectModifyOp::Remove(name) => {
+                cmd = redis::cmd(name);
+                cmd.arg(key_prefix).arg(&self.targets);
+            }
+            DirectModifyOp::SetHashField(value) => {
+                return self
+                    .targets
+                    .iter()
+                    .filter_map(|field| {
+                        RedisHashTable::new().insert_command(
+                            key_prefix,
+                            &[field.clone(), value.clone()],
+                            InsertMode::Xx,
+                        )
+                    })
+                    .collect();
+            }
+            DirectModifyOp::SetStringKey(value) => {
+                return self
+                    .targets
+                    .iter()
+                    .filter_map(|key| {
+                        RedisStringTable::new().insert_command(
+                            key,
+                            std::slice::from_ref(value),
+                            InsertMode::Xx,
+                        )
+                    })
+                    .collect();
+            }
+            DirectModifyOp::IncrementHashField { delta, .. } => {
+                return self
+                    .targets
+                    .iter()
+                    .map(|field| {
+                        let mut cmd = redis::cmd("EVAL");
+                        cmd.arg(HASH_FIELD_XX_SCRIPT)
+                            .arg(1)
+                            .arg(key_prefix)
+                            .arg(self.op.command_name())
+                            .arg(field)
+                            .arg(delta);
+                        cmd
+                    })
+                    .collect();
+            }
+            DirectModifyOp::IncrementScore(delta) => {
+                return self
+                    .targets
+                    .iter()
+                    .map(|member| {
+                        let mut cmd = redis::cmd("EVAL");
+                        cmd.arg(ZINCRBY_XX_SCRIPT)
+                            .arg(1)
+                            .arg(key_prefix)
+                            .arg(delta)
+                            .arg(member);
+                        cmd
+                    })
+                    .collect();
+            }
+            DirectModifyOp::IncrementJsonPath { path, delta } => {
+                return self
+                    .targets
+                    .iter()
+                    .map(|key| {
+                        let mut cmd = redis::cmd("EVAL");
+                        cmd.arg(JSON_NUMINCRBY_XX_SCRIPT)
+                            .arg(1)
+                            .arg(key)
+                            .arg(path)
+                            .arg(delta);
+                        cmd
+                    })
+                    .collect();
+            }
+            DirectModifyOp::IncrementStringKey { delta: arg, .. }
+            | DirectModifyOp::AppendStringKey(arg) => {
+                return self
+                    .targets
+                    .iter()
+                    .map(|key| {
+                        let mut cmd = redis::cmd("EVAL");
+                        cmd.arg(STRING_KEY_XX_SCRIPT)
+                            .arg(1)
+                            .arg(key)
+                            .arg(self.op.command_name())
+                            .arg(arg);
+                        cmd
+                    })
+                    .collect();
+            }
+            DirectModifyOp::RemoveScoreRange { min, max } => {
+                cmd = redis::cmd("ZREMRANGEBYSCORE");
+                cmd.arg(key_prefix).arg(min).arg(max);
+            }
+            DirectModifyOp::TrimStream { min_id } => {
+                cmd = redis::cmd("XTRIM");
+                cmd.arg(key_prefix).arg("MINID").arg(min_id);
+            }
+            DirectModifyOp::DeleteStreamRange { .. } | DirectModifyOp::RemoveMemberRange { .. } => {
+                return Vec::new()
+            }
+            DirectModifyOp::TrimList { start, stop } => {
+                cmd = redis::cmd("EVAL");
+                cmd.arg(LTRIM_COUNT_SCRIPT)
+                    .arg(1)
+                    .arg(key_prefix)
+                    .arg(*start)
+                    .arg(*stop);
+            }
+        }
+        vec![cmd]
+    }
+
+    /// Send the modify and return the number of rows Redis reports as
+    /// changed. Standalone servers get one pipeline; cluster mode sends the
+    /// commands one at a time.
+    pub fn execute(
+        &self,
+        conn: &mut dyn redis::ConnectionLike,
+        key_prefix: &str,
+        is_cluster: bool,
+        batch_size: usize,
+    ) -> Result<u64, redis::RedisError> {
+        if let DirectModifyOp::DeleteStreamRange { start, end } = &self.op {
+            return Self::delete_stream_range(conn, key_prefix, start, end, batch_size);
+        }
+        if let DirectModifyOp::RemoveMemberRange { min, max } = &self.op {
+            return Self::delete_member_range(conn, key_prefix, min, max, batch_size);
+        }
+
+        let commands = self.commands(key_prefix, is_cluster);
+        let replies: Vec<redis::Value> = if commands.is_empty() {
+            Vec::new()
+        } else if is_cluster {
+            commands
+                .iter()
+                .map(|cmd| cmd.query(conn))
+                .collect::<Result<_, _>>()?
+        } else {
+            let mut pipe = redis::pipe();
+            for cmd in commands {
+                pipe.add_command(cmd);
+            }
+            pipe.query(conn)?
+        };
+        Ok(Self::rows_from_replies(&replies))
+    }
+
+    /// XDEL the entries in `start..end` (XRANGE bounds), `batch_size` IDs
+    /// per round trip.
+    fn delete_stream_range(
+        conn: &mut dyn redis::ConnectionLike,
+        key: &str,
+        start: &str,
+        end: &str,
+        batch_size: usize,
+    ) -> Result<u64, redis::RedisError> {
+        let mut start = start.to_string();
+        let mut deleted = 0u64;
+        loop {
+            let entries: Vec<(String, Vec<String>)> = redis::cmd("XRANGE")
+                .arg(key)
+                .arg(&start)
+                .arg(end)
+                .arg("COUNT")
+                .arg(batch_size)
+                .query(conn)?;
+            let Some((last_id, _)) = entries.last() else {
+                return Ok(deleted);
+            };
+            start = format!("({}", last_id);
+            let ids: Vec<&str> = entries.iter().map(|(id, _)| id.as_str()).collect();
+            let n: u64 = redis::cmd("XDEL").arg(key).arg(&ids).query(conn)?;
+            deleted += n;
+            if entries.len() < batch_size {
+                return Ok(deleted);
+            }
+        }
+    }
+
+    /// Remove the zset members in `min..max` (ZREMRANGEBYLEX bounds). Lex
+    /// ranges only hold among members of equal score, so otherwise the
+    /// members are compared here, `batch_size` per ZSCAN round trip.
+    fn delete_member_range(
+        conn: &mut dyn redis::ConnectionLike,
+        key: &str,
+        min: &str,
+        max: &str,
+        batch_size: usize,
+    ) -> Result<u64, redis::RedisError> {
+        let removed: Option<u64> = redis::cmd("EVAL")
+            .arg(ZREMRANGEBYLEX_SAME_SCORE_SCRIPT)
+            .arg(1)
+            .arg(key)
+            .arg(min)
+            .arg(max)
+            .query(conn)?;
+        if let Some(removed) = removed {
+            return Ok(removed);
+        }
+
+        let mut cursor = 0u64;
+        let mut deleted = 0u64;
+        loop {
+            let (next, page): (u64, Vec<Vec<u8>>) = redis::cmd("ZSCAN")
+                .arg(key)
+                .arg(cursor)
+                .arg("COUNT")
+                .arg(batch_size)
+                .query(conn)?;
+            // ZSCAN replies with member, score pairs.
+            let members: Vec<&Vec<u8>> = page
+                .iter()
+                .step_by(2)
+                .filter(|member| in_lex_range(member, min, max))
+                .collect();
+            if !members.is_empty() {
+                let n: u64 = redis::cmd("ZREM").arg(key).arg(&members).query(conn)?;
+                deleted += n;
+            }
+            if next == 0 {
+                return Ok(deleted);
+            }
+            cursor = next;
+        }
+    }
+
+    /// Rows changed according to Redis: UNLINK/HDEL/SREM/ZREM and the range
+    /// commands reply with a count, conditional HSET/SET and the increment
+    /// scripts with one reply per target.
+    pub fn rows_from_replies(replies: &[redis::Value]) -> u64 {
+        replies
+            .iter()
+            .map(|reply| match reply {
+                redis::Value::Int(n) => (*n).max(0) as u64,
+                redis::Value::Nil => 0,
+                _ => 1,
+            })
+            .sum()
+    }
+}
+
+#[pg_guard]
+pub(crate) unsafe extern "C-unwind" fn plan_direct_modify(
+    root: *mut PlannerInfo,
+    plan: *mut ModifyTable,
+    result_relation: Index,
+    _subplan_index: ::core::ffi::c_int,
+) -> bool {
+    log!("---> plan_direct_modify");
+    let operation = (*plan).operation;
+    if !matches!(
+        operation,
+        pg_sys::CmdType::CMD_UPDATE | pg_sys::CmdType::CMD_DELETE
+    ) || !(*plan).returningLists.is_null()
+    {
+        return false;
+    }
+
+    let subplan = (*plan).plan.lefttree;
+    if subplan.is_null() || (*subplan).type_ != pg_sys::NodeTag::T_ForeignScan {
+        return false;
+    }
+    let fscan = subplan as *mut pg_sys::ForeignScan;
+    if (*fscan).scan.scanrelid != result_relation || !(*fscan).fdw_exprs.is_null() {
+        return false;
+    }
+
+    let state = state_from_ptr(deserialize_ptr_from_list((*fscan).fdw_private as _));
+    // Deferred writes have no reply to count rows from.
+    if state.transaction_config.is_some() || state.optimistic {
+        return false;
+    }
+    // A consumer-group DELETE acknowledges the entries its scan delivered.
+    if state.opts.contains_key("consumer_group") {
+        return false;
+    }
+
+    let assignment = if operation == pg_sys::CmdType::CMD_UPDATE {
+        match update_assignment(root, result_relation) {
+            Some(a) => Some(a),
+            None => return false,
+        }
+    } else {
+        None
+    };
+
+    // The regular UPDATE path re-applies TTLs: `default_ttl` on every write,
+    // and the row's TTL after a multi-key SET, which would drop it.
+    // Increments and APPEND keep the key's TTL.
+    if let Some((_, a)) = &assignment {
+        if state.default_ttl.is_some()
+            || (matches!(a, Assignment::Set(_))
+                && state.is_multi_key
+                && state.ttl_column_index.is_some())
+        {
+            return false;
+        }
+    }
+
+    let rte = pg_sys::planner_rt_fetch(result_relation, root);
+    let relation = pg_sys::relation_open((*rte).relid, pg_sys::NoLock as _);
+    if let RedisTableType::Json(ref mut t) = state.table_type {
+        // An increment targets the JSONPath of the assigned column.
+        t.key_column = state.is_multi_key;
+        let mut paths = extract_field_names((*rte).relid, (*relation).rd_att);
+        if let Some(ttl_idx) = state.ttl_column_index.filter(|&i| i < paths.len()) {
+            paths.remove(ttl_idx);
+        }
+        t.configure(&paths, 0, None);
+    }
+    let bytewise = quals_compare_bytewise((*fscan).scan.plan.qual);
+    let direct = WhereClausePushdown::analyze_modify_clauses((*fscan).scan.plan.qual, relation)
+        .and_then(|conditions| {
+            let tupdesc = (*relation).rd_att;
+            let column_order = |col: usize| {
+                if col >= (*tupdesc).natts as usize {
+                    return ColumnOrder::Other;
+                }
+                let attr = tuple_desc_attr(tupdesc, col);
+                if is_numeric_type((*attr).atttypid) {
+                    ColumnOrder::Numeric
+                } else if bytewise
+                    && matches!((*attr).atttypid, pg_sys::TEXTOID | pg_sys::VARCHAROID)
+                {
+                    ColumnOrder::Bytewise
+                } else {
+                    ColumnOrder::Other
+                }
+            };
+            DirectModify::plan(
+                &state.table_type,
+                &state.table_key_prefix,
+                state.is_multi_key,
+                state.ttl_column_index,
+                &conditions,
+                assignment,
+                &column_order,
+            )
+        });
+    pg_sys::relation_close(relation, pg_sys::NoLock as _);
+    let Some(direct) = direct else {
+        return false;
+    };
+    log!("Direct modify planned: {:?}", direct);
+    state.direct_modify = Some(direct);
+
+    (*fscan).operation = operation;
+    (*fscan).resultRelation = result_relation;
+    // Redis applies the WHERE clause; there are no rows left to filter.
+    (*fscan).scan.plan.qual = ptr::null_mut();
+    true
+}
+
+fn is_numeric_type(oid: pg_sys::Oid) -> bool {
+    matches!(
+        oid,
+        pg_sys::INT2OID
+            | pg_sys::INT4OID
+            | pg_sys::INT8OID
+            | pg_sys::FLOAT4OID
+            | pg_sys::FLOAT8OID
+            | pg_sys::NUMERICOID
+    )
+}
+
+/// Whether every operator in `quals` compares under the C or POSIX
+/// collation, the column's own or one given with COLLATE.
+unsafe fn quals_compare_bytewise(quals: *mut pg_sys::List) -> bool {
+    (0..pg_sys::list_length(quals)).all(|i| {
+        let node = pg_sys::list_nth(quals, i) as *mut pg_sys::Node;
+        (*node).type_ == pg_sys::NodeTag::T_OpExpr
+            && matches!(
+                (*(node as *mut pg_sys::OpExpr)).inputcollid,
+                pg_sys::C_COLLATION_OID | pg_sys::POSIX_COLLATION_OID
+            )
+    })
+}
+
+/// The column and right-hand side of an UPDATE that sets exactly one
+/// column to a non-NULL constant, or to itself combined with one.
+unsafe fn update_assignment(
+    root: *mut PlannerInfo,
+    result_relation: Index,
+) -> Option<(usize, Assignment)> {
+    let colnos = (*root).update_colnos;
+    if pg_sys::list_length(colnos) != 1 {
+        return None;
+    }
+    let attno = pg_sys::list_nth_int(colnos, 0);
+    let tle = pg_sys::list_nth((*root).processed_tlist, 0) as *mut pg_sys::TargetEntry;
+    if attno <= 0 || tle.is_null() {
+        return None;
+    }
+    let expr = strip_relabel((*tle).expr as *mut pg_sys::Node);
+    if expr.is_null() {
+        return None;
+    }
+    let assignment = match (*expr).type_ {
+        pg_sys::NodeTag::T_Const => Assignment::Set(non_null_constant(expr)?),
+        pg_sys::NodeTag::T_OpExpr => {
+            let op = expr as *mut pg_sys::OpExpr;
+            if pg_sys::list_length((*op).args) != 2 {
+                return None;
+            }
+            let left = strip_relabel(pg_sys::list_nth((*op).args, 0) as *mut pg_sys::Node);
+            let right = strip_relabel(pg_sys::list_nth((*op).args, 1) as *mut pg_sys::Node);
+            let is_self = |node: *mut pg_sys::Node| {
+                !node.is_null()
+                    && (*node).type_ == pg_sys::NodeTag::T_Var
+                    && (*(node as *mut pg_sys::Var)).varno as Index == result_relation
+                    && (*(node as *mut pg_sys::Var)).varlevelsup == 0
+                    && (*(node as *mut pg_sys::Var)).varattno as i32 == attno
+            };
+            let (column_first, constant) = if is_self(left) {
+                (true, right)
+            } else if is_self(right) {
+                (false, left)
+            } else {
+                return None;
+            };
+            let integer = match (*op).opresulttype {
+                pg_sys::INT2OID | pg_sys::INT4OID | pg_sys::INT8OID => true,
+                pg_sys::FLOAT4OID | pg_sys::FLOAT8OID | pg_sys::NUMERICOID | pg_sys::TEXTOID => {
+                    false
+                }
+                _ => return None,
+            };
+            let name_ptr = pg_sys::get_opname((*op).opno);
+            if name_ptr.is_null() {
+                return None;
+            }
+            let opname = std::ffi::CStr::from_ptr(name_ptr).to_str().ok()?;
+            // `||` only appends when it yields text; `+`/`-` only on numbers.
+            if (opname == "||") != ((*op).opresulttype == pg_sys::TEXTOID) {
+                return None;
+            }
+            Assignment::from_operator(opname, column_first, non_null_constant(constant)?, integer)?
+        }
+        _ => return None,
+    };
+    Some(((attno - 1) as usize, assignment))
+}
+
+unsafe fn strip_relabel(mut node: *mut pg_sys::Node) -> *mut pg_sys::Node {
+    while !node.is_null() && (*node).type_ == pg_sys::NodeTag::T_RelabelType {
+        node = (*(node as *mut pg_sys::RelabelType)).arg as *mut pg_sys::Node;
+    }
+    node
+}
+
+unsafe fn non_null_constant(node: *mut pg_sys::Node) -> Option<String> {
+    if node.is_null()
+        || (*node).type_ != pg_sys::NodeTag::T_Const
+        || (*(node as *mut pg_sys::Const)).constisnull
+    {
+        return None;
+    }
+    WhereClausePushdown::extract_constant_value(node)
+}
+
+#[pg_guard]
+pub(crate) unsafe extern "C-unwind" fn begin_direct_modify(
+    node: *mut pg_sys::ForeignScanState,
+    eflags: ::core::ffi::c_int,
+) {
+    log!("---> begin_direct_modify");
+    let plan = (*node).ss.ps.plan as *mut pg_sys::ForeignScan;
+    let relid = (*(*node).ss.ss_currentRelation).rd_id;
+    let state_ptr = deserialize_ptr_from_list((*plan).fdw_private as _);
+    let state = state_from_ptr(state_ptr);
+    PgMemoryContexts::For(state.tmp_ctx).switch_to(|_| {
+        state.update_from_options(get_foreign_table_options(relid));
+        state.set_table_type();
+
+        if eflags & pg_sys::EXEC_FLAG_EXPLAIN_ONLY as i32 == 0 && state.redis_connection.is_none() {
+            if let Err(e) = state.init_redis_connection_from_options() {
+                pgrx::error!("Failed to connect to Redis: {}", e);
+            }
+        }
+    });
+    state.row_count = 0;
+    state.scan_complete = false;
+    (*node).fdw_state = state_ptr;
+}
+
+#[pg_guard]
+pub(crate) unsafe extern "C-unwind" fn iterate_direct_modify(
+    node: *mut pg_sys::ForeignScanState,
+) -> *mut pg_sys::TupleTableSlot {
+    log!("---> iterate_direct_modify");
+    let state = state_from_ptr((*node).fdw_state);
+    let slot = (*node).ss.ss_ScanTupleSlot;
+    ExecClearTuple(slot);
+
+    if !state.scan_complete {
+        state.scan_complete = true;
+        match state.execute_direct_modify() {
+            Ok(rows) => {
+                state.row_count = rows as u32;
+                let estate = (*node).ss.ps.state;
+                (*estate).es_processed += rows;
+            }
+            Err(e) => error!("redis_fdw: direct modify failed: {}", e),
+        }
+    }
+    slot
+}
+
+#[pg_guard]
+pub(crate) unsafe extern "C-unwind" fn end_direct_modify(node: *mut pg_sys::ForeignScanState) {
+    log!("---> end_direct_modify");
+    let fdw_state = (*node).fdw_state as *mut RedisFdwState;
+    if fdw_state.is_null() {
+        return;
+    }
+    let ctx = (*fdw_state).tmp_ctx;
+    if !ctx.is_null() {
+        delete_wrappers_memctx(ctx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tables::implementations::{
+        RedisGeoTable, RedisJsonTable, RedisListTable, RedisSetTable, RedisStreamTable,
+        RedisZSetTable,
+    };
+    use crate::tests::utils::cmd_args;
+
+    fn cond(col: usize, op: ComparisonOperator, val: &str) -> PushableCondition {
+        PushableCondition {
+            column_name: "c".to_string(),
+            column_index: col,
+            operator: op,
+            value: val.to_string(),
+        }
+    }
+
+    fn hash() -> RedisTableType {
+        RedisTableType::Hash(RedisHashTable::new())
+    }
+
+    fn plan_delete(
+        table: &RedisTableType,
+        conditions: &[PushableCondition],
+    ) -> Option<DirectModifyOp> {
+        DirectModify::plan(table, "k", false, None, conditions, None, &|_| {
+            ColumnOrder::Numeric
+        })
+        .map(|d| d.op)
+    }
+
+    #[test]
+    fn delete_multi_key_unlinks_matching_keys() {
+        let d = DirectModify::plan(
+            &hash(),
+            "session:*",
+            true,
+            None,
+            &[cond(
+                0,
+                ComparisonOperator::In,
+                "session:2,other:1,session:1",
+            )],
+            None,
+            &|_| ColumnOrder::Other,
+        )
+        .unwrap();
+        assert_eq!(d.op, DirectModifyOp::Unlink);
+        assert_eq!(d.targets, vec!["session:1", "session:2"]);
+    }
+
+    #[test]
+    fn delete_single_key_removes_members() {
+        let set = RedisTableType::Set(RedisSetTable::new());
+        let zset = RedisTableType::ZSet(RedisZSetTable::new());
+        let c = [cond(0, ComparisonOperator::Equal, "m")];
+        assert_eq!(
+            plan_delete(&hash(), &c),
+            Some(DirectModifyOp::Remove("HDEL"))
+        );
+        assert_eq!(plan_delete(&set, &c), Some(DirectModifyOp::Remove("SREM")));
+        assert_eq!(plan_delete(&zset, &c), Some(DirectModifyOp::Remove("ZREM")));
+        let geo = RedisTableType::Geo(RedisGeoTable::new());
+        assert_eq!(plan_delete(&geo, &c), Some(DirectModifyOp::Remove("ZREM")));
+    }
+
+    #[test]
+    fn non_identity_or_like_condition_is_rejected() {
+        let value_col = [cond(1, ComparisonOperator::Equal, "v")];
+        assert!(plan_delete(&hash(), &value_col).is_none());
+        let like = [cond(0, ComparisonOperator::Like, "a%")];
+        assert!(plan_delete(&hash(), &like).is_none());
+    }
+
+    #[test]
+    fn update_targets_value_column_only() {
+        let c = [cond(1, ComparisonOperator::Equal, "f")];
+        let plan = |col| {
+            DirectModify::plan(
+                &hash(),
+                "k",
+                false,
+                Some(0),
+                &c,
+                Some((col, Assignment::Set("v".into()))),
+                &|_| ColumnOrder::Numeric,
+            )
+        };
+        // TTL at position 0 shifts field to 1 and value to 2.
+        assert_eq!(
+            plan(2).unwrap().op,
+            DirectModifyOp::SetHashField("v".into())
+        );
+        assert!(plan(0).is_none());
+    }
+
+    #[test]
+    fn assignment_from_operator() {
+        assert_eq!(
+            Assignment::from_operator("+", false, "5".into(), true),
+            Some(Assignment::Increment {
+                delta: "5".into(),
+                float: false
+            })
+        );
+        assert_eq!(
+            Assignment::from_operator("-", true, "-0".into(), true),
+            Some(Assignment::Increment {
+                delta: "0".into(),
+                float: false
+            })
+        );
+        assert_eq!(
+            Assignment::from_operator("-", true, "1.5".into(), false),
+            Some(Assignment::Increment {
+                delta: "-1.5".into(),
+                float: true
+            })
+        );
+        assert_eq!(
+            Assignment::from_operator("||", true, "x".into(), false),
+            Some(Assignment::Append("x".into()))
+        );
+        // `n - col` and `'x' || col` are not increments or appends.
+        assert!(Assignment::from_operator("-", false, "1".into(), true).is_none());
+        assert!(Assignment::from_operator("||", false, "x".into(), false).is_none());
+        assert!(Assignment::from_operator("+", true, "NaN".into(), false).is_none());
+    }
+
+    #[test]
+    fn increment_and_append_pick_type_specific_commands() {
+        let inc = |float| Assignment::Increment {
+            delta: "1".into(),
+            float,
+        };
+        let c = [cond(0, ComparisonOperator::Equal, "k1")];
+        let plan = |table: &RedisTableType, multi, a| {
+            DirectModify::plan(table, "k*", multi, None, &c, Some((1, a)), &|_| {
+                ColumnOrder::Numeric
+            })
+            .map(|d| d.op.command_name())
+        };
+        let string = RedisTableType::String(RedisStringTable::new());
+        let zset = RedisTableType::ZSet(RedisZSetTable::new());
+        assert_eq!(plan(&hash(), false, inc(false)), Some("HINCRBY"));
+        assert_eq!(plan(&hash(), false, inc(true)), Some("HINCRBYFLOAT"));
+        assert_eq!(plan(&string, true, inc(false)), Some("INCRBY"));
+        assert_eq!(plan(&string, true, inc(true)), Some("INCRBYFLOAT"));
+        assert_eq!(plan(&zset, false, inc(true)), Some("ZINCRBY"));
+        assert_eq!(
+            plan(&string, true, Assignment::Append("x".into())),
+            Some("APPEND")
+        );
+        assert_eq!(plan(&hash(), false, Assignment::Append("x".into())), None);
+    }
+
+    #[test]
+    fn json_increment_targets_assigned_column_path() {
+        let mut json = RedisJsonTable::new();
+        json.key_column = true;
+        json.configure(
+            &["key", "name", "$.stats.visits"].map(String::from),
+            0,
+            None,
+        );
+        let json = RedisTableType::Json(json);
+        let c = [cond(0, ComparisonOperator::Equal, "user:1")];
+        let inc = Assignment::Increment {
+            delta: "1".into(),
+            float: false,
+        };
+        let d = DirectModify::plan(
+            &json,
+            "user:*",
+            true,
+            None,
+            &c,
+            Some((2, inc.clone())),
+            &|_| ColumnOrder::Numeric,
+        )
+        .unwrap();
+        assert_eq!(
+            d.op,
+            DirectModifyOp::IncrementJsonPath {
+                path: "$.stats.visits".into(),
+                delta: "1".into(),
+            }
+        );
+        assert_eq!(d.op.command_name(), "JSON.NUMINCRBY");
+        // Not on the key column, and not on a single-key table.
+        assert!(DirectModify::plan(
+            &json,
+            "user:*",
+            true,
+            None,
+            &c,
+            Some((0, inc.clone())),
+            &|_| { ColumnOrder::Numeric }
+        )
+        .is_none());
+        assert!(
+            DirectModify::plan(&json, "user:1", false, None, &c, Some((1, inc)), &|_| {
+                ColumnOrder::Numeric
+            })
+            .is_none()
+        );
+    }
+
+    #[test]
+    fn increments_run_one_script_per_target() {
+        let d = DirectModify {
+            op: DirectModifyOp::IncrementHashField {
+                delta: "2".into(),
+                float: false,
+            },
+            targets: vec!["a".into(), "b".into()],
+        };
+        let commands = d.commands("counters", false);
+        assert_eq!(commands.len(), 2);
+        let args = cmd_args(&commands[0]);
+        assert_eq!(
+            args[2..],
+            ["1", "counters", "HINCRBY", "a", "2"].map(String::from)
+        );
+    }
+
+    #[test]
+    fn zset_score_range_becomes_zremrangebyscore() {
+        let zset = RedisTableType::ZSet(RedisZSetTable::new());
+        let c = [
+            cond(1, ComparisonOperator::GreaterThanOrEqual, "10"),
+            cond(1, ComparisonOperator::LessThan, "100"),
+        ];
+        assert_eq!(
+            plan_delete(&zset, &c),
+            Some(DirectModifyOp::RemoveScoreRange {
+                min: "10".into(),
+                max: "(100".into()
+            })
+        );
+        // Text scores compare as strings in PostgreSQL.
+        assert!(
+            DirectModify::plan(&zset, "k", false, None, &c, None, &|_| ColumnOrder::Other)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn zset_member_range_becomes_zremrangebylex_under_c_collation() {
+        let zset = RedisTableType::ZSet(RedisZSetTable::new());
+        let c = [
+            cond(0, ComparisonOperator::GreaterThan, "b"),
+            cond(0, ComparisonOperator::LessThanOrEqual, "m"),
+        ];
+        let plan = |order: ColumnOrder| {
+            DirectModify::plan(&zset, "k", false, None, &c, None, &|_| order).map(|d| d.op)
+        };
+        assert_eq!(
+            plan(ColumnOrder::Bytewise),
+            Some(DirectModifyOp::RemoveMemberRange {
+                min: "(b".into(),
+                max: "[m".into()
+            })
+        );
+        // Other collations order members differently from Redis.
+        assert!(plan(ColumnOrder::Other).is_none());
+    }
+
+    #[test]
+    fn lex_range_compares_bytes() {
+        assert!(in_lex_range(b"b", "[b", "+"));
+        assert!(!in_lex_range(b"b", "(b", "+"));
+        assert!(in_lex_range(b"B", "-", "(b"));
+        assert!(!in_lex_range(b"ba", "-", "[b"));
+    }
+
+    #[test]
+    fn stream_upper_bound_trims_and_lower_bound_deletes_range() {
+        let stream = RedisTableType::Stream(RedisStreamTable::new(100));
+        let lt = [cond(0, ComparisonOperator::LessThan, "1700-0")];
+        assert_eq!(
+            plan_delete(&stream, &lt),
+            Some(DirectModifyOp::TrimStream {
+                min_id: "1700-0".into()
+            })
+        );
+        let le = [cond(0, ComparisonOperator::LessThanOrEqual, "1700")];
+        assert_eq!(
+            plan_delete(&stream, &le),
+            Some(DirectModifyOp::TrimStream {
+                min_id: "1701-0".into()
+            })
+        );
+        let between = [
+            cond(0, ComparisonOperator::GreaterThan, "1600-0"),
+            cond(0, ComparisonOperator::LessThanOrEqual, "1700-5"),
+        ];
+        assert_eq!(
+            plan_delete(&stream, &between),
+            Some(DirectModifyOp::DeleteStreamRange {
+                start: "(1600-0".into(),
+                end: "1700-5".into()
+            })
+        );
+        let garbage = [cond(0, ComparisonOperator::LessThan, "yesterday")];
+        assert!(plan_delete(&stream, &garbage).is_none());
+    }
+
+    #[test]
+    fn list_index_range_becomes_ltrim() {
+        let mut list = RedisListTable::new();
+        list.include_index = true;
+        let list = RedisTableType::List(list);
+        let below = [cond(0, ComparisonOperator::LessThan, "3")];
+        assert_eq!(
+            plan_delete(&list, &below),
+            Some(DirectModifyOp::TrimList { start: 3, stop: -1 })
+        );
+        let from = [cond(0, ComparisonOperator::GreaterThanOrEqual, "0")];
+        assert_eq!(
+            plan_delete(&list, &from),
+            Some(DirectModifyOp::TrimList { start: 1, stop: 0 })
+        );
+        let middle = [
+            cond(0, ComparisonOperator::GreaterThan, "1"),
+            cond(0, ComparisonOperator::LessThan, "5"),
+        ];
+        assert!(plan_delete(&list, &middle).is_none());
+    }
+
+    #[test]
+    fn commands_build_one_unlink_or_one_per_key() {
+        let d = DirectModify {
+            op: DirectModifyOp::Unlink,
+            targets: vec!["a".into(), "b".into()],
+        };
+        assert_eq!(d.commands("", false).len(), 1);
+        assert_eq!(d.commands("", true).len(), 2);
+    }
+
+    #[test]
+    fn rows_from_replies_counts_changes() {
+        let replies = [
+            redis::Value::Int(2),
+            redis::Value::Int(0),
+            redis::Value::Nil,
+            redis::Value::Okay,
+        ];
+        assert_eq!(DirectModify::rows_from_replies(&replies), 3);
+    }
+}
@@ -2,7 +2,7 @@
 //!
 //! Architecture: a pure-Rust `ExplainReport` (see [`report`]) collects
 //! typed `Prop`s; a thin `emit()` adapter (see [`emit`]) is the only place
-//! that touches `pg_sys::ExplainState`. The two `#[pg_guard]` handlers
+//! that touches `pg_sys::ExplainState`. The `#[pg_guard]` handlers
 //! below are intentionally tiny — they just resolve state and delegate.
 
 pub mod emit;
@@ -44,3 +44,18 @@ pub(crate) unsafe extern "C-unwind" fn explain_foreign_modify(
     let state = &*fdw_state;
     ExplainReport::for_modify(state).emit(es);
 }
+
+#[pg_guard]
+pub(crate) unsafe extern "C-unwind" fn explain_direct_modify(
+    node: *mut pg_sys::ForeignScanState,
+    es: *mut pg_sys::ExplainState,
+) {
+    log!("---> explain_direct_modify");
+    let fdw_state = (*node).fdw_state as *mut RedisFdwState;
+    if fdw_state.is_null() {
+        return;
+    }
+    let state = &*fdw_state;
+    let analyze = (*es).analyze;
+    ExplainReport::for_direct_modify(state, analyze).emit(es);
+}
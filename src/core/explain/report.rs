@@ -117,6 +117,21 @@ impl ExplainReport {
         }
     }
 
+    /// Render the XRANGE bounds a stream scan computed from its stream ID
+    /// and entry time conditions. Nothing for an unbounded range.
+    pub fn add_stream_id_range(&mut self, (start, end): (String, String)) {
+        if start != "-" || end != "+" {
+            self.text("Stream ID Range", format!("{} {}", start, end));
+        }
+    }
+
+    /// Render the key type a keyspace scan passes to SCAN TYPE, if any.
+    pub fn add_scan_type(&mut self, key_type: Option<&str>) {
+        if let Some(t) = key_type {
+            self.text("Scan Type", t.to_string());
+        }
+    }
+
     /// Render which Redis commands this scan will issue. Helps users understand
     /// the actual access pattern at a glance.
     pub fn add_redis_ops(&mut self, ops: &[&'static str]) {
@@ -163,6 +178,26 @@ impl ExplainReport {
         r
     }
 
+    /// Build a direct-modify report: the modify fields, the pushed WHERE
+    /// clause and the Redis command that replaces the scan.
+    pub fn from_direct_modify_inputs(
+        host_port: &str,
+        key_prefix: &str,
+        type_name: &'static str,
+        analysis: Option<&crate::query::pushdown_types::PushdownAnalysis>,
+        command: &'static str,
+        analyze: bool,
+        row_count: u32,
+    ) -> Self {
+        let mut r = Self::from_modify_inputs(host_port, key_prefix, type_name);
+        r.add_pushdown_summary(analysis);
+        r.text("Direct Modify", command);
+        if analyze {
+            r.int("Rows Modified", Some("rows"), row_count as i64);
+        }
+        r
+    }
+
     /// Build a join report from raw fields.
     pub fn from_join_inputs(
         host_port: &str,
@@ -183,9 +218,9 @@ impl ExplainReport {
             if let Some(js) = state.join_state.as_ref() {
                 return Self::from_join_inputs(
                     &state.host_port,
-                    js.outer_table_type.redis_type_name(),
+                    js.outer_table_type.table_type_name(),
                     &js.outer_key_prefix,
-                    js.inner_table_type.redis_type_name(),
+                    js.inner_table_type.table_type_name(),
                     &js.inner_key_prefix,
                 );
             }
@@ -200,7 +235,7 @@ impl ExplainReport {
         let mut report = Self::from_scan_inputs(
             &state.host_port,
             &state.table_key_prefix,
-            state.table_type.redis_type_name(),
+            state.table_type.table_type_name(),
             state.is_multi_key,
             state.batch_size,
             state.pushdown_analysis.as_ref(),
@@ -210,6 +245,21 @@ impl ExplainReport {
             state.row_count,
         );
 
+        if let crate::tables::types::RedisTableType::Stream(s) = &state.table_type {
+            let analysis = state
+                .pushdown_analysis
+                .as_ref()
+                .filter(|_| s.consumer.is_none() && !state.is_parameterized);
+            if let Some(a) = analysis {
+                report.add_stream_id_range(s.xrange_bounds(&a.pushable_conditions));
+            }
+        }
+        if let crate::tables::types::RedisTableType::Keyspace(k) = &state.table_type {
+            if let Some(a) = state.pushdown_analysis.as_ref() {
+                report.add_scan_type(k.scan_type(&a.pushable_conditions));
+            }
+        }
+
         // PR-2: surface batched parameterized join info.
         if state.is_parameterized {
             let mode = match state.join_batch_mode {
@@ -244,7 +294,25 @@ impl ExplainReport {
         Self::from_modify_inputs(
             &state.host_port,
             &state.table_key_prefix,
-            state.table_type.redis_type_name(),
+            state.table_type.table_type_name(),
+        )
+    }
+
+    pub fn for_direct_modify(
+        state: &crate::core::state_manager::RedisFdwState,
+        analyze: bool,
+    ) -> Self {
+        Self::from_direct_modify_inputs(
+            &state.host_port,
+            &state.table_key_prefix,
+            state.table_type.table_type_name(),
+            state.pushdown_analysis.as_ref(),
+            state
+                .direct_modify
+                .as_ref()
+                .map_or("none", |d| d.op.command_name()),
+            analyze,
+            state.row_count,
         )
     }
 }
@@ -279,6 +347,11 @@ pub(crate) fn classify_redis_ops_for_inputs(inputs: ClassifierInputs) -> Vec<&'s
             "zset" => vec!["ZSCORE"],
             "string" => vec!["GET", "MGET"],
             "stream" => vec!["XRANGE"],
+            "hash_row" => vec!["HMGET"],
+            "json" => vec!["JSON.GET"],
+            "geo" => vec!["GEOPOS"],
+            "bitmap" => vec!["GETBIT"],
+            "hyperloglog" => vec!["PFCOUNT"],
             _ => vec![],
         };
     }
@@ -326,6 +399,19 @@ pub(crate) fn classify_redis_ops_for_inputs(inputs: ClassifierInputs) -> Vec<&'s
         })
     };
 
+    // Geo searches are conditions on the longitude column (the score slot).
+    let has_geo_search = || {
+        score_idx.is_some_and(|s| {
+            conds.iter().any(|c| {
+                c.column_index == s
+                    && matches!(
+                        c.operator,
+                        ComparisonOperator::GeoRadius { .. } | ComparisonOperator::GeoBox { .. }
+                    )
+            })
+        })
+    };
+
     let base: Vec<&'static str> = match inputs.type_name {
         "string" if inputs.is_multi_key && skip_scan_via_key_lookup => vec!["MGET"],
         "string" if inputs.is_multi_key => vec!["SCAN", "MGET"],
@@ -359,8 +445,43 @@ pub(crate) fn classify_redis_ops_for_inputs(inputs: ClassifierInputs) -> Vec<&'s
 
         // Stream: id-range and id-equality both go through XRANGE (label
         // unchanged; the args differ — Pushdown line shows the bounds).
+        "stream" if inputs.is_multi_key && !skip_scan_via_key_lookup => vec!["SCAN", "XRANGE"],
         "stream" => vec!["XRANGE"],
 
+        // hash_row: one HMGET of the mapped fields per key.
+        "hash_row" if inputs.is_multi_key && !skip_scan_via_key_lookup => vec!["SCAN", "HMGET"],
+        "hash_row" => vec!["HMGET"],
+
+        "json" if inputs.is_multi_key && !skip_scan_via_key_lookup => vec!["SCAN", "JSON.GET"],
+        "json" => vec!["JSON.GET"],
+
+        "geo" if inputs.is_multi_key && skip_scan_via_key_lookup => vec!["ZRANGE", "GEOPOS"],
+        "geo" if inputs.is_multi_key => vec!["SCAN", "ZRANGE", "GEOPOS"],
+        "geo" if has_geo_search() => vec!["GEOSEARCH"],
+        "geo" if has_target(ComparisonOperator::Equal) || has_target(ComparisonOperator::In) => {
+            vec!["GEOPOS"]
+        }
+        "geo" => vec!["ZRANGE", "GEOPOS"],
+
+        "bitmap" if inputs.is_multi_key && skip_scan_via_key_lookup => vec!["GET"],
+        "bitmap" if inputs.is_multi_key => vec!["SCAN", "GET"],
+        "bitmap" if has_target(ComparisonOperator::Equal) || has_target(ComparisonOperator::In) => {
+            vec!["GETBIT"]
+        }
+        "bitmap" => vec!["BITPOS", "GETRANGE"],
+
+        // hyperloglog: one PFCOUNT per key.
+        "hyperloglog" if inputs.is_multi_key && !skip_scan_via_key_lookup => {
+            vec!["SCAN", "PFCOUNT"]
+        }
+        "hyperloglog" => vec!["PFCOUNT"],
+
+        // keyspace: TYPE (plus the attribute commands) per key.
+        "keyspace" if inputs.is_multi_key && !skip_scan_via_key_lookup => {
+            vec!["SCAN", "TYPE"]
+        }
+        "keyspace" => vec!["TYPE"],
+
         _ => vec![],
     };
 
@@ -370,6 +491,20 @@ pub(crate) fn classify_redis_ops_for_inputs(inputs: ClassifierInputs) -> Vec<&'s
 /// Adapter from `RedisFdwState` to the pure classifier.
 fn redis_ops_for(state: &crate::core::state_manager::RedisFdwState) -> Vec<&'static str> {
     use crate::tables::types::RedisTableType;
+    // An aggregate answered by Redis reads no rows.
+    if state.count_pushdown {
+        return vec!["BITCOUNT"];
+    }
+    // A consumer-group read takes no pushdown: PostgreSQL filters the
+    // delivered entries.
+    if let RedisTableType::Stream(s) = &state.table_type {
+        if let Some(consumer) = s.consumer.as_ref().filter(|_| !state.is_parameterized) {
+            return consumer.read_ops();
+        }
+    }
+    if let RedisTableType::Function(f) = &state.table_type {
+        return f.read_ops();
+    }
     let (pushdown_col, score_col) = match &state.table_type {
         RedisTableType::ZSet(z) => (z.pushdown_column_index, Some(z.score_column_index)),
         RedisTableType::Hash(h) => (h.pushdown_column_index, None),
@@ -385,6 +520,8 @@ fn redis_ops_for(state: &crate::core::state_manager::RedisFdwState) -> Vec<&'sta
             None,
         ),
         RedisTableType::Stream(s) => (s.pushdown_column_index, None),
+        RedisTableType::Geo(g) => (g.pushdown_column_index, Some(g.longitude_column_index)),
+        RedisTableType::Bitmap(b) => (b.pushdown_column_index, None),
         _ => (0, None),
     };
     let key_col = if state.is_multi_key {
@@ -395,7 +532,7 @@ fn redis_ops_for(state: &crate::core::state_manager::RedisFdwState) -> Vec<&'sta
         None
     };
     classify_redis_ops_for_inputs(ClassifierInputs {
-        type_name: state.table_type.redis_type_name(),
+        type_name: state.table_type.table_type_name(),
         is_multi_key: state.is_multi_key,
         is_parameterized: state.is_parameterized,
         pushdown_column_index: pushdown_col,
@@ -839,6 +976,82 @@ mod tests {
         assert_eq!(ops, vec!["SCAN", "HGETALL"]);
     }
 
+    #[test]
+    fn classify_hash_row_key_eq_skips_scan() {
+        let analysis = analysis_of(vec![cond(0, ComparisonOperator::Equal, "user:1")]);
+        let inputs = |key_column_index| ClassifierInputs {
+            type_name: "hash_row",
+            is_multi_key: true,
+            is_parameterized: false,
+            pushdown_column_index: 0,
+            score_column_index: None,
+            key_column_index,
+            analysis: Some(&analysis),
+        };
+        assert_eq!(
+            classify_redis_ops_for_inputs(inputs(Some(0))),
+            vec!["HMGET"]
+        );
+        assert_eq!(
+            classify_redis_ops_for_inputs(inputs(Some(1))),
+            vec!["SCAN", "HMGET"]
+        );
+    }
+
+    #[test]
+    fn classify_geo_search_picks_geosearch() {
+        let search = analysis_of(vec![cond(
+            1,
+            ComparisonOperator::GeoRadius { latitude_column: 2 },
+            "13.5,38,200000",
+        )]);
+        let member = analysis_of(vec![cond(0, ComparisonOperator::Equal, "Palermo")]);
+        let inputs = |analysis| ClassifierInputs {
+            type_name: "geo",
+            is_multi_key: false,
+            is_parameterized: false,
+            pushdown_column_index: 0,
+            score_column_index: Some(1),
+            key_column_index: None,
+            analysis,
+        };
+        assert_eq!(
+            classify_redis_ops_for_inputs(inputs(Some(&search))),
+            vec!["GEOSEARCH"]
+        );
+        assert_eq!(
+            classify_redis_ops_for_inputs(inputs(Some(&member))),
+            vec!["GEOPOS"]
+        );
+        assert_eq!(
+            classify_redis_ops_for_inputs(inputs(None)),
+            vec!["ZRANGE", "GEOPOS"]
+        );
+    }
+
+    #[test]
+    fn classify_bitmap_offset_lookup_picks_getbit() {
+        let lookup = analysis_of(vec![cond(0, ComparisonOperator::In, "3,5")]);
+        let bit = analysis_of(vec![cond(1, ComparisonOperator::Equal, "true")]);
+        let inputs = |analysis| ClassifierInputs {
+            type_name: "bitmap",
+            is_multi_key: false,
+            is_parameterized: false,
+            pushdown_column_index: 0,
+            score_column_index: None,
+            key_column_index: None,
+            analysis,
+        };
+        assert_eq!(
+            classify_redis_ops_for_inputs(inputs(Some(&lookup))),
+            vec!["GETBIT"]
+        );
+        assert_eq!(
+            classify_redis_ops_for_inputs(inputs(Some(&bit))),
+            vec!["BITPOS", "GETRANGE"]
+        );
+    }
+
     #[test]
     fn classify_parameterized_stream_picks_xrange_point() {
         let ops = classify_redis_ops_for_inputs(ClassifierInputs {
@@ -911,4 +1124,127 @@ mod tests {
             }
         ));
     }
+
+    #[test]
+    fn direct_modify_emits_command_and_rows_when_analyzed() {
+        let r = ExplainReport::from_direct_modify_inputs(
+            "127.0.0.1:6379",
+            "session:*",
+            "hash",
+            None,
+            "UNLINK",
+            true,
+            2,
+        );
+        assert!(r.props.iter().any(|p| matches!(p,
+            Prop::Text { label: "Direct Modify", value } if value == "UNLINK"
+        )));
+        assert!(r.props.iter().any(|p| matches!(
+            p,
+            Prop::Int {
+                label: "Rows Modified",
+                unit: Some("rows"),
+                value: 2
+            }
+        )));
+
+        let plain = ExplainReport::from_direct_modify_inputs(
+            "127.0.0.1:6379",
+            "session:*",
+            "hash",
+            None,
+            "UNLINK",
+            false,
+            0,
+        );
+        assert!(!plain.props.iter().any(|p| matches!(
+            p,
+            Prop::Int {
+                label: "Rows Modified",
+                ..
+            }
+        )));
+    }
+
+    #[test]
+    fn stream_id_range_only_when_bounded() {
+        let mut r = ExplainReport::new();
+        r.add_stream_id_range(("-".to_string(), "+".to_string()));
+        assert!(r.props.is_empty());
+
+        r.add_stream_id_range(("1760605200000".to_string(), "+".to_string()));
+        assert_eq!(
+            r.props,
+            vec![Prop::Text {
+                label: "Stream ID Range",
+                value: "1760605200000 +".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn classify_multi_key_stream_key_eq_skips_scan() {
+        let analysis = analysis_of(vec![
+            cond(0, ComparisonOperator::Equal, "events:acme"),
+            cond(1, ComparisonOperator::GreaterThan, "1700000000000-0"),
+        ]);
+        let inputs = |key_column_index| ClassifierInputs {
+            type_name: "stream",
+            is_multi_key: true,
+            is_parameterized: false,
+            pushdown_column_index: 1,
+            score_column_index: None,
+            key_column_index,
+            analysis: Some(&analysis),
+        };
+        assert_eq!(
+            classify_redis_ops_for_inputs(inputs(Some(0))),
+            vec!["XRANGE"]
+        );
+        assert_eq!(
+            classify_redis_ops_for_inputs(inputs(None)),
+            vec!["SCAN", "XRANGE"]
+        );
+    }
+
+    #[test]
+    fn classify_hyperloglog_key_in_skips_scan() {
+        let analysis = analysis_of(vec![cond(0, ComparisonOperator::In, "hll:a,hll:b")]);
+        let inputs = |key_column_index| ClassifierInputs {
+            type_name: "hyperloglog",
+            is_multi_key: true,
+            is_parameterized: false,
+            pushdown_column_index: 1,
+            score_column_index: None,
+            key_column_index,
+            analysis: Some(&analysis),
+        };
+        assert_eq!(
+            classify_redis_ops_for_inputs(inputs(Some(0))),
+            vec!["PFCOUNT"]
+        );
+        assert_eq!(
+            classify_redis_ops_for_inputs(inputs(None)),
+            vec!["SCAN", "PFCOUNT"]
+        );
+    }
+
+    #[test]
+    fn classify_keyspace_key_eq_skips_scan() {
+        let analysis = analysis_of(vec![cond(0, ComparisonOperator::Equal, "session:1")]);
+        let inputs = |key_column_index| ClassifierInputs {
+            type_name: "keyspace",
+            is_multi_key: true,
+            is_parameterized: false,
+            pushdown_column_index: 1,
+            score_column_index: None,
+            key_column_index,
+            analysis: Some(&analysis),
+        };
+        assert_eq!(classify_redis_ops_for_inputs(inputs(Some(0))), vec!["TYPE"]);
+        assert_eq!(
+            classify_redis_ops_for_inputs(inputs(None)),
+            vec!["SCAN", "TYPE"]
+        );
+    }
 }
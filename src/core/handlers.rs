@@ -1,11 +1,16 @@
 use crate::{
     core::{
         column_utils::{
-            compute_pushdown_column_index, datum_to_text_string, detect_ttl_column,
-            extract_column_names, extract_delete_key, state_from_ptr, transform_insert_data,
+            compute_pushdown_column_index, configure_table_columns, datum_to_text_string,
+            detect_ttl_column, extract_column_names, extract_delete_key, extract_field_names,
+            slot_to_row_values, slot_to_text_values, state_from_ptr, transform_insert_data,
             validate_column_count,
         },
-        explain::{explain_foreign_modify, explain_foreign_scan},
+        direct_modify::{
+            begin_direct_modify, end_direct_modify, iterate_direct_modify, plan_direct_modify,
+        },
+        explain::{explain_direct_modify, explain_foreign_modify, explain_foreign_scan},
+        optimistic,
         schema_import::{analyze_foreign_table, import_foreign_schema},
         state_manager::{extract_static_prefix, validate_key_prefix, RedisFdwState},
         truncate::exec_foreign_truncate,
@@ -17,9 +22,19 @@ use crate::{
             plan_foreign_join,
         },
     },
-    query::{limit::extract_limit_offset_info, pushdown::WhereClausePushdown},
-    tables::types::RedisTableType,
-    utils::{helpers::*, memory::create_wrappers_memctx, row::Row},
+    query::{
+        aggregate::{
+            begin_foreign_count_scan, get_foreign_upper_paths, iterate_foreign_count_scan,
+            plan_foreign_count,
+        },
+        limit::{extract_limit_offset_info, extract_sorted_limit_offset_info},
+        pushdown::WhereClausePushdown,
+    },
+    tables::{
+        implementations::stream::ENTRY_TIME_FIELD,
+        types::{InsertMode, RedisTableType, RowValue},
+    },
+    utils::{helpers::*, memory::create_wrappers_memctx},
 };
 use pgrx::{
     pg_sys::{ExecClearTuple, Index, ModifyTable, PlannerInfo},
@@ -55,6 +70,7 @@ pub extern "C" fn redis_fdw_handler() -> FdwRoutine {
         // explain
         fdw_routine.ExplainForeignScan = Some(explain_foreign_scan);
         fdw_routine.ExplainForeignModify = Some(explain_foreign_modify);
+        fdw_routine.ExplainDirectModify = Some(explain_direct_modify);
 
         // modify
         fdw_routine.AddForeignUpdateTargets = Some(add_foreign_update_targets);
@@ -74,6 +90,12 @@ pub extern "C" fn redis_fdw_handler() -> FdwRoutine {
         fdw_routine.BeginForeignInsert = Some(begin_foreign_insert);
         fdw_routine.EndForeignInsert = Some(end_foreign_insert);
 
+        // direct modify (UPDATE/DELETE without a scan)
+        fdw_routine.PlanDirectModify = Some(plan_direct_modify);
+        fdw_routine.BeginDirectModify = Some(begin_direct_modify);
+        fdw_routine.IterateDirectModify = Some(iterate_direct_modify);
+        fdw_routine.EndDirectModify = Some(end_direct_modify);
+
         // truncate
         fdw_routine.ExecForeignTruncate = Some(exec_foreign_truncate);
 
@@ -86,6 +108,9 @@ pub extern "C" fn redis_fdw_handler() -> FdwRoutine {
         // join pushdown (FDW-to-FDW on same Redis server)
         fdw_routine.GetForeignJoinPaths = Some(get_foreign_join_paths);
 
+        // aggregate pushdown (count(*) over a bitmap)
+        fdw_routine.GetForeignUpperPaths = Some(get_foreign_upper_paths);
+
         fdw_routine
     }
 }
@@ -123,6 +148,19 @@ extern "C-unwind" fn get_foreign_rel_size(
         if let RedisTableType::List(ref mut list) = state.table_type {
             list.include_index = data_cols >= 2;
         }
+        if let RedisTableType::Geo(ref mut geo) = state.table_type {
+            // Path generation looks at the coordinate and distance columns.
+            geo.key_column = state.is_multi_key;
+            let pushdown_idx =
+                compute_pushdown_column_index(state.ttl_column_index, state.is_multi_key);
+            configure_table_columns(
+                &mut state.table_type,
+                &col_names,
+                (*rel).rd_att,
+                pushdown_idx,
+                state.ttl_column_index,
+            );
+        }
         pg_sys::relation_close(rel, pg_sys::AccessShareLock as i32);
 
         if let Err(e) = state.init_redis_connection_from_options() {
@@ -199,12 +237,38 @@ extern "C-unwind" fn get_foreign_paths(
 
         if !state_ptr.is_null() {
             let state = &*state_ptr;
+            let pathkeys = geo_distance_pathkeys(_root, baserel, _foreigntableid, state);
+            if !pathkeys.is_null() {
+                let path = pgrx::pg_sys::create_foreignscan_path(
+                    _root,
+                    baserel,
+                    ptr::null_mut(),
+                    (*baserel).rows,
+                    #[cfg(feature = "pg18")]
+                    0,
+                    startup_cost,
+                    total_cost,
+                    pathkeys,
+                    ptr::null_mut(),
+                    ptr::null_mut(),
+                    #[cfg(any(feature = "pg17", feature = "pg18"))]
+                    ptr::null_mut(),
+                    ptr::null_mut(),
+                );
+                pgrx::pg_sys::add_path(baserel, path as *mut pgrx::pg_sys::Path);
+            }
+
             let supports_param = match &state.table_type {
                 RedisTableType::Hash(_) | RedisTableType::Set(_) | RedisTableType::ZSet(_) => {
                     !state.is_multi_key
                 }
-                RedisTableType::Stream(_) => !state.is_multi_key,
-                RedisTableType::String(_) => state.is_multi_key,
+                RedisTableType::Stream(_) | RedisTableType::Geo(_) | RedisTableType::Bitmap(_) => {
+                    !state.is_multi_key
+                }
+                RedisTableType::String(_)
+                | RedisTableType::HashRow(_)
+                | RedisTableType::Json(_)
+                | RedisTableType::HyperLogLog(_) => state.is_multi_key,
                 _ => false,
             };
             if supports_param {
@@ -214,6 +278,74 @@ extern "C-unwind" fn get_foreign_paths(
     }
 }
 
+/// The query's pathkeys when they are `ORDER BY distance` (ascending, NULLs
+/// last) on a single-key geo table whose restrictions include a geo search:
+/// GEOSEARCH then returns rows nearest first and no sort is needed. Null
+/// otherwise.
+unsafe fn geo_distance_pathkeys(
+    root: *mut PlannerInfo,
+    baserel: *mut pg_sys::RelOptInfo,
+    foreigntableid: pg_sys::Oid,
+    state: &RedisFdwState,
+) -> *mut pg_sys::List {
+    let RedisTableType::Geo(ref geo) = state.table_type else {
+        return ptr::null_mut();
+    };
+    let Some(distance_idx) = geo.distance_column_index else {
+        return ptr::null_mut();
+    };
+    let pathkeys = (*root).query_pathkeys;
+    if state.is_multi_key || pg_sys::list_length(pathkeys) != 1 {
+        return ptr::null_mut();
+    }
+    let distance_attno = (distance_idx + 1) as pg_sys::AttrNumber;
+    if !matches!(
+        pg_sys::get_atttype(foreigntableid, distance_attno),
+        pg_sys::FLOAT4OID | pg_sys::FLOAT8OID | pg_sys::NUMERICOID
+    ) {
+        return ptr::null_mut();
+    }
+
+    let pathkey = pg_sys::list_nth(pathkeys, 0) as *mut pg_sys::PathKey;
+    #[cfg(feature = "pg18")]
+    let ascending = (*pathkey).pk_cmptype == pg_sys::CompareType::COMPARE_LT;
+    #[cfg(not(feature = "pg18"))]
+    let ascending = (*pathkey).pk_strategy == pg_sys::BTLessStrategyNumber as i32;
+    if !ascending || (*pathkey).pk_nulls_first {
+        return ptr::null_mut();
+    }
+    let members = (*(*pathkey).pk_eclass).ec_members;
+    let orders_by_distance = (0..pg_sys::list_length(members)).any(|i| {
+        let member = pg_sys::list_nth(members, i) as *mut pg_sys::EquivalenceMember;
+        let expr = (*member).em_expr as *mut pg_sys::Node;
+        if expr.is_null() || (*expr).type_ != pg_sys::NodeTag::T_Var {
+            return false;
+        }
+        let var = &*(expr as *mut pg_sys::Var);
+        var.varno as Index == (*baserel).relid && var.varattno == distance_attno
+    });
+    if !orders_by_distance {
+        return ptr::null_mut();
+    }
+
+    let relation = pg_sys::relation_open(foreigntableid, pg_sys::AccessShareLock as _);
+    let analysis = WhereClausePushdown::analyze_scan_clauses(
+        (*baserel).baserestrictinfo,
+        &state.table_type,
+        relation as _,
+    );
+    pg_sys::relation_close(relation, pg_sys::AccessShareLock as _);
+    if analysis
+        .pushable_conditions
+        .iter()
+        .any(|c| geo.search_shape(c).is_some())
+    {
+        pathkeys
+    } else {
+        ptr::null_mut()
+    }
+}
+
 #[pg_guard]
 unsafe extern "C-unwind" fn get_foreign_plan(
     root: *mut pgrx::pg_sys::PlannerInfo,
@@ -229,6 +361,9 @@ unsafe extern "C-unwind" fn get_foreign_plan(
     if (*baserel).reloptkind == pg_sys::RelOptKind::RELOPT_JOINREL {
         return plan_foreign_join(root, baserel, best_path, tlist, scan_clauses, outer_plan);
     }
+    if (*baserel).reloptkind == pg_sys::RelOptKind::RELOPT_UPPER_REL {
+        return plan_foreign_count(baserel, tlist, outer_plan);
+    }
 
     let state = state_from_ptr((*baserel).fdw_private);
 
@@ -301,7 +436,16 @@ unsafe extern "C-unwind" fn get_foreign_plan(
             pushdown_analysis
         );
 
-        pushdown_analysis.set_limit_offset(extract_limit_offset_info(root));
+        // A path sorted by the geo distance returns GEOSEARCH's order, so
+        // the LIMIT can become its COUNT when the search is the only qual.
+        let sorted = !(*best_path).path.pathkeys.is_null();
+        let search_only = pg_sys::list_length(scan_clauses) == 1
+            && pushdown_analysis.pushable_conditions.len() == 1;
+        pushdown_analysis.set_limit_offset(if sorted && search_only {
+            extract_sorted_limit_offset_info(root)
+        } else {
+            extract_limit_offset_info(root)
+        });
 
         log!(
             "Extracted LIMIT/OFFSET info: {:?}",
@@ -323,6 +467,10 @@ unsafe extern "C-unwind" fn get_foreign_plan(
         pg_sys::relation_close(relation, pg_sys::AccessShareLock as _);
     });
 
+    if let RedisTableType::Stream(_) = state.table_type {
+        plan_entry_time_bounds(state, foreigntableid, scan_clauses, &mut fdw_exprs);
+    }
+
     let fdw_private = serialize_ptr_to_list((*baserel).fdw_private);
     pgrx::pg_sys::make_foreignscan(
         tlist,
@@ -336,10 +484,54 @@ unsafe extern "C-unwind" fn get_foreign_plan(
     )
 }
 
+/// Plan the quals on the `entry_time` column of a stream scan. The column
+/// is derived from the entry ID, so its conditions leave the pushdown
+/// analysis, which would look for a stream field of that name. The values
+/// they compare with go to `fdw_exprs`, after the join parameter, and
+/// become stream ID bounds in `begin_foreign_scan`.
+unsafe fn plan_entry_time_bounds(
+    state: &mut RedisFdwState,
+    foreigntableid: pg_sys::Oid,
+    scan_clauses: *mut pg_sys::List,
+    fdw_exprs: &mut *mut pg_sys::List,
+) {
+    state.entry_time_bounds.clear();
+    let name = std::ffi::CString::new(ENTRY_TIME_FIELD).unwrap();
+    let attnum = pg_sys::get_attnum(foreigntableid, name.as_ptr());
+    if attnum <= 0 {
+        return;
+    }
+    let column_index = (attnum - 1) as usize;
+    if let Some(analysis) = state.pushdown_analysis.as_mut() {
+        analysis
+            .pushable_conditions
+            .retain(|c| c.column_index != column_index);
+        let limit_offset = analysis.limit_offset.take();
+        analysis.set_limit_offset(limit_offset);
+    }
+
+    let type_oid = pg_sys::get_atttype(foreigntableid, attnum);
+    if !matches!(type_oid, pg_sys::TIMESTAMPTZOID | pg_sys::TIMESTAMPOID) {
+        return;
+    }
+    let relation = pg_sys::relation_open(foreigntableid, pg_sys::AccessShareLock as _);
+    let comparisons = WhereClausePushdown::extract_stable_comparisons(
+        scan_clauses,
+        relation as _,
+        column_index,
+        type_oid,
+    );
+    pg_sys::relation_close(relation, pg_sys::AccessShareLock as _);
+    for (operator, value) in comparisons {
+        *fdw_exprs = pg_sys::lappend(*fdw_exprs, value as *mut std::ffi::c_void);
+        state.entry_time_bounds.push(operator);
+    }
+}
+
 #[pg_guard]
 extern "C-unwind" fn begin_foreign_scan(
     node: *mut pgrx::pg_sys::ForeignScanState,
-    _eflags: ::std::os::raw::c_int,
+    eflags: ::std::os::raw::c_int,
 ) {
     log!("---> begin_foreign_scan");
     unsafe {
@@ -348,7 +540,12 @@ extern "C-unwind" fn begin_foreign_scan(
         let scanrelid = (*plan).scan.scanrelid;
 
         if scanrelid == 0 {
-            begin_foreign_join_scan(node, plan);
+            let state = state_from_ptr(deserialize_ptr_from_list((*plan).fdw_private as _));
+            if state.count_pushdown {
+                begin_foreign_count_scan(node, plan);
+            } else {
+                begin_foreign_join_scan(node, plan);
+            }
             return;
         }
 
@@ -361,7 +558,13 @@ extern "C-unwind" fn begin_foreign_scan(
             log!("Foreign table options: {:?}", options);
             state.update_from_options(options);
 
-            if state.redis_connection.is_none() {
+            // The planner's connection went to the master; a plain SELECT
+            // may read from a replica instead.
+            if state.replica_reads() && is_read_only_statement(node) {
+                if let Err(e) = state.init_read_only_connection_from_options() {
+                    pgrx::error!("Failed to connect to a Redis replica: {}", e);
+                }
+            } else if state.redis_connection.is_none() {
                 if let Err(e) = state.init_redis_connection_from_options() {
                     pgrx::error!("Failed to connect to Redis: {}", e);
                 }
@@ -392,13 +595,14 @@ extern "C-unwind" fn begin_foreign_scan(
         let relation = (*node).ss.ss_currentRelation;
         let tupdesc = (*relation).rd_att;
         state.ttl_column_index = detect_ttl_column(tupdesc);
-        let mut col_names = extract_column_names(tupdesc);
+        let mut col_names = extract_field_names(relid, tupdesc);
         if let Some(ttl_idx) = state.ttl_column_index {
             if ttl_idx < col_names.len() {
                 col_names.remove(ttl_idx);
             }
         }
         state.column_names = col_names;
+        state.init_column_inputs(tupdesc);
 
         validate_column_count(
             &state.table_type,
@@ -409,32 +613,48 @@ extern "C-unwind" fn begin_foreign_scan(
         let pushdown_idx =
             compute_pushdown_column_index(state.ttl_column_index, state.is_multi_key);
 
-        // Compute score column index for ZSet (next active non-dropped, non-TTL column)
-        let score_column_index = if matches!(state.table_type, RedisTableType::ZSet(_)) {
-            let mut score_idx = pushdown_idx + 1;
-            let natts = (*tupdesc).natts as usize;
-            while score_idx < natts {
-                let attr = tuple_desc_attr(tupdesc, score_idx);
-                if (*attr).attisdropped || Some(score_idx) == state.ttl_column_index {
-                    score_idx += 1;
-                    continue;
-                }
-                break;
-            }
-            Some(score_idx)
-        } else {
-            None
-        };
-
-        state
-            .table_type
-            .configure(&state.column_names, pushdown_idx, score_column_index);
+        configure_table_columns(
+            &mut state.table_type,
+            &state.column_names,
+            tupdesc,
+            pushdown_idx,
+            state.ttl_column_index,
+        );
+        state.set_column_encodings(tupdesc);
 
         if state.ttl_column_index.is_some() && !state.is_multi_key {
             let key = state.table_key_prefix.clone();
             state.read_ttl(&key);
         }
 
+        // Optimistic concurrency: WATCH before the first read so EXEC at the
+        // end of the modify aborts if anything changed in between.
+        if state.optimistic
+            && eflags & pg_sys::EXEC_FLAG_EXPLAIN_ONLY as i32 == 0
+            && is_modify_target(node, scanrelid)
+        {
+            if let Err(e) = state.watch_scan_keys(relid) {
+                error!("redis_fdw: {}", e);
+            }
+        }
+
+        if !state.entry_time_bounds.is_empty() {
+            let econtext = (*node).ss.ps.ps_ExprContext;
+            let plan_state = &mut (*node).ss.ps as *mut pg_sys::PlanState;
+            let first = usize::from(state.is_parameterized);
+            let values: Vec<_> = (first..)
+                .zip(&state.entry_time_bounds)
+                .map(|(i, operator)| {
+                    let expr = pg_sys::list_nth((*plan).fdw_exprs, i as i32) as *mut pg_sys::Expr;
+                    let expr_state = pg_sys::ExecInitExpr(expr, plan_state);
+                    let mut is_null = false;
+                    let datum = pg_sys::ExecEvalExpr(expr_state, econtext, &mut is_null);
+                    (operator.clone(), (!is_null).then(|| datum.value() as i64))
+                })
+                .collect();
+            state.set_entry_time_bounds(&values);
+        }
+
         if state.is_parameterized && !(*plan).fdw_exprs.is_null() {
             let expr_list = (*plan).fdw_exprs;
             if pg_sys::list_length(expr_list) > 0 {
@@ -451,6 +671,33 @@ extern "C-unwind" fn begin_foreign_scan(
     }
 }
 
+/// Whether the scanned relation is the result relation of the running
+/// UPDATE/DELETE.
+unsafe fn is_modify_target(node: *mut pg_sys::ForeignScanState, scanrelid: Index) -> bool {
+    let estate = (*node).ss.ps.state;
+    if estate.is_null() || (*estate).es_plannedstmt.is_null() {
+        return false;
+    }
+    let stmt = (*estate).es_plannedstmt;
+    matches!(
+        (*stmt).commandType,
+        pg_sys::CmdType::CMD_UPDATE | pg_sys::CmdType::CMD_DELETE
+    ) && pg_sys::list_member_int((*stmt).resultRelations, scanrelid as i32)
+}
+
+/// Whether the running statement is a SELECT that writes nothing, not even
+/// through a data-modifying CTE.
+unsafe fn is_read_only_statement(node: *mut pg_sys::ForeignScanState) -> bool {
+    let estate = (*node).ss.ps.state;
+    if estate.is_null() || (*estate).es_plannedstmt.is_null() {
+        return false;
+    }
+    let stmt = (*estate).es_plannedstmt;
+    (*stmt).commandType == pg_sys::CmdType::CMD_SELECT
+        && !(*stmt).hasModifyingCTE
+        && (*stmt).rowMarks.is_null()
+}
+
 #[pg_guard]
 unsafe extern "C-unwind" fn iterate_foreign_scan(
     node: *mut pgrx::pg_sys::ForeignScanState,
@@ -463,6 +710,10 @@ unsafe extern "C-unwind" fn iterate_foreign_scan(
 
     ExecClearTuple(slot);
 
+    if state.count_pushdown {
+        return iterate_foreign_count_scan(state, slot);
+    }
+
     // Join pushdown mode
     if state.is_join_scan {
         if let Some(ref mut join_state) = state.join_state {
@@ -493,7 +744,7 @@ unsafe extern "C-unwind" fn iterate_foreign_scan(
                 let limit = std::cmp::min(natts, outer_cols);
                 for col_idx in 0..limit {
                     if let Some(v) = outer_row.get(col_idx) {
-                        write_datum_to_slot(slot, tupdesc, col_idx, v);
+                        write_datum_to_slot(slot, tupdesc, col_idx, Some(v));
                     } else {
                         (*slot).tts_isnull.add(col_idx).write(true);
                     }
@@ -503,7 +754,7 @@ unsafe extern "C-unwind" fn iterate_foreign_scan(
                     let inner_col = col_idx - outer_cols;
                     if let Some(inner) = inner_row {
                         if let Some(v) = inner.get(inner_col) {
-                            write_datum_to_slot(slot, tupdesc, col_idx, v);
+                            write_datum_to_slot(slot, tupdesc, col_idx, Some(v));
                             continue;
                         }
                     }
@@ -549,9 +800,9 @@ unsafe extern "C-unwind" fn iterate_foreign_scan(
                 for col_idx in 0..natts_param {
                     if state.ttl_column_index == Some(col_idx) {
                         let val = state.cached_ttl.unwrap_or(-2);
-                        write_datum_to_slot(slot, tupdesc, col_idx, &val.to_string());
+                        state.store_value(slot, col_idx, Some(val.to_string().as_bytes()));
                     } else if data_idx < row_data.len() {
-                        write_datum_to_slot(slot, tupdesc, col_idx, row_data[data_idx].as_ref());
+                        state.store_value(slot, col_idx, row_data[data_idx].as_deref());
                         data_idx += 1;
                     }
                 }
@@ -587,9 +838,10 @@ unsafe extern "C-unwind" fn iterate_foreign_scan(
         if let Some(flat_data) = dataset.as_filtered() {
             if row_offset + cols_per_row <= flat_data.len() {
                 let ttl_value = if state.ttl_column_index.is_some() {
-                    state
-                        .multi_key_ttl_cache
-                        .get(&flat_data[row_offset])
+                    flat_data[row_offset]
+                        .as_deref()
+                        .and_then(|key| std::str::from_utf8(key).ok())
+                        .and_then(|key| state.multi_key_ttl_cache.get(key))
                         .copied()
                 } else {
                     None
@@ -599,13 +851,12 @@ unsafe extern "C-unwind" fn iterate_foreign_scan(
                 for col_idx in 0..natts {
                     if state.ttl_column_index == Some(col_idx) {
                         let ttl_str = ttl_value.unwrap_or(-2).to_string();
-                        write_datum_to_slot(slot, tupdesc, col_idx, &ttl_str);
+                        state.store_value(slot, col_idx, Some(ttl_str.as_bytes()));
                     } else if data_idx < cols_per_row {
-                        write_datum_to_slot(
+                        state.store_value(
                             slot,
-                            tupdesc,
                             col_idx,
-                            &flat_data[row_offset + data_idx],
+                            flat_data[row_offset + data_idx].as_deref(),
                         );
                         data_idx += 1;
                     }
@@ -627,9 +878,9 @@ unsafe extern "C-unwind" fn iterate_foreign_scan(
             for col_idx in 0..natts {
                 if state.ttl_column_index == Some(col_idx) {
                     let val = ttl_value.unwrap_or(-2);
-                    write_datum_to_slot(slot, tupdesc, col_idx, &val.to_string());
+                    state.store_value(slot, col_idx, Some(val.to_string().as_bytes()));
                 } else if data_idx < row_data.len() {
-                    write_datum_to_slot(slot, tupdesc, col_idx, row_data[data_idx].as_ref());
+                    state.store_value(slot, col_idx, row_data[data_idx].as_deref());
                     data_idx += 1;
                 }
             }
@@ -802,11 +1053,15 @@ unsafe extern "C-unwind" fn add_foreign_update_targets(
 #[pg_guard]
 unsafe extern "C-unwind" fn plan_foreign_modify(
     root: *mut PlannerInfo,
-    _plan: *mut ModifyTable,
+    plan: *mut ModifyTable,
     result_relation: Index,
     _subplan_index: ::core::ffi::c_int,
 ) -> *mut pgrx::pg_sys::List {
     log!("---> plan_foreign_modify");
+    let on_conflict = (*plan).onConflictAction;
+    if on_conflict == pg_sys::OnConflictAction::ONCONFLICT_UPDATE {
+        error!("redis_fdw: ON CONFLICT DO UPDATE is not supported; use ON CONFLICT DO NOTHING or insert_mode 'xx'");
+    }
     let rte = pg_sys::planner_rt_fetch(result_relation, root);
     let rel = PgRelation::with_lock((*rte).relid, pg_sys::NoLock as _);
     let ftable_id = rel.oid();
@@ -817,6 +1072,18 @@ unsafe extern "C-unwind" fn plan_foreign_modify(
         let opts = get_foreign_table_options(ftable_id);
         log!("Foreign table options for modify: {:?}", opts);
         state.update_from_options(opts);
+        if on_conflict == pg_sys::OnConflictAction::ONCONFLICT_NOTHING {
+            if state.transaction_config.is_some() {
+                error!("redis_fdw: ON CONFLICT DO NOTHING cannot be combined with transactional 'true'");
+            }
+            state.insert_mode = InsertMode::Nx;
+        }
+        reject_deferred_returning(
+            &state,
+            !(*plan).returningLists.is_null() && (*plan).operation != pg_sys::CmdType::CMD_DELETE,
+        );
+        state.returning_needed = !(*plan).returningLists.is_null()
+            || has_after_row_trigger(rel.trigdesc, (*plan).operation);
 
         if let Err(e) = state.init_redis_connection_from_options() {
             pgrx::error!("Failed to connect to Redis: {}", e);
@@ -828,6 +1095,56 @@ unsafe extern "C-unwind" fn plan_foreign_modify(
     serialize_ptr_to_list(state_ptr as *mut std::os::raw::c_void)
 }
 
+/// Transactional writes only reach Redis at COMMIT, so RETURNING on an
+/// INSERT or UPDATE could only echo the input row, not what Redis stored.
+/// DELETE reads the old row first and is fine.
+fn reject_deferred_returning(state: &RedisFdwState, returning: bool) {
+    if returning && state.transaction_config.is_some() {
+        error!("redis_fdw: RETURNING is not supported with transactional 'true'");
+    }
+}
+
+/// Whether `trigdesc` has an AFTER ROW trigger for `operation`; those
+/// triggers see the slot returned by the ExecForeign* callbacks.
+unsafe fn has_after_row_trigger(
+    trigdesc: *mut pg_sys::TriggerDesc,
+    operation: pg_sys::CmdType::Type,
+) -> bool {
+    if trigdesc.is_null() {
+        return false;
+    }
+    match operation {
+        pg_sys::CmdType::CMD_INSERT => (*trigdesc).trig_insert_after_row,
+        pg_sys::CmdType::CMD_UPDATE => (*trigdesc).trig_update_after_row,
+        pg_sys::CmdType::CMD_DELETE => (*trigdesc).trig_delete_after_row,
+        _ => false,
+    }
+}
+
+/// Rebuild `slot` from `row` (one value per non-dropped column) so RETURNING
+/// and AFTER ROW triggers see what Redis stored.
+unsafe fn store_returned_row(
+    state: &RedisFdwState,
+    slot: *mut pg_sys::TupleTableSlot,
+    row: &[RowValue],
+) {
+    let tupdesc = (*slot).tts_tupleDescriptor;
+    let natts = (*tupdesc).natts as usize;
+    ExecClearTuple(slot);
+    let mut values = row.iter();
+    for col_idx in 0..natts {
+        if (*tuple_desc_attr(tupdesc, col_idx)).attisdropped {
+            (*slot).tts_isnull.add(col_idx).write(true);
+            continue;
+        }
+        match values.next() {
+            Some(v) => state.store_value(slot, col_idx, v.as_deref()),
+            None => (*slot).tts_isnull.add(col_idx).write(true),
+        }
+    }
+    pg_sys::ExecStoreVirtualTuple(slot);
+}
+
 #[pg_guard]
 unsafe extern "C-unwind" fn begin_foreign_modify(
     mtstate: *mut pgrx::pg_sys::ModifyTableState,
@@ -847,13 +1164,14 @@ unsafe extern "C-unwind" fn begin_foreign_modify(
     let relation = (*rinfo).ri_RelationDesc;
     let tupdesc = (*relation).rd_att;
     state.ttl_column_index = detect_ttl_column(tupdesc);
-    let mut col_names = extract_column_names(tupdesc);
+    let mut col_names = extract_field_names((*relation).rd_id, tupdesc);
     if let Some(ttl_idx) = state.ttl_column_index {
         if ttl_idx < col_names.len() {
             col_names.remove(ttl_idx);
         }
     }
     state.column_names = col_names;
+    state.init_column_inputs(tupdesc);
 
     validate_column_count(
         &state.table_type,
@@ -862,24 +1180,18 @@ unsafe extern "C-unwind" fn begin_foreign_modify(
     );
 
     let pushdown_idx = compute_pushdown_column_index(state.ttl_column_index, state.is_multi_key);
-    let score_column_index = if matches!(state.table_type, RedisTableType::ZSet(_)) {
-        let mut score_idx = pushdown_idx + 1;
-        let natts = (*tupdesc).natts as usize;
-        while score_idx < natts {
-            let attr = tuple_desc_attr(tupdesc, score_idx);
-            if (*attr).attisdropped || Some(score_idx) == state.ttl_column_index {
-                score_idx += 1;
-                continue;
-            }
-            break;
-        }
-        Some(score_idx)
-    } else {
-        None
-    };
-    state
-        .table_type
-        .configure(&state.column_names, pushdown_idx, score_column_index);
+    configure_table_columns(
+        &mut state.table_type,
+        &state.column_names,
+        tupdesc,
+        pushdown_idx,
+        state.ttl_column_index,
+    );
+    state.set_column_encodings(tupdesc);
+
+    if state.optimistic && optimistic::claim_watched((*relation).rd_id) {
+        state.watched_relid = Some((*relation).rd_id);
+    }
 
     (*rinfo).ri_FdwState = state_ptr;
 }
@@ -898,13 +1210,9 @@ unsafe extern "C-unwind" fn exec_foreign_insert(
 ) -> *mut pgrx::pg_sys::TupleTableSlot {
     log!("---> exec_foreign_insert");
     let state = state_from_ptr((*rinfo).ri_FdwState);
-    let row: Row = tuple_table_slot_to_row(slot);
+    let all_data = slot_to_text_values(slot);
 
-    let all_data: Vec<String> = row
-        .cells
-        .iter()
-        .map(|cell| cell_to_string(cell.as_ref()))
-        .collect();
+    let mut returned = state.returning_needed.then(|| slot_to_row_values(slot));
 
     let (data, row_ttl) = if let Some(ttl_idx) = state.ttl_column_index {
         let ttl_val = all_data.get(ttl_idx).and_then(|s| {
@@ -943,19 +1251,34 @@ unsafe extern "C-unwind" fn exec_foreign_insert(
                 row_data.len()
             );
         }
-        if let Err(e) = state.insert_data_to_key(&key, row_data) {
-            error!("Failed to insert data to key '{}': {:?}", key, e);
+        match state.insert_data_to_key(&key, row_data) {
+            Ok(true) => state.apply_ttl(&key, row_ttl),
+            Ok(false) => return std::ptr::null_mut(),
+            Err(e) => error!("Failed to insert data to key '{}': {:?}", key, e),
+        }
+        if let Some(row) = returned.as_mut() {
+            state.refresh_returned_row(&key, row);
         }
-        state.apply_ttl(&key, row_ttl);
     } else {
         let data = transform_insert_data(&state.table_type, &state.column_names, data);
-        if let Err(e) = state.insert_data(&data) {
-            error!("Failed to insert data: {:?}", e);
+        match state.insert_data(&data) {
+            Ok(true) => {
+                let key = state.table_key_prefix.clone();
+                state.apply_ttl(&key, row_ttl);
+                if let Some(row) = returned.as_mut() {
+                    state.refresh_returned_row(&key, row);
+                }
+            }
+            // Skipped by insert_mode / ON CONFLICT DO NOTHING: a NULL slot
+            // keeps the row out of the command's row count and RETURNING.
+            Ok(false) => return std::ptr::null_mut(),
+            Err(e) => error!("Failed to insert data: {:?}", e),
         }
-        let key = state.table_key_prefix.clone();
-        state.apply_ttl(&key, row_ttl);
     }
 
+    if let Some(row) = returned {
+        store_returned_row(state, slot, &row);
+    }
     (*slot).tts_tableOid = pgrx::pg_sys::InvalidOid;
     slot
 }
@@ -977,12 +1300,9 @@ unsafe extern "C-unwind" fn exec_foreign_update(
         }
     };
 
-    let new_row: Row = tuple_table_slot_to_row(slot);
-    let all_new_data: Vec<String> = new_row
-        .cells
-        .iter()
-        .map(|cell| cell_to_string(cell.as_ref()))
-        .collect();
+    let all_new_data = slot_to_text_values(slot);
+
+    let mut returned = state.returning_needed.then(|| slot_to_row_values(slot));
 
     let (new_data, row_ttl) = if let Some(ttl_idx) = state.ttl_column_index {
         let ttl_val = all_new_data.get(ttl_idx).and_then(|s| {
@@ -1027,14 +1347,23 @@ unsafe extern "C-unwind" fn exec_foreign_update(
             error!("Failed to update data for key '{}': {:?}", key, e);
         }
         state.apply_ttl(&key, row_ttl);
+        if let Some(row) = returned.as_mut() {
+            state.refresh_returned_row(&key, row);
+        }
     } else {
         if let Err(e) = state.update_data(std::slice::from_ref(&old_key), &new_data) {
             error!("Failed to update data: {:?}", e);
         }
         let key = state.table_key_prefix.clone();
         state.apply_ttl(&key, row_ttl);
+        if let Some(row) = returned.as_mut() {
+            state.refresh_returned_row(&key, row);
+        }
     }
 
+    if let Some(row) = returned {
+        store_returned_row(state, slot, &row);
+    }
     (*slot).tts_tableOid = pgrx::pg_sys::InvalidOid;
     slot
 }
@@ -1054,6 +1383,18 @@ unsafe extern "C-unwind" fn exec_foreign_delete(
         Ok(key) => {
             log!("Attempting to delete key: '{}'", key);
 
+            // Read the old row before it is gone.
+            if state.returning_needed {
+                match state.fetch_deleted_row(&key) {
+                    Ok(Some(row)) => store_returned_row(state, slot, &row),
+                    Ok(None) => {}
+                    Err(e) => error!(
+                        "Failed to read row for key '{}' before delete: {:?}",
+                        key, e
+                    ),
+                }
+            }
+
             if state.is_multi_key {
                 if let Err(e) = state.delete_key(&key) {
                     error!("Failed to delete Redis key '{}': {:?}", key, e);
@@ -1085,11 +1426,27 @@ unsafe extern "C-unwind" fn end_foreign_modify(
             return;
         }
 
-        let state = &*fdw_state;
+        let state = &mut *fdw_state;
+        let outcome = state.commit_optimistic();
         let ctx = state.tmp_ctx;
         if !ctx.is_null() {
             delete_wrappers_memctx(ctx);
         }
+
+        match outcome {
+            Ok(true) => {}
+            Ok(false) => {
+                ereport!(
+                    ERROR,
+                    PgSqlErrorCode::ERRCODE_T_R_SERIALIZATION_FAILURE,
+                    "redis_fdw: could not serialize access due to concurrent update",
+                    "A watched Redis key was modified after the scan started; no changes were applied."
+                );
+            }
+            Err(e) => {
+                error!("redis_fdw: failed to apply optimistic writes: {}", e);
+            }
+        }
     }
 }
 
@@ -1108,6 +1465,9 @@ unsafe extern "C-unwind" fn begin_foreign_insert(
         let opts = get_foreign_table_options(ftable_id);
         log!("Foreign table options for insert: {:?}", opts);
         state.update_from_options(opts);
+        reject_deferred_returning(&state, !(*rinfo).ri_returningList.is_null());
+        state.returning_needed = !(*rinfo).ri_returningList.is_null()
+            || has_after_row_trigger((*rinfo).ri_TrigDesc, pg_sys::CmdType::CMD_INSERT);
 
         if let Err(e) = state.init_redis_connection_from_options() {
             pgrx::error!("Failed to connect to Redis: {}", e);
@@ -1118,7 +1478,7 @@ unsafe extern "C-unwind" fn begin_foreign_insert(
 
     let tupdesc = (*relation).rd_att;
     state.ttl_column_index = detect_ttl_column(tupdesc);
-    let mut col_names = extract_column_names(tupdesc);
+    let mut col_names = extract_field_names(ftable_id, tupdesc);
     if let Some(ttl_idx) = state.ttl_column_index {
         if ttl_idx < col_names.len() {
             col_names.remove(ttl_idx);
@@ -1127,24 +1487,14 @@ unsafe extern "C-unwind" fn begin_foreign_insert(
     state.column_names = col_names;
 
     let pushdown_idx = compute_pushdown_column_index(state.ttl_column_index, state.is_multi_key);
-    let score_column_index = if matches!(state.table_type, RedisTableType::ZSet(_)) {
-        let mut score_idx = pushdown_idx + 1;
-        let natts = (*tupdesc).natts as usize;
-        while score_idx < natts {
-            let attr = tuple_desc_attr(tupdesc, score_idx);
-            if (*attr).attisdropped || Some(score_idx) == state.ttl_column_index {
-                score_idx += 1;
-                continue;
-            }
-            break;
-        }
-        Some(score_idx)
-    } else {
-        None
-    };
-    state
-        .table_type
-        .configure(&state.column_names, pushdown_idx, score_column_index);
+    configure_table_columns(
+        &mut state.table_type,
+        &state.column_names,
+        tupdesc,
+        pushdown_idx,
+        state.ttl_column_index,
+    );
+    state.set_column_encodings(tupdesc);
 
     let state_ptr = PgMemoryContexts::For(ctx).leak_and_drop_on_delete(state);
     (*rinfo).ri_FdwState = state_ptr as *mut std::os::raw::c_void;
@@ -1195,12 +1545,21 @@ unsafe extern "C-unwind" fn get_foreign_modify_batch_size(
         return 1;
     }
     let state = &*fdw_state;
+    // RETURNING and row triggers work on the slot of each ExecForeignInsert
+    // call, so rows that need them are not batched.
+    let trigdesc = (*rinfo).ri_TrigDesc;
+    if state.returning_needed
+        || (!trigdesc.is_null()
+            && ((*trigdesc).trig_insert_before_row || (*trigdesc).trig_insert_after_row))
+    {
+        return 1;
+    }
     state.batch_size as ::core::ffi::c_int
 }
 
 #[pg_guard]
 unsafe extern "C-unwind" fn exec_foreign_batch_insert(
-    _estate: *mut pg_sys::EState,
+    estate: *mut pg_sys::EState,
     rinfo: *mut pg_sys::ResultRelInfo,
     slots: *mut *mut pg_sys::TupleTableSlot,
     _plan_slots: *mut *mut pg_sys::TupleTableSlot,
@@ -1213,12 +1572,7 @@ unsafe extern "C-unwind" fn exec_foreign_batch_insert(
     let mut rows: Vec<(Vec<String>, Option<i64>)> = Vec::with_capacity(count);
     for i in 0..count {
         let slot = *slots.add(i);
-        let row: Row = tuple_table_slot_to_row(slot);
-        let all_data: Vec<String> = row
-            .cells
-            .iter()
-            .map(|cell| cell_to_string(cell.as_ref()))
-            .collect();
+        let all_data = slot_to_text_values(slot);
 
         let (data, row_ttl) = if let Some(ttl_idx) = state.ttl_column_index {
             let ttl_val = all_data.get(ttl_idx).and_then(|s| {
@@ -1240,14 +1594,28 @@ unsafe extern "C-unwind" fn exec_foreign_batch_insert(
         rows.push((data, row_ttl));
     }
 
-    if let Err(e) = state.batch_insert_data(&rows) {
-        error!("{}", e);
-    }
+    let applied = match state.batch_insert_data(&rows) {
+        Ok(applied) => applied,
+        Err(e) => error!("{}", e),
+    };
 
-    for i in 0..count {
-        let slot = *slots.add(i);
-        (*slot).tts_tableOid = pg_sys::InvalidOid;
+    // Return only the rows that were written; *num_slots is what the
+    // executor adds to the command's row count. They go in a new array: the
+    // executor reuses `slots` (its ri_Slots) by position for later batches.
+    let written = pg_sys::MemoryContextAlloc(
+        (*estate).es_query_cxt,
+        count.max(1) * std::mem::size_of::<*mut pg_sys::TupleTableSlot>(),
+    ) as *mut *mut pg_sys::TupleTableSlot;
+    let mut num_written = 0;
+    for (i, row_applied) in applied.iter().enumerate().take(count) {
+        if *row_applied {
+            let slot = *slots.add(i);
+            (*slot).tts_tableOid = pg_sys::InvalidOid;
+            *written.add(num_written) = slot;
+            num_written += 1;
+        }
     }
+    *num_slots = num_written as ::core::ffi::c_int;
 
-    slots
+    written
 }
@@ -1,10 +1,13 @@
 pub mod column_utils;
 pub mod connection_factory;
 pub mod ddl_hook;
+pub mod direct_modify;
 pub mod explain;
 pub mod handlers;
+pub mod optimistic;
 pub mod pool_manager;
 pub mod schema_import;
 pub mod state_manager;
+pub mod transaction;
 pub mod truncate;
 pub mod validator;
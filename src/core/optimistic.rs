@@ -0,0 +1,163 @@
+//! Optimistic concurrency for UPDATE/DELETE (`concurrency 'optimistic'`).
+//!
+//! `begin_foreign_scan` of the target relation WATCHes the keys it is about
+//! to read on a dedicated connection and parks that connection here;
+//! `begin_foreign_modify` claims it, the row writes are buffered, and
+//! `end_foreign_modify` sends them in MULTI/EXEC. A nil EXEC reply means a
+//! watched key changed after the scan started and is reported as a
+//! serialization failure (SQLSTATE 40001) so client retry loops kick in.
+
+use crate::core::{
+    connection_factory::{RedisConnectionConfig, RedisConnectionFactory},
+    pool_manager::PooledConnection,
+};
+use pgrx::pg_sys;
+use redis::Value;
+use std::sync::Mutex;
+
+/// A pooled connection with an active WATCH. Dropping it sends UNWATCH so
+/// the connection goes back to the pool clean.
+///
+/// Connections stay in the registry below until their MULTI/EXEC is sent,
+/// even once claimed: the FDW state lives in a memory context, and deleting
+/// that on ERROR never runs Rust destructors. The transaction callback
+/// drops whatever is left through [`release_all`].
+pub struct WatchedConnection {
+    conn: PooledConnection,
+}
+
+impl WatchedConnection {
+    pub fn as_connection_like_mut(&mut self) -> &mut dyn redis::ConnectionLike {
+        self.conn.as_connection_like_mut()
+    }
+
+    /// Send `commands` as MULTI/EXEC. Returns `Ok(false)` when EXEC was
+    /// aborted because a watched key was modified.
+    pub fn exec(mut self, commands: Vec<redis::Cmd>) -> Result<bool, redis::RedisError> {
+        if commands.is_empty() {
+            return Ok(true);
+        }
+        let mut pipe = redis::pipe();
+        pipe.atomic();
+        for cmd in commands {
+            pipe.add_command(cmd);
+        }
+        let reply: Value = pipe.query(self.conn.as_connection_like_mut())?;
+        let replies = match reply {
+            Value::Nil => return Ok(false),
+            Value::Array(replies) => replies,
+            _ => return Ok(true),
+        };
+        // EXEC applies the other commands when one fails at runtime (e.g.
+        // WRONGTYPE); report the first failure like a transactional flush.
+        match replies.into_iter().find_map(|v| match v {
+            Value::ServerError(e) => Some(e),
+            _ => None,
+        }) {
+            Some(err) => Err(err.into()),
+            None => Ok(true),
+        }
+    }
+}
+
+impl Drop for WatchedConnection {
+    fn drop(&mut self) {
+        let _ = redis::cmd("UNWATCH").query::<()>(self.conn.as_connection_like_mut());
+    }
+}
+
+struct Watched {
+    relid: pg_sys::Oid,
+    /// Taken over by the modify of `relid`, waiting for its MULTI/EXEC
+    claimed: bool,
+    conn: WatchedConnection,
+}
+
+/// Connections WATCHed by a scan, claimed or not, for the current transaction.
+static WATCHED: Mutex<Vec<Watched>> = Mutex::new(Vec::new());
+
+fn with_watched<T>(f: impl FnOnce(&mut Vec<Watched>) -> T) -> T {
+    let mut guard = WATCHED
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    f(&mut guard)
+}
+
+/// WATCH `keys` on a fresh connection and park it for `relid`.
+pub fn watch_keys(
+    relid: pg_sys::Oid,
+    config: &RedisConnectionConfig,
+    keys: &[String],
+) -> Result<(), String> {
+    let conn = RedisConnectionFactory::create_connection_with_retry(config)
+        .map_err(|e| format!("cannot open WATCH connection: {}", e))?;
+    if conn.is_cluster() {
+        return Err("optimistic concurrency is not supported with cluster_mode".to_string());
+    }
+    let mut watched = WatchedConnection { conn };
+    redis::cmd("WATCH")
+        .arg(keys)
+        .query::<()>(watched.as_connection_like_mut())
+        .map_err(|e| format!("WATCH failed: {}", e))?;
+
+    with_watched(|all| {
+        all.retain(|w| w.claimed || w.relid != relid);
+        all.push(Watched {
+            relid,
+            claimed: false,
+            conn: watched,
+        });
+    });
+    Ok(())
+}
+
+/// Claim the connection WATCHed by the scan of `relid` for its modify.
+/// Returns false when the scan watched nothing.
+pub fn claim_watched(relid: pg_sys::Oid) -> bool {
+    with_watched(
+        |all| match all.iter_mut().find(|w| !w.claimed && w.relid == relid) {
+            Some(w) => {
+                w.claimed = true;
+                true
+            }
+            None => false,
+        },
+    )
+}
+
+/// Run `f` on the connection claimed for `relid`, if it is still registered.
+pub fn with_claimed<T>(
+    relid: pg_sys::Oid,
+    f: impl FnOnce(&mut WatchedConnection) -> T,
+) -> Option<T> {
+    with_watched(|all| {
+        all.iter_mut()
+            .find(|w| w.claimed && w.relid == relid)
+            .map(|w| f(&mut w.conn))
+    })
+}
+
+/// Send `commands` as MULTI/EXEC on the connection claimed for `relid` and
+/// hand it back to the pool. See [`WatchedConnection::exec`].
+pub fn exec_claimed(
+    relid: pg_sys::Oid,
+    commands: Vec<redis::Cmd>,
+) -> Result<bool, redis::RedisError> {
+    let watched = with_watched(|all| {
+        let pos = all.iter().position(|w| w.claimed && w.relid == relid)?;
+        Some(all.swap_remove(pos).conn)
+    });
+    match watched {
+        Some(watched) => watched.exec(commands),
+        None => Err(redis::RedisError::from((
+            redis::ErrorKind::Io,
+            "WATCH connection was released before EXEC",
+        ))),
+    }
+}
+
+/// Drop every registered WATCH, claimed or not (transaction end).
+pub fn release_all() {
+    let released = with_watched(std::mem::take);
+    drop(released);
+}
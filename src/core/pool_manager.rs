@@ -5,7 +5,11 @@
 /// of creating new pools for every query, significantly improving performance under
 /// concurrent workloads.
 use crate::auth::RedisAuthConfig;
-use redis::{cluster::ClusterClient, Client};
+use redis::{
+    cluster::ClusterClient,
+    sentinel::{LockedSentinelClient, SentinelClientBuilder, SentinelServerType},
+    Client, ConnectionAddr, RedisError, RedisResult, Value,
+};
 use std::collections::HashMap;
 use std::sync::{OnceLock, RwLock};
 use std::time::Duration;
@@ -122,6 +126,159 @@ pub enum PoolError {
     LockPoisoned,
 }
 
+/// Sentinel-managed deployment described by the `sentinel_*` server options
+#[derive(Debug, Clone, PartialEq)]
+pub struct SentinelConfig {
+    /// Comma-separated `host:port` list of Sentinel nodes
+    pub nodes: String,
+    /// Name the Sentinels monitor the master under
+    pub master_name: String,
+    /// ACL user for the Sentinel nodes themselves
+    pub username: Option<String>,
+    /// Password for the Sentinel nodes themselves
+    pub password: Option<String>,
+    /// Route read-only scans to the replicas Sentinel reports
+    pub replica_reads: bool,
+}
+
+impl SentinelConfig {
+    /// Sentinel settings of a server, `None` when it has no `sentinel_nodes`.
+    pub fn from_options(opts: &HashMap<String, String>) -> Result<Option<Self>, String> {
+        let (nodes, master_name) =
+            match (opts.get("sentinel_nodes"), opts.get("sentinel_master_name")) {
+                (Some(nodes), Some(master_name)) => (nodes, master_name),
+                (None, None) => {
+                    if let Some(option) = [
+                        "sentinel_username",
+                        "sentinel_password",
+                        "sentinel_replica_reads",
+                    ]
+                    .into_iter()
+                    .find(|o| opts.contains_key(*o))
+                    {
+                        return Err(format!(
+                            "{} needs sentinel_nodes and sentinel_master_name",
+                            option
+                        ));
+                    }
+                    return Ok(None);
+                }
+                _ => {
+                    return Err(
+                        "sentinel_nodes and sentinel_master_name must be set together".to_string(),
+                    )
+                }
+            };
+        if master_name.trim().is_empty() {
+            return Err("sentinel_master_name must not be empty".to_string());
+        }
+        sentinel_addrs(nodes)?;
+        let replica_reads = match opts.get("sentinel_replica_reads").map(String::as_str) {
+            None | Some("false") => false,
+            Some("true") => true,
+            Some(other) => {
+                return Err(format!(
+                    "sentinel_replica_reads must be \"true\" or \"false\", got '{}'",
+                    other
+                ))
+            }
+        };
+
+        Ok(Some(Self {
+            nodes: nodes.clone(),
+            master_name: master_name.trim().to_string(),
+            username: opts.get("sentinel_username").cloned(),
+            password: opts.get("sentinel_password").cloned(),
+            replica_reads,
+        }))
+    }
+
+    /// How EXPLAIN and error messages name the server
+    pub fn server_label(&self) -> String {
+        format!("{} via sentinel {}", self.master_name, self.nodes)
+    }
+
+    /// Generate a cache key for pool identification
+    fn cache_key(&self, replica: bool, database: i64, auth_config: &RedisAuthConfig) -> String {
+        format!(
+            "{}:{}:{}@{}:{}:{}:{}",
+            RedisConnectionType::Sentinel.cache_key_prefix(),
+            if replica { "replica" } else { "master" },
+            self.master_name,
+            self.nodes,
+            database,
+            auth_config.cache_key(),
+            self.username.as_deref().unwrap_or("")
+        )
+    }
+
+    /// Create a pool whose connections go to the node Sentinel reports when
+    /// each one is opened: the master, or a random replica.
+    fn create_pool(
+        &self,
+        replica: bool,
+        database: i64,
+        auth_config: &RedisAuthConfig,
+        pool_config: &PoolConfig,
+    ) -> Result<r2d2::Pool<LockedSentinelClient>, PoolError> {
+        let server_type = if replica {
+            SentinelServerType::Replica
+        } else {
+            SentinelServerType::Master
+        };
+        let addrs = sentinel_addrs(&self.nodes).map_err(PoolError::InvalidConfig)?;
+        let mut builder = SentinelClientBuilder::new(addrs, &self.master_name, server_type)
+            .map_err(|e| PoolError::ClientCreation(e.to_string()))?
+            .set_client_to_redis_db(database);
+        if let Some(username) = &auth_config.username {
+            builder = builder.set_client_to_redis_username(username);
+        }
+        if let Some(password) = &auth_config.password {
+            builder = builder.set_client_to_redis_password(password);
+        }
+        if let Some(username) = &self.username {
+            builder = builder.set_client_to_sentinel_username(username);
+        }
+        if let Some(password) = &self.password {
+            builder = builder.set_client_to_sentinel_password(password);
+        }
+        let client = builder
+            .build()
+            .map_err(|e| PoolError::ClientCreation(e.to_string()))?;
+
+        pool_config
+            .apply_to_builder(r2d2::Pool::builder())
+            .build(LockedSentinelClient::new(client))
+            .map_err(|e| PoolError::PoolCreation(e.to_string()))
+    }
+}
+
+/// Addresses of the comma-separated Sentinel nodes; the port defaults to 26379.
+fn sentinel_addrs(nodes: &str) -> Result<Vec<ConnectionAddr>, String> {
+    nodes
+        .split(',')
+        .map(|node| {
+            let node = node.trim();
+            if node.is_empty() {
+                return Err("sentinel_nodes has an empty entry".to_string());
+            }
+            let (host, port) = match node.rsplit_once(':') {
+                Some((host, port)) => (
+                    host,
+                    port.parse::<u16>().map_err(|_| {
+                        format!("sentinel_nodes entry '{}' has an invalid port", node)
+                    })?,
+                ),
+                None => (node, 26379),
+            };
+            if host.is_empty() {
+                return Err(format!("sentinel_nodes entry '{}' has no host", node));
+            }
+            Ok(ConnectionAddr::Tcp(host.to_string(), port))
+        })
+        .collect()
+}
+
 // ============================================================================
 // URL Building Utilities
 // ============================================================================
@@ -178,6 +335,8 @@ pub enum RedisConnectionType {
     Single,
     /// Redis Cluster (comma-separated nodes in host_port)
     Cluster,
+    /// Master or replicas found through Sentinel (sentinel_nodes server option)
+    Sentinel,
 }
 
 impl RedisConnectionType {
@@ -195,6 +354,7 @@ impl RedisConnectionType {
         match self {
             Self::Single => "single",
             Self::Cluster => "cluster",
+            Self::Sentinel => "sentinel",
         }
     }
 }
@@ -282,6 +442,8 @@ pub struct PoolManager {
     single_pools: HashMap<String, r2d2::Pool<Client>>,
     /// Cache for cluster Redis pools
     cluster_pools: HashMap<String, r2d2::Pool<ClusterClient>>,
+    /// Cache for Sentinel master and replica pools
+    sentinel_pools: HashMap<String, r2d2::Pool<LockedSentinelClient>>,
 }
 
 impl PoolManager {
@@ -289,6 +451,7 @@ impl PoolManager {
         Self {
             single_pools: HashMap::new(),
             cluster_pools: HashMap::new(),
+            sentinel_pools: HashMap::new(),
         }
     }
 
@@ -355,6 +518,42 @@ impl PoolManager {
         Ok(pool)
     }
 
+    /// Get or create a pool for the master (or the replicas) of a
+    /// Sentinel-managed deployment
+    pub fn get_or_create_sentinel_pool(
+        &mut self,
+        sentinel: &SentinelConfig,
+        replica: bool,
+        database: i64,
+        auth_config: &RedisAuthConfig,
+        pool_config: &PoolConfig,
+    ) -> Result<r2d2::Pool<LockedSentinelClient>, PoolError> {
+        let key = sentinel.cache_key(replica, database, auth_config);
+
+        if let Some(pool) = self.sentinel_pools.get(&key) {
+            return Ok(pool.clone());
+        }
+
+        let pool = sentinel.create_pool(replica, database, auth_config, pool_config)?;
+
+        if self.sentinel_pools.len() >= Self::MAX_CACHED_POOLS {
+            pgrx::warning!(
+                "Redis FDW: sentinel pool cache full ({} pools), connection will not be cached",
+                Self::MAX_CACHED_POOLS
+            );
+            return Ok(pool);
+        }
+
+        self.sentinel_pools.insert(key, pool.clone());
+        Ok(pool)
+    }
+
+    /// Drop a cached Sentinel pool so the next connection asks Sentinel
+    /// where the master is; connections already handed out keep working.
+    pub fn evict_sentinel_pool(&mut self, key: &str) -> bool {
+        self.sentinel_pools.remove(key).is_some()
+    }
+
     /// Get the number of cached single-node pools (for testing/monitoring)
     #[cfg(any(test, feature = "pg_test"))]
     pub fn single_pool_count(&self) -> usize {
@@ -367,11 +566,18 @@ impl PoolManager {
         self.cluster_pools.len()
     }
 
+    /// Get the number of cached Sentinel pools (for testing/monitoring)
+    #[cfg(any(test, feature = "pg_test"))]
+    pub fn sentinel_pool_count(&self) -> usize {
+        self.sentinel_pools.len()
+    }
+
     /// Clear all cached pools (useful for testing)
     #[cfg(any(test, feature = "pg_test"))]
     pub fn clear_all(&mut self) {
         self.single_pools.clear();
         self.cluster_pools.clear();
+        self.sentinel_pools.clear();
     }
 }
 
@@ -406,6 +612,7 @@ impl RedisPool {
 pub enum PooledConnection {
     Single(r2d2::PooledConnection<Client>),
     Cluster(r2d2::PooledConnection<ClusterClient>),
+    Sentinel(SentinelConnection),
 }
 
 impl PooledConnection {
@@ -413,13 +620,14 @@ impl PooledConnection {
         match self {
             PooledConnection::Single(conn) => conn,
             PooledConnection::Cluster(conn) => conn,
+            PooledConnection::Sentinel(conn) => conn,
         }
     }
 
     pub fn as_cluster_connection_mut(&mut self) -> Option<&mut redis::cluster::ClusterConnection> {
         match self {
             PooledConnection::Cluster(conn) => Some(&mut *conn),
-            PooledConnection::Single(_) => None,
+            PooledConnection::Single(_) | PooledConnection::Sentinel(_) => None,
         }
     }
 
@@ -428,6 +636,119 @@ impl PooledConnection {
     }
 }
 
+/// Connection to the node Sentinel reported when it was opened
+///
+/// After a failover the old master answers writes with READONLY, and a dead
+/// node breaks the connection. Either drops the pool this connection came
+/// from, so the statement fails but the next connection asks Sentinel for the
+/// current master.
+pub struct SentinelConnection {
+    conn: r2d2::PooledConnection<LockedSentinelClient>,
+    pool_key: String,
+}
+
+impl SentinelConnection {
+    fn evict_pool(&self) {
+        if let Ok(mut writer) = PoolManager::global().write() {
+            if writer.evict_sentinel_pool(&self.pool_key) {
+                pgrx::log!(
+                    "Redis FDW: dropped sentinel pool {} to rediscover the master",
+                    self.pool_key
+                );
+            }
+        }
+    }
+
+    fn check<T>(&self, result: RedisResult<T>) -> RedisResult<T> {
+        if result.as_ref().is_err_and(needs_rediscovery) {
+            self.evict_pool();
+        }
+        result
+    }
+}
+
+/// Whether an error means the node is no longer the master or is gone.
+fn needs_rediscovery(e: &RedisError) -> bool {
+    e.code() == Some("READONLY") || e.is_io_error() || e.is_connection_dropped()
+}
+
+impl redis::ConnectionLike for SentinelConnection {
+    fn req_packed_command(&mut self, cmd: &[u8]) -> RedisResult<Value> {
+        let result = self.conn.req_packed_command(cmd);
+        self.check(result)
+    }
+
+    fn req_packed_commands(
+        &mut self,
+        cmd: &[u8],
+        offset: usize,
+        count: usize,
+    ) -> RedisResult<Vec<Value>> {
+        let result = self.conn.req_packed_commands(cmd, offset, count);
+        // Pipelines hand back per-command errors as values.
+        if let Ok(values) = &result {
+            if values
+                .iter()
+                .any(|v| matches!(v, Value::ServerError(e) if e.code() == "READONLY"))
+            {
+                self.evict_pool();
+            }
+        }
+        self.check(result)
+    }
+
+    fn get_db(&self) -> i64 {
+        self.conn.get_db()
+    }
+
+    fn check_connection(&mut self) -> bool {
+        self.conn.check_connection()
+    }
+
+    fn is_open(&self) -> bool {
+        self.conn.is_open()
+    }
+}
+
+/// Get a connection to the master of a Sentinel-managed deployment, or to
+/// one of its replicas when `replica` is set, from the global pool
+pub fn get_sentinel_connection(
+    sentinel: &SentinelConfig,
+    replica: bool,
+    database: i64,
+    auth_config: &RedisAuthConfig,
+    pool_config: &PoolConfig,
+) -> Result<PooledConnection, PoolError> {
+    let manager = PoolManager::global();
+    let pool_key = sentinel.cache_key(replica, database, auth_config);
+
+    let cached = {
+        let reader = manager.read().map_err(|_| PoolError::LockPoisoned)?;
+        reader.sentinel_pools.get(&pool_key).cloned()
+    };
+    let pool = match cached {
+        Some(pool) => pool,
+        None => {
+            let mut writer = manager.write().map_err(|_| PoolError::LockPoisoned)?;
+            writer.get_or_create_sentinel_pool(
+                sentinel,
+                replica,
+                database,
+                auth_config,
+                pool_config,
+            )?
+        }
+    };
+
+    let conn = pool
+        .get()
+        .map_err(|e| PoolError::ConnectionAcquisition(e.to_string()))?;
+    Ok(PooledConnection::Sentinel(SentinelConnection {
+        conn,
+        pool_key,
+    }))
+}
+
 /// High-level helper to get a connection from the global pool
 ///
 /// Detects single vs cluster mode based on:
@@ -470,6 +791,9 @@ pub fn get_pooled_connection(
                     return Ok(PooledConnection::Cluster(conn));
                 }
             }
+            RedisConnectionType::Sentinel => {
+                unreachable!("sentinel servers connect through get_sentinel_connection")
+            }
         }
     }
     // Read lock dropped here
@@ -496,6 +820,9 @@ pub fn get_pooled_connection(
                 )?;
                 RedisPool::Cluster(p)
             }
+            RedisConnectionType::Sentinel => {
+                unreachable!("sentinel servers connect through get_sentinel_connection")
+            }
         }
     };
     pool.get_connection()
@@ -614,6 +941,105 @@ mod tests {
     fn test_connection_type_cache_key_prefix() {
         assert_eq!(RedisConnectionType::Single.cache_key_prefix(), "single");
         assert_eq!(RedisConnectionType::Cluster.cache_key_prefix(), "cluster");
+        assert_eq!(RedisConnectionType::Sentinel.cache_key_prefix(), "sentinel");
+    }
+
+    // --------------------------------
+    // Sentinel Tests
+    // --------------------------------
+
+    fn sentinel_options(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_sentinel_config_from_options() {
+        let opts = sentinel_options(&[
+            ("sentinel_nodes", "10.0.0.1:26379, 10.0.0.2"),
+            ("sentinel_master_name", "mymaster"),
+            ("sentinel_password", "s3cret"),
+            ("sentinel_replica_reads", "true"),
+        ]);
+        let config = SentinelConfig::from_options(&opts).unwrap().unwrap();
+        assert_eq!(config.master_name, "mymaster");
+        assert_eq!(config.password.as_deref(), Some("s3cret"));
+        assert!(config.replica_reads);
+        assert_eq!(
+            config.server_label(),
+            "mymaster via sentinel 10.0.0.1:26379, 10.0.0.2"
+        );
+
+        assert_eq!(SentinelConfig::from_options(&HashMap::new()), Ok(None));
+    }
+
+    #[test]
+    fn test_sentinel_config_rejects_incomplete_options() {
+        let only_nodes = sentinel_options(&[("sentinel_nodes", "10.0.0.1:26379")]);
+        assert!(SentinelConfig::from_options(&only_nodes)
+            .unwrap_err()
+            .contains("must be set together"));
+
+        let stray = sentinel_options(&[("sentinel_password", "x")]);
+        assert!(SentinelConfig::from_options(&stray)
+            .unwrap_err()
+            .starts_with("sentinel_password needs"));
+
+        let bad_flag = sentinel_options(&[
+            ("sentinel_nodes", "10.0.0.1:26379"),
+            ("sentinel_master_name", "mymaster"),
+            ("sentinel_replica_reads", "yes"),
+        ]);
+        assert!(SentinelConfig::from_options(&bad_flag).is_err());
+    }
+
+    #[test]
+    fn test_sentinel_addrs() {
+        assert_eq!(
+            sentinel_addrs("10.0.0.1:26380, sentinel-b").unwrap(),
+            vec![
+                ConnectionAddr::Tcp("10.0.0.1".to_string(), 26380),
+                ConnectionAddr::Tcp("sentinel-b".to_string(), 26379),
+            ]
+        );
+        assert!(sentinel_addrs("10.0.0.1:26379,,10.0.0.2:26379").is_err());
+        assert!(sentinel_addrs("10.0.0.1:port").is_err());
+        assert!(sentinel_addrs(":26379").is_err());
+    }
+
+    #[test]
+    fn test_sentinel_cache_key() {
+        let opts = sentinel_options(&[
+            ("sentinel_nodes", "10.0.0.1:26379"),
+            ("sentinel_master_name", "mymaster"),
+        ]);
+        let config = SentinelConfig::from_options(&opts).unwrap().unwrap();
+        let auth = RedisAuthConfig::default();
+
+        let master = config.cache_key(false, 0, &auth);
+        assert!(master.starts_with("sentinel:master:mymaster@10.0.0.1:26379:0:"));
+        assert_ne!(master, config.cache_key(true, 0, &auth));
+        assert_ne!(master, config.cache_key(false, 1, &auth));
+    }
+
+    #[test]
+    fn test_needs_rediscovery() {
+        let readonly = RedisError::from((
+            redis::ErrorKind::Server(redis::ServerErrorKind::ReadOnly),
+            "You can't write against a read only replica.",
+        ));
+        assert!(needs_rediscovery(&readonly));
+
+        let reset = RedisError::from(std::io::Error::from(std::io::ErrorKind::ConnectionReset));
+        assert!(needs_rediscovery(&reset));
+
+        let no_perm = RedisError::from((
+            redis::ErrorKind::Server(redis::ServerErrorKind::NoPerm),
+            "this user has no permissions to run the 'set' command",
+        ));
+        assert!(!needs_rediscovery(&no_perm));
     }
 
     // --------------------------------
@@ -769,6 +1195,7 @@ mod tests {
         let manager = PoolManager::new();
         assert_eq!(manager.single_pool_count(), 0);
         assert_eq!(manager.cluster_pool_count(), 0);
+        assert_eq!(manager.sentinel_pool_count(), 0);
     }
 
     #[test]
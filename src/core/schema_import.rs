@@ -1,11 +1,19 @@
 use crate::{
+    core::column_utils::{
+        column_encodings, compute_pushdown_column_index, configure_table_columns,
+        detect_ttl_column, extract_column_names, extract_field_names,
+    },
     core::connection_factory::{RedisConnectionConfig, RedisConnectionFactory},
     core::state_manager::is_multi_key_pattern,
     query::limit::LimitOffsetInfo,
-    tables::types::RedisTableType,
-    utils::helpers::*,
+    tables::types::{RedisTableType, RowValue},
+    utils::{
+        helpers::*,
+        type_mapping::{ColumnInputs, OnConversionError},
+    },
 };
 use pgrx::prelude::*;
+use std::borrow::Cow;
 use std::ffi::CString;
 use std::ptr;
 
@@ -181,7 +189,12 @@ pub(crate) unsafe extern "C-unwind" fn import_foreign_schema(
 
         let ddl = format!(
             "CREATE FOREIGN TABLE \"{}\" ({}) SERVER \"{}\" OPTIONS (database '{}', table_type '{}', table_key_prefix '{}')",
-            quoted_table, columns, quoted_server, database_str, redis_type, escaped_prefix
+            quoted_table,
+            columns,
+            quoted_server,
+            database_str,
+            table_type_for(redis_type),
+            escaped_prefix
         );
 
         let ddl_cstr = match CString::new(ddl) {
@@ -272,7 +285,7 @@ pub(crate) unsafe extern "C-unwind" fn analyze_foreign_table(
                 .arg(&key_prefix)
                 .query::<u64>(conn_like)
                 .unwrap_or(0),
-            "zset" => redis::cmd("ZCARD")
+            "zset" | "geo" => redis::cmd("ZCARD")
                 .arg(&key_prefix)
                 .query::<u64>(conn_like)
                 .unwrap_or(0),
@@ -280,7 +293,11 @@ pub(crate) unsafe extern "C-unwind" fn analyze_foreign_table(
                 .arg(&key_prefix)
                 .query::<u64>(conn_like)
                 .unwrap_or(0),
-            "string" => {
+            "bitmap" => redis::cmd("BITCOUNT")
+                .arg(&key_prefix)
+                .query::<u64>(conn_like)
+                .unwrap_or(0),
+            "string" | "hash_row" | "json" | "hyperloglog" => {
                 let exists: u64 = redis::cmd("EXISTS")
                     .arg(&key_prefix)
                     .query(conn_like)
@@ -344,9 +361,49 @@ pub(crate) unsafe extern "C-unwind" fn acquire_sample_rows(
 
     let mut table_type = RedisTableType::from_str(table_type_str);
     let is_multi_key = is_multi_key_pattern(&key_prefix);
+    match table_type {
+        RedisTableType::HashRow(ref mut t) => t.key_column = is_multi_key,
+        RedisTableType::Json(ref mut t) => t.key_column = is_multi_key,
+        RedisTableType::Geo(ref mut t) => t.key_column = is_multi_key,
+        RedisTableType::Bitmap(ref mut t) => t.key_column = is_multi_key,
+        RedisTableType::HyperLogLog(ref mut t) => t.key_column = is_multi_key,
+        _ => {}
+    }
+    if matches!(
+        table_type,
+        RedisTableType::HashRow(_) | RedisTableType::Json(_)
+    ) {
+        let mut fields = extract_field_names(relid, tupdesc);
+        if let Some(ttl_idx) = detect_ttl_column(tupdesc).filter(|&i| i < fields.len()) {
+            fields.remove(ttl_idx);
+        }
+        table_type.configure(&fields, 0, None);
+        table_type.set_column_encodings(column_encodings(
+            tupdesc,
+            detect_ttl_column(tupdesc),
+            is_multi_key,
+        ));
+    } else if matches!(
+        table_type,
+        RedisTableType::Geo(_) | RedisTableType::HyperLogLog(_) | RedisTableType::Keyspace(_)
+    ) {
+        let ttl_idx = detect_ttl_column(tupdesc);
+        let mut names = extract_column_names(tupdesc);
+        if let Some(i) = ttl_idx.filter(|&i| i < names.len()) {
+            names.remove(i);
+        }
+        configure_table_columns(
+            &mut table_type,
+            &names,
+            tupdesc,
+            compute_pushdown_column_index(ttl_idx, is_multi_key),
+            ttl_idx,
+        );
+        table_type.set_column_encodings(column_encodings(tupdesc, ttl_idx, is_multi_key));
+    }
 
     let max_per_key = targrows as usize;
-    let sample_data: Vec<Vec<String>> = if is_multi_key {
+    let sample_data: Vec<Vec<RowValue>> = if is_multi_key {
         let mut keys = Vec::new();
         let mut cursor = 0u64;
         loop {
@@ -372,9 +429,9 @@ pub(crate) unsafe extern "C-unwind" fn acquire_sample_rows(
             for key in &keys {
                 pipe.cmd("GET").arg(key);
             }
-            let vals: Vec<String> = pipe.query(conn_like).unwrap_or_default();
+            let vals: Vec<RowValue> = pipe.query(conn_like).unwrap_or_default();
             for (key, val) in keys.iter().zip(vals.into_iter()) {
-                result.push(vec![key.clone(), val]);
+                result.push(vec![Some(key.clone().into_bytes()), val]);
             }
         } else {
             const PIPE_BATCH: usize = 1000;
@@ -392,15 +449,15 @@ pub(crate) unsafe extern "C-unwind" fn acquire_sample_rows(
                                 .arg("COUNT")
                                 .arg(100u64);
                         }
-                        let results: Vec<(u64, Vec<String>)> =
+                        let results: Vec<(u64, Vec<Vec<u8>>)> =
                             pipe.query(conn_like).unwrap_or_default();
                         for (key, (_, vals)) in batch.iter().zip(results) {
                             for chunk in vals.chunks(2) {
                                 if chunk.len() == 2 && result.len() < max_per_key {
                                     result.push(vec![
-                                        key.clone(),
-                                        chunk[0].clone(),
-                                        chunk[1].clone(),
+                                        Some(key.clone().into_bytes()),
+                                        Some(chunk[0].clone()),
+                                        Some(chunk[1].clone()),
                                     ]);
                                 }
                             }
@@ -417,13 +474,13 @@ pub(crate) unsafe extern "C-unwind" fn acquire_sample_rows(
                             let remaining = (max_per_key - result.len()).min(100) as i64;
                             pipe.cmd("LRANGE").arg(key).arg(0i64).arg(remaining - 1);
                         }
-                        let results: Vec<Vec<String>> = pipe.query(conn_like).unwrap_or_default();
+                        let results: Vec<Vec<Vec<u8>>> = pipe.query(conn_like).unwrap_or_default();
                         for (key, vals) in batch.iter().zip(results) {
                             for v in vals {
                                 if result.len() >= max_per_key {
                                     break;
                                 }
-                                result.push(vec![key.clone(), v]);
+                                result.push(vec![Some(key.clone().into_bytes()), Some(v)]);
                             }
                         }
                     }
@@ -441,14 +498,14 @@ pub(crate) unsafe extern "C-unwind" fn acquire_sample_rows(
                                 .arg("COUNT")
                                 .arg(100u64);
                         }
-                        let results: Vec<(u64, Vec<String>)> =
+                        let results: Vec<(u64, Vec<Vec<u8>>)> =
                             pipe.query(conn_like).unwrap_or_default();
                         for (key, (_, vals)) in batch.iter().zip(results) {
                             for v in vals {
                                 if result.len() >= max_per_key {
                                     break;
                                 }
-                                result.push(vec![key.clone(), v]);
+                                result.push(vec![Some(key.clone().into_bytes()), Some(v)]);
                             }
                         }
                     }
@@ -466,23 +523,30 @@ pub(crate) unsafe extern "C-unwind" fn acquire_sample_rows(
                                 .arg(99i64)
                                 .arg("WITHSCORES");
                         }
-                        let results: Vec<Vec<String>> = pipe.query(conn_like).unwrap_or_default();
+                        let results: Vec<Vec<Vec<u8>>> = pipe.query(conn_like).unwrap_or_default();
                         for (key, vals) in batch.iter().zip(results) {
                             for chunk in vals.chunks(2) {
                                 if chunk.len() == 2 && result.len() < max_per_key {
                                     result.push(vec![
-                                        key.clone(),
-                                        chunk[0].clone(),
-                                        chunk[1].clone(),
+                                        Some(key.clone().into_bytes()),
+                                        Some(chunk[0].clone()),
+                                        Some(chunk[1].clone()),
                                     ]);
                                 }
                             }
                         }
                     }
                 }
+                "hash_row" | "json" | "geo" | "bitmap" | "hyperloglog" | "keyspace" => {
+                    let width = table_type.multi_key_columns_per_row();
+                    let flat = table_type
+                        .load_multi_key_data(conn_like, &keys)
+                        .unwrap_or_default();
+                    result.extend(flat.chunks(width).map(|row| row.to_vec()));
+                }
                 _ => {
                     for key in &keys {
-                        result.push(vec![key.clone()]);
+                        result.push(vec![Some(key.clone().into_bytes())]);
                     }
                 }
             }
@@ -493,13 +557,23 @@ pub(crate) unsafe extern "C-unwind" fn acquire_sample_rows(
             limit: Some(targrows as usize),
             offset: None,
         };
+        table_type.set_column_encodings(column_encodings(
+            tupdesc,
+            detect_ttl_column(tupdesc),
+            false,
+        ));
         let _ = table_type.load_data(conn_like, &key_prefix, None, &limit_info);
 
         let mut result = Vec::new();
         let len = table_type.data_len();
         for i in 0..len {
             if let Some(row_data) = table_type.get_row(i) {
-                result.push(row_data.into_iter().map(|c| c.into_owned()).collect());
+                result.push(
+                    row_data
+                        .into_iter()
+                        .map(|c| c.map(Cow::into_owned))
+                        .collect(),
+                );
             }
         }
         result
@@ -509,22 +583,23 @@ pub(crate) unsafe extern "C-unwind" fn acquire_sample_rows(
     *totalrows = num_rows as f64;
     *totaldeadrows = 0.0;
 
+    // Unparsable values are sampled as NULL instead of failing ANALYZE.
+    let inputs = ColumnInputs::new(
+        tupdesc,
+        OnConversionError::Null,
+        pg_sys::CurrentMemoryContext,
+    );
     let mut actual = 0i32;
     for (idx, row_data) in sample_data.iter().take(num_rows).enumerate() {
         let mut values: Vec<pg_sys::Datum> = Vec::with_capacity(natts);
         let mut nulls: Vec<bool> = Vec::with_capacity(natts);
 
         for col_idx in 0..natts {
-            if col_idx < row_data.len() {
-                let attr = tuple_desc_attr(tupdesc, col_idx);
-                let typid = (*attr).atttypid;
-                let datum = get_datum(&row_data[col_idx], typid);
-                values.push(datum);
-                nulls.push(false);
-            } else {
-                values.push(pg_sys::Datum::from(0));
-                nulls.push(true);
-            }
+            let datum = row_data
+                .get(col_idx)
+                .and_then(|value| inputs.datum(col_idx, value.as_deref()));
+            values.push(datum.unwrap_or(pg_sys::Datum::from(0)));
+            nulls.push(datum.is_none());
         }
 
         let tuple = pg_sys::heap_form_tuple(tupdesc, values.as_mut_ptr(), nulls.as_mut_ptr());
@@ -575,6 +650,15 @@ fn columns_for_type(redis_type: &str) -> &'static str {
         "zset" => "key text, member text, score text",
         "string" => "key text, value text",
         "stream" => "stream_id text, field text, value text",
+        "ReJSON-RL" => "key text, doc jsonb OPTIONS (path '$')",
         _ => "value text",
     }
 }
+
+/// `table_type` option for keys of Redis TYPE `redis_type`.
+fn table_type_for(redis_type: &str) -> &str {
+    match redis_type {
+        "ReJSON-RL" => "json",
+        other => other,
+    }
+}
@@ -4,18 +4,29 @@
 /// configuration, connection status, and coordination between components.
 use crate::{
     core::{
-        column_utils::compute_key_column_index,
+        column_utils::{column_encodings, compute_key_column_index, compute_pushdown_column_index},
         connection_factory::{RedisConnectionConfig, RedisConnectionFactory},
-        pool_manager::PooledConnection,
+        direct_modify::DirectModify,
+        optimistic,
+        pool_manager::{PooledConnection, SentinelConfig},
+        transaction::{self, BufferedConnection},
     },
     query::{
         cost_estimation::{CostEstimate, CostEstimator},
-        pushdown_types::{ComparisonOperator, PushdownAnalysis},
+        pushdown_types::{ComparisonOperator, PushableCondition, PushdownAnalysis},
         scan_ops::PatternMatcher,
     },
     tables::{
+        implementations::{
+            function::RedisCallable,
+            stream::{entry_time_id_bounds, StreamConsumer, StreamTrim, ENTRY_TIME_FIELD},
+        },
         interface::RedisTableOperations,
-        types::{RedisTableType, RowVec},
+        types::{ColumnEncodings, InsertMode, RedisTableType, RowValue, RowVec, ValueEncoding},
+    },
+    utils::{
+        helpers::write_datum_to_slot,
+        type_mapping::{ColumnInputs, OnConversionError},
     },
 };
 use pgrx::{pg_sys, pg_sys::MemoryContext, prelude::*};
@@ -69,17 +80,22 @@ pub struct RedisFdwState {
     pub join_batch_size: usize,
     /// Cache populated lazily on first miss during a parameterized scan;
     /// keyed by param value, holds the row that was returned (or absence).
-    pub join_batch_cache: std::collections::HashMap<String, Option<Vec<String>>>,
+    pub join_batch_cache: std::collections::HashMap<String, Option<Vec<RowValue>>>,
     /// Whether batching uses pipelined Redis (standalone) or per-key fallback (cluster).
     pub join_batch_mode: BatchMode,
     /// Whether this is a join pushdown scan (FDW-to-FDW on same server)
     pub is_join_scan: bool,
+    /// Whether this scan answers a `count(*)` with BITCOUNT (planned on the
+    /// aggregate's upper relation, see `query::aggregate`)
+    pub count_pushdown: bool,
     /// Join execution state (populated during begin_foreign_scan for join scans)
     pub join_state: Option<crate::join::types::RedisJoinState>,
     /// Whether the join has been executed (lazy: execute on first iterate call)
     pub join_executed: bool,
     /// Column names from the foreign table's tuple descriptor
     pub column_names: Vec<String>,
+    /// Encoding of each data column, as given to the table type
+    pub column_encodings: ColumnEncodings,
     /// Whether this is a parameterized scan (receives join key from outer NestLoop)
     pub is_parameterized: bool,
     /// Column index (0-based, after TTL strip) that receives the parameter value
@@ -90,6 +106,30 @@ pub struct RedisFdwState {
     pub param_expr_state: *mut pg_sys::ExprState,
     /// PlanState pointer for expression evaluation context
     pub param_plan_state: *mut pg_sys::PlanState,
+    /// Operators of the stream `entry_time` quals whose values follow the
+    /// join parameter in `fdw_exprs`, evaluated when the scan begins
+    pub entry_time_bounds: Vec<ComparisonOperator>,
+    /// Server the writes are buffered for when `transactional 'true'`
+    /// (None = writes go to Redis immediately)
+    pub transaction_config: Option<RedisConnectionConfig>,
+    /// `concurrency 'optimistic'`: WATCH scanned keys, commit via MULTI/EXEC
+    pub optimistic: bool,
+    /// Relation whose WATCHed connection this modify claimed; the connection
+    /// itself stays in the `optimistic` registry
+    pub watched_relid: Option<pg_sys::Oid>,
+    /// Writes waiting for the MULTI/EXEC on the watched connection
+    pub optimistic_queue: Vec<redis::Cmd>,
+    /// `insert_mode` option, or `Nx` for `INSERT ... ON CONFLICT DO NOTHING`
+    pub insert_mode: InsertMode,
+    /// The modify has RETURNING or AFTER ROW triggers, so the returned slot
+    /// must carry what Redis stored
+    pub returning_needed: bool,
+    /// UPDATE/DELETE planned to run without a scan (see `direct_modify`)
+    pub direct_modify: Option<DirectModify>,
+    /// `on_conversion_error` option: error or NULL for unparsable values
+    pub on_conversion_error: OnConversionError,
+    /// Column input functions, set up when a scan or modify begins
+    pub column_inputs: Option<ColumnInputs>,
 }
 
 impl RedisFdwState {
@@ -119,22 +159,61 @@ impl RedisFdwState {
             join_batch_cache: std::collections::HashMap::new(),
             join_batch_mode: BatchMode::NotApplicable,
             is_join_scan: false,
+            count_pushdown: false,
             join_state: None,
             join_executed: false,
             column_names: Vec::new(),
+            column_encodings: ColumnEncodings::default(),
             is_parameterized: false,
             param_column: 0,
             param_type_oid: pg_sys::InvalidOid,
             param_expr_state: std::ptr::null_mut(),
             param_plan_state: std::ptr::null_mut(),
+            entry_time_bounds: Vec::new(),
+            transaction_config: None,
+            optimistic: false,
+            watched_relid: None,
+            optimistic_queue: Vec::new(),
+            insert_mode: InsertMode::Overwrite,
+            returning_needed: false,
+            direct_modify: None,
+            on_conversion_error: OnConversionError::Error,
+            column_inputs: None,
         }
     }
 
     /// Initialize Redis connection using the connection factory with authentication
     /// Returns Result for proper error handling instead of panicking
     pub fn init_redis_connection_from_options(&mut self) -> Result<(), String> {
-        let config = RedisConnectionConfig::from_options(&self.opts)
+        self.connect(false)
+    }
+
+    /// Replace the connection with one for a scan that only reads, which
+    /// comes from a replica on a Sentinel-managed server with
+    /// `sentinel_replica_reads 'true'`.
+    pub fn init_read_only_connection_from_options(&mut self) -> Result<(), String> {
+        self.connect(true)
+    }
+
+    /// Whether read-only scans of this table may go to a replica. Consumer
+    /// group reads and function calls can write, so they stay on the master.
+    pub fn replica_reads(&self) -> bool {
+        self.opts.contains_key("sentinel_nodes")
+            && self
+                .opts
+                .get("sentinel_replica_reads")
+                .is_some_and(|v| v == "true")
+            && !self.opts.contains_key("consumer_group")
+            && !self
+                .opts
+                .get("table_type")
+                .is_some_and(|tt| tt.eq_ignore_ascii_case("function"))
+    }
+
+    fn connect(&mut self, read_only: bool) -> Result<(), String> {
+        let mut config = RedisConnectionConfig::from_options(&self.opts)
             .map_err(|e| format!("Failed to create Redis configuration: {}", e))?;
+        config.read_only = read_only;
 
         // Create connection using the factory with retry logic
         match RedisConnectionFactory::create_connection_with_retry(&config) {
@@ -154,11 +233,13 @@ impl RedisFdwState {
     pub fn update_from_options(&mut self, opts: HashMap<String, String>) {
         self.opts = opts;
 
-        self.host_port = self
-            .opts
-            .get("host_port")
-            .expect("`host_port` option is required for redis_fdw")
-            .clone();
+        self.host_port = match self.opts.get("host_port") {
+            Some(host_port) => host_port.clone(),
+            None => match SentinelConfig::from_options(&self.opts) {
+                Ok(Some(sentinel)) => sentinel.server_label(),
+                _ => panic!("`host_port` or `sentinel_nodes` option is required for redis_fdw"),
+            },
+        };
 
         if let Some(db_str) = self.opts.get("database") {
             self.database = db_str
@@ -189,6 +270,77 @@ impl RedisFdwState {
         if let Some(skp) = self.opts.get("strict_key_prefix") {
             self.strict_key_prefix = skp == "true";
         }
+
+        if self.opts.get("transactional").is_some_and(|v| v == "true") {
+            if self.is_cluster_mode() {
+                error!("redis_fdw: transactional mode is not supported with cluster_mode");
+            }
+            self.transaction_config = RedisConnectionConfig::from_options(&self.opts).ok();
+        }
+
+        self.optimistic = self
+            .opts
+            .get("concurrency")
+            .is_some_and(|v| v == "optimistic");
+        if self.optimistic {
+            if self.is_cluster_mode() {
+                error!("redis_fdw: concurrency 'optimistic' is not supported with cluster_mode");
+            }
+            if self.transaction_config.is_some() {
+                error!("redis_fdw: concurrency 'optimistic' cannot be combined with transactional 'true'");
+            }
+        }
+
+        if let Some(mode) = self.opts.get("insert_mode") {
+            self.insert_mode = InsertMode::parse(mode).unwrap_or_else(|| {
+                error!("redis_fdw: insert_mode must be 'overwrite', 'nx' or 'xx', got '{mode}'")
+            });
+        }
+        // Buffered writes only reply at COMMIT, so a skipped row could not be
+        // told apart from a written one.
+        if self.insert_mode != InsertMode::Overwrite && self.transaction_config.is_some() {
+            error!(
+                "redis_fdw: insert_mode 'nx' and 'xx' cannot be combined with transactional 'true'"
+            );
+        }
+
+        if let Some(mode) = self.opts.get("on_conversion_error") {
+            self.on_conversion_error = OnConversionError::parse(mode).unwrap_or_else(|| {
+                error!("redis_fdw: on_conversion_error must be 'error' or 'null', got '{mode}'")
+            });
+        }
+    }
+
+    /// Look up the input function of each column of `tupdesc` once, in the
+    /// state's memory context.
+    pub unsafe fn init_column_inputs(&mut self, tupdesc: pg_sys::TupleDesc) {
+        self.column_inputs = Some(ColumnInputs::new(
+            tupdesc,
+            self.on_conversion_error,
+            self.tmp_ctx,
+        ));
+    }
+
+    /// Store the Redis value `value` in column `colno` of `slot`, converted
+    /// to the column's type.
+    pub unsafe fn store_value(
+        &self,
+        slot: *mut pg_sys::TupleTableSlot,
+        colno: usize,
+        value: Option<&[u8]>,
+    ) {
+        match &self.column_inputs {
+            Some(inputs) => inputs.store(slot, colno, value),
+            None => write_datum_to_slot(slot, (*slot).tts_tupleDescriptor, colno, value),
+        }
+    }
+
+    /// Record which data columns of `tupdesc` carry raw bytes, in the table
+    /// type and for filtering parameterized lookup rows.
+    pub unsafe fn set_column_encodings(&mut self, tupdesc: pg_sys::TupleDesc) {
+        self.column_encodings = column_encodings(tupdesc, self.ttl_column_index, self.is_multi_key);
+        self.table_type
+            .set_column_encodings(self.column_encodings.clone());
     }
 
     /// Set table type and prepare for streaming iteration
@@ -199,6 +351,28 @@ impl RedisFdwState {
             .expect("`table_type` option is required for redis_fdw");
 
         self.table_type = RedisTableType::from_str(table_type);
+        let sharded = self.is_cluster_mode();
+        match self.table_type {
+            RedisTableType::HashRow(ref mut t) => t.key_column = self.is_multi_key,
+            RedisTableType::Json(ref mut t) => t.key_column = self.is_multi_key,
+            RedisTableType::Geo(ref mut t) => t.key_column = self.is_multi_key,
+            RedisTableType::Bitmap(ref mut t) => t.key_column = self.is_multi_key,
+            RedisTableType::HyperLogLog(ref mut t) => t.key_column = self.is_multi_key,
+            RedisTableType::Stream(ref mut t) => {
+                t.key_column = self.is_multi_key;
+                t.consumer = stream_consumer(&self.opts);
+                t.trim = stream_trim(&self.opts);
+            }
+            RedisTableType::PubSub(ref mut t) => {
+                t.channel = self.opts.get("channel").cloned();
+                t.sharded = sharded;
+            }
+            RedisTableType::Function(ref mut t) => {
+                t.read = function_callable(&self.opts, "read_function", "read_script");
+                t.write = function_callable(&self.opts, "write_function", "write_script");
+            }
+            _ => {}
+        }
     }
 
     /// Fetch the next batch of data using cursor-based iteration.
@@ -250,7 +424,7 @@ impl RedisFdwState {
                             .arg(&self.table_key_prefix)
                             .query(conn_like)
                             .unwrap_or(0),
-                        RedisTableType::ZSet(_) => redis::cmd("ZCARD")
+                        RedisTableType::ZSet(_) | RedisTableType::Geo(_) => redis::cmd("ZCARD")
                             .arg(&self.table_key_prefix)
                             .query(conn_like)
                             .unwrap_or(0),
@@ -262,6 +436,10 @@ impl RedisFdwState {
                             .arg(&self.table_key_prefix)
                             .query(conn_like)
                             .unwrap_or(0),
+                        RedisTableType::Bitmap(_) => redis::cmd("BITCOUNT")
+                            .arg(&self.table_key_prefix)
+                            .query(conn_like)
+                            .unwrap_or(0),
                         _ => 0,
                     };
                     if cardinality > SINGLE_KEY_WARN_THRESHOLD as u64 {
@@ -401,12 +579,26 @@ impl RedisFdwState {
                     return true;
                 }
             }
+
+            // Use direct load for geo searches: GEOSEARCH reads only the area.
+            if let RedisTableType::Geo(ref g) = self.table_type {
+                if analysis
+                    .pushable_conditions
+                    .iter()
+                    .any(|c| g.search_shape(c).is_some())
+                {
+                    return true;
+                }
+            }
         }
         false
     }
 
     fn is_cluster_mode(&self) -> bool {
-        self.host_port.contains(',')
+        // Not `self.host_port`: a Sentinel server's label lists its Sentinels.
+        self.opts
+            .get("host_port")
+            .is_some_and(|hp| hp.contains(','))
             || self
                 .opts
                 .get("cluster_mode")
@@ -460,6 +652,44 @@ impl RedisFdwState {
         self.pushdown_analysis = Some(analysis);
     }
 
+    /// Add the stream ID conditions equivalent to the `entry_time` quals,
+    /// given the values they compare with in microseconds since the
+    /// PostgreSQL epoch (None for NULL), replacing those of an earlier
+    /// execution of the plan. Infinite and NULL values bound nothing; the
+    /// quals stay local either way.
+    pub fn set_entry_time_bounds(&mut self, values: &[(ComparisonOperator, Option<i64>)]) {
+        /// Microseconds from 1970-01-01 to 2000-01-01.
+        const UNIX_TO_PG_EPOCH_US: i64 = 946_684_800_000_000;
+        let RedisTableType::Stream(ref s) = self.table_type else {
+            return;
+        };
+        let id_idx = s.pushdown_column_index;
+        let analysis = self
+            .pushdown_analysis
+            .get_or_insert_with(PushdownAnalysis::new);
+        analysis
+            .pushable_conditions
+            .retain(|c| c.column_name != ENTRY_TIME_FIELD);
+        for (operator, value) in values {
+            let Some(t) = value
+                .filter(|t| *t != i64::MIN && *t != i64::MAX)
+                .and_then(|t| t.checked_add(UNIX_TO_PG_EPOCH_US))
+            else {
+                continue;
+            };
+            for (operator, value) in entry_time_id_bounds(operator, t) {
+                analysis.pushable_conditions.push(PushableCondition {
+                    column_name: ENTRY_TIME_FIELD.to_string(),
+                    column_index: id_idx,
+                    operator,
+                    value,
+                });
+            }
+        }
+        let limit_offset = analysis.limit_offset.take();
+        analysis.set_limit_offset(limit_offset);
+    }
+
     /// Check if we've read all available data
     pub fn is_read_end(&self) -> bool {
         self.row_count >= self.data_len() as u32
@@ -498,32 +728,274 @@ impl RedisFdwState {
         }
     }
 
-    /// Insert data using the appropriate table type
-    pub fn insert_data(&mut self, data: &[String]) -> Result<(), redis::RedisError> {
-        if let Some(conn) = self.redis_connection.as_mut() {
-            let conn_like = conn.as_connection_like_mut();
-            self.table_type
-                .insert(conn_like, &self.table_key_prefix, data)
-        } else {
-            Err(redis::RedisError::from((
+    /// Run a write against the current connection. In transactional mode the
+    /// commands are captured and queued for COMMIT instead of being sent; in
+    /// optimistic mode they wait for the MULTI/EXEC at end of modify.
+    fn run_write<T>(
+        &mut self,
+        op: impl FnOnce(
+            &mut dyn redis::ConnectionLike,
+            &mut RedisTableType,
+            &str,
+        ) -> Result<T, redis::RedisError>,
+    ) -> Result<T, redis::RedisError> {
+        if let Some(relid) = self.watched_relid {
+            let (table_type, prefix, queue) = (
+                &mut self.table_type,
+                self.table_key_prefix.as_str(),
+                &mut self.optimistic_queue,
+            );
+            return optimistic::with_claimed(relid, |watched| {
+                let mut buffered = BufferedConnection::new(watched.as_connection_like_mut());
+                let result = op(&mut buffered, table_type, prefix)?;
+                queue.extend(buffered.into_commands());
+                Ok(result)
+            })
+            .unwrap_or_else(|| {
+                Err(redis::RedisError::from((
+                    redis::ErrorKind::Io,
+                    "WATCH connection was released",
+                )))
+            });
+        }
+
+        let Some(conn) = self.redis_connection.as_mut() else {
+            return Err(redis::RedisError::from((
                 redis::ErrorKind::Io,
                 "Redis connection not initialized",
-            )))
+            )));
+        };
+        let conn_like = conn.as_connection_like_mut();
+
+        match self.transaction_config.as_ref() {
+            Some(config) => {
+                let mut buffered = BufferedConnection::new(conn_like);
+                let result = op(&mut buffered, &mut self.table_type, &self.table_key_prefix)?;
+                transaction::enqueue(config, buffered.into_commands());
+                Ok(result)
+            }
+            None => op(conn_like, &mut self.table_type, &self.table_key_prefix),
         }
     }
 
-    /// Delete data using the appropriate table type
-    pub fn delete_data(&mut self, data: &[String]) -> Result<(), redis::RedisError> {
-        if let Some(conn) = self.redis_connection.as_mut() {
-            let conn_like = conn.as_connection_like_mut();
-            self.table_type
-                .delete(conn_like, &self.table_key_prefix, data)
+    /// WATCH the keys this scan is about to read, for the UPDATE/DELETE that
+    /// owns it. Single-key tables watch their key; multi-key tables need an
+    /// `=`/`IN` condition on the key column so the key set is known upfront.
+    pub fn watch_scan_keys(&self, relid: pg_sys::Oid) -> Result<(), String> {
+        let keys: Vec<String> = if !self.is_multi_key {
+            vec![self.table_key_prefix.clone()]
         } else {
-            Err(redis::RedisError::from((
-                redis::ErrorKind::Io,
-                "Redis connection not initialized",
-            )))
+            let key_idx = compute_key_column_index(self.ttl_column_index);
+            let condition = self.pushdown_analysis.as_ref().and_then(|a| {
+                a.pushable_conditions.iter().find(|c| {
+                    c.column_index == key_idx
+                        && matches!(
+                            c.operator,
+                            ComparisonOperator::Equal | ComparisonOperator::In
+                        )
+                })
+            });
+            match condition {
+                Some(c) if c.operator == ComparisonOperator::In => c
+                    .value
+                    .split(',')
+                    .filter(|s| !s.is_empty())
+                    .map(|s| s.to_string())
+                    .collect(),
+                Some(c) => vec![c.value.clone()],
+                None => {
+                    return Err("concurrency 'optimistic' on a multi-key table requires a key = or key IN condition".to_string());
+                }
+            }
+        };
+        if keys.is_empty() {
+            return Ok(());
+        }
+
+        let config = RedisConnectionConfig::from_options(&self.opts)
+            .map_err(|e| format!("Failed to create Redis configuration: {}", e))?;
+        optimistic::watch_keys(relid, &config, &keys)
+    }
+
+    /// Commit the writes buffered under WATCH. `Ok(false)` means a watched
+    /// key changed and nothing was applied.
+    pub fn commit_optimistic(&mut self) -> Result<bool, redis::RedisError> {
+        let Some(relid) = self.watched_relid.take() else {
+            return Ok(true);
+        };
+        let commands = std::mem::take(&mut self.optimistic_queue);
+        optimistic::exec_claimed(relid, commands)
+    }
+
+    /// Whether writes are held back (transactional buffer or optimistic
+    /// MULTI/EXEC), so Redis has not stored anything that could be read back.
+    fn writes_deferred(&self) -> bool {
+        self.transaction_config.is_some() || self.watched_relid.is_some()
+    }
+
+    /// Replace the values Redis decides on write with what it stored: the
+    /// generated stream ID, the zset score as written, the geo coordinates
+    /// (GEOADD rounds them to its geohash grid) and the remaining TTL. `row`
+    /// holds every column of the written row, TTL included.
+    pub fn refresh_returned_row(&mut self, key: &str, row: &mut [RowValue]) {
+        if self.writes_deferred() {
+            return;
+        }
+        let data_idx = compute_pushdown_column_index(self.ttl_column_index, self.is_multi_key);
+        let ttl_idx = self.ttl_column_index;
+        let Some(conn) = self.redis_connection.as_mut() else {
+            return;
+        };
+        let conn_like = conn.as_connection_like_mut();
+
+        match &self.table_type {
+            RedisTableType::Stream(s) => {
+                if let (Some(id), Some(cell)) = (&s.last_added_id, row.get_mut(data_idx)) {
+                    *cell = Some(id.clone().into_bytes());
+                }
+            }
+            RedisTableType::ZSet(z) if z.score_column_index < row.len() => {
+                if let Some(score) = z.last_written_score {
+                    row[z.score_column_index] = Some(score.to_string().into_bytes());
+                }
+            }
+            RedisTableType::Geo(g)
+                if g.longitude_column_index < row.len() && g.latitude_column_index < row.len() =>
+            {
+                if let Some(Some(member)) = row.get(data_idx) {
+                    match redis::cmd("GEOPOS")
+                        .arg(key)
+                        .arg(member.as_slice())
+                        .query::<Vec<Option<(String, String)>>>(conn_like)
+                    {
+                        Ok(mut positions) => {
+                            if let Some(Some((longitude, latitude))) = positions.pop() {
+                                row[g.longitude_column_index] = Some(longitude.into_bytes());
+                                row[g.latitude_column_index] = Some(latitude.into_bytes());
+                            }
+                        }
+                        Err(e) => {
+                            log!(
+                                "WARNING: Failed to read back position of '{}': {}",
+                                String::from_utf8_lossy(member),
+                                e
+                            )
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        if let Some(cell) = ttl_idx.and_then(|i| row.get_mut(i)) {
+            match redis::cmd("TTL").arg(key).query::<i64>(conn_like) {
+                Ok(ttl) => *cell = Some(ttl.to_string().into_bytes()),
+                Err(e) => log!("WARNING: Failed to read back TTL of '{}': {}", key, e),
+            }
+        }
+    }
+
+    /// Read the row a DELETE is about to remove, so RETURNING sees the old
+    /// value. Returns every column, TTL included, or `None` when the table
+    /// type has no point lookup for `key`.
+    pub fn fetch_deleted_row(
+        &mut self,
+        key: &str,
+    ) -> Result<Option<Vec<RowValue>>, redis::RedisError> {
+        let ttl_key = if self.is_multi_key {
+            key.to_string()
+        } else {
+            self.table_key_prefix.clone()
+        };
+        let Some(conn) = self.redis_connection.as_mut() else {
+            return Ok(None);
+        };
+        let conn_like = conn.as_connection_like_mut();
+        let params = [key.to_string()];
+
+        let row = match &mut self.table_type {
+            // Single-key string: the identity column is the value itself.
+            RedisTableType::String(t) if !self.is_multi_key => {
+                Some(vec![Some(t.encodings.get(0).encode(key).into_owned())])
+            }
+            RedisTableType::String(t) => t
+                .batch_parameterized_lookup(conn_like, "", &params)?
+                .pop()
+                .flatten(),
+            RedisTableType::Hash(t) if !self.is_multi_key => t
+                .batch_parameterized_lookup(conn_like, &self.table_key_prefix, &params)?
+                .pop()
+                .flatten(),
+            RedisTableType::Set(t) if !self.is_multi_key => t
+                .batch_parameterized_lookup(conn_like, &self.table_key_prefix, &params)?
+                .pop()
+                .flatten(),
+            RedisTableType::ZSet(t) if !self.is_multi_key => t
+                .batch_parameterized_lookup(conn_like, &self.table_key_prefix, &params)?
+                .pop()
+                .flatten(),
+            RedisTableType::Geo(t) if !self.is_multi_key => t
+                .batch_parameterized_lookup(conn_like, &self.table_key_prefix, &params)?
+                .pop()
+                .flatten(),
+            RedisTableType::Bitmap(t) if !self.is_multi_key => t
+                .batch_parameterized_lookup(conn_like, &self.table_key_prefix, &params)?
+                .pop()
+                .flatten(),
+            RedisTableType::Stream(t) if !self.is_multi_key => t
+                .batch_parameterized_lookup(conn_like, &self.table_key_prefix, &params)?
+                .pop()
+                .flatten(),
+            RedisTableType::HashRow(t) => t
+                .batch_parameterized_lookup(conn_like, "", std::slice::from_ref(&ttl_key))?
+                .pop()
+                .flatten(),
+            RedisTableType::Json(t) => t
+                .batch_parameterized_lookup(conn_like, "", std::slice::from_ref(&ttl_key))?
+                .pop()
+                .flatten(),
+            RedisTableType::HyperLogLog(t) => t
+                .batch_parameterized_lookup(conn_like, "", std::slice::from_ref(&ttl_key))?
+                .pop()
+                .flatten(),
+            _ => None,
+        };
+        let Some(mut row) = row else {
+            return Ok(None);
+        };
+
+        if let Some(ttl_idx) = self.ttl_column_index {
+            let ttl: i64 = redis::cmd("TTL").arg(&ttl_key).query(conn_like)?;
+            row.insert(ttl_idx.min(row.len()), Some(ttl.to_string().into_bytes()));
         }
+        Ok(Some(row))
+    }
+
+    /// Send the planned direct UPDATE/DELETE and return the number of rows
+    /// Redis reports as changed.
+    pub fn execute_direct_modify(&mut self) -> Result<u64, redis::RedisError> {
+        let Some(direct) = self.direct_modify.clone() else {
+            return Ok(0);
+        };
+        let is_cluster = self.is_cluster_mode();
+        let batch_size = self.batch_size;
+        self.run_write(|conn, _, key_prefix| {
+            direct.execute(conn, key_prefix, is_cluster, batch_size)
+        })
+    }
+
+    /// Insert data using the appropriate table type
+    /// Returns `false` when `insert_mode` skipped the row.
+    pub fn insert_data(&mut self, data: &[String]) -> Result<bool, redis::RedisError> {
+        let mode = self.insert_mode;
+        self.run_write(|conn, table_type, key_prefix| {
+            table_type.insert(conn, key_prefix, data, mode)
+        })
+    }
+
+    /// Delete data using the appropriate table type
+    pub fn delete_data(&mut self, data: &[String]) -> Result<(), redis::RedisError> {
+        self.run_write(|conn, table_type, key_prefix| table_type.delete(conn, key_prefix, data))
     }
 
     /// Update data using the appropriate table type
@@ -532,16 +1004,9 @@ impl RedisFdwState {
         old_data: &[String],
         new_data: &[String],
     ) -> Result<(), redis::RedisError> {
-        if let Some(conn) = self.redis_connection.as_mut() {
-            let conn_like = conn.as_connection_like_mut();
-            self.table_type
-                .update(conn_like, &self.table_key_prefix, old_data, new_data)
-        } else {
-            Err(redis::RedisError::from((
-                redis::ErrorKind::Io,
-                "Redis connection not initialized",
-            )))
-        }
+        self.run_write(|conn, table_type, key_prefix| {
+            table_type.update(conn, key_prefix, old_data, new_data)
+        })
     }
 
     /// Get a row at the specified index
@@ -581,6 +1046,16 @@ impl RedisFdwState {
     /// If pushdown conditions target the key column, use optimized paths.
     fn fetch_next_batch_multi_key(&mut self) -> bool {
         let key_col_idx = compute_key_column_index(self.ttl_column_index);
+        if let (RedisTableType::Stream(s), Some(analysis)) =
+            (&mut self.table_type, &self.pushdown_analysis)
+        {
+            s.set_id_range(&analysis.pushable_conditions);
+        }
+        if let (RedisTableType::Keyspace(k), Some(analysis)) =
+            (&mut self.table_type, &self.pushdown_analysis)
+        {
+            k.set_type_filter(&analysis.pushable_conditions);
+        }
         let key_condition = self.pushdown_analysis.as_ref().and_then(|a| {
             a.pushable_conditions
                 .iter()
@@ -873,20 +1348,20 @@ impl RedisFdwState {
             },
         };
 
-        if let Some(ref mut conn) = self.redis_connection {
-            let conn_like = conn.as_connection_like_mut();
-            if effective_ttl > 0 {
-                if let Err(e) = redis::cmd("EXPIRE")
+        if effective_ttl > 0 {
+            if let Err(e) = self.run_write(|conn, _, _| {
+                redis::cmd("EXPIRE")
                     .arg(key)
                     .arg(effective_ttl)
-                    .query::<()>(conn_like)
-                {
-                    log!("WARNING: Failed to set EXPIRE on key '{}': {}", key, e);
-                }
-            } else if effective_ttl == -1 {
-                if let Err(e) = redis::cmd("PERSIST").arg(key).query::<()>(conn_like) {
-                    log!("WARNING: Failed to PERSIST key '{}': {}", key, e);
-                }
+                    .query::<()>(conn)
+            }) {
+                log!("WARNING: Failed to set EXPIRE on key '{}': {}", key, e);
+            }
+        } else if effective_ttl == -1 {
+            if let Err(e) =
+                self.run_write(|conn, _, _| redis::cmd("PERSIST").arg(key).query::<()>(conn))
+            {
+                log!("WARNING: Failed to PERSIST key '{}': {}", key, e);
             }
         }
     }
@@ -923,16 +1398,9 @@ impl RedisFdwState {
         &mut self,
         key: &str,
         data: &[String],
-    ) -> Result<(), redis::RedisError> {
-        if let Some(conn) = self.redis_connection.as_mut() {
-            let conn_like = conn.as_connection_like_mut();
-            self.table_type.insert(conn_like, key, data)
-        } else {
-            Err(redis::RedisError::from((
-                redis::ErrorKind::Io,
-                "Redis connection not initialized",
-            )))
-        }
+    ) -> Result<bool, redis::RedisError> {
+        let mode = self.insert_mode;
+        self.run_write(|conn, table_type, _| table_type.insert(conn, key, data, mode))
     }
 
     pub fn update_data_to_key(
@@ -941,21 +1409,23 @@ impl RedisFdwState {
         old_data: &[String],
         new_data: &[String],
     ) -> Result<(), redis::RedisError> {
-        if let Some(conn) = self.redis_connection.as_mut() {
-            let conn_like = conn.as_connection_like_mut();
-            self.table_type.update(conn_like, key, old_data, new_data)
-        } else {
-            Err(redis::RedisError::from((
-                redis::ErrorKind::Io,
-                "Redis connection not initialized",
-            )))
-        }
+        self.run_write(|conn, table_type, _| table_type.update(conn, key, old_data, new_data))
     }
 
     /// Batch insert multiple rows using Redis pipelining.
     /// Handles cluster vs standalone internally. Applies TTL per row.
-    pub fn batch_insert_data(&mut self, rows: &[(Vec<String>, Option<i64>)]) -> Result<(), String> {
+    /// Returns one flag per row: `false` when the row produced no command or
+    /// `insert_mode` skipped it.
+    pub fn batch_insert_data(
+        &mut self,
+        rows: &[(Vec<String>, Option<i64>)],
+    ) -> Result<Vec<bool>, String> {
         if let Some(ref mut conn) = self.redis_connection {
+            // Pipelined calls use EVALSHA, so the script must be loaded first.
+            if let RedisTableType::Function(ref mut t) = self.table_type {
+                t.load_writer(conn.as_connection_like_mut())
+                    .map_err(|e| format!("Redis function table write failed: {}", e))?;
+            }
             if let Some(cluster_conn) = conn.as_cluster_connection_mut() {
                 Self::batch_insert_cluster(
                     cluster_conn,
@@ -964,8 +1434,23 @@ impl RedisFdwState {
                     self.is_multi_key,
                     self.strict_key_prefix,
                     self.default_ttl,
+                    self.insert_mode,
                     rows,
                 )
+            } else if let Some(config) = self.transaction_config.as_ref() {
+                let mut buffered = BufferedConnection::new(conn.as_connection_like_mut());
+                let applied = Self::batch_insert_standalone(
+                    &mut buffered,
+                    &self.table_type,
+                    &self.table_key_prefix,
+                    self.is_multi_key,
+                    self.strict_key_prefix,
+                    self.default_ttl,
+                    self.insert_mode,
+                    rows,
+                )?;
+                transaction::enqueue(config, buffered.into_commands());
+                Ok(applied)
             } else {
                 let conn_like = conn.as_connection_like_mut();
                 Self::batch_insert_standalone(
@@ -975,6 +1460,7 @@ impl RedisFdwState {
                     self.is_multi_key,
                     self.strict_key_prefix,
                     self.default_ttl,
+                    self.insert_mode,
                     rows,
                 )
             }
@@ -983,6 +1469,40 @@ impl RedisFdwState {
         }
     }
 
+    /// Resolve the target key and column data of one batch row.
+    fn batch_row_target<'a>(
+        data: &'a [String],
+        table_key_prefix: &'a str,
+        is_multi_key: bool,
+        strict_key_prefix: bool,
+        static_prefix: &str,
+    ) -> Option<(&'a str, &'a [String])> {
+        if !is_multi_key {
+            return Some((table_key_prefix, data));
+        }
+        let key = data.first()?;
+        validate_key_prefix(key, static_prefix, table_key_prefix, strict_key_prefix);
+        Some((key.as_str(), &data[1..]))
+    }
+
+    /// Per-row "written" flags from the pipeline replies. `reply_index` maps
+    /// each row to the reply of its insert command.
+    fn batch_applied_rows(
+        reply_index: &[Option<usize>],
+        replies: &[redis::Value],
+        insert_mode: InsertMode,
+    ) -> Vec<bool> {
+        reply_index
+            .iter()
+            .map(|idx| {
+                idx.and_then(|i| replies.get(i)).is_some_and(|reply| {
+                    insert_mode == InsertMode::Overwrite || InsertMode::reply_applied(reply)
+                })
+            })
+            .collect()
+    }
+
+    #[allow(clippy::too_many_arguments)]
     fn batch_insert_standalone(
         conn: &mut dyn redis::ConnectionLike,
         table_type: &RedisTableType,
@@ -990,10 +1510,13 @@ impl RedisFdwState {
         is_multi_key: bool,
         strict_key_prefix: bool,
         default_ttl: Option<i64>,
+        insert_mode: InsertMode,
         rows: &[(Vec<String>, Option<i64>)],
-    ) -> Result<(), String> {
+    ) -> Result<Vec<bool>, String> {
         let mut pipe = redis::pipe();
-        let mut has_cmds = false;
+        let mut reply_index = Vec::with_capacity(rows.len());
+        let mut targets = Vec::with_capacity(rows.len());
+        let mut num_cmds = 0;
         let static_prefix = if is_multi_key {
             extract_static_prefix(table_key_prefix)
         } else {
@@ -1001,34 +1524,58 @@ impl RedisFdwState {
         };
 
         for (data, row_ttl) in rows {
-            let (key, row_data) = if is_multi_key {
-                if data.is_empty() {
-                    continue;
+            let target = Self::batch_row_target(
+                data,
+                table_key_prefix,
+                is_multi_key,
+                strict_key_prefix,
+                static_prefix,
+            );
+            let mut idx = None;
+            if let Some((key, row_data)) = target {
+                if Self::add_insert_to_pipeline(&mut pipe, table_type, key, row_data, insert_mode) {
+                    idx = Some(num_cmds);
+                    num_cmds += 1;
+                    // Conditional inserts only learn which rows were written
+                    // from the replies, so their TTLs go in a second round-trip.
+                    if insert_mode == InsertMode::Overwrite
+                        && Self::add_ttl_to_pipeline(&mut pipe, key, *row_ttl, default_ttl)
+                    {
+                        num_cmds += 1;
+                    }
                 }
-                validate_key_prefix(
-                    data[0].as_str(),
-                    static_prefix,
-                    table_key_prefix,
-                    strict_key_prefix,
-                );
-                (data[0].as_str(), &data[1..])
-            } else {
-                (table_key_prefix, data.as_slice())
-            };
-            if Self::add_insert_to_pipeline(&mut pipe, table_type, key, row_data) {
-                has_cmds = true;
-                has_cmds |= Self::add_ttl_to_pipeline(&mut pipe, key, *row_ttl, default_ttl);
             }
+            reply_index.push(idx);
+            targets.push(target.map(|(key, _)| (key, *row_ttl)));
         }
 
-        if has_cmds {
-            pipe.query::<()>(conn)
-                .map_err(|e| format!("Redis batch insert pipeline failed: {}", e))?;
+        if num_cmds == 0 {
+            return Ok(vec![false; rows.len()]);
+        }
+        let replies: Vec<redis::Value> = pipe
+            .query(conn)
+            .map_err(|e| format!("Redis batch insert pipeline failed: {}", e))?;
+        let applied = Self::batch_applied_rows(&reply_index, &replies, insert_mode);
+
+        if insert_mode != InsertMode::Overwrite {
+            let mut ttl_pipe = redis::pipe();
+            let mut has_ttl = false;
+            for (target, _) in targets.iter().zip(&applied).filter(|(_, a)| **a) {
+                if let Some((key, row_ttl)) = target {
+                    has_ttl |= Self::add_ttl_to_pipeline(&mut ttl_pipe, key, *row_ttl, default_ttl);
+                }
+            }
+            if has_ttl {
+                ttl_pipe
+                    .query::<()>(conn)
+                    .map_err(|e| format!("Redis batch insert TTL pipeline failed: {}", e))?;
+            }
         }
 
-        Ok(())
+        Ok(applied)
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn batch_insert_cluster(
         cluster_conn: &mut redis::cluster::ClusterConnection,
         table_type: &RedisTableType,
@@ -1036,10 +1583,13 @@ impl RedisFdwState {
         is_multi_key: bool,
         strict_key_prefix: bool,
         default_ttl: Option<i64>,
+        insert_mode: InsertMode,
         rows: &[(Vec<String>, Option<i64>)],
-    ) -> Result<(), String> {
+    ) -> Result<Vec<bool>, String> {
         let mut pipe = redis::cluster::cluster_pipe();
-        let mut has_cmds = false;
+        let mut reply_index = Vec::with_capacity(rows.len());
+        let mut targets = Vec::with_capacity(rows.len());
+        let mut num_cmds = 0;
         let static_prefix = if is_multi_key {
             extract_static_prefix(table_key_prefix)
         } else {
@@ -1047,102 +1597,157 @@ impl RedisFdwState {
         };
 
         for (data, row_ttl) in rows {
-            let (key, row_data) = if is_multi_key {
-                if data.is_empty() {
-                    continue;
+            let target = Self::batch_row_target(
+                data,
+                table_key_prefix,
+                is_multi_key,
+                strict_key_prefix,
+                static_prefix,
+            );
+            let mut idx = None;
+            if let Some((key, row_data)) = target {
+                if Self::add_insert_to_cluster_pipeline(
+                    &mut pipe,
+                    table_type,
+                    key,
+                    row_data,
+                    insert_mode,
+                ) {
+                    idx = Some(num_cmds);
+                    num_cmds += 1;
+                    if insert_mode == InsertMode::Overwrite
+                        && Self::add_ttl_to_cluster_pipeline(&mut pipe, key, *row_ttl, default_ttl)
+                    {
+                        num_cmds += 1;
+                    }
                 }
-                validate_key_prefix(
-                    data[0].as_str(),
-                    static_prefix,
-                    table_key_prefix,
-                    strict_key_prefix,
-                );
-                (data[0].as_str(), &data[1..])
-            } else {
-                (table_key_prefix, data.as_slice())
-            };
-            if Self::add_insert_to_cluster_pipeline(&mut pipe, table_type, key, row_data) {
-                has_cmds = true;
-                has_cmds |=
-                    Self::add_ttl_to_cluster_pipeline(&mut pipe, key, *row_ttl, default_ttl);
             }
+            reply_index.push(idx);
+            targets.push(target.map(|(key, _)| (key, *row_ttl)));
         }
 
-        if has_cmds {
-            pipe.query::<()>(cluster_conn)
-                .map_err(|e| format!("Redis cluster batch insert pipeline failed: {}", e))?;
+        if num_cmds == 0 {
+            return Ok(vec![false; rows.len()]);
+        }
+        let replies: Vec<redis::Value> = pipe
+            .query(cluster_conn)
+            .map_err(|e| format!("Redis cluster batch insert pipeline failed: {}", e))?;
+        let applied = Self::batch_applied_rows(&reply_index, &replies, insert_mode);
+
+        if insert_mode != InsertMode::Overwrite {
+            let mut ttl_pipe = redis::cluster::cluster_pipe();
+            let mut has_ttl = false;
+            for (target, _) in targets.iter().zip(&applied).filter(|(_, a)| **a) {
+                if let Some((key, row_ttl)) = target {
+                    has_ttl |= Self::add_ttl_to_cluster_pipeline(
+                        &mut ttl_pipe,
+                        key,
+                        *row_ttl,
+                        default_ttl,
+                    );
+                }
+            }
+            if has_ttl {
+                ttl_pipe.query::<()>(cluster_conn).map_err(|e| {
+                    format!("Redis cluster batch insert TTL pipeline failed: {}", e)
+                })?;
+            }
         }
 
-        Ok(())
+        Ok(applied)
     }
 
-    fn add_insert_to_pipeline(
-        pipe: &mut redis::Pipeline,
+    /// Insert command for one batch row, honoring `insert_mode`. Shared by
+    /// the standalone and cluster pipeline builders.
+    fn insert_command_for_row(
         table_type: &RedisTableType,
         key: &str,
         data: &[String],
-    ) -> bool {
+        insert_mode: InsertMode,
+    ) -> Option<redis::Cmd> {
         match table_type {
-            RedisTableType::Hash(_) => {
-                if data.len() >= 2 {
-                    pipe.cmd("HSET").arg(key).arg(&data[0]).arg(&data[1]);
-                    return true;
-                }
+            RedisTableType::Hash(t) if data.len() >= 2 => {
+                t.insert_command(key, &data[..2], insert_mode)
             }
-            RedisTableType::List(_) => {
-                if !data.is_empty() {
-                    pipe.cmd("RPUSH").arg(key).arg(&data[0]);
-                    return true;
-                }
+            RedisTableType::List(t) if !data.is_empty() => {
+                t.insert_command(key, &data[..1], insert_mode)
             }
-            RedisTableType::Set(_) => {
-                if !data.is_empty() {
-                    pipe.cmd("SADD").arg(key).arg(&data[0]);
-                    return true;
-                }
+            RedisTableType::Set(t) if !data.is_empty() => {
+                t.insert_command(key, &data[..1], insert_mode)
             }
-            RedisTableType::ZSet(_) => {
-                if data.len() >= 2 {
-                    if data[1].parse::<f64>().is_ok() {
-                        pipe.cmd("ZADD").arg(key).arg(&data[1]).arg(&data[0]);
-                        return true;
-                    } else {
-                        pgrx::warning!(
-                            "ZSet batch insert: invalid score '{}' for member '{}', row skipped",
-                            data[1],
-                            data[0]
-                        );
-                    }
+            RedisTableType::ZSet(t) if data.len() >= 2 => {
+                if data[1].parse::<f64>().is_err() {
+                    pgrx::warning!(
+                        "ZSet batch insert: invalid score '{}' for member '{}', row skipped",
+                        data[1],
+                        data[0]
+                    );
+                    return None;
                 }
+                t.insert_command(key, &data[..2], insert_mode)
             }
-            RedisTableType::String(_) => {
-                if !data.is_empty() {
-                    pipe.cmd("SET").arg(key).arg(&data[0]);
-                    return true;
-                }
+            RedisTableType::String(t) if !data.is_empty() => {
+                t.insert_command(key, &data[..1], insert_mode)
             }
-            RedisTableType::Stream(_) => {
+            RedisTableType::Stream(t) if t.key_column => t
+                .multi_key_entry(data)
+                .map(|(id, fields)| t.xadd_command(key, id, &fields, insert_mode)),
+            RedisTableType::Stream(t) if data.len() >= 3 => {
                 // data format after transform: [id, field1, val1, field2, val2, ...]
-                if data.len() >= 3 {
-                    let id = if data[0] == "*" || data[0].contains('-') {
-                        data[0].as_str()
-                    } else {
-                        "*"
-                    };
-                    let mut cmd = redis::cmd("XADD");
-                    cmd.arg(key).arg(id);
-                    for chunk in data[1..].chunks(2) {
-                        if chunk.len() == 2 {
-                            cmd.arg(&chunk[0]).arg(&chunk[1]);
-                        }
-                    }
-                    pipe.add_command(cmd);
-                    return true;
+                let id = if data[0] == "*" || data[0].contains('-') {
+                    data[0].as_str()
+                } else {
+                    "*"
+                };
+                let fields: Vec<(String, String)> = data[1..]
+                    .chunks_exact(2)
+                    .map(|chunk| (chunk[0].clone(), chunk[1].clone()))
+                    .collect();
+                Some(t.xadd_command(key, id, &fields, insert_mode))
+            }
+            RedisTableType::HashRow(t) => t.insert_command(key, data, insert_mode),
+            RedisTableType::Json(t) => t.insert_command(key, data, insert_mode),
+            RedisTableType::Geo(t) => {
+                let cmd = t.insert_command(key, data, insert_mode);
+                if cmd.is_none() {
+                    pgrx::warning!(
+                        "Geo batch insert: invalid coordinates for member '{}', row skipped",
+                        data.first().map_or("", String::as_str)
+                    );
+                }
+                cmd
+            }
+            RedisTableType::Bitmap(t) => {
+                let cmd = t.insert_command(key, data, insert_mode);
+                if cmd.is_none() {
+                    pgrx::warning!(
+                        "Bitmap batch insert: invalid offset or bit in row {:?}, row skipped",
+                        data
+                    );
                 }
+                cmd
             }
-            RedisTableType::None => {}
+            RedisTableType::HyperLogLog(t) => Some(t.insert_command(key, data, insert_mode)),
+            RedisTableType::PubSub(t) => t.publish_command(data),
+            RedisTableType::Function(t) => t.write_command(key, "insert", data),
+            _ => None,
+        }
+    }
+
+    fn add_insert_to_pipeline(
+        pipe: &mut redis::Pipeline,
+        table_type: &RedisTableType,
+        key: &str,
+        data: &[String],
+        insert_mode: InsertMode,
+    ) -> bool {
+        match Self::insert_command_for_row(table_type, key, data, insert_mode) {
+            Some(cmd) => {
+                pipe.add_command(cmd);
+                true
+            }
+            None => false,
         }
-        false
     }
 
     fn add_insert_to_cluster_pipeline(
@@ -1150,68 +1755,15 @@ impl RedisFdwState {
         table_type: &RedisTableType,
         key: &str,
         data: &[String],
+        insert_mode: InsertMode,
     ) -> bool {
-        match table_type {
-            RedisTableType::Hash(_) => {
-                if data.len() >= 2 {
-                    pipe.cmd("HSET").arg(key).arg(&data[0]).arg(&data[1]);
-                    return true;
-                }
-            }
-            RedisTableType::List(_) => {
-                if !data.is_empty() {
-                    pipe.cmd("RPUSH").arg(key).arg(&data[0]);
-                    return true;
-                }
-            }
-            RedisTableType::Set(_) => {
-                if !data.is_empty() {
-                    pipe.cmd("SADD").arg(key).arg(&data[0]);
-                    return true;
-                }
-            }
-            RedisTableType::ZSet(_) => {
-                if data.len() >= 2 {
-                    if data[1].parse::<f64>().is_ok() {
-                        pipe.cmd("ZADD").arg(key).arg(&data[1]).arg(&data[0]);
-                        return true;
-                    } else {
-                        pgrx::warning!(
-                            "ZSet batch insert: invalid score '{}' for member '{}', row skipped",
-                            data[1],
-                            data[0]
-                        );
-                    }
-                }
+        match Self::insert_command_for_row(table_type, key, data, insert_mode) {
+            Some(cmd) => {
+                pipe.add_command(cmd);
+                true
             }
-            RedisTableType::String(_) => {
-                if !data.is_empty() {
-                    pipe.cmd("SET").arg(key).arg(&data[0]);
-                    return true;
-                }
-            }
-            RedisTableType::Stream(_) => {
-                // data format after transform: [id, field1, val1, field2, val2, ...]
-                if data.len() >= 3 {
-                    let id = if data[0] == "*" || data[0].contains('-') {
-                        data[0].as_str()
-                    } else {
-                        "*"
-                    };
-                    let mut cmd = redis::cmd("XADD");
-                    cmd.arg(key).arg(id);
-                    for chunk in data[1..].chunks(2) {
-                        if chunk.len() == 2 {
-                            cmd.arg(&chunk[0]).arg(&chunk[1]);
-                        }
-                    }
-                    pipe.add_command(cmd);
-                    return true;
-                }
-            }
-            RedisTableType::None => {}
+            None => false,
         }
-        false
     }
 
     fn add_ttl_to_pipeline(
@@ -1264,19 +1816,26 @@ impl RedisFdwState {
         }
     }
 
-    /// Delete a Redis key directly (for multi-key mode DELETE).
-    pub fn delete_key(&mut self, key: &str) -> Result<(), redis::RedisError> {
-        if let Some(conn) = self.redis_connection.as_mut() {
-            let conn_like = conn.as_connection_like_mut();
-            redis::cmd("DEL").arg(key).query::<()>(conn_like)
-        } else {
-            Err(redis::RedisError::from((
-                redis::ErrorKind::Io,
-                "Redis connection not initialized",
-            )))
+    /// Delete a Redis key directly (for multi-key mode DELETE). A hash_row
+    /// key only loses the mapped fields.
+    /// Number of set bits of the table's bitmap key, for a pushed-down
+    /// `count(*)`.
+    pub fn count_set_bits(&mut self) -> Result<i64, redis::RedisError> {
+        match self.redis_connection.as_mut() {
+            Some(conn) => redis::cmd("BITCOUNT")
+                .arg(&self.table_key_prefix)
+                .query(conn.as_connection_like_mut()),
+            None => Ok(0),
         }
     }
 
+    pub fn delete_key(&mut self, key: &str) -> Result<(), redis::RedisError> {
+        self.run_write(|conn, table_type, _| match table_type {
+            RedisTableType::HashRow(t) => t.delete(conn, key, &[]),
+            _ => redis::cmd("DEL").arg(key).query::<()>(conn),
+        })
+    }
+
     /// Execute a parameterized point-lookup for a single value.
     /// Consults `join_batch_cache` first; on miss, calls the per-type
     /// `batch_parameterized_lookup` for one element and caches the result.
@@ -1309,7 +1868,7 @@ impl RedisFdwState {
         // batch_parameterized_lookup returns Result so per-type impls don't
         // call pgrx::error! (which longjmps past Rust destructors). We handle
         // the Err here at the FDW boundary, where the call stack is shallow.
-        let lookup_result: Result<Vec<Option<Vec<String>>>, redis::RedisError> = {
+        let lookup_result: Result<Vec<Option<Vec<RowValue>>>, redis::RedisError> = {
             let conn = match self.redis_connection.as_mut() {
                 Some(c) => c.as_connection_like_mut(),
                 None => return false,
@@ -1322,6 +1881,14 @@ impl RedisFdwState {
                 RT::Set(t) => t.batch_parameterized_lookup(conn, key_prefix, &params),
                 RT::ZSet(t) => t.batch_parameterized_lookup(conn, key_prefix, &params),
                 RT::Stream(t) => t.batch_parameterized_lookup(conn, key_prefix, &params),
+                RT::HashRow(t) => t.batch_parameterized_lookup(conn, key_prefix, &params),
+                RT::Json(t) => t.batch_parameterized_lookup(conn, key_prefix, &params),
+                RT::Geo(t) => t.batch_parameterized_lookup(conn, key_prefix, &params),
+                RT::Bitmap(t) => t.batch_parameterized_lookup(conn, key_prefix, &params),
+                RT::HyperLogLog(t) => t.batch_parameterized_lookup(conn, key_prefix, &params),
+                RT::PubSub(t) => t.batch_parameterized_lookup(conn, key_prefix, &params),
+                RT::Keyspace(t) => t.batch_parameterized_lookup(conn, key_prefix, &params),
+                RT::Function(t) => t.batch_parameterized_lookup(conn, key_prefix, &params),
                 RT::None => Ok(vec![None]),
             }
         };
@@ -1349,7 +1916,7 @@ impl RedisFdwState {
     /// Take a (possibly cached) lookup result, apply WHERE filters, and
     /// materialize it onto the type-specific dataset. Returns true if the
     /// row survived filtering, false otherwise.
-    fn apply_cached_lookup(&mut self, row: Option<Vec<String>>) -> bool {
+    fn apply_cached_lookup(&mut self, row: Option<Vec<RowValue>>) -> bool {
         let Some(row) = row else {
             self.table_type.clear_data();
             return false;
@@ -1357,7 +1924,14 @@ impl RedisFdwState {
 
         if let Some(analysis) = self.pushdown_analysis.as_ref() {
             for cond in &analysis.pushable_conditions {
-                if !row_matches_condition(&row, cond, self.ttl_column_index) {
+                let matches = row_matches_condition(
+                    &row,
+                    cond,
+                    self.ttl_column_index,
+                    &self.column_encodings,
+                    self.is_multi_key,
+                );
+                if !matches {
                     self.table_type.clear_data();
                     return false;
                 }
@@ -1380,12 +1954,19 @@ impl RedisFdwState {
 ///   the row's TTL here; PG will filter)
 /// - if cond targets a column AFTER the TTL → subtract one
 ///
+/// Equality and IN compare the stored bytes with the condition value encoded
+/// as the column's `encodings` entry (the multi-key key column is text);
+/// ranges and LIKE compare text.
+///
 /// Returns true if the row matches (or if the translated index is out of
-/// range — be permissive rather than drop legitimate data).
+/// range — be permissive rather than drop legitimate data). A NULL cell
+/// matches nothing.
 fn row_matches_condition(
-    row: &[String],
+    row: &[RowValue],
     cond: &crate::query::pushdown_types::PushableCondition,
     ttl_column_index: Option<usize>,
+    encodings: &ColumnEncodings,
+    is_multi_key: bool,
 ) -> bool {
     use crate::query::pushdown_types::ComparisonOperator;
     let mut col_idx = cond.column_index;
@@ -1398,12 +1979,21 @@ fn row_matches_condition(
         }
     }
     let cell = match row.get(col_idx) {
-        Some(s) => s,
+        Some(Some(value)) => value.as_slice(),
+        Some(None) => return false,
         None => return true,
     };
+    let encoding = match (is_multi_key, col_idx) {
+        (true, 0) => ValueEncoding::Text,
+        (true, i) => encodings.get(i - 1),
+        (false, i) => encodings.get(i),
+    };
+    let is_value = |val: &str| *cell == *encoding.encode(val);
+    let text = String::from_utf8_lossy(cell);
+    let cell = text.as_ref();
     match cond.operator {
-        ComparisonOperator::Equal => cell == &cond.value,
-        ComparisonOperator::NotEqual => cell != &cond.value,
+        ComparisonOperator::Equal => is_value(&cond.value),
+        ComparisonOperator::NotEqual => !is_value(&cond.value),
         ComparisonOperator::GreaterThan
         | ComparisonOperator::GreaterThanOrEqual
         | ComparisonOperator::LessThan
@@ -1418,17 +2008,62 @@ fn row_matches_condition(
                     _ => unreachable!(),
                 },
                 _ => match cond.operator {
-                    ComparisonOperator::GreaterThan => cell.as_str() > cond.value.as_str(),
-                    ComparisonOperator::GreaterThanOrEqual => cell.as_str() >= cond.value.as_str(),
-                    ComparisonOperator::LessThan => cell.as_str() < cond.value.as_str(),
-                    ComparisonOperator::LessThanOrEqual => cell.as_str() <= cond.value.as_str(),
+                    ComparisonOperator::GreaterThan => cell > cond.value.as_str(),
+                    ComparisonOperator::GreaterThanOrEqual => cell >= cond.value.as_str(),
+                    ComparisonOperator::LessThan => cell < cond.value.as_str(),
+                    ComparisonOperator::LessThanOrEqual => cell <= cond.value.as_str(),
                     _ => unreachable!(),
                 },
             }
         }
         ComparisonOperator::Like => PatternMatcher::from_like_pattern(&cond.value).matches(cell),
-        ComparisonOperator::In => cond.value.split(',').any(|val| cell == val),
-        ComparisonOperator::NotIn => !cond.value.split(',').any(|val| cell == val),
+        ComparisonOperator::In => cond.value.split(',').any(is_value),
+        ComparisonOperator::NotIn => !cond.value.split(',').any(is_value),
+        // The search is rechecked by PostgreSQL with the SQL function.
+        ComparisonOperator::GeoRadius { .. } | ComparisonOperator::GeoBox { .. } => true,
+    }
+}
+
+/// Consumer-group reading of a stream table, from the `consumer_group`,
+/// `consumer_name`, `create_group` and `claim_idle_ms` options.
+fn stream_consumer(opts: &HashMap<String, String>) -> Option<StreamConsumer> {
+    let group = opts.get("consumer_group")?;
+    let Some(consumer) = opts.get("consumer_name") else {
+        error!("redis_fdw: consumer_group requires the consumer_name option");
+    };
+    let claim_idle_ms = match opts.get("claim_idle_ms") {
+        Some(ms) => match ms.parse::<u64>() {
+            Ok(ms) => Some(ms),
+            Err(_) => error!("redis_fdw: claim_idle_ms must be a non-negative integer, got '{ms}'"),
+        },
+        None => None,
+    };
+    Some(StreamConsumer {
+        group: group.clone(),
+        consumer: consumer.clone(),
+        create_group: opts.get("create_group").is_some_and(|v| v == "true"),
+        claim_idle_ms,
+    })
+}
+
+/// Trimming every XADD of a stream table applies, from the `stream_maxlen`
+/// or `stream_minid_age` option.
+fn stream_trim(opts: &HashMap<String, String>) -> Option<StreamTrim> {
+    match StreamTrim::from_options(opts) {
+        Ok(trim) => trim,
+        Err(e) => error!("redis_fdw: {e}"),
+    }
+}
+
+/// The function or script option pair of a function table.
+fn function_callable(
+    opts: &HashMap<String, String>,
+    function_option: &str,
+    script_option: &str,
+) -> Option<RedisCallable> {
+    match RedisCallable::from_options(opts, function_option, script_option) {
+        Ok(callable) => callable,
+        Err(e) => error!("redis_fdw: {e}"),
     }
 }
 
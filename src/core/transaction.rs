@@ -0,0 +1,701 @@
+//! Transactional write buffering.
+//!
+//! With `transactional 'true'`, writes issued through `RedisFdwState` are not
+//! sent to Redis immediately. They are captured by [`BufferedConnection`] and
+//! queued per server; the transaction callback registered from `_PG_init`
+//! flushes every server's queue as one MULTI/EXEC block at pre-commit and
+//! drops the queues on abort.
+//!
+//! Each queue is stacked by subtransaction nesting level (see
+//! [`SavepointQueue`]) so that `ROLLBACK TO SAVEPOINT` — and PL/pgSQL
+//! `EXCEPTION` blocks, which are subtransactions — discard only the commands
+//! queued after the savepoint, while `RELEASE` merges them into the parent.
+
+use crate::core::{
+    connection_factory::{RedisConnectionConfig, RedisConnectionFactory},
+    optimistic,
+};
+use pgrx::prelude::*;
+use redis::{ConnectionLike, ErrorKind, RedisError, RedisResult, Value};
+use std::sync::Mutex;
+
+/// Commands that only read, which write paths use to look up state (e.g.
+/// LPOS before LSET). They are sent straight through a `BufferedConnection`;
+/// anything else is queued, so a write missing here still waits for COMMIT.
+const READ_COMMANDS: &[&str] = &[
+    "BITCOUNT",
+    "BITPOS",
+    "EXISTS",
+    "GEODIST",
+    "GEOHASH",
+    "GEOPOS",
+    "GEOSEARCH",
+    "GET",
+    "GETBIT",
+    "GETRANGE",
+    "HEXISTS",
+    "HGET",
+    "HGETALL",
+    "HKEYS",
+    "HLEN",
+    "HMGET",
+    "HSCAN",
+    "HVALS",
+    "JSON.GET",
+    "JSON.TYPE",
+    "LINDEX",
+    "LLEN",
+    "LPOS",
+    "LRANGE",
+    "MGET",
+    "PFCOUNT",
+    "PTTL",
+    "SCAN",
+    "SCARD",
+    "SISMEMBER",
+    "SMEMBERS",
+    "SMISMEMBER",
+    "SSCAN",
+    "STRLEN",
+    "TTL",
+    "TYPE",
+    "XLEN",
+    "XPENDING",
+    "XRANGE",
+    "XREVRANGE",
+    "ZCARD",
+    "ZCOUNT",
+    "ZMSCORE",
+    "ZRANGE",
+    "ZRANGEBYLEX",
+    "ZRANGEBYSCORE",
+    "ZRANK",
+    "ZSCAN",
+    "ZSCORE",
+];
+
+/// Commands grouped into frames by subtransaction nesting level, oldest
+/// first. Levels follow `GetCurrentTransactionNestLevel()`: 1 is the
+/// top-level transaction, each open savepoint adds one.
+#[derive(Debug)]
+pub struct SavepointQueue<T> {
+    frames: Vec<(i32, Vec<T>)>,
+}
+
+impl<T> Default for SavepointQueue<T> {
+    fn default() -> Self {
+        Self { frames: Vec::new() }
+    }
+}
+
+impl<T> SavepointQueue<T> {
+    /// Queue `items` in the frame of subtransaction `level`.
+    pub fn push(&mut self, level: i32, items: impl IntoIterator<Item = T>) {
+        // Frames deeper than `level` belong to subtransactions that have
+        // already ended without a callback reaching us; fold them in.
+        self.release(level + 1);
+        match self.frames.last_mut() {
+            Some((l, frame)) if *l == level => frame.extend(items),
+            _ => self.frames.push((level, items.into_iter().collect())),
+        }
+    }
+
+    /// RELEASE of the subtransaction at `level`: its commands (and those of
+    /// any deeper level) now belong to the parent.
+    pub fn release(&mut self, level: i32) {
+        let Some(split) = self.frames.iter().position(|(l, _)| *l >= level) else {
+            return;
+        };
+        let merged: Vec<T> = self
+            .frames
+            .drain(split..)
+            .flat_map(|(_, frame)| frame)
+            .collect();
+        if merged.is_empty() {
+            return;
+        }
+        match self.frames.last_mut() {
+            Some((l, frame)) if *l == level - 1 => frame.extend(merged),
+            _ => self.frames.push((level - 1, merged)),
+        }
+    }
+
+    /// ROLLBACK TO SAVEPOINT for the subtransaction at `level`: drop every
+    /// command queued at that level or deeper.
+    pub fn rollback(&mut self, level: i32) {
+        self.frames.retain(|(l, _)| *l < level);
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.iter().map(|(_, frame)| frame.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// All commands in issue order.
+    pub fn into_items(self) -> Vec<T> {
+        self.frames
+            .into_iter()
+            .flat_map(|(_, frame)| frame)
+            .collect()
+    }
+}
+
+/// Pending writes for one Redis server.
+struct ServerQueue {
+    server_key: String,
+    config: RedisConnectionConfig,
+    commands: SavepointQueue<redis::Cmd>,
+}
+
+/// Per-backend write queues, in first-write order. PostgreSQL backends are
+/// single-threaded; the mutex only satisfies `static` requirements.
+static PENDING_WRITES: Mutex<Vec<ServerQueue>> = Mutex::new(Vec::new());
+
+fn with_pending<T>(f: impl FnOnce(&mut Vec<ServerQueue>) -> T) -> T {
+    let mut guard = PENDING_WRITES
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    f(&mut guard)
+}
+
+/// Identity of a server connection: one MULTI/EXEC block is sent per key.
+pub fn server_key(config: &RedisConnectionConfig) -> String {
+    format!(
+        "{}/{}/{}",
+        config.host_port,
+        config.database,
+        config.auth_config.cache_key()
+    )
+}
+
+/// Append commands to the queue of the server described by `config`, in the
+/// frame of the current subtransaction.
+pub fn enqueue(config: &RedisConnectionConfig, commands: Vec<redis::Cmd>) {
+    if commands.is_empty() {
+        return;
+    }
+    let level = unsafe { pg_sys::GetCurrentTransactionNestLevel() };
+    let key = server_key(config);
+    with_pending(|pending| {
+        let index = match pending.iter().position(|q| q.server_key == key) {
+            Some(i) => i,
+            None => {
+                pending.push(ServerQueue {
+                    server_key: key,
+                    config: config.clone(),
+                    commands: SavepointQueue::default(),
+                });
+                pending.len() - 1
+            }
+        };
+        pending[index].commands.push(level, commands);
+    });
+}
+
+/// Number of commands waiting to be flushed, across all servers.
+pub fn pending_command_count() -> usize {
+    with_pending(|pending| pending.iter().map(|q| q.commands.len()).sum())
+}
+
+/// Merge the commands of the subtransaction at `level` into its parent.
+fn release_savepoint(level: i32) {
+    with_pending(|pending| {
+        for queue in pending.iter_mut() {
+            queue.commands.release(level);
+        }
+    });
+}
+
+/// Drop the commands of the subtransaction at `level`.
+fn rollback_savepoint(level: i32) {
+    with_pending(|pending| {
+        for queue in pending.iter_mut() {
+            queue.commands.rollback(level);
+        }
+        pending.retain(|q| !q.commands.is_empty());
+    });
+}
+
+/// Drop all queued writes (transaction abort).
+pub fn discard_pending() {
+    with_pending(|pending| pending.clear());
+}
+
+fn take_pending() -> Vec<ServerQueue> {
+    with_pending(std::mem::take)
+}
+
+/// Send one server's queue as a single MULTI/EXEC block.
+fn flush_queue(queue: ServerQueue) -> Result<(), String> {
+    if queue.commands.is_empty() {
+        return Ok(());
+    }
+
+    let mut conn = RedisConnectionFactory::create_connection_with_retry(&queue.config)
+        .map_err(|e| format!("cannot connect to {}: {}", queue.config.host_port, e))?;
+
+    let mut pipe = redis::pipe();
+    pipe.atomic();
+    for cmd in queue.commands.into_items() {
+        pipe.add_command(cmd);
+    }
+
+    let replies: Vec<Value> = pipe
+        .query(conn.as_connection_like_mut())
+        .map_err(|e| format!("MULTI/EXEC on {} failed: {}", queue.config.host_port, e))?;
+
+    // EXEC does not roll back when a queued command fails at runtime
+    // (e.g. WRONGTYPE); surface the first failure instead of ignoring it.
+    if let Some(err) = replies.iter().find_map(|v| match v {
+        Value::ServerError(e) => Some(e),
+        _ => None,
+    }) {
+        return Err(format!(
+            "command in MULTI/EXEC on {} failed: {:?}",
+            queue.config.host_port, err
+        ));
+    }
+
+    Ok(())
+}
+
+/// Flush every server queue. Returns the first error; queues after a failed
+/// one are discarded since the PostgreSQL transaction is about to abort.
+fn flush_pending() -> Result<(), String> {
+    for queue in take_pending() {
+        flush_queue(queue)?;
+    }
+    Ok(())
+}
+
+pub unsafe fn init_xact_callback() {
+    pg_sys::RegisterXactCallback(Some(redis_xact_callback), std::ptr::null_mut());
+    pg_sys::RegisterSubXactCallback(Some(redis_subxact_callback), std::ptr::null_mut());
+}
+
+#[pg_guard]
+unsafe extern "C-unwind" fn redis_xact_callback(
+    event: pg_sys::XactEvent::Type,
+    _arg: *mut std::ffi::c_void,
+) {
+    match event {
+        pg_sys::XactEvent::XACT_EVENT_PRE_COMMIT => {
+            // Keep the error message as the only live allocation when
+            // error!() longjmps out of this frame.
+            if let Err(msg) = flush_pending() {
+                error!("redis_fdw: failed to commit buffered writes: {}", msg);
+            }
+        }
+        pg_sys::XactEvent::XACT_EVENT_PRE_PREPARE if pending_command_count() > 0 => {
+            error!("redis_fdw: cannot PREPARE a transaction that has buffered Redis writes");
+        }
+        pg_sys::XactEvent::XACT_EVENT_COMMIT | pg_sys::XactEvent::XACT_EVENT_PARALLEL_COMMIT => {
+            optimistic::release_all();
+        }
+        pg_sys::XactEvent::XACT_EVENT_ABORT | pg_sys::XactEvent::XACT_EVENT_PARALLEL_ABORT => {
+            discard_pending();
+            optimistic::release_all();
+        }
+        _ => {}
+    }
+}
+
+/// Subtransaction events fire while the ending subtransaction is still the
+/// current one, so the current nest level identifies its frame.
+#[pg_guard]
+unsafe extern "C-unwind" fn redis_subxact_callback(
+    event: pg_sys::SubXactEvent::Type,
+    _my_subid: pg_sys::SubTransactionId,
+    _parent_subid: pg_sys::SubTransactionId,
+    _arg: *mut std::ffi::c_void,
+) {
+    match event {
+        pg_sys::SubXactEvent::SUBXACT_EVENT_COMMIT_SUB => {
+            release_savepoint(pg_sys::GetCurrentTransactionNestLevel());
+        }
+        pg_sys::SubXactEvent::SUBXACT_EVENT_ABORT_SUB => {
+            rollback_savepoint(pg_sys::GetCurrentTransactionNestLevel());
+        }
+        _ => {}
+    }
+}
+
+/// `ConnectionLike` wrapper that records commands instead of sending them.
+/// The reads in [`READ_COMMANDS`] pass through to the wrapped connection so
+/// that write paths which look up state first (e.g. LPOS before LSET) keep
+/// working.
+///
+/// Buffered writes get a placeholder `Int(1)` reply, which converts to
+/// the `()`/integer/string types the table write paths ask for and makes
+/// a buffered insert count as applied.
+pub struct BufferedConnection<'a> {
+    inner: &'a mut dyn ConnectionLike,
+    queued: Vec<redis::Cmd>,
+}
+
+impl<'a> BufferedConnection<'a> {
+    pub fn new(inner: &'a mut dyn ConnectionLike) -> Self {
+        Self {
+            inner,
+            queued: Vec::new(),
+        }
+    }
+
+    /// Commands captured so far, in issue order.
+    pub fn into_commands(self) -> Vec<redis::Cmd> {
+        self.queued
+    }
+
+    fn queue(&mut self, args: Vec<Vec<u8>>) {
+        let mut cmd = redis::Cmd::new();
+        for arg in args {
+            cmd.arg(arg);
+        }
+        self.queued.push(cmd);
+    }
+}
+
+impl ConnectionLike for BufferedConnection<'_> {
+    fn req_packed_command(&mut self, cmd: &[u8]) -> RedisResult<Value> {
+        let mut commands = parse_packed_commands(cmd)?;
+        match commands.pop() {
+            Some(args) if commands.is_empty() && !is_read_command(&args) => {
+                self.queue(args);
+                Ok(Value::Int(1))
+            }
+            _ => self.inner.req_packed_command(cmd),
+        }
+    }
+
+    fn req_packed_commands(
+        &mut self,
+        cmd: &[u8],
+        offset: usize,
+        count: usize,
+    ) -> RedisResult<Vec<Value>> {
+        // Replay the pipeline command by command so the reply layout matches
+        // what redis-rs expects, including MULTI/EXEC wrapping from
+        // `pipe().atomic()` (nested MULTI is not allowed, so it is dropped).
+        let mut replies = Vec::new();
+        let mut in_multi: Option<Vec<Value>> = None;
+        for args in parse_packed_commands(cmd)? {
+            let name = command_name(&args);
+            if name.eq_ignore_ascii_case("MULTI") {
+                in_multi = Some(Vec::new());
+                replies.push(Value::Okay);
+                continue;
+            }
+            if name.eq_ignore_ascii_case("EXEC") {
+                replies.push(Value::Array(in_multi.take().unwrap_or_default()));
+                continue;
+            }
+
+            let reply = if !is_read_command(&args) {
+                self.queue(args);
+                Value::Int(1)
+            } else {
+                let mut read = redis::Cmd::new();
+                for arg in args {
+                    read.arg(arg);
+                }
+                self.inner.req_packed_command(&read.get_packed_command())?
+            };
+
+            match in_multi.as_mut() {
+                Some(results) => {
+                    results.push(reply);
+                    replies.push(Value::SimpleString("QUEUED".to_string()));
+                }
+                None => replies.push(reply),
+            }
+        }
+
+        Ok(replies.into_iter().skip(offset).take(count).collect())
+    }
+
+    fn get_db(&self) -> i64 {
+        self.inner.get_db()
+    }
+
+    fn check_connection(&mut self) -> bool {
+        self.inner.check_connection()
+    }
+
+    fn is_open(&self) -> bool {
+        self.inner.is_open()
+    }
+}
+
+fn command_name(args: &[Vec<u8>]) -> &str {
+    args.first()
+        .and_then(|a| std::str::from_utf8(a).ok())
+        .unwrap_or("")
+}
+
+fn is_read_command(args: &[Vec<u8>]) -> bool {
+    let name = command_name(args);
+    READ_COMMANDS.iter().any(|r| r.eq_ignore_ascii_case(name))
+}
+
+fn protocol_error(detail: &str) -> RedisError {
+    RedisError::from((
+        ErrorKind::Client,
+        "Cannot decode buffered Redis command",
+        detail.to_string(),
+    ))
+}
+
+/// Split a RESP-encoded request (`*<n>\r\n$<len>\r\n<arg>\r\n...`, possibly
+/// several back to back) into per-command argument lists.
+fn parse_packed_commands(mut buf: &[u8]) -> RedisResult<Vec<Vec<Vec<u8>>>> {
+    fn read_line(buf: &mut &[u8], marker: u8) -> RedisResult<usize> {
+        if buf.first() != Some(&marker) {
+            return Err(protocol_error("unexpected RESP marker"));
+        }
+        let end = buf
+            .windows(2)
+            .position(|w| w == b"\r\n")
+            .ok_or_else(|| protocol_error("missing CRLF"))?;
+        let n = std::str::from_utf8(&buf[1..end])
+            .ok()
+            .and_then(|s| s.parse::<usize>().ok())
+            .ok_or_else(|| protocol_error("invalid length"))?;
+        *buf = &buf[end + 2..];
+        Ok(n)
+    }
+
+    let mut commands = Vec::new();
+    while !buf.is_empty() {
+        let argc = read_line(&mut buf, b'*')?;
+        let mut args = Vec::with_capacity(argc);
+        for _ in 0..argc {
+            let len = read_line(&mut buf, b'$')?;
+            if buf.len() < len + 2 {
+                return Err(protocol_error("truncated argument"));
+            }
+            args.push(buf[..len].to_vec());
+            buf = &buf[len + 2..];
+        }
+        commands.push(args);
+    }
+    Ok(commands)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Answers every read with `Int(7)` and counts how many reached it.
+    #[derive(Default)]
+    struct ReadConn {
+        reads: usize,
+    }
+
+    impl ConnectionLike for ReadConn {
+        fn req_packed_command(&mut self, _cmd: &[u8]) -> RedisResult<Value> {
+            self.reads += 1;
+            Ok(Value::Int(7))
+        }
+        fn req_packed_commands(
+            &mut self,
+            _cmd: &[u8],
+            _offset: usize,
+            count: usize,
+        ) -> RedisResult<Vec<Value>> {
+            self.reads += count;
+            Ok(vec![Value::Int(7); count])
+        }
+        fn get_db(&self) -> i64 {
+            0
+        }
+        fn check_connection(&mut self) -> bool {
+            true
+        }
+        fn is_open(&self) -> bool {
+            true
+        }
+    }
+
+    fn packed(cmd: &redis::Cmd) -> Vec<u8> {
+        cmd.get_packed_command()
+    }
+
+    #[test]
+    fn test_parse_packed_commands_roundtrip() {
+        let mut bytes = redis::cmd("HSET")
+            .arg("k")
+            .arg("f")
+            .arg("v\r\n")
+            .get_packed_command();
+        bytes.extend(redis::cmd("DEL").arg("k").get_packed_command());
+        let parsed = parse_packed_commands(&bytes).unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[0][0], b"HSET");
+        assert_eq!(parsed[0][3], b"v\r\n");
+        assert_eq!(parsed[1], vec![b"DEL".to_vec(), b"k".to_vec()]);
+    }
+
+    #[test]
+    fn test_parse_packed_commands_rejects_garbage() {
+        assert!(parse_packed_commands(b"GET k\r\n").is_err());
+        assert!(parse_packed_commands(b"*1\r\n$5\r\nGET\r\n").is_err());
+    }
+
+    #[test]
+    fn test_write_is_queued_read_passes_through() {
+        let mut inner = ReadConn::default();
+        let mut conn = BufferedConnection::new(&mut inner);
+
+        let _: () = redis::cmd("SET")
+            .arg("k")
+            .arg("v")
+            .query(&mut conn)
+            .unwrap();
+        let pos: i64 = redis::cmd("LPOS")
+            .arg("l")
+            .arg("x")
+            .query(&mut conn)
+            .unwrap();
+        let added: i32 = redis::cmd("sadd")
+            .arg("s")
+            .arg("m")
+            .query(&mut conn)
+            .unwrap();
+
+        assert_eq!(pos, 7);
+        assert_eq!(added, 1);
+        let queued = conn.into_commands();
+        assert_eq!(queued.len(), 2);
+        assert_eq!(
+            packed(&queued[0]),
+            packed(redis::cmd("SET").arg("k").arg("v"))
+        );
+        assert_eq!(
+            packed(&queued[1]),
+            packed(redis::cmd("sadd").arg("s").arg("m"))
+        );
+        assert_eq!(inner.reads, 1);
+    }
+
+    #[test]
+    fn test_unlisted_command_is_queued() {
+        let mut inner = ReadConn::default();
+        let mut conn = BufferedConnection::new(&mut inner);
+
+        let _: () = redis::cmd("COPY")
+            .arg("a")
+            .arg("b")
+            .query(&mut conn)
+            .unwrap();
+
+        assert_eq!(conn.into_commands().len(), 1);
+        assert_eq!(inner.reads, 0);
+    }
+
+    #[test]
+    fn test_pipeline_writes_are_queued() {
+        let mut inner = ReadConn::default();
+        let mut conn = BufferedConnection::new(&mut inner);
+
+        let mut pipe = redis::pipe();
+        pipe.cmd("LREM").arg("l").arg(0).arg("a");
+        pipe.cmd("LREM").arg("l").arg(0).arg("b");
+        let removed: Vec<i32> = pipe.query(&mut conn).unwrap();
+
+        assert_eq!(removed, vec![1, 1]);
+        assert_eq!(conn.into_commands().len(), 2);
+        assert_eq!(inner.reads, 0);
+    }
+
+    #[test]
+    fn test_atomic_pipeline_is_flattened() {
+        let mut inner = ReadConn::default();
+        let mut conn = BufferedConnection::new(&mut inner);
+
+        redis::pipe()
+            .atomic()
+            .cmd("HDEL")
+            .arg("h")
+            .arg("old")
+            .cmd("HSET")
+            .arg("h")
+            .arg("new")
+            .arg("v")
+            .query::<()>(&mut conn)
+            .unwrap();
+
+        let queued = conn.into_commands();
+        assert_eq!(queued.len(), 2);
+        assert_eq!(
+            packed(&queued[0]),
+            packed(redis::cmd("HDEL").arg("h").arg("old"))
+        );
+        assert_eq!(
+            packed(&queued[1]),
+            packed(redis::cmd("HSET").arg("h").arg("new").arg("v"))
+        );
+    }
+
+    #[test]
+    fn test_savepoint_queue_rollback_drops_only_inner_frames() {
+        let mut q = SavepointQueue::default();
+        q.push(1, ["a"]);
+        q.push(2, ["b"]);
+        q.push(3, ["c"]);
+        q.rollback(3);
+        assert_eq!(q.len(), 2);
+        q.push(3, ["d"]);
+        q.rollback(2);
+        q.push(1, ["e"]);
+        assert_eq!(q.into_items(), vec!["a", "e"]);
+    }
+
+    #[test]
+    fn test_savepoint_queue_release_merges_into_parent() {
+        let mut q = SavepointQueue::default();
+        q.push(1, ["a"]);
+        q.push(2, ["b"]);
+        q.push(3, ["c"]);
+        q.release(3);
+        q.release(2);
+        // Everything now belongs to the top level, so rolling back a new
+        // savepoint must keep it.
+        q.push(2, ["d"]);
+        q.rollback(2);
+        assert_eq!(q.into_items(), vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_savepoint_queue_release_then_rollback_parent() {
+        let mut q = SavepointQueue::default();
+        q.push(1, ["a"]);
+        q.push(2, ["b"]);
+        q.push(3, ["c"]);
+        q.release(3);
+        q.rollback(2);
+        assert_eq!(q.into_items(), vec!["a"]);
+    }
+
+    #[test]
+    fn test_savepoint_queue_push_folds_stale_deeper_frames() {
+        let mut q = SavepointQueue::default();
+        q.push(2, ["a"]);
+        q.push(1, ["b"]);
+        q.rollback(2);
+        assert_eq!(q.into_items(), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_server_key_distinguishes_database() {
+        let mut opts = std::collections::HashMap::new();
+        opts.insert("host_port".to_string(), "127.0.0.1:6379".to_string());
+        opts.insert("database".to_string(), "0".to_string());
+        let a = RedisConnectionConfig::from_options(&opts).unwrap();
+        opts.insert("database".to_string(), "1".to_string());
+        let b = RedisConnectionConfig::from_options(&opts).unwrap();
+        assert_ne!(server_key(&a), server_key(&b));
+    }
+}
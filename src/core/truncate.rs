@@ -1,6 +1,7 @@
 use crate::{
     core::connection_factory::{RedisConnectionConfig, RedisConnectionFactory},
     core::state_manager::is_multi_key_pattern,
+    core::transaction::{self, BufferedConnection},
     utils::helpers::get_foreign_table_options,
 };
 use pgrx::prelude::*;
@@ -21,6 +22,19 @@ pub(crate) unsafe extern "C-unwind" fn exec_foreign_truncate(
         let rel_list = pgrx::list::List::<*mut std::ffi::c_void>::downcast_ptr_in_memcx(rels, mcx)
             .expect("Failed to downcast rels list");
 
+        // Only the write function knows what its rows are stored as; refuse
+        // before any other table is truncated.
+        for rel_ptr in rel_list.iter() {
+            let relation = *rel_ptr as pg_sys::Relation;
+            if !relation.is_null()
+                && get_foreign_table_options((*relation).rd_id)
+                    .get("table_type")
+                    .is_some_and(|tt| tt.eq_ignore_ascii_case("function"))
+            {
+                error!("redis_fdw: TRUNCATE is not supported for function tables, use DELETE");
+            }
+        }
+
         for rel_ptr in rel_list.iter() {
             let relation = *rel_ptr as pg_sys::Relation;
             if relation.is_null() {
@@ -28,6 +42,13 @@ pub(crate) unsafe extern "C-unwind" fn exec_foreign_truncate(
             }
             let relid = (*relation).rd_id;
             let options = get_foreign_table_options(relid);
+            // Published messages are not stored: nothing to remove.
+            if options
+                .get("table_type")
+                .is_some_and(|tt| tt.eq_ignore_ascii_case("pubsub"))
+            {
+                continue;
+            }
 
             let config = match RedisConnectionConfig::from_options(&options) {
                 Ok(c) => c,
@@ -43,7 +64,15 @@ pub(crate) unsafe extern "C-unwind" fn exec_foreign_truncate(
                 }
             };
 
-            let conn_like = conn.as_connection_like_mut();
+            // In transactional mode the UNLINKs are queued for COMMIT like any
+            // other write; the SCAN reads still go straight to Redis.
+            let mut buffered = None;
+            let conn_like: &mut dyn redis::ConnectionLike =
+                if options.get("transactional").is_some_and(|v| v == "true") {
+                    buffered.insert(BufferedConnection::new(conn.as_connection_like_mut()))
+                } else {
+                    conn.as_connection_like_mut()
+                };
             let key_prefix = options.get("table_key_prefix").cloned().unwrap_or_default();
 
             if is_multi_key_pattern(&key_prefix) {
@@ -85,6 +114,10 @@ pub(crate) unsafe extern "C-unwind" fn exec_foreign_truncate(
             {
                 error!("Redis UNLINK failed for key '{}': {}", key_prefix, e);
             }
+
+            if let Some(buffered) = buffered {
+                transaction::enqueue(&config, buffered.into_commands());
+            }
         }
     });
 }
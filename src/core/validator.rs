@@ -2,9 +2,35 @@ use pgrx::pg_sys;
 use pgrx::prelude::*;
 use std::collections::HashMap;
 
-const VALID_TABLE_TYPES: &[&str] = &["string", "hash", "list", "set", "zset", "stream"];
+const VALID_TABLE_TYPES: &[&str] = &[
+    "string",
+    "hash",
+    "list",
+    "set",
+    "zset",
+    "stream",
+    "hash_row",
+    "json",
+    "geo",
+    "bitmap",
+    "hyperloglog",
+    "pubsub",
+    "keyspace",
+    "function",
+];
 
-const KNOWN_SERVER_OPTIONS: &[&str] = &["host_port", "password", "username", "cluster_mode"];
+const KNOWN_SERVER_OPTIONS: &[&str] = &[
+    "host_port",
+    "password",
+    "username",
+    "cluster_mode",
+    "allowed_commands",
+    "sentinel_nodes",
+    "sentinel_master_name",
+    "sentinel_username",
+    "sentinel_password",
+    "sentinel_replica_reads",
+];
 const KNOWN_TABLE_OPTIONS: &[&str] = &[
     "table_type",
     "table_key_prefix",
@@ -13,7 +39,24 @@ const KNOWN_TABLE_OPTIONS: &[&str] = &[
     "batch_size",
     "strict_key_prefix",
     "join_batch_size",
+    "concurrency",
+    "insert_mode",
+    "on_conversion_error",
+    "consumer_group",
+    "consumer_name",
+    "create_group",
+    "claim_idle_ms",
+    "stream_maxlen",
+    "stream_minid_age",
+    "channel",
+    "read_function",
+    "write_function",
+    "read_script",
+    "write_script",
 ];
+const KNOWN_COLUMN_OPTIONS: &[&str] = &["field", "path"];
+/// Options accepted on both the server and the foreign table (table wins).
+const KNOWN_SHARED_OPTIONS: &[&str] = &["transactional"];
 
 // Register the validator function with text[] SQL type so PostgreSQL can find it
 // for the VALIDATOR clause in CREATE FOREIGN DATA WRAPPER.
@@ -41,6 +84,8 @@ pub unsafe extern "C-unwind" fn redis_fdw_validator_wrapper(
         validate_server_options(&opts);
     } else if catalog == pg_sys::ForeignTableRelationId {
         validate_table_options(&opts);
+    } else if catalog == pg_sys::AttributeRelationId {
+        validate_column_options(&opts);
     }
 
     pg_sys::Datum::from(0)
@@ -79,12 +124,22 @@ unsafe fn parse_options_list_raw(datum: pg_sys::Datum, is_null: bool) -> HashMap
 }
 
 fn validate_server_options(opts: &HashMap<String, String>) {
+    let sentinel = match crate::core::pool_manager::SentinelConfig::from_options(opts) {
+        Ok(s) => s,
+        Err(e) => error!("redis_fdw: {}", e),
+    };
     if let Some(hp) = opts.get("host_port") {
         if !validation_rules::is_valid_host_port(hp) {
             error!("host_port must be in format 'host:port', got '{}'", hp);
         }
-    } else {
-        error!("missing required option \"host_port\" for redis_fdw server");
+        if sentinel.is_some() {
+            error!("redis_fdw: host_port and sentinel_nodes cannot be used together");
+        }
+    } else if sentinel.is_none() {
+        error!("missing required option \"host_port\" (or sentinel_nodes and sentinel_master_name) for redis_fdw server");
+    }
+    if sentinel.is_some() && opts.get("cluster_mode").is_some_and(|cm| cm == "true") {
+        error!("redis_fdw: sentinel_nodes cannot be used with cluster_mode");
     }
 
     if let Some(cm) = opts.get("cluster_mode") {
@@ -93,7 +148,26 @@ fn validate_server_options(opts: &HashMap<String, String>) {
         }
     }
 
+    if let Some(allowed) = opts.get("allowed_commands") {
+        if !validation_rules::is_valid_allowed_commands(allowed) {
+            error!(
+                "allowed_commands must be a comma-separated list of commands or command|subcommand pairs, got '{}'",
+                allowed
+            );
+        }
+    }
+
+    validate_transactional_option(opts);
+    if opts.get("transactional").is_some_and(|v| v == "true")
+        && validation_rules::is_cluster_config(opts)
+    {
+        error!("redis_fdw: transactional mode is not supported with cluster_mode");
+    }
+
     for key in opts.keys() {
+        if KNOWN_SHARED_OPTIONS.contains(&key.as_str()) {
+            continue;
+        }
         if KNOWN_TABLE_OPTIONS.contains(&key.as_str()) {
             warning!(
                 "redis_fdw: option \"{}\" is a table option, not a server option",
@@ -109,7 +183,7 @@ fn validate_table_options(opts: &HashMap<String, String>) {
     if let Some(tt) = opts.get("table_type") {
         if !validation_rules::is_valid_table_type(tt) {
             error!(
-                "invalid table_type \"{}\". Must be one of: string, hash, list, set, zset, stream",
+                "invalid table_type \"{}\". Must be one of: string, hash, list, set, zset, stream, hash_row, json, geo, bitmap, hyperloglog, pubsub, keyspace, function",
                 tt
             );
         }
@@ -117,11 +191,39 @@ fn validate_table_options(opts: &HashMap<String, String>) {
         error!("missing required option \"table_type\" for redis_fdw foreign table");
     }
 
+    let is_pubsub = opts
+        .get("table_type")
+        .is_some_and(|tt| tt.eq_ignore_ascii_case("pubsub"));
     if let Some(prefix) = opts.get("table_key_prefix") {
         if prefix.is_empty() {
             error!("table_key_prefix must not be empty");
         }
-    } else {
+        if is_pubsub {
+            error!(
+                "redis_fdw: pubsub tables take a channel option or column, not table_key_prefix"
+            );
+        }
+        if opts
+            .get("table_type")
+            .is_some_and(|tt| tt.eq_ignore_ascii_case("keyspace"))
+            && !crate::core::state_manager::is_multi_key_pattern(prefix)
+        {
+            error!(
+                "redis_fdw: keyspace tables need a glob table_key_prefix (e.g. 'session:*' or '*'), got '{}'",
+                prefix
+            );
+        }
+        if opts
+            .get("table_type")
+            .is_some_and(|tt| tt.eq_ignore_ascii_case("function"))
+            && crate::core::state_manager::is_multi_key_pattern(prefix)
+        {
+            error!(
+                "redis_fdw: function tables take a single key as table_key_prefix, got pattern '{}'",
+                prefix
+            );
+        }
+    } else if !is_pubsub {
         error!("missing required option \"table_key_prefix\" for redis_fdw foreign table");
     }
 
@@ -177,7 +279,56 @@ fn validate_table_options(opts: &HashMap<String, String>) {
         }
     }
 
+    validate_transactional_option(opts);
+
+    if let Some(cc) = opts.get("concurrency") {
+        if !validation_rules::is_valid_concurrency(cc) {
+            error!(
+                "concurrency must be \"none\" or \"optimistic\", got '{}'",
+                cc
+            );
+        }
+        if cc == "optimistic" && opts.get("transactional").is_some_and(|v| v == "true") {
+            error!(
+                "redis_fdw: concurrency 'optimistic' cannot be combined with transactional 'true'"
+            );
+        }
+    }
+
+    if let Some(im) = opts.get("insert_mode") {
+        if !validation_rules::is_valid_insert_mode(im) {
+            error!(
+                "insert_mode must be \"overwrite\", \"nx\" or \"xx\", got '{}'",
+                im
+            );
+        }
+        if !im.eq_ignore_ascii_case("overwrite")
+            && opts.get("transactional").is_some_and(|v| v == "true")
+        {
+            error!(
+                "redis_fdw: insert_mode 'nx' and 'xx' cannot be combined with transactional 'true'"
+            );
+        }
+    }
+
+    if let Some(oce) = opts.get("on_conversion_error") {
+        if !validation_rules::is_valid_on_conversion_error(oce) {
+            error!(
+                "on_conversion_error must be \"error\" or \"null\", got '{}'",
+                oce
+            );
+        }
+    }
+
+    validate_consumer_group_options(opts);
+    validate_stream_trim_options(opts);
+    validate_pubsub_options(opts, is_pubsub);
+    validate_function_options(opts);
+
     for key in opts.keys() {
+        if KNOWN_SHARED_OPTIONS.contains(&key.as_str()) {
+            continue;
+        }
         if KNOWN_SERVER_OPTIONS.contains(&key.as_str()) {
             warning!(
                 "redis_fdw: option \"{}\" is a server option, not a table option",
@@ -189,11 +340,180 @@ fn validate_table_options(opts: &HashMap<String, String>) {
     }
 }
 
+fn validate_consumer_group_options(opts: &HashMap<String, String>) {
+    let has_group = opts.contains_key("consumer_group");
+    if has_group
+        && !opts
+            .get("table_type")
+            .is_some_and(|tt| tt.eq_ignore_ascii_case("stream"))
+    {
+        error!("redis_fdw: consumer_group is only supported for stream tables");
+    }
+    if has_group
+        && opts
+            .get("table_key_prefix")
+            .is_some_and(|p| crate::core::state_manager::is_multi_key_pattern(p))
+    {
+        error!("redis_fdw: consumer_group is not supported with a multi-key table_key_prefix");
+    }
+    if has_group != opts.contains_key("consumer_name") {
+        error!("redis_fdw: consumer_group and consumer_name must be set together");
+    }
+    for key in ["create_group", "claim_idle_ms"] {
+        if opts.contains_key(key) && !has_group {
+            error!("redis_fdw: {} requires consumer_group", key);
+        }
+    }
+    if let Some(cg) = opts.get("create_group") {
+        if cg != "true" && cg != "false" {
+            error!("create_group must be \"true\" or \"false\", got '{}'", cg);
+        }
+    }
+    if let Some(ms) = opts.get("claim_idle_ms") {
+        if !validation_rules::is_valid_claim_idle_ms(ms) {
+            error!("claim_idle_ms must be a non-negative integer, got '{}'", ms);
+        }
+    }
+}
+
+fn validate_stream_trim_options(opts: &HashMap<String, String>) {
+    let Some(key) = ["stream_maxlen", "stream_minid_age"]
+        .into_iter()
+        .find(|k| opts.contains_key(*k))
+    else {
+        return;
+    };
+    if !opts
+        .get("table_type")
+        .is_some_and(|tt| tt.eq_ignore_ascii_case("stream"))
+    {
+        error!("redis_fdw: {} is only supported for stream tables", key);
+    }
+    if let Err(e) = crate::tables::implementations::stream::StreamTrim::from_options(opts) {
+        error!("{}", e);
+    }
+}
+
+fn validate_pubsub_options(opts: &HashMap<String, String>, is_pubsub: bool) {
+    if let Some(channel) = opts.get("channel") {
+        if !is_pubsub {
+            error!("redis_fdw: channel is only supported for pubsub tables");
+        }
+        if channel.is_empty() {
+            error!("channel must not be empty");
+        }
+    }
+    if is_pubsub && opts.get("insert_mode").is_some_and(|m| m != "overwrite") {
+        error!("redis_fdw: insert_mode is not supported for pubsub tables");
+    }
+}
+
+fn validate_function_options(opts: &HashMap<String, String>) {
+    use crate::tables::implementations::function::RedisCallable;
+
+    let is_function = opts
+        .get("table_type")
+        .is_some_and(|tt| tt.eq_ignore_ascii_case("function"));
+    if !is_function {
+        if let Some(key) = [
+            "read_function",
+            "write_function",
+            "read_script",
+            "write_script",
+        ]
+        .into_iter()
+        .find(|k| opts.contains_key(*k))
+        {
+            error!("redis_fdw: {} is only supported for function tables", key);
+        }
+        return;
+    }
+    let read = RedisCallable::from_options(opts, "read_function", "read_script");
+    let write = RedisCallable::from_options(opts, "write_function", "write_script");
+    match (read, write) {
+        (Err(e), _) | (_, Err(e)) => error!("redis_fdw: {}", e),
+        (Ok(None), Ok(None)) => error!(
+            "redis_fdw: function tables need read_function or read_script, write_function or write_script, or both"
+        ),
+        _ => {}
+    }
+    for key in ["insert_mode", "ttl"] {
+        if opts.contains_key(key) {
+            error!("redis_fdw: {} is not supported for function tables", key);
+        }
+    }
+}
+
+fn validate_column_options(opts: &HashMap<String, String>) {
+    if opts.get("field").is_some_and(|f| f.is_empty()) {
+        error!("field must not be empty");
+    }
+    if let Some(path) = opts.get("path") {
+        if !path.starts_with('$') {
+            error!("path must be a JSONPath starting with '$', got '{}'", path);
+        }
+    }
+
+    for key in opts.keys() {
+        if !KNOWN_COLUMN_OPTIONS.contains(&key.as_str()) {
+            warning!("redis_fdw: unrecognized column option \"{}\"", key);
+        }
+    }
+}
+
+fn validate_transactional_option(opts: &HashMap<String, String>) {
+    if let Some(tx) = opts.get("transactional") {
+        if tx != "true" && tx != "false" {
+            error!("transactional must be \"true\" or \"false\", got '{}'", tx);
+        }
+    }
+}
+
 pub mod validation_rules {
+    use std::collections::HashMap;
+
+    /// Whether server options describe a Redis Cluster (comma-separated
+    /// nodes or `cluster_mode 'true'`).
+    pub fn is_cluster_config(opts: &HashMap<String, String>) -> bool {
+        opts.get("host_port").is_some_and(|hp| hp.contains(','))
+            || opts.get("cluster_mode").is_some_and(|cm| cm == "true")
+    }
+
     pub fn is_valid_table_type(s: &str) -> bool {
         super::VALID_TABLE_TYPES.contains(&s.to_lowercase().as_str())
     }
 
+    pub fn is_valid_concurrency(s: &str) -> bool {
+        matches!(s, "none" | "optimistic")
+    }
+
+    pub fn is_valid_insert_mode(s: &str) -> bool {
+        crate::tables::types::InsertMode::parse(s).is_some()
+    }
+
+    pub fn is_valid_on_conversion_error(s: &str) -> bool {
+        crate::utils::type_mapping::OnConversionError::parse(s).is_some()
+    }
+
+    /// A comma-separated list of `command` or `command|subcommand` entries.
+    pub fn is_valid_allowed_commands(s: &str) -> bool {
+        let is_name = |n: &str| {
+            !n.is_empty()
+                && n.chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.')
+        };
+        s.split(',')
+            .map(str::trim)
+            .all(|entry| match entry.split_once('|') {
+                Some((command, sub)) => is_name(command) && is_name(sub),
+                None => is_name(entry),
+            })
+    }
+
+    pub fn is_valid_claim_idle_ms(s: &str) -> bool {
+        s.parse::<u64>().is_ok()
+    }
+
     pub fn is_valid_ttl(s: &str) -> bool {
         match s.parse::<i64>() {
             Ok(n) => n > 0 || n == -1,
@@ -263,12 +583,74 @@ mod tests {
         assert!(is_valid_table_type("set"));
         assert!(is_valid_table_type("zset"));
         assert!(is_valid_table_type("stream"));
+        assert!(is_valid_table_type("hash_row"));
+        assert!(is_valid_table_type("json"));
+        assert!(is_valid_table_type("geo"));
+        assert!(is_valid_table_type("bitmap"));
+        assert!(is_valid_table_type("hyperloglog"));
+        assert!(is_valid_table_type("pubsub"));
+        assert!(is_valid_table_type("keyspace"));
+        assert!(is_valid_table_type("function"));
         assert!(is_valid_table_type("STRING"));
         assert!(is_valid_table_type("Hash"));
         assert!(!is_valid_table_type("invalid"));
         assert!(!is_valid_table_type(""));
     }
 
+    #[test]
+    fn test_is_cluster_config() {
+        let mut opts = std::collections::HashMap::new();
+        opts.insert("host_port".to_string(), "127.0.0.1:6379".to_string());
+        assert!(!is_cluster_config(&opts));
+        opts.insert("cluster_mode".to_string(), "true".to_string());
+        assert!(is_cluster_config(&opts));
+        opts.insert("cluster_mode".to_string(), "false".to_string());
+        opts.insert("host_port".to_string(), "node1:6379,node2:6379".to_string());
+        assert!(is_cluster_config(&opts));
+    }
+
+    #[test]
+    fn test_valid_concurrency() {
+        assert!(is_valid_concurrency("none"));
+        assert!(is_valid_concurrency("optimistic"));
+        assert!(!is_valid_concurrency("pessimistic"));
+        assert!(!is_valid_concurrency(""));
+    }
+
+    #[test]
+    fn test_valid_insert_mode() {
+        assert!(is_valid_insert_mode("overwrite"));
+        assert!(is_valid_insert_mode("nx"));
+        assert!(is_valid_insert_mode("XX"));
+        assert!(!is_valid_insert_mode("upsert"));
+        assert!(!is_valid_insert_mode(""));
+    }
+
+    #[test]
+    fn test_valid_on_conversion_error() {
+        assert!(is_valid_on_conversion_error("error"));
+        assert!(is_valid_on_conversion_error("NULL"));
+        assert!(!is_valid_on_conversion_error("skip"));
+    }
+
+    #[test]
+    fn test_valid_claim_idle_ms() {
+        assert!(is_valid_claim_idle_ms("0"));
+        assert!(is_valid_claim_idle_ms("60000"));
+        assert!(!is_valid_claim_idle_ms("-1"));
+        assert!(!is_valid_claim_idle_ms("1s"));
+    }
+
+    #[test]
+    fn test_valid_allowed_commands() {
+        assert!(is_valid_allowed_commands("get,set"));
+        assert!(is_valid_allowed_commands("GET, object|freq, xinfo|stream"));
+        assert!(!is_valid_allowed_commands(""));
+        assert!(!is_valid_allowed_commands("get,,set"));
+        assert!(!is_valid_allowed_commands("config|"));
+        assert!(!is_valid_allowed_commands("flush all"));
+    }
+
     #[test]
     fn test_valid_ttl() {
         assert!(is_valid_ttl("3600"));
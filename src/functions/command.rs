@@ -0,0 +1,334 @@
+use std::collections::HashMap;
+
+use pgrx::{prelude::*, JsonB};
+use redis::Value;
+use serde_json::{json, Map, Value as Json};
+
+use crate::functions::server::{connect_server, server_config, server_options};
+
+/// Role whose members may run raw commands besides superusers.
+const ADMIN_ROLE: &std::ffi::CStr = c"redis_fdw_admin";
+
+/// Commands that change the state of a pooled connection (database,
+/// protocol, user, replies, transaction, subscriptions, tracking, cluster
+/// reads) for whoever uses it next; rejected whatever `allowed_commands`
+/// says. Entries use the `allowed_commands` form.
+const CONNECTION_STATE_COMMANDS: &[&str] = &[
+    "AUTH",
+    "CLIENT|CACHING",
+    "CLIENT|REPLY",
+    "CLIENT|TRACKING",
+    "DISCARD",
+    "EXEC",
+    "HELLO",
+    "MONITOR",
+    "MULTI",
+    "PSUBSCRIBE",
+    "PUNSUBSCRIBE",
+    "QUIT",
+    "READONLY",
+    "READWRITE",
+    "RESET",
+    "SELECT",
+    "SSUBSCRIBE",
+    "SUNSUBSCRIBE",
+    "SUBSCRIBE",
+    "UNSUBSCRIBE",
+    "UNWATCH",
+    "WATCH",
+];
+
+/// Run the command `args` (name first) on database `database` of foreign
+/// server `server` and return its reply as jsonb. Only superusers and
+/// members of `redis_fdw_admin` may call it, and the server's
+/// `allowed_commands`, when set, limits the commands it runs.
+#[pg_extern]
+pub fn redis_command(server: &str, args: Vec<Option<String>>, database: default!(i32, 0)) -> JsonB {
+    check_admin("redis_command");
+    let Some(args) = args.into_iter().collect::<Option<Vec<String>>>() else {
+        error!("redis_fdw: redis_command arguments cannot be NULL");
+    };
+    let options = server_options(server, database);
+    check_command(server, &options, &args);
+
+    let mut conn = connect_server(server, &server_config(server, &options));
+    match command(&args).query::<Value>(conn.as_connection_like_mut()) {
+        Ok(reply) => JsonB(value_to_json(&reply)),
+        Err(e) => error!("redis_fdw: {} failed: {}", args[0], error_text(&e)),
+    }
+}
+
+/// Run `commands`, a JSON array of commands each given as an array of
+/// arguments, on database `database` of foreign server `server` in one
+/// pipeline and return the array of their replies. A command Redis rejects
+/// has `{"error": "<message>"}` as its reply; the others still run. Same
+/// restrictions as `redis_command`; in cluster mode the commands run one at
+/// a time.
+#[pg_extern]
+pub fn redis_pipeline(server: &str, commands: JsonB, database: default!(i32, 0)) -> JsonB {
+    check_admin("redis_pipeline");
+    let commands = match parse_commands(&commands.0) {
+        Ok(c) => c,
+        Err(e) => error!("redis_fdw: {}", e),
+    };
+    let options = server_options(server, database);
+    for args in &commands {
+        check_command(server, &options, args);
+    }
+    if commands.is_empty() {
+        return JsonB(Json::Array(Vec::new()));
+    }
+
+    let mut conn = connect_server(server, &server_config(server, &options));
+    let replies: Vec<redis::RedisResult<Value>> = if conn.is_cluster() {
+        let conn = conn.as_connection_like_mut();
+        commands
+            .iter()
+            .map(|args| command(args).query(conn))
+            .collect()
+    } else {
+        let mut pipe = redis::pipe();
+        pipe.ignore_errors();
+        for args in &commands {
+            pipe.add_command(command(args));
+        }
+        match pipe.query(conn.as_connection_like_mut()) {
+            Ok(replies) => replies,
+            Err(e) => error!("redis_fdw: pipeline failed: {}", error_text(&e)),
+        }
+    };
+    JsonB(Json::Array(
+        replies
+            .iter()
+            .map(|reply| match reply {
+                Ok(value) => value_to_json(value),
+                Err(e) => json!({ "error": error_text(e) }),
+            })
+            .collect(),
+    ))
+}
+
+/// Raise an error unless the current user is a superuser or a member of
+/// `redis_fdw_admin`.
+fn check_admin(function: &str) {
+    let allowed = unsafe {
+        pg_sys::superuser() || {
+            let role = pg_sys::get_role_oid(ADMIN_ROLE.as_ptr(), true);
+            role != pg_sys::InvalidOid && pg_sys::has_privs_of_role(pg_sys::GetUserId(), role)
+        }
+    };
+    if !allowed {
+        ereport!(
+            ERROR,
+            PgSqlErrorCode::ERRCODE_INSUFFICIENT_PRIVILEGE,
+            format!("redis_fdw: permission denied for function {}", function),
+            "Only superusers and members of the redis_fdw_admin role can run raw Redis commands."
+        );
+    }
+}
+
+/// Raise an error unless `args` is a command server `server` may run.
+fn check_command(server: &str, options: &HashMap<String, String>, args: &[String]) {
+    let Some(name) = args.first() else {
+        error!("redis_fdw: a command needs at least its name");
+    };
+    if let Some(entry) = CONNECTION_STATE_COMMANDS
+        .iter()
+        .find(|entry| command_matches(entry, args))
+    {
+        error!(
+            "redis_fdw: {} is not allowed, it changes the state of pooled connections",
+            entry.replace('|', " ")
+        );
+    }
+    if let Some(allowed) = options.get("allowed_commands") {
+        if !command_allowed(allowed, args) {
+            error!(
+                "redis_fdw: {} is not in allowed_commands of server '{}'",
+                name, server
+            );
+        }
+    }
+}
+
+/// Whether `args` matches an entry of the comma-separated `allowed` list:
+/// a command name (any subcommand) or `command|subcommand`, in any case.
+fn command_allowed(allowed: &str, args: &[String]) -> bool {
+    allowed
+        .split(',')
+        .map(str::trim)
+        .any(|entry| command_matches(entry, args))
+}
+
+/// Whether `args` is the command `entry` names: `command` or
+/// `command|subcommand`, in any case.
+fn command_matches(entry: &str, args: &[String]) -> bool {
+    let Some(name) = args.first() else {
+        return false;
+    };
+    match entry.split_once('|') {
+        Some((command, sub)) => {
+            command.eq_ignore_ascii_case(name)
+                && args.get(1).is_some_and(|s| sub.eq_ignore_ascii_case(s))
+        }
+        None => entry.eq_ignore_ascii_case(name),
+    }
+}
+
+fn command(args: &[String]) -> redis::Cmd {
+    let mut cmd = redis::cmd(&args[0]);
+    cmd.arg(&args[1..]);
+    cmd
+}
+
+/// The argument lists of a `redis_pipeline` commands array; numbers and
+/// booleans are passed as their text.
+fn parse_commands(commands: &Json) -> Result<Vec<Vec<String>>, String> {
+    const EXPECTED: &str = "redis_pipeline expects a JSON array of commands, each an array of arguments such as [[\"SET\", \"k\", \"1\"], [\"GET\", \"k\"]]";
+    let Json::Array(commands) = commands else {
+        return Err(EXPECTED.to_string());
+    };
+    commands
+        .iter()
+        .map(|command| match command {
+            Json::Array(args) if !args.is_empty() => args
+                .iter()
+                .map(|arg| match arg {
+                    Json::String(s) => Ok(s.clone()),
+                    Json::Number(_) | Json::Bool(_) => Ok(arg.to_string()),
+                    _ => Err(EXPECTED.to_string()),
+                })
+                .collect(),
+            _ => Err(EXPECTED.to_string()),
+        })
+        .collect()
+}
+
+/// A Redis error as Redis words it, `CODE detail` for server errors.
+fn error_text(e: &redis::RedisError) -> String {
+    match (e.code(), e.detail()) {
+        (Some(code), Some(detail)) => format!("{} {}", code, detail),
+        (Some(code), None) => code.to_string(),
+        _ => e.to_string(),
+    }
+}
+
+/// jsonb form of a reply: nil is null, integers, doubles and booleans are
+/// JSON scalars, strings and verbatim strings are strings, arrays, sets and
+/// pushes are arrays, maps are objects keyed by the key's text and errors
+/// are `{"error": "<message>"}`. Attributes are dropped.
+fn value_to_json(value: &Value) -> Json {
+    match value {
+        Value::Nil => Json::Null,
+        Value::Int(n) => json!(n),
+        Value::BulkString(bytes) => Json::String(String::from_utf8_lossy(bytes).into_owned()),
+        Value::SimpleString(s) => Json::String(s.clone()),
+        Value::Okay => Json::String("OK".to_string()),
+        Value::VerbatimString { text, .. } => Json::String(text.clone()),
+        Value::Array(items) | Value::Set(items) | Value::Push { data: items, .. } => {
+            Json::Array(items.iter().map(value_to_json).collect())
+        }
+        Value::Map(pairs) => Json::Object(
+            pairs
+                .iter()
+                .map(|(k, v)| {
+                    let key = match value_to_json(k) {
+                        Json::String(s) => s,
+                        other => other.to_string(),
+                    };
+                    (key, value_to_json(v))
+                })
+                .collect::<Map<_, _>>(),
+        ),
+        Value::Attribute { data, .. } => value_to_json(data),
+        Value::Double(d) => serde_json::Number::from_f64(*d)
+            .map(Json::Number)
+            .unwrap_or_else(|| Json::String(d.to_string())),
+        Value::Boolean(b) => Json::Bool(*b),
+        Value::ServerError(e) => json!({
+            "error": match e.details() {
+                Some(detail) => format!("{} {}", e.code(), detail),
+                None => e.code().to_string(),
+            }
+        }),
+        // Big numbers, and whatever later RESP versions add.
+        other => Json::String(format!("{:?}", other)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_command_allowed() {
+        let allowed = "get, SET,object|freq,config|get";
+        assert!(command_allowed(allowed, &strings(&["GET", "k"])));
+        assert!(command_allowed(allowed, &strings(&["set", "k", "v"])));
+        assert!(command_allowed(allowed, &strings(&["OBJECT", "FREQ", "k"])));
+        assert!(!command_allowed(
+            allowed,
+            &strings(&["OBJECT", "ENCODING", "k"])
+        ));
+        assert!(command_allowed(
+            allowed,
+            &strings(&["config", "get", "maxmemory"])
+        ));
+        assert!(!command_allowed(
+            allowed,
+            &strings(&["CONFIG", "SET", "maxmemory", "0"])
+        ));
+        assert!(!command_allowed(allowed, &strings(&["FLUSHALL"])));
+        assert!(!command_allowed(allowed, &[]));
+    }
+
+    #[test]
+    fn test_connection_state_commands() {
+        let blocked = |args: &[&str]| {
+            CONNECTION_STATE_COMMANDS
+                .iter()
+                .any(|entry| command_matches(entry, &strings(args)))
+        };
+        assert!(blocked(&["client", "reply", "off"]));
+        assert!(blocked(&["CLIENT", "TRACKING", "on"]));
+        assert!(blocked(&["readonly"]));
+        assert!(blocked(&["READWRITE"]));
+        assert!(blocked(&["reset"]));
+        assert!(!blocked(&["CLIENT", "LIST"]));
+        assert!(!blocked(&["GET", "k"]));
+    }
+
+    #[test]
+    fn test_value_to_json() {
+        let reply = Value::Array(vec![
+            Value::Nil,
+            Value::Int(3),
+            Value::BulkString(b"v".to_vec()),
+            Value::Okay,
+            Value::Map(vec![
+                (Value::SimpleString("a".into()), Value::Double(1.5)),
+                (Value::Int(2), Value::Boolean(true)),
+            ]),
+            Value::Double(f64::INFINITY),
+        ]);
+        assert_eq!(
+            value_to_json(&reply),
+            json!([null, 3, "v", "OK", {"a": 1.5, "2": true}, "inf"])
+        );
+    }
+
+    #[test]
+    fn test_parse_commands() {
+        assert_eq!(
+            parse_commands(&json!([["SET", "k", 1], ["GET", "k"]])).unwrap(),
+            vec![strings(&["SET", "k", "1"]), strings(&["GET", "k"])]
+        );
+        assert!(parse_commands(&json!([[]])).is_err());
+        assert!(parse_commands(&json!({"cmd": "GET"})).is_err());
+        assert!(parse_commands(&json!([["GET", null]])).is_err());
+    }
+}
@@ -0,0 +1,39 @@
+use pgrx::prelude::*;
+
+use crate::tables::implementations::geo::{geo_distance, geo_within_box};
+
+/// Whether (`longitude`, `latitude`) lies within `radius_m` meters of the
+/// center. On the longitude and latitude columns of a `geo` table this is
+/// pushed down as `GEOSEARCH ... BYRADIUS`; the distance is computed the way
+/// Redis computes it, so both agree on points near the edge.
+#[pg_extern(immutable, parallel_safe)]
+pub fn redis_geo_within(
+    longitude: f64,
+    latitude: f64,
+    center_longitude: f64,
+    center_latitude: f64,
+    radius_m: f64,
+) -> bool {
+    geo_distance(longitude, latitude, center_longitude, center_latitude) <= radius_m
+}
+
+/// Whether (`longitude`, `latitude`) lies within the `width_m` x `height_m`
+/// box centred on the given point, pushed down as `GEOSEARCH ... BYBOX`.
+#[pg_extern(immutable, parallel_safe)]
+pub fn redis_geo_within_box(
+    longitude: f64,
+    latitude: f64,
+    center_longitude: f64,
+    center_latitude: f64,
+    width_m: f64,
+    height_m: f64,
+) -> bool {
+    geo_within_box(
+        longitude,
+        latitude,
+        center_longitude,
+        center_latitude,
+        width_m,
+        height_m,
+    )
+}
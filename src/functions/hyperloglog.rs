@@ -0,0 +1,23 @@
+use pgrx::prelude::*;
+
+use crate::functions::server::server_connection;
+
+/// Approximate number of distinct elements across the HyperLogLogs at
+/// `keys` on foreign server `server`: one PFCOUNT of all of them, which
+/// counts their union. Absent keys count as empty; NULL keys are skipped.
+/// In cluster mode the keys must share a hash slot (use a hash tag).
+#[pg_extern]
+pub fn redis_pfcount(server: &str, keys: Vec<Option<String>>, database: default!(i32, 0)) -> i64 {
+    let keys: Vec<String> = keys.into_iter().flatten().collect();
+    if keys.is_empty() {
+        return 0;
+    }
+    let mut conn = server_connection(server, database);
+    match redis::cmd("PFCOUNT")
+        .arg(&keys)
+        .query::<i64>(conn.as_connection_like_mut())
+    {
+        Ok(count) => count,
+        Err(e) => error!("redis_fdw: PFCOUNT failed: {}", e),
+    }
+}
@@ -0,0 +1,425 @@
+use pgrx::prelude::*;
+use redis::{
+    cluster_routing::{MultipleNodeRoutingInfo, RoutingInfo},
+    Value,
+};
+
+use crate::functions::server::{connect_server, server_config, server_options};
+
+/// CONFIG parameters whose values are credentials; `redis_config_get`
+/// reports them as NULL.
+const REDACTED_PARAMETERS: &[&str] = &[
+    "requirepass",
+    "masterauth",
+    "tls-key-file-pass",
+    "tls-client-key-file-pass",
+];
+
+/// Sections, keys and values of INFO `section` on every node of foreign
+/// server `server`.
+#[pg_extern]
+pub fn redis_info(
+    server: &str,
+    section: default!(&str, "'default'"),
+) -> TableIterator<
+    'static,
+    (
+        name!(section, String),
+        name!(key, String),
+        name!(value, String),
+        name!(node, String),
+    ),
+> {
+    let mut cmd = redis::cmd("INFO");
+    cmd.arg(section);
+    let rows: Vec<_> = query_nodes(server, &cmd, "INFO")
+        .into_iter()
+        .flat_map(|(node, reply)| {
+            parse_info(&value_text(&reply).unwrap_or_default())
+                .into_iter()
+                .map(move |(section, key, value)| (section, key, value, node.clone()))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    TableIterator::new(rows)
+}
+
+/// The `n` most recent SLOWLOG entries of every node of foreign server
+/// `server`, newest first on each node.
+#[pg_extern]
+#[allow(clippy::type_complexity)]
+pub fn redis_slowlog(
+    server: &str,
+    n: default!(i32, 10),
+) -> TableIterator<
+    'static,
+    (
+        name!(id, i64),
+        name!(start_time, TimestampWithTimeZone),
+        name!(duration_us, i64),
+        name!(command, Vec<String>),
+        name!(client_addr, Option<String>),
+        name!(client_name, Option<String>),
+        name!(node, String),
+    ),
+> {
+    let mut cmd = redis::cmd("SLOWLOG");
+    cmd.arg("GET").arg(n.max(0));
+    let rows: Vec<_> = query_nodes(server, &cmd, "SLOWLOG GET")
+        .into_iter()
+        .flat_map(|(node, reply)| {
+            parse_slowlog(&reply)
+                .into_iter()
+                .map(move |e| {
+                    (
+                        e.id,
+                        to_timestamp(e.start_time as f64),
+                        e.duration_us,
+                        e.command,
+                        e.client_addr,
+                        e.client_name,
+                        node.clone(),
+                    )
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    TableIterator::new(rows)
+}
+
+/// Clients connected to every node of foreign server `server`, as reported
+/// by CLIENT LIST (`username` is its `user` field). Fields a server version
+/// does not report are NULL.
+#[pg_extern]
+#[allow(clippy::type_complexity)]
+pub fn redis_client_list(
+    server: &str,
+) -> TableIterator<
+    'static,
+    (
+        name!(id, Option<i64>),
+        name!(addr, Option<String>),
+        name!(name, Option<String>),
+        name!(age, Option<i64>),
+        name!(idle, Option<i64>),
+        name!(db, Option<i32>),
+        name!(flags, Option<String>),
+        name!(cmd, Option<String>),
+        name!(username, Option<String>),
+        name!(node, String),
+    ),
+> {
+    let mut cmd = redis::cmd("CLIENT");
+    cmd.arg("LIST");
+    let rows: Vec<_> = query_nodes(server, &cmd, "CLIENT LIST")
+        .into_iter()
+        .flat_map(|(node, reply)| {
+            parse_client_list(&value_text(&reply).unwrap_or_default())
+                .into_iter()
+                .map(move |c| {
+                    let field = |name: &str| {
+                        c.iter()
+                            .find(|(k, _)| *k == name)
+                            .map(|(_, v)| v.to_string())
+                    };
+                    let number = |name: &str| field(name).and_then(|v| v.parse::<i64>().ok());
+                    (
+                        number("id"),
+                        field("addr"),
+                        field("name").filter(|n| !n.is_empty()),
+                        number("age"),
+                        number("idle"),
+                        number("db").and_then(|db| i32::try_from(db).ok()),
+                        field("flags"),
+                        field("cmd"),
+                        field("user"),
+                        node.clone(),
+                    )
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    TableIterator::new(rows)
+}
+
+/// Latest and maximum latency of each event LATENCY LATEST reports on every
+/// node of foreign server `server`. Empty unless the latency monitor is
+/// enabled (`latency-monitor-threshold`).
+#[pg_extern]
+pub fn redis_latency_latest(
+    server: &str,
+) -> TableIterator<
+    'static,
+    (
+        name!(event, String),
+        name!(last_time, TimestampWithTimeZone),
+        name!(latest_ms, i64),
+        name!(max_ms, i64),
+        name!(node, String),
+    ),
+> {
+    let mut cmd = redis::cmd("LATENCY");
+    cmd.arg("LATEST");
+    let rows: Vec<_> = query_nodes(server, &cmd, "LATENCY LATEST")
+        .into_iter()
+        .flat_map(|(node, reply)| {
+            parse_latency_latest(&reply)
+                .into_iter()
+                .map(move |(event, time, latest, max)| {
+                    (event, to_timestamp(time as f64), latest, max, node.clone())
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    TableIterator::new(rows)
+}
+
+/// Configuration parameters matching glob `pattern` on every node of
+/// foreign server `server`. Passwords read as NULL.
+#[pg_extern]
+pub fn redis_config_get(
+    server: &str,
+    pattern: default!(&str, "'*'"),
+) -> TableIterator<
+    'static,
+    (
+        name!(name, String),
+        name!(value, Option<String>),
+        name!(node, String),
+    ),
+> {
+    let mut cmd = redis::cmd("CONFIG");
+    cmd.arg("GET").arg(pattern);
+    let rows: Vec<_> = query_nodes(server, &cmd, "CONFIG GET")
+        .into_iter()
+        .flat_map(|(node, reply)| {
+            parse_config(&reply)
+                .into_iter()
+                .map(move |(name, value)| {
+                    let value = value.filter(|_| !REDACTED_PARAMETERS.contains(&name.as_str()));
+                    (name, value, node.clone())
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    TableIterator::new(rows)
+}
+
+/// Reply of `cmd` from every node of foreign server `server`, by node
+/// address: each node of a cluster, or the one server's `host_port`.
+fn query_nodes(server: &str, cmd: &redis::Cmd, name: &str) -> Vec<(String, Value)> {
+    let config = server_config(server, &server_options(server, 0));
+    let mut conn = connect_server(server, &config);
+    let replies = match conn.as_cluster_connection_mut() {
+        Some(cluster) => cluster
+            .route_command(
+                cmd,
+                RoutingInfo::MultiNode((MultipleNodeRoutingInfo::AllNodes, None)),
+            )
+            .map(|reply| match reply {
+                Value::Map(nodes) => nodes
+                    .into_iter()
+                    .map(|(node, reply)| (value_text(&node).unwrap_or_default(), reply))
+                    .collect(),
+                reply => vec![(config.host_port.clone(), reply)],
+            }),
+        None => cmd
+            .query::<Value>(conn.as_connection_like_mut())
+            .map(|reply| vec![(config.host_port.clone(), reply)]),
+    };
+    let mut replies =
+        replies.unwrap_or_else(|e| error!("redis_fdw: {} failed on '{}': {}", name, server, e));
+    replies.sort_by(|a, b| a.0.cmp(&b.0));
+    replies
+}
+
+/// One SLOWLOG GET entry.
+#[derive(Debug, PartialEq)]
+struct SlowlogEntry {
+    id: i64,
+    /// Unix time the command started, in seconds
+    start_time: i64,
+    duration_us: i64,
+    command: Vec<String>,
+    client_addr: Option<String>,
+    client_name: Option<String>,
+}
+
+/// A reply as text; nil and aggregates read as None.
+fn value_text(value: &Value) -> Option<String> {
+    match value {
+        Value::BulkString(bytes) => Some(String::from_utf8_lossy(bytes).into_owned()),
+        Value::SimpleString(s) => Some(s.clone()),
+        Value::VerbatimString { text, .. } => Some(text.clone()),
+        Value::Int(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+fn value_int(value: &Value) -> Option<i64> {
+    match value {
+        Value::Int(n) => Some(*n),
+        other => value_text(other)?.parse().ok(),
+    }
+}
+
+/// `(section, key, value)` of an INFO reply, sections named in lowercase
+/// as INFO takes them.
+fn parse_info(text: &str) -> Vec<(String, String, String)> {
+    let mut section = String::new();
+    let mut rows = Vec::new();
+    for line in text.lines().map(str::trim) {
+        if let Some(name) = line.strip_prefix('#') {
+            section = name.trim().to_lowercase();
+        } else if let Some((key, value)) = line.split_once(':') {
+            rows.push((section.clone(), key.to_string(), value.to_string()));
+        }
+    }
+    rows
+}
+
+fn parse_slowlog(reply: &Value) -> Vec<SlowlogEntry> {
+    let Value::Array(entries) = reply else {
+        return Vec::new();
+    };
+    entries
+        .iter()
+        .filter_map(|entry| {
+            let Value::Array(fields) = entry else {
+                return None;
+            };
+            let command = match fields.get(3) {
+                Some(Value::Array(args)) => args.iter().filter_map(value_text).collect(),
+                _ => Vec::new(),
+            };
+            Some(SlowlogEntry {
+                id: value_int(fields.first()?)?,
+                start_time: value_int(fields.get(1)?)?,
+                duration_us: value_int(fields.get(2)?)?,
+                command,
+                client_addr: fields.get(4).and_then(value_text),
+                client_name: fields.get(5).and_then(value_text).filter(|n| !n.is_empty()),
+            })
+        })
+        .collect()
+}
+
+/// The `field=value` pairs of each CLIENT LIST line.
+fn parse_client_list(text: &str) -> Vec<Vec<(&str, &str)>> {
+    text.lines()
+        .map(|line| {
+            line.split_whitespace()
+                .filter_map(|pair| pair.split_once('='))
+                .collect::<Vec<_>>()
+        })
+        .filter(|fields| !fields.is_empty())
+        .collect()
+}
+
+/// `(event, unix time, latest ms, max ms)` of a LATENCY LATEST reply.
+fn parse_latency_latest(reply: &Value) -> Vec<(String, i64, i64, i64)> {
+    let Value::Array(events) = reply else {
+        return Vec::new();
+    };
+    events
+        .iter()
+        .filter_map(|event| match event {
+            Value::Array(fields) => Some((
+                value_text(fields.first()?)?,
+                value_int(fields.get(1)?)?,
+                value_int(fields.get(2)?)?,
+                value_int(fields.get(3)?)?,
+            )),
+            _ => None,
+        })
+        .collect()
+}
+
+/// `(name, value)` pairs of a CONFIG GET reply: a flat array (RESP2) or a
+/// map (RESP3).
+fn parse_config(reply: &Value) -> Vec<(String, Option<String>)> {
+    match reply {
+        Value::Array(items) => items
+            .chunks_exact(2)
+            .filter_map(|pair| Some((value_text(&pair[0])?, value_text(&pair[1]))))
+            .collect(),
+        Value::Map(pairs) => pairs
+            .iter()
+            .filter_map(|(name, value)| Some((value_text(name)?, value_text(value))))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bulk(s: &str) -> Value {
+        Value::BulkString(s.as_bytes().to_vec())
+    }
+
+    #[test]
+    fn test_parse_info() {
+        let text = "# Server\r\nredis_version:7.2.4\r\nrun_id:abc\r\n\r\n# Keyspace\r\ndb15:keys=3,expires=0\r\n";
+        assert_eq!(
+            parse_info(text),
+            vec![
+                ("server".into(), "redis_version".into(), "7.2.4".into()),
+                ("server".into(), "run_id".into(), "abc".into()),
+                ("keyspace".into(), "db15".into(), "keys=3,expires=0".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_slowlog() {
+        let reply = Value::Array(vec![Value::Array(vec![
+            Value::Int(14),
+            Value::Int(1700000000),
+            Value::Int(12500),
+            Value::Array(vec![bulk("KEYS"), bulk("*")]),
+            bulk("127.0.0.1:58224"),
+            bulk(""),
+        ])]);
+        assert_eq!(
+            parse_slowlog(&reply),
+            vec![SlowlogEntry {
+                id: 14,
+                start_time: 1700000000,
+                duration_us: 12500,
+                command: vec!["KEYS".into(), "*".into()],
+                client_addr: Some("127.0.0.1:58224".into()),
+                client_name: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_parse_client_list_and_config() {
+        let clients =
+            parse_client_list("id=3 addr=127.0.0.1:6379 name= db=15 cmd=client|list\nid=4 db=0\n");
+        assert_eq!(clients.len(), 2);
+        assert_eq!(clients[0][3], ("db", "15"));
+        assert_eq!(clients[0][2], ("name", ""));
+
+        let flat = Value::Array(vec![
+            bulk("maxmemory"),
+            bulk("0"),
+            bulk("appendonly"),
+            bulk("no"),
+        ]);
+        let map = Value::Map(vec![(bulk("maxmemory"), bulk("0"))]);
+        assert_eq!(
+            parse_config(&flat),
+            vec![
+                ("maxmemory".into(), Some("0".into())),
+                ("appendonly".into(), Some("no".into())),
+            ]
+        );
+        assert_eq!(
+            parse_config(&map),
+            vec![("maxmemory".into(), Some("0".into()))]
+        );
+    }
+}
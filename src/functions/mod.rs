@@ -0,0 +1,12 @@
+/// SQL-callable functions shipped with the extension
+///
+/// Functions the planner can push down to Redis live next to the table type
+/// they serve; PostgreSQL also evaluates them itself when it rechecks rows.
+/// Functions that query Redis directly reach it through a foreign server.
+pub mod command;
+pub mod geo;
+pub mod hyperloglog;
+pub mod introspection;
+pub mod pubsub;
+mod server;
+pub mod stream;
@@ -0,0 +1,34 @@
+use pgrx::prelude::*;
+
+use crate::{
+    core::{transaction, validator::validation_rules::is_cluster_config},
+    functions::server::{connect_server, server_config, server_options},
+};
+
+/// Publish `message` on `channel` through foreign server `server`, with
+/// SPUBLISH in cluster mode, returning the number of subscribers that
+/// received it. A server with `transactional 'true'` publishes at COMMIT
+/// instead, and the count is NULL.
+#[pg_extern]
+pub fn redis_publish(server: &str, channel: &str, message: &str) -> Option<i64> {
+    // Pub/Sub ignores the database; channels are server-wide.
+    let options = server_options(server, 0);
+    let config = server_config(server, &options);
+    let cluster = is_cluster_config(&options);
+    let mut cmd = redis::cmd(if cluster { "SPUBLISH" } else { "PUBLISH" });
+    cmd.arg(channel).arg(message);
+
+    if options.get("transactional").is_some_and(|v| v == "true") {
+        if cluster {
+            error!("redis_fdw: transactional mode is not supported with cluster_mode");
+        }
+        transaction::enqueue(&config, vec![cmd]);
+        return None;
+    }
+
+    let mut conn = connect_server(server, &config);
+    match cmd.query::<i64>(conn.as_connection_like_mut()) {
+        Ok(receivers) => Some(receivers),
+        Err(e) => error!("redis_fdw: PUBLISH failed: {}", e),
+    }
+}
@@ -0,0 +1,74 @@
+use std::{collections::HashMap, ffi::CString};
+
+use pgrx::prelude::*;
+
+use crate::{
+    core::{
+        connection_factory::{RedisConnectionConfig, RedisConnectionFactory},
+        pool_manager::PooledConnection,
+    },
+    utils::helpers::get_foreign_server_options,
+};
+
+/// Connection to database `database` of the Redis behind foreign server
+/// `server`, for functions that talk to Redis without a foreign table. The
+/// caller needs USAGE on the server, as for creating a table on it.
+pub(crate) fn server_connection(server: &str, database: i32) -> PooledConnection {
+    connect_server(
+        server,
+        &server_config(server, &server_options(server, database)),
+    )
+}
+
+/// Pooled connection to foreign server `server` with config `config`.
+pub(crate) fn connect_server(server: &str, config: &RedisConnectionConfig) -> PooledConnection {
+    match RedisConnectionFactory::create_connection_with_retry(config) {
+        Ok(c) => c,
+        Err(e) => error!("redis_fdw: failed to connect to server '{}': {}", server, e),
+    }
+}
+
+/// Options of foreign server `server`, after checking USAGE on it, with
+/// `database` set.
+pub(crate) fn server_options(server: &str, database: i32) -> HashMap<String, String> {
+    let name = CString::new(server)
+        .unwrap_or_else(|_| error!("redis_fdw: invalid server name '{}'", server));
+    let mut options = unsafe {
+        let server = pg_sys::GetForeignServerByName(name.as_ptr(), false);
+        check_server_usage(server);
+        get_foreign_server_options(server)
+    };
+    options.insert("database".to_string(), database.to_string());
+    options
+}
+
+/// Connection config of foreign server `server` with options `options`.
+pub(crate) fn server_config(
+    server: &str,
+    options: &HashMap<String, String>,
+) -> RedisConnectionConfig {
+    match RedisConnectionConfig::from_options(options) {
+        Ok(c) => c,
+        Err(e) => error!("redis_fdw: invalid options of server '{}': {}", server, e),
+    }
+}
+
+unsafe fn check_server_usage(server: *mut pg_sys::ForeignServer) {
+    let mode = pg_sys::ACL_USAGE as pg_sys::AclMode;
+    #[cfg(any(feature = "pg14", feature = "pg15"))]
+    let result = pg_sys::pg_foreign_server_aclcheck((*server).serverid, pg_sys::GetUserId(), mode);
+    #[cfg(not(any(feature = "pg14", feature = "pg15")))]
+    let result = pg_sys::object_aclcheck(
+        pg_sys::ForeignServerRelationId,
+        (*server).serverid,
+        pg_sys::GetUserId(),
+        mode,
+    );
+    if result != pg_sys::AclResult::ACLCHECK_OK {
+        pg_sys::aclcheck_error(
+            result,
+            pg_sys::ObjectType::OBJECT_FOREIGN_SERVER,
+            (*server).servername,
+        );
+    }
+}
@@ -0,0 +1,141 @@
+use pgrx::{prelude::*, PgRelation};
+
+use crate::{
+    core::{
+        connection_factory::{RedisConnectionConfig, RedisConnectionFactory},
+        state_manager::is_multi_key_pattern,
+    },
+    tables::implementations::stream::StreamTrim,
+    utils::helpers::get_foreign_table_options,
+};
+
+/// Trim the stream(s) behind stream foreign table `foreign_table` with its
+/// `stream_maxlen` or `stream_minid_age` policy now, as XTRIM, returning the
+/// number of entries removed. A multi-key table trims every stream its
+/// pattern matches. Needs DELETE on the table.
+#[pg_extern]
+pub fn redis_xtrim(foreign_table: PgRelation) -> i64 {
+    let relid = foreign_table.oid();
+    let name = foreign_table.name().to_string();
+    let options = unsafe {
+        if foreign_table.rd_rel.is_null()
+            || (*foreign_table.rd_rel).relkind as u8 != pg_sys::RELKIND_FOREIGN_TABLE
+        {
+            error!("redis_fdw: \"{}\" is not a foreign table", name);
+        }
+        check_table_delete(relid, &name);
+        get_foreign_table_options(relid)
+    };
+    if !options
+        .get("table_type")
+        .is_some_and(|tt| tt.eq_ignore_ascii_case("stream"))
+    {
+        error!("redis_fdw: \"{}\" is not a stream table", name);
+    }
+    let trim = match StreamTrim::from_options(&options) {
+        Ok(Some(trim)) => trim,
+        Ok(None) => error!(
+            "redis_fdw: \"{}\" has neither stream_maxlen nor stream_minid_age",
+            name
+        ),
+        Err(e) => error!("redis_fdw: {}", e),
+    };
+
+    let config = match RedisConnectionConfig::from_options(&options) {
+        Ok(c) => c,
+        Err(e) => error!("redis_fdw: invalid options of \"{}\": {}", name, e),
+    };
+    let mut conn = match RedisConnectionFactory::create_connection_with_retry(&config) {
+        Ok(c) => c,
+        Err(e) => error!("redis_fdw: failed to connect for \"{}\": {}", name, e),
+    };
+    let cluster = conn.is_cluster();
+    let conn = conn.as_connection_like_mut();
+    let key_prefix = options.get("table_key_prefix").cloned().unwrap_or_default();
+    let args = trim.args();
+
+    if !is_multi_key_pattern(&key_prefix) {
+        // XTRIM on some other type would fail with WRONGTYPE; name the
+        // table instead, and leave a missing stream missing.
+        let key_type: String = match redis::cmd("TYPE").arg(&key_prefix).query(conn) {
+            Ok(t) => t,
+            Err(e) => error!("redis_fdw: TYPE failed for key '{}': {}", key_prefix, e),
+        };
+        match key_type.as_str() {
+            "none" => return 0,
+            "stream" => {}
+            other => error!(
+                "redis_fdw: key '{}' of \"{}\" holds a {}, not a stream",
+                key_prefix, name, other
+            ),
+        }
+        return match redis::cmd("XTRIM").arg(&key_prefix).arg(&args).query(conn) {
+            Ok(n) => n,
+            Err(e) => error!("redis_fdw: XTRIM failed for key '{}': {}", key_prefix, e),
+        };
+    }
+    let mut removed = 0;
+    let mut cursor: u64 = 0;
+    loop {
+        pgrx::check_for_interrupts!();
+        let (new_cursor, keys): (u64, Vec<String>) = match redis::cmd("SCAN")
+            .arg(cursor)
+            .arg("MATCH")
+            .arg(&key_prefix)
+            .arg("COUNT")
+            .arg(1000u32)
+            .arg("TYPE")
+            .arg("stream")
+            .query(conn)
+        {
+            Ok(r) => r,
+            Err(e) => error!("redis_fdw: SCAN failed during XTRIM: {}", e),
+        };
+        removed += xtrim_keys(conn, &keys, &args, cluster);
+        cursor = new_cursor;
+        if cursor == 0 {
+            break;
+        }
+    }
+    removed
+}
+
+/// XTRIM each of `keys`, one pipeline per SCAN batch, or one command at a
+/// time in cluster mode where the keys may live in different slots.
+/// Returns the number of entries removed.
+fn xtrim_keys(
+    conn: &mut dyn redis::ConnectionLike,
+    keys: &[String],
+    args: &[String],
+    cluster: bool,
+) -> i64 {
+    if keys.is_empty() {
+        return 0;
+    }
+    let counts: redis::RedisResult<Vec<i64>> = if cluster {
+        keys.iter()
+            .map(|key| redis::cmd("XTRIM").arg(key).arg(args).query(conn))
+            .collect()
+    } else {
+        let mut pipe = redis::pipe();
+        for key in keys {
+            pipe.cmd("XTRIM").arg(key).arg(args);
+        }
+        pipe.query(conn)
+    };
+    match counts {
+        Ok(counts) => counts.iter().sum(),
+        Err(e) => error!("redis_fdw: XTRIM of matched streams failed: {}", e),
+    }
+}
+
+unsafe fn check_table_delete(relid: pg_sys::Oid, name: &str) {
+    let result = pg_sys::pg_class_aclcheck(
+        relid,
+        pg_sys::GetUserId(),
+        pg_sys::ACL_DELETE as pg_sys::AclMode,
+    );
+    if result != pg_sys::AclResult::ACLCHECK_OK {
+        error!("permission denied for foreign table {}", name);
+    }
+}
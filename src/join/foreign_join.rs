@@ -60,10 +60,10 @@ pub(crate) fn perform_hash_join(
             )
         };
 
-    let mut hash_table: HashMap<&str, Vec<usize>> = HashMap::new();
+    let mut hash_table: HashMap<&[u8], Vec<usize>> = HashMap::new();
     for (idx, row) in build_data.iter().enumerate() {
         if let Some(key) = row.get(build_col) {
-            hash_table.entry(key.as_str()).or_default().push(idx);
+            hash_table.entry(key.as_slice()).or_default().push(idx);
         }
     }
 
@@ -78,7 +78,7 @@ pub(crate) fn perform_hash_join(
     for (probe_idx, probe_row) in probe_data.iter().enumerate() {
         let mut matched = false;
         if let Some(probe_key) = probe_row.get(probe_col) {
-            if let Some(build_indices) = hash_table.get(probe_key.as_str()) {
+            if let Some(build_indices) = hash_table.get(probe_key.as_slice()) {
                 matched = true;
                 for &build_idx in build_indices {
                     if needs_build_tracking {
@@ -148,7 +148,7 @@ fn fetch_dataset(
 
     match table_type {
         RedisTableType::Hash(_) => {
-            let pairs: Vec<(String, String)> = redis::cmd("HGETALL")
+            let pairs: Vec<(Vec<u8>, Vec<u8>)> = redis::cmd("HGETALL")
                 .arg(key_prefix)
                 .query(conn)
                 .unwrap_or_else(|e| {
@@ -157,7 +157,7 @@ fn fetch_dataset(
             pairs.into_iter().map(|(f, v)| smallvec![f, v]).collect()
         }
         RedisTableType::Set(_) => {
-            let members: Vec<String> = redis::cmd("SMEMBERS")
+            let members: Vec<Vec<u8>> = redis::cmd("SMEMBERS")
                 .arg(key_prefix)
                 .query(conn)
                 .unwrap_or_else(|e| {
@@ -166,7 +166,7 @@ fn fetch_dataset(
             members.into_iter().map(|m| smallvec![m]).collect()
         }
         RedisTableType::ZSet(_) => {
-            let items: Vec<(String, f64)> = redis::cmd("ZRANGE")
+            let items: Vec<(Vec<u8>, f64)> = redis::cmd("ZRANGE")
                 .arg(key_prefix)
                 .arg(0i64)
                 .arg(-1i64)
@@ -177,11 +177,11 @@ fn fetch_dataset(
                 });
             items
                 .into_iter()
-                .map(|(member, score)| smallvec![member, score.to_string()])
+                .map(|(member, score)| smallvec![member, score.to_string().into_bytes()])
                 .collect()
         }
         RedisTableType::List(list) => {
-            let items: Vec<String> = redis::cmd("LRANGE")
+            let items: Vec<Vec<u8>> = redis::cmd("LRANGE")
                 .arg(key_prefix)
                 .arg(0i64)
                 .arg(-1i64)
@@ -193,21 +193,21 @@ fn fetch_dataset(
                 items
                     .into_iter()
                     .enumerate()
-                    .map(|(idx, v)| smallvec![idx.to_string(), v])
+                    .map(|(idx, v)| smallvec![idx.to_string().into_bytes(), v])
                     .collect()
             } else {
                 items.into_iter().map(|v| smallvec![v]).collect()
             }
         }
         RedisTableType::String(_) => {
-            let val: Option<String> = redis::cmd("GET")
+            let val: Option<Vec<u8>> = redis::cmd("GET")
                 .arg(key_prefix)
                 .query(conn)
                 .unwrap_or_else(|e| {
                     pgrx::error!("Redis FDW: GET '{}' failed: {}", key_prefix, e);
                 });
             match val {
-                Some(v) => vec![smallvec![key_prefix.to_string(), v]],
+                Some(v) => vec![smallvec![key_prefix.as_bytes().to_vec(), v]],
                 None => vec![],
             }
         }
@@ -229,6 +229,14 @@ pub fn expected_columns_for_type(table_type: &RedisTableType) -> usize {
         }
         RedisTableType::String(_) => 2,
         RedisTableType::Stream(_) => 3,
+        RedisTableType::HashRow(t) => t.fields.len(),
+        RedisTableType::Json(t) => t.paths.len(),
+        RedisTableType::Geo(t) => 3 + t.extras.len(),
+        RedisTableType::Bitmap(_) => 2,
+        RedisTableType::HyperLogLog(t) => 1 + usize::from(t.element_column),
+        RedisTableType::PubSub(t) => 2 - usize::from(t.channel.is_some()),
+        RedisTableType::Keyspace(t) => t.width - 1,
+        RedisTableType::Function(t) => t.width,
         RedisTableType::None => 0,
     }
 }
@@ -257,14 +265,14 @@ mod tests {
     #[test]
     fn test_inner_join_basic() {
         let outer_data: Vec<JoinRow> = vec![
-            smallvec!["a".to_string(), "val_a".to_string()],
-            smallvec!["b".to_string(), "val_b".to_string()],
-            smallvec!["c".to_string(), "val_c".to_string()],
+            smallvec![b"a".to_vec(), b"val_a".to_vec()],
+            smallvec![b"b".to_vec(), b"val_b".to_vec()],
+            smallvec![b"c".to_vec(), b"val_c".to_vec()],
         ];
         let inner_data: Vec<JoinRow> = vec![
-            smallvec!["a".to_string(), "inner_a".to_string()],
-            smallvec!["b".to_string(), "inner_b".to_string()],
-            smallvec!["d".to_string(), "inner_d".to_string()],
+            smallvec![b"a".to_vec(), b"inner_a".to_vec()],
+            smallvec![b"b".to_vec(), b"inner_b".to_vec()],
+            smallvec![b"d".to_vec(), b"inner_d".to_vec()],
         ];
 
         let result = perform_hash_join(&outer_data, &inner_data, 0, 0, &RedisJoinType::Inner);
@@ -288,13 +296,13 @@ mod tests {
     #[test]
     fn test_left_join_with_nulls() {
         let outer_data: Vec<JoinRow> = vec![
-            smallvec!["a".to_string(), "val_a".to_string()],
-            smallvec!["b".to_string(), "val_b".to_string()],
-            smallvec!["missing".to_string(), "val_m".to_string()],
+            smallvec![b"a".to_vec(), b"val_a".to_vec()],
+            smallvec![b"b".to_vec(), b"val_b".to_vec()],
+            smallvec![b"missing".to_vec(), b"val_m".to_vec()],
         ];
         let inner_data: Vec<JoinRow> = vec![
-            smallvec!["a".to_string(), "inner_a".to_string()],
-            smallvec!["b".to_string(), "inner_b".to_string()],
+            smallvec![b"a".to_vec(), b"inner_a".to_vec()],
+            smallvec![b"b".to_vec(), b"inner_b".to_vec()],
         ];
 
         let result = perform_hash_join(&outer_data, &inner_data, 0, 0, &RedisJoinType::Left);
@@ -308,7 +316,7 @@ mod tests {
 
         for row in &result {
             if let JoinResultRow::OuterOnly { outer_idx } = row {
-                assert_eq!(outer_data[*outer_idx][0], "missing");
+                assert_eq!(outer_data[*outer_idx][0], b"missing");
             }
         }
     }
@@ -316,14 +324,14 @@ mod tests {
     #[test]
     fn test_inner_join_empty_outer() {
         let outer_data: Vec<JoinRow> = vec![];
-        let inner_data: Vec<JoinRow> = vec![smallvec!["a".to_string(), "val".to_string()]];
+        let inner_data: Vec<JoinRow> = vec![smallvec![b"a".to_vec(), b"val".to_vec()]];
         let result = perform_hash_join(&outer_data, &inner_data, 0, 0, &RedisJoinType::Inner);
         assert_eq!(result.len(), 0);
     }
 
     #[test]
     fn test_inner_join_empty_inner() {
-        let outer_data: Vec<JoinRow> = vec![smallvec!["a".to_string(), "val".to_string()]];
+        let outer_data: Vec<JoinRow> = vec![smallvec![b"a".to_vec(), b"val".to_vec()]];
         let inner_data: Vec<JoinRow> = vec![];
         let result = perform_hash_join(&outer_data, &inner_data, 0, 0, &RedisJoinType::Inner);
         assert_eq!(result.len(), 0);
@@ -332,8 +340,8 @@ mod tests {
     #[test]
     fn test_left_join_empty_inner() {
         let outer_data: Vec<JoinRow> = vec![
-            smallvec!["a".to_string(), "val_a".to_string()],
-            smallvec!["b".to_string(), "val_b".to_string()],
+            smallvec![b"a".to_vec(), b"val_a".to_vec()],
+            smallvec![b"b".to_vec(), b"val_b".to_vec()],
         ];
         let inner_data: Vec<JoinRow> = vec![];
         let result = perform_hash_join(&outer_data, &inner_data, 0, 0, &RedisJoinType::Left);
@@ -346,13 +354,13 @@ mod tests {
     #[test]
     fn test_join_duplicate_keys() {
         let outer_data: Vec<JoinRow> = vec![
-            smallvec!["dup".to_string()],
-            smallvec!["dup".to_string()],
-            smallvec!["unique".to_string()],
+            smallvec![b"dup".to_vec()],
+            smallvec![b"dup".to_vec()],
+            smallvec![b"unique".to_vec()],
         ];
         let inner_data: Vec<JoinRow> = vec![
-            smallvec!["dup".to_string(), "val_dup".to_string()],
-            smallvec!["unique".to_string(), "val_unique".to_string()],
+            smallvec![b"dup".to_vec(), b"val_dup".to_vec()],
+            smallvec![b"unique".to_vec(), b"val_unique".to_vec()],
         ];
         let result = perform_hash_join(&outer_data, &inner_data, 0, 0, &RedisJoinType::Inner);
         assert_eq!(
@@ -365,13 +373,13 @@ mod tests {
     #[test]
     fn test_left_join_outer_larger() {
         let outer_data: Vec<JoinRow> = vec![
-            smallvec!["a".to_string(), "val_a".to_string()],
-            smallvec!["b".to_string(), "val_b".to_string()],
-            smallvec!["c".to_string(), "val_c".to_string()],
-            smallvec!["d".to_string(), "val_d".to_string()],
-            smallvec!["e".to_string(), "val_e".to_string()],
+            smallvec![b"a".to_vec(), b"val_a".to_vec()],
+            smallvec![b"b".to_vec(), b"val_b".to_vec()],
+            smallvec![b"c".to_vec(), b"val_c".to_vec()],
+            smallvec![b"d".to_vec(), b"val_d".to_vec()],
+            smallvec![b"e".to_vec(), b"val_e".to_vec()],
         ];
-        let inner_data: Vec<JoinRow> = vec![smallvec!["a".to_string()], smallvec!["c".to_string()]];
+        let inner_data: Vec<JoinRow> = vec![smallvec![b"a".to_vec()], smallvec![b"c".to_vec()]];
         let result = perform_hash_join(&outer_data, &inner_data, 0, 0, &RedisJoinType::Left);
         assert_eq!(result.len(), 5, "LEFT JOIN should preserve all outer rows");
 
@@ -158,7 +158,16 @@ pub(crate) unsafe fn add_parameterized_paths(
             RedisTableType::Set(_) => my_col_idx == pushdown_col,
             RedisTableType::ZSet(_) => my_col_idx == pushdown_col,
             RedisTableType::Stream(_) => my_col_idx == pushdown_col,
-            RedisTableType::String(_) if state.is_multi_key => my_col_idx == pushdown_col,
+            RedisTableType::Geo(_) => my_col_idx == pushdown_col,
+            RedisTableType::Bitmap(_) => my_col_idx == pushdown_col,
+            RedisTableType::String(_)
+            | RedisTableType::HashRow(_)
+            | RedisTableType::Json(_)
+            | RedisTableType::HyperLogLog(_)
+                if state.is_multi_key =>
+            {
+                my_col_idx == pushdown_col
+            }
             _ => false,
         };
         if !valid {
@@ -462,6 +471,26 @@ pub(crate) unsafe extern "C-unwind" fn get_foreign_join_paths(
         return;
     }
 
+    let no_join_pushdown = |table_type: &RedisTableType| {
+        matches!(
+            table_type,
+            RedisTableType::HashRow(_)
+                | RedisTableType::Json(_)
+                | RedisTableType::Geo(_)
+                | RedisTableType::Bitmap(_)
+                | RedisTableType::HyperLogLog(_)
+                | RedisTableType::PubSub(_)
+                | RedisTableType::Keyspace(_)
+                | RedisTableType::Function(_)
+        )
+    };
+    if no_join_pushdown(&outer_state.table_type) || no_join_pushdown(&inner_state.table_type) {
+        log!(
+            "hash_row/json/geo/bitmap/hyperloglog/pubsub/keyspace/function table detected, join pushdown not supported"
+        );
+        return;
+    }
+
     if !(*outerrel).baserestrictinfo.is_null()
         && pg_sys::list_length((*outerrel).baserestrictinfo) > 0
     {
@@ -2,7 +2,7 @@ use crate::core::pool_manager::PooledConnection;
 use crate::tables::types::RedisTableType;
 use smallvec::SmallVec;
 
-pub type JoinRow = SmallVec<[String; 3]>;
+pub type JoinRow = SmallVec<[Vec<u8>; 3]>;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum RedisJoinType {
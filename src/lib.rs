@@ -13,6 +13,9 @@ mod join;
 // Table type implementations
 mod tables;
 
+// SQL-callable functions
+mod functions;
+
 // Utility functions and helpers
 mod utils;
 
@@ -30,6 +33,7 @@ pub use core::validator::redis_fdw_validator_wrapper;
 #[pgrx::pg_guard]
 pub unsafe extern "C-unwind" fn _PG_init() {
     core::ddl_hook::init_hook();
+    core::transaction::init_xact_callback();
 }
 
 /// This module is required by `cargo pgrx test` invocations.
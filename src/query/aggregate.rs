@@ -0,0 +1,160 @@
+/// Aggregate pushdown: `count(*)` over a whole single-key bitmap table is
+/// answered by one BITCOUNT instead of reading every set bit.
+use crate::{
+    core::{column_utils::state_from_ptr, state_manager::RedisFdwState},
+    query::cost_estimation::costs,
+    tables::types::RedisTableType,
+    utils::helpers::*,
+};
+use pgrx::prelude::*;
+use std::{ffi::CStr, ptr};
+
+#[pg_guard]
+pub(crate) unsafe extern "C-unwind" fn get_foreign_upper_paths(
+    root: *mut pg_sys::PlannerInfo,
+    stage: pg_sys::UpperRelationKind::Type,
+    input_rel: *mut pg_sys::RelOptInfo,
+    output_rel: *mut pg_sys::RelOptInfo,
+    _extra: *mut std::ffi::c_void,
+) {
+    log!("---> get_foreign_upper_paths (stage={})", stage);
+
+    // Called again for the same output relation when it is planned twice.
+    if stage != pg_sys::UpperRelationKind::UPPERREL_GROUP_AGG
+        || !(*output_rel).fdw_private.is_null()
+        || (*input_rel).reloptkind != pg_sys::RelOptKind::RELOPT_BASEREL
+        || (*input_rel).fdw_private.is_null()
+    {
+        return;
+    }
+    let state = &*((*input_rel).fdw_private as *mut RedisFdwState);
+    if !matches!(state.table_type, RedisTableType::Bitmap(_))
+        || state.is_multi_key
+        || !(*input_rel).baserestrictinfo.is_null()
+        || !is_plain_count_star(root, (*output_rel).reltarget)
+    {
+        return;
+    }
+
+    (*output_rel).fdw_private = (*input_rel).fdw_private;
+    let cost = costs::CONNECTION_OVERHEAD + costs::NETWORK_ROUND_TRIP;
+    let path = pg_sys::create_foreign_upper_path(
+        root,
+        output_rel,
+        (*output_rel).reltarget,
+        1.0,
+        #[cfg(feature = "pg18")]
+        0,
+        cost,
+        cost + costs::CPU_TUPLE_COST,
+        ptr::null_mut(),
+        ptr::null_mut(),
+        #[cfg(any(feature = "pg17", feature = "pg18"))]
+        ptr::null_mut(),
+        ptr::null_mut(),
+    );
+    pg_sys::add_path(output_rel, path as *mut pg_sys::Path);
+}
+
+/// Whether the query is an ungrouped `SELECT count(*)` with no HAVING: its
+/// grouping target is a lone plain `count(*)` aggregate.
+unsafe fn is_plain_count_star(
+    root: *mut pg_sys::PlannerInfo,
+    target: *mut pg_sys::PathTarget,
+) -> bool {
+    let parse = (*root).parse;
+    if !(*parse).groupClause.is_null()
+        || !(*parse).groupingSets.is_null()
+        || !(*parse).havingQual.is_null()
+        || target.is_null()
+        || pg_sys::list_length((*target).exprs) != 1
+    {
+        return false;
+    }
+    let expr = pg_sys::list_nth((*target).exprs, 0) as *mut pg_sys::Node;
+    if !pgrx::is_a(expr, pg_sys::NodeTag::T_Aggref) {
+        return false;
+    }
+    let aggref = expr as *mut pg_sys::Aggref;
+    if !(*aggref).aggstar
+        || (*aggref).aggtype != pg_sys::INT8OID
+        || !(*aggref).aggfilter.is_null()
+        || !(*aggref).aggdistinct.is_null()
+        || !(*aggref).aggorder.is_null()
+        // AGGKIND_NORMAL, not an ordered-set or hypothetical aggregate
+        || (*aggref).aggkind != b'n' as std::ffi::c_char
+    {
+        return false;
+    }
+    let name = pg_sys::get_func_name((*aggref).aggfnoid);
+    !name.is_null() && CStr::from_ptr(name).to_bytes() == b"count"
+}
+
+/// Plan of a pushed-down `count(*)`: a scan of no relation whose single
+/// output column is the count.
+pub(crate) unsafe fn plan_foreign_count(
+    upper_rel: *mut pg_sys::RelOptInfo,
+    tlist: *mut pg_sys::List,
+    outer_plan: *mut pg_sys::Plan,
+) -> *mut pg_sys::ForeignScan {
+    let state = state_from_ptr((*upper_rel).fdw_private);
+    state.count_pushdown = true;
+
+    let fdw_private = serialize_ptr_to_list((*upper_rel).fdw_private);
+    let fdw_scan_tlist = pg_sys::copyObjectImpl(tlist as *const std::ffi::c_void);
+    pg_sys::make_foreignscan(
+        tlist,
+        ptr::null_mut(),
+        0,
+        ptr::null_mut(),
+        fdw_private as _,
+        fdw_scan_tlist as *mut pg_sys::List,
+        ptr::null_mut(),
+        outer_plan,
+    )
+}
+
+pub(crate) unsafe fn begin_foreign_count_scan(
+    node: *mut pg_sys::ForeignScanState,
+    plan: *mut pg_sys::ForeignScan,
+) {
+    log!("---> begin_foreign_count_scan");
+    let state_ptr = deserialize_ptr_from_list((*plan).fdw_private as _);
+    let state = state_from_ptr(state_ptr);
+
+    if state.redis_connection.is_none() {
+        if let Err(e) = state.init_redis_connection_from_options() {
+            pgrx::error!("Failed to connect to Redis for count scan: {}", e);
+        }
+    }
+
+    (*node).fdw_state = state_ptr;
+}
+
+/// Store the count of a pushed-down `count(*)` in `slot`, once per scan.
+pub(crate) unsafe fn iterate_foreign_count_scan(
+    state: &mut RedisFdwState,
+    slot: *mut pg_sys::TupleTableSlot,
+) -> *mut pg_sys::TupleTableSlot {
+    if state.scan_complete {
+        return slot;
+    }
+    state.scan_complete = true;
+
+    let count = match state.count_set_bits() {
+        Ok(count) => count,
+        Err(e) => pgrx::error!(
+            "redis_fdw: BITCOUNT of '{}' failed: {}",
+            state.table_key_prefix,
+            e
+        ),
+    };
+    write_datum_to_slot(
+        slot,
+        (*slot).tts_tupleDescriptor,
+        0,
+        Some(count.to_string().as_bytes()),
+    );
+    pg_sys::ExecStoreVirtualTuple(slot);
+    slot
+}
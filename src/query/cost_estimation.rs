@@ -168,8 +168,11 @@ impl<'a> CostEstimator<'a> {
         }
 
         match self.table_type {
-            RedisTableType::String(_) => {
-                // String type has cardinality of 1 per key
+            RedisTableType::String(_)
+            | RedisTableType::HashRow(_)
+            | RedisTableType::Json(_)
+            | RedisTableType::HyperLogLog(_) => {
+                // One row per key: check if key exists
                 // Check if key exists
                 if let Ok(exists) = cmd("EXISTS").arg(self.key_prefix).query::<i32>(conn) {
                     Some(exists.max(0) as u64)
@@ -189,15 +192,23 @@ impl<'a> CostEstimator<'a> {
                 // SCARD returns set cardinality
                 cmd("SCARD").arg(self.key_prefix).query::<u64>(conn).ok()
             }
-            RedisTableType::ZSet(_) => {
-                // ZCARD returns sorted set cardinality
+            RedisTableType::ZSet(_) | RedisTableType::Geo(_) => {
+                // ZCARD returns sorted set cardinality (geo keys are zsets)
                 cmd("ZCARD").arg(self.key_prefix).query::<u64>(conn).ok()
             }
             RedisTableType::Stream(_) => {
                 // XLEN returns stream length
                 cmd("XLEN").arg(self.key_prefix).query::<u64>(conn).ok()
             }
-            RedisTableType::None => None,
+            RedisTableType::Bitmap(_) => {
+                // BITCOUNT returns the number of set bits, one row each
+                cmd("BITCOUNT").arg(self.key_prefix).query::<u64>(conn).ok()
+            }
+            // Published messages are not stored
+            RedisTableType::PubSub(_) => Some(0),
+            RedisTableType::Keyspace(_) => Some(1),
+            // Only the read function knows how many rows it returns
+            RedisTableType::Function(_) | RedisTableType::None => None,
         }
     }
 
@@ -271,13 +282,19 @@ impl<'a> CostEstimator<'a> {
         if let Some(matching) = stats.matching_key_count {
             // For multi-key patterns, estimate based on type
             return match self.table_type {
-                RedisTableType::String(_) => matching as f64,
+                RedisTableType::String(_)
+                | RedisTableType::HashRow(_)
+                | RedisTableType::Json(_)
+                | RedisTableType::HyperLogLog(_) => matching as f64,
                 RedisTableType::Hash(_) => (matching * 10) as f64, // Assume avg 10 fields
                 RedisTableType::List(_) => (matching * 100) as f64, // Assume avg 100 items
                 RedisTableType::Set(_) => (matching * 50) as f64,
-                RedisTableType::ZSet(_) => (matching * 50) as f64,
+                RedisTableType::ZSet(_) | RedisTableType::Geo(_) => (matching * 50) as f64,
                 RedisTableType::Stream(_) => (matching * 1000) as f64,
-                RedisTableType::None => matching as f64,
+                RedisTableType::Bitmap(_) => (matching * 1000) as f64,
+                RedisTableType::PubSub(_) => 0.0,
+                RedisTableType::Keyspace(_) => matching as f64,
+                RedisTableType::Function(_) | RedisTableType::None => matching as f64,
             };
         }
 
@@ -313,7 +330,9 @@ impl<'a> CostEstimator<'a> {
                 ComparisonOperator::GreaterThan
                 | ComparisonOperator::GreaterThanOrEqual
                 | ComparisonOperator::LessThan
-                | ComparisonOperator::LessThanOrEqual => costs::RANGE_SELECTIVITY,
+                | ComparisonOperator::LessThanOrEqual
+                | ComparisonOperator::GeoRadius { .. }
+                | ComparisonOperator::GeoBox { .. } => costs::RANGE_SELECTIVITY,
             };
 
             // Combine selectivities (assuming independence)
@@ -394,12 +413,20 @@ impl<'a> CostEstimator<'a> {
     /// Estimate average row width based on table type
     fn estimate_row_width(&self) -> i32 {
         match self.table_type {
-            RedisTableType::String(_) => 100, // key + value
-            RedisTableType::Hash(_) => 150,   // key + field + value
-            RedisTableType::List(_) => 50,    // index + value
-            RedisTableType::Set(_) => 50,     // member
-            RedisTableType::ZSet(_) => 60,    // member + score
-            RedisTableType::Stream(_) => 200, // id + multiple fields
+            RedisTableType::String(_) => 100,     // key + value
+            RedisTableType::Hash(_) => 150,       // key + field + value
+            RedisTableType::List(_) => 50,        // index + value
+            RedisTableType::Set(_) => 50,         // member
+            RedisTableType::ZSet(_) => 60,        // member + score
+            RedisTableType::Stream(_) => 200,     // id + multiple fields
+            RedisTableType::HashRow(_) => 150,    // key + mapped fields
+            RedisTableType::Json(_) => 200,       // key + mapped paths
+            RedisTableType::Geo(_) => 80,         // member + coordinates
+            RedisTableType::Bitmap(_) => 16,      // offset + bit
+            RedisTableType::HyperLogLog(_) => 40, // key + count
+            RedisTableType::PubSub(_) => 100,     // channel + message
+            RedisTableType::Keyspace(_) => 80,    // key + type + attributes
+            RedisTableType::Function(_) => 100,   // whatever the function returns
             RedisTableType::None => 100,
         }
     }
@@ -38,6 +38,14 @@ impl LimitOffsetInfo {
 
         data
     }
+
+    /// Rows the scan must return for the LIMIT node above it to produce its
+    /// output: the offset rows it skips plus the limit. `None` when there is
+    /// no LIMIT.
+    pub fn rows_needed(&self) -> Option<usize> {
+        self.limit
+            .map(|limit| limit.saturating_add(self.offset.unwrap_or(0)))
+    }
 }
 
 /// Extract LIMIT and OFFSET information from PostgreSQL planner
@@ -45,6 +53,28 @@ impl LimitOffsetInfo {
 /// # Safety
 /// This function assumes valid pointers and proper PostgreSQL context
 pub unsafe fn extract_limit_offset_info(root: *mut pg_sys::PlannerInfo) -> Option<LimitOffsetInfo> {
+    limit_offset_above_scan(root, false)
+}
+
+/// Like [`extract_limit_offset_info`], for a scan whose path already returns
+/// rows in the query's ORDER BY order, so no sort stands between the scan and
+/// the LIMIT. The query must read this relation alone.
+///
+/// # Safety
+/// This function assumes valid pointers and proper PostgreSQL context
+pub unsafe fn extract_sorted_limit_offset_info(
+    root: *mut pg_sys::PlannerInfo,
+) -> Option<LimitOffsetInfo> {
+    if root.is_null() || pg_sys::bms_num_members((*root).all_baserels) != 1 {
+        return None;
+    }
+    limit_offset_above_scan(root, true)
+}
+
+unsafe fn limit_offset_above_scan(
+    root: *mut pg_sys::PlannerInfo,
+    sorted_scan: bool,
+) -> Option<LimitOffsetInfo> {
     if root.is_null() {
         return None;
     }
@@ -59,7 +89,7 @@ pub unsafe fn extract_limit_offset_info(root: *mut pg_sys::PlannerInfo) -> Optio
         || (*parse).hasWindowFuncs
         || !(*parse).groupClause.is_null()
         || !(*parse).distinctClause.is_null()
-        || !(*parse).sortClause.is_null()
+        || (!(*parse).sortClause.is_null() && !sorted_scan)
     {
         return None;
     }
@@ -200,6 +230,20 @@ mod tests {
         assert!(info.apply_to_vec(data).is_empty());
     }
 
+    #[test]
+    fn test_rows_needed_counts_skipped_rows() {
+        let info = LimitOffsetInfo {
+            limit: Some(5),
+            offset: Some(10),
+        };
+        assert_eq!(info.rows_needed(), Some(15));
+        let info = LimitOffsetInfo {
+            limit: None,
+            offset: Some(10),
+        };
+        assert_eq!(info.rows_needed(), None);
+    }
+
     #[test]
     fn test_apply_to_vec_no_constraints() {
         let data = vec![1, 2, 3, 4, 5];
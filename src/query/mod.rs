@@ -2,6 +2,7 @@
 ///
 /// This module handles query planning, WHERE clause pushdown optimization,
 /// cost estimation, and other query processing enhancements for the Redis FDW.
+pub mod aggregate;
 pub mod cost_estimation;
 pub mod limit;
 pub mod pushdown;
@@ -30,8 +30,9 @@ impl WhereClausePushdown {
         // Extract clauses from the list
         let clauses = Self::extract_clauses_from_list(scan_clauses);
 
+        let supports = |op: &ComparisonOperator| table_type.supports_pushdown(op);
         for clause in clauses {
-            if let Some(condition) = Self::analyze_expression(clause, table_type, relation) {
+            if let Some(condition) = Self::analyze_expression(clause, &supports, relation) {
                 analysis.pushable_conditions.push(condition);
                 analysis.can_optimize = true;
             }
@@ -40,6 +41,77 @@ impl WhereClausePushdown {
         analysis
     }
 
+    /// Analyze the quals of an UPDATE/DELETE for direct modify. Every clause
+    /// must be a column/constant comparison, whatever operators the table
+    /// type pushes down for scans; `None` if any clause is not.
+    pub unsafe fn analyze_modify_clauses(
+        clauses: *mut pg_sys::List,
+        relation: pg_sys::Relation,
+    ) -> Option<Vec<PushableCondition>> {
+        Self::extract_clauses_from_list(clauses)
+            .into_iter()
+            .map(|clause| Self::analyze_expression(clause, &|_| true, relation))
+            .collect()
+    }
+
+    /// `column <op> value` clauses among `scan_clauses` on the column at
+    /// `column_index` whose value is of type `type_oid` and can be computed
+    /// once when the scan begins: no column references, volatile functions
+    /// or executor parameters, so `now() - interval '1 hour'` or a `$1`
+    /// qualify. The operator reads with the column on the left.
+    pub unsafe fn extract_stable_comparisons(
+        scan_clauses: *mut pg_sys::List,
+        relation: pg_sys::Relation,
+        column_index: usize,
+        type_oid: pg_sys::Oid,
+    ) -> Vec<(ComparisonOperator, *mut pg_sys::Expr)> {
+        let clauses = pg_sys::extract_actual_clauses(scan_clauses, false);
+        Self::extract_clauses_from_list(clauses)
+            .into_iter()
+            .filter_map(|clause| {
+                if (*clause).type_ != pg_sys::NodeTag::T_OpExpr {
+                    return None;
+                }
+                let op_expr = &*(clause as *mut pg_sys::OpExpr);
+                if pg_sys::list_length(op_expr.args) != 2 {
+                    return None;
+                }
+                let arg = |i: i32| pg_sys::list_nth(op_expr.args, i) as *mut pg_sys::Node;
+                let on_column =
+                    |i| Self::extract_column_info(arg(i), relation).map(|(_, index)| index);
+                let (value, swapped) = if on_column(0) == Some(column_index) {
+                    (arg(1), false)
+                } else if on_column(1) == Some(column_index) {
+                    (arg(0), true)
+                } else {
+                    return None;
+                };
+                if pg_sys::exprType(value) != type_oid
+                    || pg_sys::contain_var_clause(value)
+                    || pg_sys::contain_volatile_functions(value)
+                    || !pg_sys::pull_paramids(value as *mut pg_sys::Expr).is_null()
+                {
+                    return None;
+                }
+                let operator = Self::get_operator_from_oid(op_expr.opno)?;
+                let operator = if swapped {
+                    operator.commuted()
+                } else {
+                    operator
+                };
+                matches!(
+                    operator,
+                    ComparisonOperator::Equal
+                        | ComparisonOperator::GreaterThan
+                        | ComparisonOperator::GreaterThanOrEqual
+                        | ComparisonOperator::LessThan
+                        | ComparisonOperator::LessThanOrEqual
+                )
+                .then_some((operator, value as *mut pg_sys::Expr))
+            })
+            .collect()
+    }
+
     /// Extract individual clauses from PostgreSQL List
     unsafe fn extract_clauses_from_list(scan_clauses: *mut pg_sys::List) -> Vec<*mut pg_sys::Node> {
         let mut clauses = Vec::new();
@@ -62,7 +134,7 @@ impl WhereClausePushdown {
     /// Analyze a single expression to see if it can be pushed down
     unsafe fn analyze_expression(
         node: *mut pg_sys::Node,
-        table_type: &RedisTableType,
+        supports: &dyn Fn(&ComparisonOperator) -> bool,
         relation: pg_sys::Relation,
     ) -> Option<PushableCondition> {
         if node.is_null() {
@@ -72,15 +144,18 @@ impl WhereClausePushdown {
         //log!("Analyzing expression (*node).type_: {:?}", (*node).type_);
         match (*node).type_ {
             pg_sys::NodeTag::T_OpExpr => {
-                Self::analyze_op_expr(node as *mut pg_sys::OpExpr, table_type, relation)
+                Self::analyze_op_expr(node as *mut pg_sys::OpExpr, supports, relation)
             }
             pg_sys::NodeTag::T_ScalarArrayOpExpr => Self::analyze_scalar_array_op_expr(
                 node as *mut pg_sys::ScalarArrayOpExpr,
-                table_type,
+                supports,
                 relation,
             ),
             pg_sys::NodeTag::T_RestrictInfo => {
-                Self::analyze_restrict_info(node as *mut pg_sys::RestrictInfo, table_type, relation)
+                Self::analyze_restrict_info(node as *mut pg_sys::RestrictInfo, supports, relation)
+            }
+            pg_sys::NodeTag::T_FuncExpr => {
+                Self::analyze_func_expr(node as *mut pg_sys::FuncExpr, supports, relation)
             }
             _ => {
                 // Other expression types are not supported for pushdown yet
@@ -92,7 +167,7 @@ impl WhereClausePushdown {
     /// Analyze operator expressions (=, <>, LIKE, etc.)
     unsafe fn analyze_op_expr(
         op_expr: *mut pg_sys::OpExpr,
-        table_type: &RedisTableType,
+        supports: &dyn Fn(&ComparisonOperator) -> bool,
         relation: pg_sys::Relation,
     ) -> Option<PushableCondition> {
         if op_expr.is_null() {
@@ -110,13 +185,19 @@ impl WhereClausePushdown {
         let right_arg = pg_sys::list_nth(op_expr.args, 1) as *mut pg_sys::Node;
 
         // Extract column name and value
-        let (column_name, column_index, value) =
+        let (column_name, column_index, value, swapped) =
             Self::extract_column_and_value(left_arg, right_arg, relation)?;
-        // Determine operator type based on operator OID
+        // Determine operator type based on operator OID; `'5' < column` is
+        // `column > '5'`
         let operator = Self::get_operator_from_oid(op_expr.opno)?;
+        let operator = if swapped {
+            operator.commuted()
+        } else {
+            operator
+        };
 
         // Check if this condition is suitable for the table type
-        if table_type.supports_pushdown(&operator) {
+        if supports(&operator) {
             Some(PushableCondition {
                 column_name,
                 column_index,
@@ -128,10 +209,60 @@ impl WhereClausePushdown {
         }
     }
 
+    /// Analyze calls of `redis_geo_within(lon, lat, center_lon, center_lat,
+    /// radius)` and `redis_geo_within_box(lon, lat, center_lon, center_lat,
+    /// width, height)` whose first two arguments are columns and the rest
+    /// constants. The condition is on the longitude column; its value holds
+    /// the constants, comma-separated.
+    unsafe fn analyze_func_expr(
+        func_expr: *mut pg_sys::FuncExpr,
+        supports: &dyn Fn(&ComparisonOperator) -> bool,
+        relation: pg_sys::Relation,
+    ) -> Option<PushableCondition> {
+        if func_expr.is_null() {
+            return None;
+        }
+
+        let func_expr = &*func_expr;
+        let name_ptr = pg_sys::get_func_name(func_expr.funcid);
+        if name_ptr.is_null() {
+            return None;
+        }
+        let nargs = pg_sys::list_length(func_expr.args);
+        let is_box = match (std::ffi::CStr::from_ptr(name_ptr).to_str().ok()?, nargs) {
+            ("redis_geo_within", 5) => false,
+            ("redis_geo_within_box", 6) => true,
+            _ => return None,
+        };
+        let arg = |i: i32| pg_sys::list_nth(func_expr.args, i) as *mut pg_sys::Node;
+
+        let (column_name, column_index) = Self::extract_column_info(arg(0), relation)?;
+        let (_, latitude_column) = Self::extract_column_info(arg(1), relation)?;
+        let operator = if is_box {
+            ComparisonOperator::GeoBox { latitude_column }
+        } else {
+            ComparisonOperator::GeoRadius { latitude_column }
+        };
+        if !supports(&operator) {
+            return None;
+        }
+
+        let value = (2..nargs)
+            .map(|i| Self::extract_constant_value(arg(i)))
+            .collect::<Option<Vec<_>>>()?
+            .join(",");
+        Some(PushableCondition {
+            column_name,
+            column_index,
+            operator,
+            value,
+        })
+    }
+
     /// Analyze restrict info nodes (wrapper around actual expressions)
     unsafe fn analyze_restrict_info(
         restrict_info: *mut pg_sys::RestrictInfo,
-        table_type: &RedisTableType,
+        supports: &dyn Fn(&ComparisonOperator) -> bool,
         relation: pg_sys::Relation,
     ) -> Option<PushableCondition> {
         if restrict_info.is_null() {
@@ -149,13 +280,13 @@ impl WhereClausePushdown {
         }
 
         // Recursively analyze the wrapped clause
-        Self::analyze_expression(clause, table_type, relation)
+        Self::analyze_expression(clause, supports, relation)
     }
 
     /// Analyze scalar array operator expressions (IN, NOT IN)
     unsafe fn analyze_scalar_array_op_expr(
         array_op_expr: *mut pg_sys::ScalarArrayOpExpr,
-        table_type: &RedisTableType,
+        supports: &dyn Fn(&ComparisonOperator) -> bool,
         relation: pg_sys::Relation,
     ) -> Option<PushableCondition> {
         if array_op_expr.is_null() {
@@ -183,12 +314,18 @@ impl WhereClausePushdown {
         };
 
         // Check if this condition is suitable for the table type
-        if !table_type.supports_pushdown(&operator) {
+        if !supports(&operator) {
             return None;
         }
 
         // Try to extract array values using a simpler approach
         if let Some(array_values) = Self::extract_array_values(right_arg) {
+            // The list travels comma-joined: `key IN ('a,b')` would read back
+            // as `a` and `b`, and a direct DELETE would unlink those keys.
+            if array_values.iter().any(|v| v.contains(',')) {
+                log!("IN list element contains a comma, disabling pushdown for this IN clause");
+                return None;
+            }
             let value = array_values.join(",");
             Some(PushableCondition {
                 column_name,
@@ -353,18 +490,19 @@ impl WhereClausePushdown {
         }
     }
 
-    /// Extract column name and value from binary expression arguments
+    /// Extract column name and value from binary expression arguments.
+    /// The flag is set when the column is the right-hand operand.
     unsafe fn extract_column_and_value(
         left_arg: *mut pg_sys::Node,
         right_arg: *mut pg_sys::Node,
         relation: pg_sys::Relation,
-    ) -> Option<(String, usize, String)> {
+    ) -> Option<(String, usize, String, bool)> {
         // Try left as column, right as value
         if let (Some((column, index)), Some(value)) = (
             Self::extract_column_info(left_arg, relation),
             Self::extract_constant_value(right_arg),
         ) {
-            return Some((column, index, value));
+            return Some((column, index, value, false));
         }
 
         // Try right as column, left as value (for cases like '5' = column)
@@ -372,7 +510,7 @@ impl WhereClausePushdown {
             Self::extract_column_info(right_arg, relation),
             Self::extract_constant_value(left_arg),
         ) {
-            return Some((column, index, value));
+            return Some((column, index, value, true));
         }
 
         None
@@ -414,7 +552,7 @@ impl WhereClausePushdown {
     }
 
     /// Extract constant value from a Const node
-    unsafe fn extract_constant_value(node: *mut pg_sys::Node) -> Option<String> {
+    pub(crate) unsafe fn extract_constant_value(node: *mut pg_sys::Node) -> Option<String> {
         if node.is_null() {
             return None;
         }
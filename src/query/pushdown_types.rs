@@ -23,6 +23,17 @@ pub enum ComparisonOperator {
     GreaterThanOrEqual, // >=
     LessThan,           // <
     LessThanOrEqual,    // <=
+    /// `redis_geo_within(longitude, latitude, ...)`; the condition's column
+    /// is the longitude one, its value `center_lon,center_lat,radius_m`
+    GeoRadius {
+        latitude_column: usize,
+    },
+    /// `redis_geo_within_box(longitude, latitude, ...)`; the condition's
+    /// column is the longitude one, its value
+    /// `center_lon,center_lat,width_m,height_m`
+    GeoBox {
+        latitude_column: usize,
+    },
 }
 
 impl std::fmt::Display for ComparisonOperator {
@@ -37,6 +48,21 @@ impl std::fmt::Display for ComparisonOperator {
             ComparisonOperator::GreaterThanOrEqual => write!(f, ">="),
             ComparisonOperator::LessThan => write!(f, "<"),
             ComparisonOperator::LessThanOrEqual => write!(f, "<="),
+            ComparisonOperator::GeoRadius { .. } => write!(f, "BYRADIUS"),
+            ComparisonOperator::GeoBox { .. } => write!(f, "BYBOX"),
+        }
+    }
+}
+
+impl ComparisonOperator {
+    /// The operator with its operands swapped: `a < b` is `b > a`.
+    pub fn commuted(&self) -> Self {
+        match self {
+            ComparisonOperator::GreaterThan => ComparisonOperator::LessThan,
+            ComparisonOperator::GreaterThanOrEqual => ComparisonOperator::LessThanOrEqual,
+            ComparisonOperator::LessThan => ComparisonOperator::GreaterThan,
+            ComparisonOperator::LessThanOrEqual => ComparisonOperator::GreaterThanOrEqual,
+            other => other.clone(),
         }
     }
 }
@@ -153,4 +179,24 @@ mod tests {
         assert_ne!(ComparisonOperator::Equal, ComparisonOperator::NotEqual);
         assert_ne!(ComparisonOperator::Like, ComparisonOperator::In);
     }
+
+    #[test]
+    fn test_commuted_flips_range_operators_only() {
+        assert_eq!(
+            ComparisonOperator::LessThan.commuted(),
+            ComparisonOperator::GreaterThan
+        );
+        assert_eq!(
+            ComparisonOperator::GreaterThanOrEqual.commuted(),
+            ComparisonOperator::LessThanOrEqual
+        );
+        assert_eq!(
+            ComparisonOperator::Equal.commuted(),
+            ComparisonOperator::Equal
+        );
+        assert_eq!(
+            ComparisonOperator::Like.commuted(),
+            ComparisonOperator::Like
+        );
+    }
 }
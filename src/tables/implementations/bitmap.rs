@@ -0,0 +1,530 @@
+use std::borrow::Cow;
+
+use crate::{
+    query::{
+        limit::LimitOffsetInfo,
+        pushdown_types::{ComparisonOperator, PushableCondition},
+    },
+    tables::{
+        interface::RedisTableOperations,
+        types::{DataSet, InsertMode, LoadDataResult, RowValue, RowVec},
+    },
+};
+
+/// Largest bit offset SETBIT accepts (the value is capped at 512 MB).
+pub const MAX_BIT_OFFSET: u64 = (1 << 32) - 1;
+
+/// Bytes read per GETRANGE when a scan enumerates the whole bitmap.
+const SCAN_CHUNK_BYTES: usize = 8192;
+
+/// SETBIT only when the bit is already set (`xx`) or clear (`nx`),
+/// reporting whether it wrote.
+const SETBIT_IF_SCRIPT: &str = "if (redis.call('GETBIT', KEYS[1], ARGV[1]) == 1) == \
+     (ARGV[3] == 'xx') then redis.call('SETBIT', KEYS[1], ARGV[1], ARGV[2]) return 1 end \
+     return 0";
+
+/// The bit offset held by a column value, `None` when SETBIT would reject it.
+pub fn parse_offset(value: &str) -> Option<u64> {
+    value
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .filter(|&o| o <= MAX_BIT_OFFSET)
+}
+
+/// The bit a `bit` column value writes. A NULL or missing bit is a set bit:
+/// the table's rows are the set bits.
+fn parse_bit(value: Option<&str>) -> Option<u8> {
+    let Some(value) = value.map(|v| v.trim().to_lowercase()) else {
+        return Some(1);
+    };
+    match value.as_str() {
+        "null" | "true" | "t" | "1" | "on" | "yes" => Some(1),
+        "false" | "f" | "0" | "off" | "no" => Some(0),
+        _ => None,
+    }
+}
+
+/// Offsets of the set bits of `bytes`, which start at byte `first_byte` of
+/// the value. Bit 0 is the most significant bit of the first byte, as in
+/// SETBIT.
+pub fn set_bit_offsets(bytes: &[u8], first_byte: u64) -> impl Iterator<Item = u64> + '_ {
+    bytes.iter().enumerate().flat_map(move |(i, &byte)| {
+        (0..8u64)
+            .filter(move |bit| byte & (0x80 >> bit) != 0)
+            .map(move |bit| (first_byte + i as u64) * 8 + bit)
+    })
+}
+
+/// Redis bitmap table type: each set bit of the string value is a row of
+/// `(offset, bit)`, `bit` always true. Clear bits have no row.
+#[derive(Debug, Clone, Default)]
+pub struct RedisBitmapTable {
+    /// Rows as flat `[key?, offset, bit]` chunks
+    pub dataset: DataSet,
+    /// Whether rows start with the Redis key (multi-key mode)
+    pub key_column: bool,
+    pub pushdown_column_index: usize,
+}
+
+impl RedisBitmapTable {
+    pub fn new() -> Self {
+        Self {
+            dataset: DataSet::Empty,
+            key_column: false,
+            pushdown_column_index: 0,
+        }
+    }
+
+    /// Number of values in one row.
+    fn row_width(&self) -> usize {
+        usize::from(self.key_column) + 2
+    }
+
+    /// Write command for one row of `[offset, bit]`. `None` when the offset
+    /// or the bit is not one SETBIT takes. NX only sets a clear bit and XX
+    /// only writes a set one, through [`SETBIT_IF_SCRIPT`].
+    pub fn insert_command(
+        &self,
+        key: &str,
+        data: &[String],
+        mode: InsertMode,
+    ) -> Option<redis::Cmd> {
+        let offset = parse_offset(data.first()?)?;
+        let bit = parse_bit(data.get(1).map(String::as_str))?;
+        let cmd = match mode {
+            InsertMode::Overwrite => {
+                let mut cmd = redis::cmd("SETBIT");
+                cmd.arg(key).arg(offset).arg(bit);
+                cmd
+            }
+            InsertMode::Nx | InsertMode::Xx => {
+                let mut cmd = redis::cmd("EVAL");
+                cmd.arg(SETBIT_IF_SCRIPT)
+                    .arg(1)
+                    .arg(key)
+                    .arg(offset)
+                    .arg(bit)
+                    .arg(if mode == InsertMode::Xx { "xx" } else { "nx" });
+                cmd
+            }
+        };
+        Some(cmd)
+    }
+
+    /// Offsets named by an `=` or `IN` condition on the offset column.
+    /// Values that are no bit offset cannot match and are left out.
+    fn offset_lookup(&self, conditions: &[PushableCondition]) -> Option<Vec<u64>> {
+        let condition = conditions.iter().find(|c| {
+            c.column_index == self.pushdown_column_index
+                && matches!(
+                    c.operator,
+                    ComparisonOperator::Equal | ComparisonOperator::In
+                )
+        })?;
+        let values = match condition.operator {
+            ComparisonOperator::Equal => vec![condition.value.as_str()],
+            _ => condition.value.split(',').collect(),
+        };
+        Some(values.into_iter().filter_map(parse_offset).collect())
+    }
+
+    /// The given offsets of `key` whose bit is set, with one pipelined
+    /// GETBIT per offset.
+    fn set_offsets(
+        &self,
+        conn: &mut dyn redis::ConnectionLike,
+        key: &str,
+        offsets: &[u64],
+    ) -> Result<Vec<u64>, redis::RedisError> {
+        if offsets.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut pipe = redis::pipe();
+        for offset in offsets {
+            pipe.cmd("GETBIT").arg(key).arg(offset);
+        }
+        let bits: Vec<u8> = pipe.query(conn)?;
+        Ok(offsets
+            .iter()
+            .zip(bits)
+            .filter(|(_, bit)| *bit == 1)
+            .map(|(&offset, _)| offset)
+            .collect())
+    }
+
+    /// Set bits of up to `chunk_bytes` bytes of `key`, from the byte of the
+    /// first set bit at or after byte `cursor` (BITPOS) on (GETRANGE).
+    /// Returns the byte to continue from, 0 once the value is exhausted.
+    fn read_chunk(
+        &self,
+        conn: &mut dyn redis::ConnectionLike,
+        key: &str,
+        cursor: u64,
+        chunk_bytes: usize,
+    ) -> Result<(u64, Vec<u64>), redis::RedisError> {
+        let first: i64 = redis::cmd("BITPOS")
+            .arg(key)
+            .arg(1)
+            .arg(cursor)
+            .query(conn)?;
+        if first < 0 {
+            return Ok((0, Vec::new()));
+        }
+        let start = first as u64 / 8;
+        let bytes: Vec<u8> = redis::cmd("GETRANGE")
+            .arg(key)
+            .arg(start)
+            .arg(start + chunk_bytes as u64 - 1)
+            .query(conn)?;
+        let next = if bytes.len() < chunk_bytes {
+            0
+        } else {
+            start + bytes.len() as u64
+        };
+        Ok((next, set_bit_offsets(&bytes, start).collect()))
+    }
+
+    /// Append the row of the set bit at `offset` to `rows`.
+    fn push_row(&self, rows: &mut Vec<RowValue>, key: &str, offset: u64) {
+        if self.key_column {
+            rows.push(Some(key.as_bytes().to_vec()));
+        }
+        rows.push(Some(offset.to_string().into_bytes()));
+        rows.push(Some(b"true".to_vec()));
+    }
+
+    fn set_rows(&mut self, key: &str, offsets: Vec<u64>) -> LoadDataResult {
+        if offsets.is_empty() {
+            self.dataset = DataSet::Empty;
+            return LoadDataResult::Empty;
+        }
+        let mut rows = Vec::with_capacity(offsets.len() * self.row_width());
+        for offset in offsets {
+            self.push_row(&mut rows, key, offset);
+        }
+        self.dataset = DataSet::Filtered(rows);
+        LoadDataResult::FullyLoaded
+    }
+}
+
+impl RedisTableOperations for RedisBitmapTable {
+    /// An offset `=`/`IN` condition turns into pipelined GETBITs; anything
+    /// else walks the value with BITPOS and GETRANGE, stopping once the
+    /// LIMIT is met when there are no conditions.
+    fn load_data(
+        &mut self,
+        conn: &mut dyn redis::ConnectionLike,
+        key_prefix: &str,
+        conditions: Option<&[PushableCondition]>,
+        limit_offset: &LimitOffsetInfo,
+    ) -> Result<LoadDataResult, redis::RedisError> {
+        if limit_offset.limit == Some(0) {
+            self.dataset = DataSet::Empty;
+            return Ok(LoadDataResult::Empty);
+        }
+        let conditions = conditions.unwrap_or(&[]);
+
+        let offsets = if let Some(offsets) = self.offset_lookup(conditions) {
+            self.set_offsets(conn, key_prefix, &offsets)?
+        } else {
+            // Other conditions filter the found rows, so the LIMIT would be short.
+            let needed = limit_offset.rows_needed().filter(|_| conditions.is_empty());
+            let mut offsets = Vec::new();
+            let mut cursor = 0;
+            loop {
+                pgrx::check_for_interrupts!();
+                let (next, found) = self.read_chunk(conn, key_prefix, cursor, SCAN_CHUNK_BYTES)?;
+                offsets.extend(found);
+                if let Some(needed) = needed.filter(|&n| offsets.len() >= n) {
+                    offsets.truncate(needed);
+                    break;
+                }
+                if next == 0 {
+                    break;
+                }
+                cursor = next;
+            }
+            offsets
+        };
+
+        Ok(self.set_rows(key_prefix, offsets))
+    }
+
+    /// The cursor is the byte to continue from; each batch reads the
+    /// `batch_size` bits after the next set bit.
+    fn load_batch(
+        &mut self,
+        conn: &mut dyn redis::ConnectionLike,
+        key_prefix: &str,
+        cursor: u64,
+        batch_size: usize,
+        _conditions: Option<&[PushableCondition]>,
+    ) -> Result<(u64, usize), redis::RedisError> {
+        let chunk_bytes = batch_size.div_ceil(8).max(1);
+        let (next, offsets) = self.read_chunk(conn, key_prefix, cursor, chunk_bytes)?;
+        self.set_rows(key_prefix, offsets);
+        Ok((next, self.data_len()))
+    }
+
+    fn get_dataset(&self) -> &DataSet {
+        &self.dataset
+    }
+
+    #[inline]
+    fn get_row(&self, index: usize) -> Option<RowVec<'_>> {
+        let width = self.row_width();
+        let data = self.dataset.as_filtered()?;
+        let start = index.checked_mul(width)?;
+        data.get(start..start + width).map(|row| {
+            row.iter()
+                .map(|v| v.as_deref().map(Cow::Borrowed))
+                .collect()
+        })
+    }
+
+    #[inline]
+    fn data_len(&self) -> usize {
+        match &self.dataset {
+            DataSet::Filtered(data) => data.len() / self.row_width(),
+            _ => 0,
+        }
+    }
+
+    fn insert(
+        &mut self,
+        conn: &mut dyn redis::ConnectionLike,
+        key_prefix: &str,
+        data: &[String],
+        mode: InsertMode,
+    ) -> Result<bool, redis::RedisError> {
+        let Some(cmd) = self.insert_command(key_prefix, data, mode) else {
+            return Ok(false);
+        };
+        let reply: redis::Value = cmd.query(conn)?;
+        Ok(mode == InsertMode::Overwrite || InsertMode::reply_applied(&reply))
+    }
+
+    /// Clear the bits at the given offsets.
+    fn delete(
+        &mut self,
+        conn: &mut dyn redis::ConnectionLike,
+        key_prefix: &str,
+        data: &[String],
+    ) -> Result<(), redis::RedisError> {
+        let offsets: Vec<u64> = data.iter().filter_map(|o| parse_offset(o)).collect();
+        if offsets.is_empty() {
+            return Ok(());
+        }
+        let mut pipe = redis::pipe();
+        pipe.atomic();
+        for offset in offsets {
+            pipe.cmd("SETBIT")
+                .arg(key_prefix)
+                .arg(offset)
+                .arg(0)
+                .ignore();
+        }
+        pipe.query::<()>(conn)
+    }
+
+    /// SETBIT of the new row. A moved bit is cleared at its old offset in
+    /// the same transaction; in multi-key mode `old_data[0]` is the key,
+    /// not the offset.
+    fn update(
+        &mut self,
+        conn: &mut dyn redis::ConnectionLike,
+        key_prefix: &str,
+        old_data: &[String],
+        new_data: &[String],
+    ) -> Result<(), redis::RedisError> {
+        let invalid = |what: &str, value: &str| {
+            redis::RedisError::from((
+                redis::ErrorKind::InvalidClientConfig,
+                "Invalid bitmap value",
+                format!("{} '{}'", what, value),
+            ))
+        };
+        let Some(offset) = new_data.first() else {
+            return Ok(());
+        };
+        let offset = parse_offset(offset).ok_or_else(|| invalid("bit offset", offset))?;
+        let bit = parse_bit(new_data.get(1).map(String::as_str))
+            .ok_or_else(|| invalid("bit", new_data.get(1).map_or("", String::as_str)))?;
+
+        let mut pipe = redis::pipe();
+        pipe.atomic();
+        if let Some(old_offset) = old_data
+            .first()
+            .filter(|_| !self.key_column)
+            .and_then(|old| parse_offset(old))
+            .filter(|&old| old != offset)
+        {
+            pipe.cmd("SETBIT")
+                .arg(key_prefix)
+                .arg(old_offset)
+                .arg(0)
+                .ignore();
+        }
+        pipe.cmd("SETBIT")
+            .arg(key_prefix)
+            .arg(offset)
+            .arg(bit)
+            .ignore();
+        pipe.query::<()>(conn)
+    }
+
+    fn supports_pushdown(&self, operator: &ComparisonOperator) -> bool {
+        matches!(operator, ComparisonOperator::Equal | ComparisonOperator::In)
+    }
+
+    fn configure(
+        &mut self,
+        _column_names: &[String],
+        pushdown_column_index: usize,
+        _score_column_index: Option<usize>,
+    ) {
+        self.pushdown_column_index = pushdown_column_index;
+    }
+
+    fn load_multi_key_data(
+        &mut self,
+        conn: &mut dyn redis::ConnectionLike,
+        keys: &[String],
+    ) -> Result<Vec<RowValue>, redis::RedisError> {
+        if keys.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut pipe = redis::pipe();
+        for key in keys {
+            pipe.cmd("GET").arg(key);
+        }
+        let values: Vec<Option<Vec<u8>>> = pipe.query(conn)?;
+        let mut rows = Vec::new();
+        for (key, value) in keys.iter().zip(values) {
+            pgrx::check_for_interrupts!();
+            for offset in set_bit_offsets(value.as_deref().unwrap_or_default(), 0) {
+                self.push_row(&mut rows, key, offset);
+            }
+        }
+        Ok(rows)
+    }
+
+    fn clear(&mut self) {
+        self.dataset = DataSet::default();
+    }
+
+    fn redis_type_name(&self) -> &'static str {
+        "string"
+    }
+
+    fn set_filtered_data(&mut self, data: Vec<RowValue>) {
+        self.dataset = DataSet::Filtered(data);
+    }
+
+    fn multi_key_columns_per_row(&self) -> usize {
+        1 + 2
+    }
+
+    /// One pipelined GETBIT per param, each an offset; clear bits miss.
+    fn batch_parameterized_lookup(
+        &mut self,
+        conn: &mut dyn redis::ConnectionLike,
+        key_prefix: &str,
+        params: &[String],
+    ) -> Result<Vec<Option<Vec<RowValue>>>, redis::RedisError> {
+        let offsets: Vec<u64> = params.iter().filter_map(|p| parse_offset(p)).collect();
+        let set = self.set_offsets(conn, key_prefix, &offsets)?;
+        Ok(params
+            .iter()
+            .map(|p| {
+                let offset = parse_offset(p).filter(|o| set.contains(o))?;
+                let mut row = Vec::with_capacity(self.row_width());
+                self.push_row(&mut row, key_prefix, offset);
+                Some(row)
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::utils::{cmd_args, row_text};
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_set_bit_offsets_msb_first() {
+        let offsets: Vec<u64> = set_bit_offsets(&[0b1000_0001, 0, 0b0100_0000], 0).collect();
+        assert_eq!(offsets, vec![0, 7, 17]);
+        let offsets: Vec<u64> = set_bit_offsets(&[0b0010_0000], 4).collect();
+        assert_eq!(offsets, vec![34]);
+    }
+
+    #[test]
+    fn test_parse_offset() {
+        assert_eq!(parse_offset("42"), Some(42));
+        assert_eq!(parse_offset("4294967295"), Some(MAX_BIT_OFFSET));
+        assert_eq!(parse_offset("4294967296"), None);
+        assert_eq!(parse_offset("-1"), None);
+        assert_eq!(parse_offset("x"), None);
+    }
+
+    #[test]
+    fn test_insert_command() {
+        let t = RedisBitmapTable::new();
+        let cmd = t
+            .insert_command("bits", &strings(&["7", "true"]), InsertMode::Overwrite)
+            .unwrap();
+        assert_eq!(cmd_args(&cmd), strings(&["SETBIT", "bits", "7", "1"]));
+
+        let cmd = t
+            .insert_command("bits", &strings(&["7", "false"]), InsertMode::Overwrite)
+            .unwrap();
+        assert_eq!(cmd_args(&cmd), strings(&["SETBIT", "bits", "7", "0"]));
+
+        let cmd = t
+            .insert_command("bits", &strings(&["7", "NULL"]), InsertMode::Nx)
+            .unwrap();
+        assert_eq!(cmd_args(&cmd)[2..], strings(&["1", "bits", "7", "1", "nx"]));
+
+        assert!(t
+            .insert_command("bits", &strings(&["-1", "true"]), InsertMode::Overwrite)
+            .is_none());
+        assert!(t
+            .insert_command("bits", &strings(&["1", "maybe"]), InsertMode::Overwrite)
+            .is_none());
+    }
+
+    #[test]
+    fn test_offset_lookup_only_on_offset_column() {
+        let t = RedisBitmapTable::new();
+        let condition = |column_index, operator, value: &str| PushableCondition {
+            column_name: String::new(),
+            column_index,
+            operator,
+            value: value.to_string(),
+        };
+        assert_eq!(
+            t.offset_lookup(&[condition(0, ComparisonOperator::In, "3,x,5")]),
+            Some(vec![3, 5])
+        );
+        assert_eq!(
+            t.offset_lookup(&[condition(1, ComparisonOperator::Equal, "true")]),
+            None
+        );
+    }
+
+    #[test]
+    fn test_rows_with_key_column() {
+        let mut t = RedisBitmapTable::new();
+        t.key_column = true;
+        t.set_rows("bits", vec![1, 9]);
+        assert_eq!(t.data_len(), 2);
+        let row = t.get_row(1).unwrap();
+        assert_eq!(row_text(&row), ["bits", "9", "true"]);
+    }
+}
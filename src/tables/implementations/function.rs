@@ -0,0 +1,493 @@
+use std::{borrow::Cow, collections::HashMap};
+
+use crate::{
+    query::{
+        limit::LimitOffsetInfo,
+        pushdown_types::{ComparisonOperator, PushableCondition},
+    },
+    tables::{
+        interface::RedisTableOperations,
+        types::{DataSet, InsertMode, LoadDataResult, RowValue, RowVec},
+    },
+};
+
+/// Server-side code a function table calls, always with one key:
+/// `table_key_prefix` as KEYS[1].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RedisCallable {
+    /// A Redis Function (`read_function`/`write_function`), called with FCALL
+    Function(String),
+    /// A Lua script (`read_script`/`write_script`), run with EVALSHA once
+    /// SCRIPT LOAD has returned its SHA1 (None = not loaded yet)
+    Script { body: String, sha: Option<String> },
+}
+
+impl RedisCallable {
+    /// The callable set by option `function_option` or `script_option` of
+    /// table options `opts`, if any.
+    pub fn from_options(
+        opts: &HashMap<String, String>,
+        function_option: &str,
+        script_option: &str,
+    ) -> Result<Option<Self>, String> {
+        match (opts.get(function_option), opts.get(script_option)) {
+            (Some(_), Some(_)) => Err(format!(
+                "{function_option} and {script_option} cannot be set together"
+            )),
+            (Some(name), None) if name.trim().is_empty() => {
+                Err(format!("{function_option} must not be empty"))
+            }
+            (Some(name), None) => Ok(Some(Self::Function(name.clone()))),
+            (None, Some(body)) if body.trim().is_empty() => {
+                Err(format!("{script_option} must not be empty"))
+            }
+            (None, Some(body)) => Ok(Some(Self::Script {
+                body: body.clone(),
+                sha: None,
+            })),
+            (None, None) => Ok(None),
+        }
+    }
+
+    /// FCALL, EVALSHA or, for a script not loaded yet, EVAL with KEYS[1] =
+    /// `key` and ARGV = `args`.
+    pub fn command<T: redis::ToRedisArgs>(&self, key: &str, args: &[T]) -> redis::Cmd {
+        let mut cmd = match self {
+            Self::Function(name) => {
+                let mut cmd = redis::cmd("FCALL");
+                cmd.arg(name);
+                cmd
+            }
+            Self::Script { sha: Some(sha), .. } => {
+                let mut cmd = redis::cmd("EVALSHA");
+                cmd.arg(sha);
+                cmd
+            }
+            Self::Script { body, sha: None } => {
+                let mut cmd = redis::cmd("EVAL");
+                cmd.arg(body);
+                cmd
+            }
+        };
+        cmd.arg(1).arg(key).arg(args);
+        cmd
+    }
+
+    /// SCRIPT LOAD a script whose SHA1 is not known yet; once per table.
+    pub fn load(&mut self, conn: &mut dyn redis::ConnectionLike) -> redis::RedisResult<()> {
+        if let Self::Script {
+            body,
+            sha: sha @ None,
+        } = self
+        {
+            *sha = Some(redis::cmd("SCRIPT").arg("LOAD").arg(&*body).query(conn)?);
+        }
+        Ok(())
+    }
+
+    /// Call with KEYS[1] = `key` and ARGV = `args`. A script the server no
+    /// longer has (NOSCRIPT, after SCRIPT FLUSH or a restart) is loaded
+    /// again and the call retried once.
+    pub fn call<T: redis::ToRedisArgs>(
+        &mut self,
+        conn: &mut dyn redis::ConnectionLike,
+        key: &str,
+        args: &[T],
+    ) -> redis::RedisResult<redis::Value> {
+        self.load(conn)?;
+        match self.command(key, args).query(conn) {
+            Err(e) if e.code() == Some("NOSCRIPT") => {
+                if let Self::Script { sha, .. } = self {
+                    *sha = None;
+                }
+                self.load(conn)?;
+                self.command(key, args).query(conn)
+            }
+            result => result,
+        }
+    }
+
+    /// The command EXPLAIN names for a call.
+    fn op(&self) -> &'static str {
+        match self {
+            Self::Function(_) => "FCALL",
+            Self::Script { .. } => "EVALSHA",
+        }
+    }
+}
+
+/// Redis function table type: rows come from, and writes go to, a Redis
+/// Function or Lua script with `table_key_prefix` as its only key.
+///
+/// SELECT calls the read callable with ARGV `[column, value, ...]` for the
+/// `column = value` conditions pushed down, and expects an array of rows,
+/// each an array of column values in declaration order (nil = NULL). INSERT,
+/// UPDATE and DELETE call the write callable with ARGV `insert, <row...>`,
+/// `update, <old first column>, <new row...>` and `delete, <first column>`.
+#[derive(Debug, Clone, Default)]
+pub struct RedisFunctionTable {
+    /// Rows as flat chunks of `width` values
+    pub dataset: DataSet,
+    /// Number of columns declared
+    pub width: usize,
+    /// Callable behind SELECT (None = the table is write-only)
+    pub read: Option<RedisCallable>,
+    /// Callable behind INSERT/UPDATE/DELETE (None = the table is read-only)
+    pub write: Option<RedisCallable>,
+}
+
+impl RedisFunctionTable {
+    pub fn new() -> Self {
+        Self {
+            dataset: DataSet::Empty,
+            width: 1,
+            read: None,
+            write: None,
+        }
+    }
+
+    /// The write callable, or an error naming `op` for a read-only table.
+    fn writer(&mut self, op: &str) -> Result<&mut RedisCallable, redis::RedisError> {
+        self.write.as_mut().ok_or_else(|| {
+            redis::RedisError::from((
+                redis::ErrorKind::InvalidClientConfig,
+                "operation needs write_function or write_script on function tables",
+                op.to_string(),
+            ))
+        })
+    }
+
+    /// SCRIPT LOAD the write script ahead of a pipelined batch of
+    /// `write_command`s.
+    pub fn load_writer(
+        &mut self,
+        conn: &mut dyn redis::ConnectionLike,
+    ) -> Result<(), redis::RedisError> {
+        self.writer("INSERT")?.load(conn)
+    }
+
+    /// Call of the write callable for operation `op` of row `data`, for
+    /// batch pipelines. None on a read-only table.
+    pub fn write_command(&self, key: &str, op: &str, data: &[String]) -> Option<redis::Cmd> {
+        let args: Vec<&str> = std::iter::once(op)
+            .chain(data.iter().map(String::as_str))
+            .collect();
+        self.write.as_ref().map(|w| w.command(key, &args))
+    }
+
+    /// Commands EXPLAIN names for a scan.
+    pub fn read_ops(&self) -> Vec<&'static str> {
+        self.read.iter().map(RedisCallable::op).collect()
+    }
+
+    /// ARGV of the read callable: name and value of every `=` condition.
+    fn read_args(conditions: Option<&[PushableCondition]>) -> Vec<&str> {
+        conditions
+            .unwrap_or_default()
+            .iter()
+            .filter(|c| c.operator == ComparisonOperator::Equal)
+            .flat_map(|c| [c.column_name.as_str(), c.value.as_str()])
+            .collect()
+    }
+
+    /// Flatten a read reply into rows of `width` values: each row an array,
+    /// or a single value for a one-column row. Missing trailing values and
+    /// nested arrays read as NULL; extra values are dropped.
+    fn rows_from_reply(&self, reply: redis::Value) -> Vec<RowValue> {
+        let rows = match reply {
+            redis::Value::Array(rows) | redis::Value::Set(rows) => rows,
+            redis::Value::Nil => Vec::new(),
+            row => vec![row],
+        };
+        let mut data = Vec::with_capacity(rows.len() * self.width);
+        for row in rows {
+            let values = match row {
+                redis::Value::Array(values) => values,
+                value => vec![value],
+            };
+            let len = data.len();
+            data.extend(values.into_iter().take(self.width).map(value_text));
+            data.resize(len + self.width, None);
+        }
+        data
+    }
+}
+
+/// One value of a read reply as column text; nil and values with no text
+/// form read as NULL.
+fn value_text(value: redis::Value) -> RowValue {
+    let text = match value {
+        redis::Value::Int(n) => n.to_string(),
+        redis::Value::BulkString(bytes) => return Some(bytes),
+        redis::Value::SimpleString(s) => s,
+        redis::Value::Okay => "OK".to_string(),
+        redis::Value::Double(d) => d.to_string(),
+        redis::Value::Boolean(b) => b.to_string(),
+        redis::Value::VerbatimString { text, .. } => text,
+        _ => return None,
+    };
+    Some(text.into_bytes())
+}
+
+impl RedisTableOperations for RedisFunctionTable {
+    fn load_data(
+        &mut self,
+        conn: &mut dyn redis::ConnectionLike,
+        key_prefix: &str,
+        conditions: Option<&[PushableCondition]>,
+        _limit_offset: &LimitOffsetInfo,
+    ) -> Result<LoadDataResult, redis::RedisError> {
+        let Some(read) = self.read.as_mut() else {
+            return Err(redis::RedisError::from((
+                redis::ErrorKind::InvalidClientConfig,
+                "SELECT needs read_function or read_script on function tables",
+            )));
+        };
+        let reply = read.call(conn, key_prefix, &Self::read_args(conditions))?;
+        let data = self.rows_from_reply(reply);
+        if data.is_empty() {
+            self.dataset = DataSet::Empty;
+            Ok(LoadDataResult::Empty)
+        } else {
+            self.dataset = DataSet::Filtered(data);
+            Ok(LoadDataResult::FullyLoaded)
+        }
+    }
+
+    /// The callable returns every row at once.
+    fn load_batch(
+        &mut self,
+        conn: &mut dyn redis::ConnectionLike,
+        key_prefix: &str,
+        _cursor: u64,
+        _batch_size: usize,
+        conditions: Option<&[PushableCondition]>,
+    ) -> Result<(u64, usize), redis::RedisError> {
+        self.load_data(conn, key_prefix, conditions, &LimitOffsetInfo::default())?;
+        Ok((0, self.data_len()))
+    }
+
+    fn get_dataset(&self) -> &DataSet {
+        &self.dataset
+    }
+
+    #[inline]
+    fn get_row(&self, index: usize) -> Option<RowVec<'_>> {
+        let data = self.dataset.as_filtered()?;
+        let start = index.checked_mul(self.width)?;
+        data.get(start..start + self.width).map(|row| {
+            row.iter()
+                .map(|v| v.as_deref().map(Cow::Borrowed))
+                .collect()
+        })
+    }
+
+    #[inline]
+    fn data_len(&self) -> usize {
+        match &self.dataset {
+            DataSet::Filtered(data) => data.len() / self.width,
+            _ => 0,
+        }
+    }
+
+    fn insert(
+        &mut self,
+        conn: &mut dyn redis::ConnectionLike,
+        key_prefix: &str,
+        data: &[String],
+        _mode: InsertMode,
+    ) -> Result<bool, redis::RedisError> {
+        let args: Vec<&str> = std::iter::once("insert")
+            .chain(data.iter().map(String::as_str))
+            .collect();
+        self.writer("INSERT")?.call(conn, key_prefix, &args)?;
+        Ok(true)
+    }
+
+    fn delete(
+        &mut self,
+        conn: &mut dyn redis::ConnectionLike,
+        key_prefix: &str,
+        data: &[String],
+    ) -> Result<(), redis::RedisError> {
+        let args: Vec<&str> = std::iter::once("delete")
+            .chain(data.first().map(String::as_str))
+            .collect();
+        self.writer("DELETE")?.call(conn, key_prefix, &args)?;
+        Ok(())
+    }
+
+    fn update(
+        &mut self,
+        conn: &mut dyn redis::ConnectionLike,
+        key_prefix: &str,
+        old_data: &[String],
+        new_data: &[String],
+    ) -> Result<(), redis::RedisError> {
+        let args: Vec<&str> = ["update", old_data.first().map_or("", String::as_str)]
+            .into_iter()
+            .chain(new_data.iter().map(String::as_str))
+            .collect();
+        self.writer("UPDATE")?.call(conn, key_prefix, &args)?;
+        Ok(())
+    }
+
+    /// `column = value` conditions become arguments of the read callable;
+    /// PostgreSQL rechecks them on the rows it returns.
+    fn supports_pushdown(&self, operator: &ComparisonOperator) -> bool {
+        matches!(operator, ComparisonOperator::Equal)
+    }
+
+    fn configure(
+        &mut self,
+        column_names: &[String],
+        _pushdown_column_index: usize,
+        _score_column_index: Option<usize>,
+    ) {
+        self.width = column_names.len().max(1);
+    }
+
+    /// Function tables have a single key.
+    fn load_multi_key_data(
+        &mut self,
+        _conn: &mut dyn redis::ConnectionLike,
+        _keys: &[String],
+    ) -> Result<Vec<RowValue>, redis::RedisError> {
+        Ok(Vec::new())
+    }
+
+    fn clear(&mut self) {
+        self.dataset = DataSet::default();
+    }
+
+    fn redis_type_name(&self) -> &'static str {
+        ""
+    }
+
+    fn set_filtered_data(&mut self, data: Vec<RowValue>) {
+        self.dataset = DataSet::Filtered(data);
+    }
+
+    fn multi_key_columns_per_row(&self) -> usize {
+        self.width
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::utils::{cmd_args, row_text};
+    use redis::Value;
+
+    fn options(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_callable_from_options() {
+        let read = |pairs: &[(&str, &str)]| {
+            RedisCallable::from_options(&options(pairs), "read_function", "read_script")
+        };
+        assert_eq!(read(&[]), Ok(None));
+        assert_eq!(
+            read(&[("read_function", "lib_read")]),
+            Ok(Some(RedisCallable::Function("lib_read".to_string())))
+        );
+        assert_eq!(
+            read(&[("read_script", "return {}")]),
+            Ok(Some(RedisCallable::Script {
+                body: "return {}".to_string(),
+                sha: None
+            }))
+        );
+        assert!(read(&[("read_function", "f"), ("read_script", "return 1")]).is_err());
+        assert!(read(&[("read_function", " ")]).is_err());
+        assert!(read(&[("read_script", "")]).is_err());
+    }
+
+    #[test]
+    fn test_callable_command() {
+        let function = RedisCallable::Function("lib_write".to_string());
+        assert_eq!(
+            cmd_args(&function.command("k", &["insert", "a"])),
+            ["FCALL", "lib_write", "1", "k", "insert", "a"]
+        );
+        let mut script = RedisCallable::Script {
+            body: "return 1".to_string(),
+            sha: None,
+        };
+        assert_eq!(
+            cmd_args(&script.command::<&str>("k", &[])),
+            ["EVAL", "return 1", "1", "k"]
+        );
+        if let RedisCallable::Script { sha, .. } = &mut script {
+            *sha = Some("abc".to_string());
+        }
+        assert_eq!(
+            cmd_args(&script.command("k", &["delete", "1"])),
+            ["EVALSHA", "abc", "1", "k", "delete", "1"]
+        );
+    }
+
+    #[test]
+    fn test_read_args_from_equal_conditions() {
+        let cond = |name: &str, operator, value: &str| PushableCondition {
+            column_index: 0,
+            column_name: name.to_string(),
+            operator,
+            value: value.to_string(),
+        };
+        let conditions = [
+            cond("id", ComparisonOperator::Equal, "7"),
+            cond("name", ComparisonOperator::Like, "a%"),
+            cond("status", ComparisonOperator::Equal, "open"),
+        ];
+        assert_eq!(
+            RedisFunctionTable::read_args(Some(&conditions)),
+            ["id", "7", "status", "open"]
+        );
+        assert!(RedisFunctionTable::read_args(None).is_empty());
+    }
+
+    #[test]
+    fn test_rows_from_reply() {
+        let mut t = RedisFunctionTable::new();
+        t.configure(&["id".to_string(), "name".to_string()], 0, None);
+        let reply = Value::Array(vec![
+            Value::Array(vec![Value::Int(1), Value::BulkString(b"one".to_vec())]),
+            Value::Array(vec![Value::Int(2)]),
+            Value::Array(vec![Value::Int(3), Value::Nil, Value::Int(9)]),
+            Value::BulkString(b"4".to_vec()),
+        ]);
+        t.dataset = DataSet::Filtered(t.rows_from_reply(reply));
+        assert_eq!(t.data_len(), 4);
+        assert_eq!(row_text(&t.get_row(0).unwrap()), ["1", "one"]);
+        for (i, id) in [(1, "2"), (2, "3"), (3, "4")] {
+            let row = t.get_row(i).unwrap();
+            assert_eq!(row_text(&row[..1]), [id]);
+            assert!(row[1].is_none());
+        }
+        assert!(t.get_row(4).is_none());
+        assert!(t.rows_from_reply(Value::Nil).is_empty());
+    }
+
+    #[test]
+    fn test_write_command_and_read_ops() {
+        let mut t = RedisFunctionTable::new();
+        assert!(t.write_command("k", "insert", &["a".to_string()]).is_none());
+        assert!(t.read_ops().is_empty());
+        t.write = Some(RedisCallable::Function("lib_write".to_string()));
+        t.read = Some(RedisCallable::Script {
+            body: "return {}".to_string(),
+            sha: None,
+        });
+        assert_eq!(
+            cmd_args(&t.write_command("k", "insert", &["a".to_string()]).unwrap()),
+            ["FCALL", "lib_write", "1", "k", "insert", "a"]
+        );
+        assert_eq!(t.read_ops(), ["EVALSHA"]);
+    }
+}
@@ -0,0 +1,829 @@
+use std::borrow::Cow;
+
+use crate::{
+    query::{
+        limit::LimitOffsetInfo,
+        pushdown_types::{ComparisonOperator, PushableCondition},
+    },
+    tables::{
+        interface::RedisTableOperations,
+        types::{ColumnEncodings, DataSet, InsertMode, LoadDataResult, RowValue, RowVec},
+    },
+};
+
+/// Earth radius Redis computes geo distances with, in meters.
+pub const EARTH_RADIUS_M: f64 = 6372797.560856;
+
+/// Largest latitude GEOADD accepts; the geohash grid stops there.
+const MAX_LATITUDE: f64 = 85.05112878;
+
+/// GEOADD only when the member already exists, reporting whether it did.
+/// `GEOADD XX` alone replies 0 either way, so it cannot tell a skip apart.
+const GEOADD_XX_SCRIPT: &str = "if redis.call('ZSCORE', KEYS[1], ARGV[3]) then \
+     redis.call('GEOADD', KEYS[1], ARGV[1], ARGV[2], ARGV[3]) return 1 end return 0";
+
+/// Great-circle distance in meters between two points, computed the way
+/// GEODIST and GEOSEARCH do.
+pub fn geo_distance(lon1: f64, lat1: f64, lon2: f64, lat2: f64) -> f64 {
+    let (lat1, lat2) = (lat1.to_radians(), lat2.to_radians());
+    let u = ((lat2 - lat1) / 2.0).sin();
+    let v = ((lon2 - lon1).to_radians() / 2.0).sin();
+    2.0 * EARTH_RADIUS_M * (u * u + lat1.cos() * lat2.cos() * v * v).sqrt().asin()
+}
+
+/// Whether a point lies in the `width` x `height` meter box centred on
+/// (`center_lon`, `center_lat`), decided the way GEOSEARCH BYBOX does.
+pub fn geo_within_box(
+    lon: f64,
+    lat: f64,
+    center_lon: f64,
+    center_lat: f64,
+    width: f64,
+    height: f64,
+) -> bool {
+    EARTH_RADIUS_M * (lat - center_lat).to_radians().abs() <= height / 2.0
+        && geo_distance(lon, lat, center_lon, lat) <= width / 2.0
+}
+
+/// The area of a GEOSEARCH pushed down from `redis_geo_within` or
+/// `redis_geo_within_box`. Sizes are in meters.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GeoShape {
+    Radius {
+        longitude: f64,
+        latitude: f64,
+        radius: f64,
+    },
+    Box {
+        longitude: f64,
+        latitude: f64,
+        width: f64,
+        height: f64,
+    },
+}
+
+impl GeoShape {
+    /// The shape of a geo search condition, `None` when its arguments are
+    /// ones GEOSEARCH would reject (PostgreSQL then evaluates the function).
+    pub fn from_condition(condition: &PushableCondition) -> Option<Self> {
+        let args: Vec<f64> = condition
+            .value
+            .split(',')
+            .map(|v| v.parse().ok().filter(|v: &f64| v.is_finite()))
+            .collect::<Option<_>>()?;
+        let shape = match (&condition.operator, args.as_slice()) {
+            (ComparisonOperator::GeoRadius { .. }, &[longitude, latitude, radius])
+                if radius >= 0.0 =>
+            {
+                GeoShape::Radius {
+                    longitude,
+                    latitude,
+                    radius,
+                }
+            }
+            (ComparisonOperator::GeoBox { .. }, &[longitude, latitude, width, height])
+                if width >= 0.0 && height >= 0.0 =>
+            {
+                GeoShape::Box {
+                    longitude,
+                    latitude,
+                    width,
+                    height,
+                }
+            }
+            _ => return None,
+        };
+        let (longitude, latitude) = shape.center();
+        (longitude.abs() <= 180.0 && latitude.abs() <= MAX_LATITUDE).then_some(shape)
+    }
+
+    fn center(&self) -> (f64, f64) {
+        match *self {
+            GeoShape::Radius {
+                longitude,
+                latitude,
+                ..
+            }
+            | GeoShape::Box {
+                longitude,
+                latitude,
+                ..
+            } => (longitude, latitude),
+        }
+    }
+
+    /// The FROMLONLAT and BYRADIUS/BYBOX arguments of GEOSEARCH.
+    fn add_args(&self, cmd: &mut redis::Cmd) {
+        let (longitude, latitude) = self.center();
+        cmd.arg("FROMLONLAT").arg(longitude).arg(latitude);
+        match *self {
+            GeoShape::Radius { radius, .. } => cmd.arg("BYRADIUS").arg(radius),
+            GeoShape::Box { width, height, .. } => cmd.arg("BYBOX").arg(width).arg(height),
+        };
+        cmd.arg("m");
+    }
+}
+
+/// A virtual column of a geo table, computed by Redis instead of stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GeoExtra {
+    /// Meters from the center of the pushed-down search; NULL without one
+    Distance,
+    /// The member's 11-character geohash string, as GEOHASH reports it
+    Geohash,
+}
+
+/// A member and its position, as GEOPOS or GEOSEARCH report them.
+#[derive(Debug)]
+struct GeoEntry {
+    member: Vec<u8>,
+    longitude: String,
+    latitude: String,
+    distance: Option<String>,
+    geohash: Option<String>,
+}
+
+/// Redis geospatial index table type: each member of the geo key is a row
+/// of `(member, longitude, latitude)`, followed by the optional virtual
+/// columns in [`GeoExtra`].
+#[derive(Debug, Clone, Default)]
+pub struct RedisGeoTable {
+    /// Rows as flat `[key?, member, longitude, latitude, extra...]` chunks
+    pub dataset: DataSet,
+    /// Whether rows start with the Redis key (multi-key mode)
+    pub key_column: bool,
+    pub pushdown_column_index: usize,
+    pub longitude_column_index: usize,
+    pub latitude_column_index: usize,
+    /// Column of the [`GeoExtra::Distance`] value, when set by the caller
+    pub distance_column_index: Option<usize>,
+    /// Virtual columns after the latitude, in column order
+    pub extras: Vec<GeoExtra>,
+    /// Encoding of the member column
+    pub encodings: ColumnEncodings,
+}
+
+impl RedisGeoTable {
+    pub fn new() -> Self {
+        Self {
+            dataset: DataSet::Empty,
+            key_column: false,
+            pushdown_column_index: 0,
+            longitude_column_index: 1,
+            latitude_column_index: 2,
+            distance_column_index: None,
+            extras: Vec::new(),
+            encodings: ColumnEncodings::default(),
+        }
+    }
+
+    /// Number of values in one row.
+    fn row_width(&self) -> usize {
+        usize::from(self.key_column) + 3 + self.extras.len()
+    }
+
+    /// Bytes sent to Redis for a member.
+    fn member_arg<'a>(&self, member: &'a str) -> Cow<'a, [u8]> {
+        self.encodings.get(0).encode(member)
+    }
+
+    /// Write command for one row of `[member, longitude, latitude, ...]`;
+    /// values of the virtual columns are ignored. `None` when a coordinate
+    /// is not a number. XX applies through [`GEOADD_XX_SCRIPT`].
+    pub fn insert_command(
+        &self,
+        key: &str,
+        data: &[String],
+        mode: InsertMode,
+    ) -> Option<redis::Cmd> {
+        let [member, longitude, latitude, ..] = data else {
+            return None;
+        };
+        let longitude: f64 = longitude.parse().ok()?;
+        let latitude: f64 = latitude.parse().ok()?;
+
+        let mut cmd = match mode {
+            InsertMode::Overwrite | InsertMode::Nx => {
+                let mut cmd = redis::cmd("GEOADD");
+                cmd.arg(key);
+                if mode == InsertMode::Nx {
+                    cmd.arg("NX");
+                }
+                cmd
+            }
+            InsertMode::Xx => {
+                let mut cmd = redis::cmd("EVAL");
+                cmd.arg(GEOADD_XX_SCRIPT).arg(1).arg(key);
+                cmd
+            }
+        };
+        cmd.arg(longitude)
+            .arg(latitude)
+            .arg(self.member_arg(member));
+        Some(cmd)
+    }
+
+    /// The search a condition pushes down, when it is a geo search over
+    /// this table's longitude and latitude columns.
+    pub fn search_shape(&self, condition: &PushableCondition) -> Option<GeoShape> {
+        let latitude_column = match condition.operator {
+            ComparisonOperator::GeoRadius { latitude_column }
+            | ComparisonOperator::GeoBox { latitude_column } => latitude_column,
+            _ => return None,
+        };
+        if condition.column_index != self.longitude_column_index
+            || latitude_column != self.latitude_column_index
+        {
+            return None;
+        }
+        GeoShape::from_condition(condition)
+    }
+
+    /// Members named by an `=` or `IN` condition on the member column.
+    fn member_lookup(&self, conditions: &[PushableCondition]) -> Option<Vec<Vec<u8>>> {
+        let condition = conditions.iter().find(|c| {
+            c.column_index == self.pushdown_column_index
+                && matches!(
+                    c.operator,
+                    ComparisonOperator::Equal | ComparisonOperator::In
+                )
+        })?;
+        let members = match condition.operator {
+            ComparisonOperator::Equal => vec![condition.value.as_str()],
+            _ => condition.value.split(',').collect(),
+        };
+        Some(
+            members
+                .into_iter()
+                .map(|m| self.member_arg(m).into_owned())
+                .collect(),
+        )
+    }
+
+    /// GEOSEARCH of `key`, nearest first, with coordinates and distances.
+    fn search(
+        &self,
+        conn: &mut dyn redis::ConnectionLike,
+        key: &str,
+        shape: &GeoShape,
+        count: Option<usize>,
+    ) -> Result<Vec<GeoEntry>, redis::RedisError> {
+        let mut cmd = redis::cmd("GEOSEARCH");
+        cmd.arg(key);
+        shape.add_args(&mut cmd);
+        cmd.arg("ASC");
+        if let Some(count) = count {
+            cmd.arg("COUNT").arg(count);
+        }
+        cmd.arg("WITHCOORD").arg("WITHDIST");
+        let reply: Vec<(Vec<u8>, String, (String, String))> = cmd.query(conn)?;
+        Ok(reply
+            .into_iter()
+            .map(|(member, distance, (longitude, latitude))| GeoEntry {
+                member,
+                longitude,
+                latitude,
+                distance: Some(distance),
+                geohash: None,
+            })
+            .collect())
+    }
+
+    /// Positions of the given members of each key, one GEOPOS per key in a
+    /// single pipeline. Members that do not exist are left out.
+    fn positions(
+        &self,
+        conn: &mut dyn redis::ConnectionLike,
+        lookups: Vec<(String, Vec<Vec<u8>>)>,
+    ) -> Result<Vec<(String, Vec<GeoEntry>)>, redis::RedisError> {
+        let lookups: Vec<_> = lookups.into_iter().filter(|(_, m)| !m.is_empty()).collect();
+        if lookups.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut pipe = redis::pipe();
+        for (key, members) in &lookups {
+            pipe.cmd("GEOPOS").arg(key).arg(members);
+        }
+        let replies: Vec<Vec<Option<(String, String)>>> = pipe.query(conn)?;
+        Ok(lookups
+            .into_iter()
+            .zip(replies)
+            .map(|((key, members), positions)| {
+                let entries = members
+                    .into_iter()
+                    .zip(positions)
+                    .filter_map(|(member, position)| {
+                        let (longitude, latitude) = position?;
+                        Some(GeoEntry {
+                            member,
+                            longitude,
+                            latitude,
+                            distance: None,
+                            geohash: None,
+                        })
+                    })
+                    .collect();
+                (key, entries)
+            })
+            .collect())
+    }
+
+    /// Fill in the geohashes of `groups` with one pipelined GEOHASH per key,
+    /// when the table has a geohash column.
+    fn add_geohashes(
+        &self,
+        conn: &mut dyn redis::ConnectionLike,
+        groups: &mut [(String, Vec<GeoEntry>)],
+    ) -> Result<(), redis::RedisError> {
+        if !self.extras.contains(&GeoExtra::Geohash) || groups.iter().all(|(_, e)| e.is_empty()) {
+            return Ok(());
+        }
+        let mut pipe = redis::pipe();
+        for (key, entries) in groups.iter().filter(|(_, e)| !e.is_empty()) {
+            let members: Vec<&[u8]> = entries.iter().map(|e| e.member.as_slice()).collect();
+            pipe.cmd("GEOHASH").arg(key).arg(members);
+        }
+        let replies: Vec<Vec<Option<String>>> = pipe.query(conn)?;
+        for ((_, entries), hashes) in groups
+            .iter_mut()
+            .filter(|(_, e)| !e.is_empty())
+            .zip(replies)
+        {
+            for (entry, hash) in entries.iter_mut().zip(hashes) {
+                entry.geohash = hash;
+            }
+        }
+        Ok(())
+    }
+
+    /// Append the row of `entry` to `rows`.
+    fn push_row(
+        &self,
+        rows: &mut Vec<RowValue>,
+        key: &str,
+        entry: GeoEntry,
+    ) -> Result<(), redis::RedisError> {
+        if self.key_column {
+            rows.push(Some(key.as_bytes().to_vec()));
+        }
+        rows.push(Some(self.encodings.get(0).decode(entry.member)?));
+        rows.push(Some(entry.longitude.into_bytes()));
+        rows.push(Some(entry.latitude.into_bytes()));
+        for extra in &self.extras {
+            let value = match extra {
+                GeoExtra::Distance => entry.distance.clone(),
+                GeoExtra::Geohash => entry.geohash.clone(),
+            };
+            rows.push(value.map(String::into_bytes));
+        }
+        Ok(())
+    }
+
+    /// Flat rows of `groups`, geohashes fetched as needed.
+    fn rows(
+        &self,
+        conn: &mut dyn redis::ConnectionLike,
+        mut groups: Vec<(String, Vec<GeoEntry>)>,
+    ) -> Result<Vec<RowValue>, redis::RedisError> {
+        self.add_geohashes(conn, &mut groups)?;
+        let mut rows = Vec::new();
+        for (key, entries) in groups {
+            for entry in entries {
+                self.push_row(&mut rows, &key, entry)?;
+            }
+        }
+        Ok(rows)
+    }
+
+    fn set_rows(&mut self, rows: Vec<RowValue>) -> LoadDataResult {
+        if rows.is_empty() {
+            self.dataset = DataSet::Empty;
+            LoadDataResult::Empty
+        } else {
+            self.dataset = DataSet::Filtered(rows);
+            LoadDataResult::FullyLoaded
+        }
+    }
+}
+
+impl RedisTableOperations for RedisGeoTable {
+    /// A geo search condition turns into GEOSEARCH, nearest first, with the
+    /// LIMIT as COUNT; member `=`/`IN` into GEOPOS; anything else reads the
+    /// members with ZRANGE and their positions with GEOPOS.
+    fn load_data(
+        &mut self,
+        conn: &mut dyn redis::ConnectionLike,
+        key_prefix: &str,
+        conditions: Option<&[PushableCondition]>,
+        limit_offset: &LimitOffsetInfo,
+    ) -> Result<LoadDataResult, redis::RedisError> {
+        if limit_offset.limit == Some(0) {
+            self.dataset = DataSet::Empty;
+            return Ok(LoadDataResult::Empty);
+        }
+        let count = limit_offset.rows_needed();
+        let conditions = conditions.unwrap_or(&[]);
+
+        let entries = if let Some(shape) = conditions.iter().find_map(|c| self.search_shape(c)) {
+            // Other conditions filter the found rows, so COUNT would be short.
+            let count = count.filter(|_| conditions.len() == 1);
+            self.search(conn, key_prefix, &shape, count)?
+        } else {
+            let members = match self.member_lookup(conditions) {
+                Some(members) => members,
+                None => redis::cmd("ZRANGE")
+                    .arg(key_prefix)
+                    .arg(0)
+                    .arg(count.map_or(-1, |n| n as i64 - 1))
+                    .query(conn)?,
+            };
+            self.positions(conn, vec![(key_prefix.to_string(), members)])?
+                .pop()
+                .map(|(_, entries)| entries)
+                .unwrap_or_default()
+        };
+
+        let rows = self.rows(conn, vec![(key_prefix.to_string(), entries)])?;
+        Ok(self.set_rows(rows))
+    }
+
+    fn load_batch(
+        &mut self,
+        conn: &mut dyn redis::ConnectionLike,
+        key_prefix: &str,
+        cursor: u64,
+        batch_size: usize,
+        _conditions: Option<&[PushableCondition]>,
+    ) -> Result<(u64, usize), redis::RedisError> {
+        let (new_cursor, flat): (u64, Vec<Vec<u8>>) = redis::cmd("ZSCAN")
+            .arg(key_prefix)
+            .arg(cursor)
+            .arg("COUNT")
+            .arg(batch_size)
+            .query(conn)?;
+        // ZSCAN replies member/score pairs; the scores are geohashes.
+        let members: Vec<Vec<u8>> = flat.into_iter().step_by(2).collect();
+        let groups = self.positions(conn, vec![(key_prefix.to_string(), members)])?;
+        let rows = self.rows(conn, groups)?;
+        self.set_rows(rows);
+        Ok((new_cursor, self.data_len()))
+    }
+
+    fn get_dataset(&self) -> &DataSet {
+        &self.dataset
+    }
+
+    #[inline]
+    fn get_row(&self, index: usize) -> Option<RowVec<'_>> {
+        let width = self.row_width();
+        let data = self.dataset.as_filtered()?;
+        let start = index.checked_mul(width)?;
+        data.get(start..start + width).map(|row| {
+            row.iter()
+                .map(|v| v.as_deref().map(Cow::Borrowed))
+                .collect()
+        })
+    }
+
+    #[inline]
+    fn data_len(&self) -> usize {
+        match &self.dataset {
+            DataSet::Filtered(data) => data.len() / self.row_width(),
+            _ => 0,
+        }
+    }
+
+    fn insert(
+        &mut self,
+        conn: &mut dyn redis::ConnectionLike,
+        key_prefix: &str,
+        data: &[String],
+        mode: InsertMode,
+    ) -> Result<bool, redis::RedisError> {
+        let Some(cmd) = self.insert_command(key_prefix, data, mode) else {
+            return Ok(false);
+        };
+        let reply: redis::Value = cmd.query(conn)?;
+        Ok(mode == InsertMode::Overwrite || InsertMode::reply_applied(&reply))
+    }
+
+    fn delete(
+        &mut self,
+        conn: &mut dyn redis::ConnectionLike,
+        key_prefix: &str,
+        data: &[String],
+    ) -> Result<(), redis::RedisError> {
+        if !data.is_empty() {
+            let mut cmd = redis::cmd("ZREM");
+            cmd.arg(key_prefix);
+            for member in data {
+                cmd.arg(self.member_arg(member));
+            }
+            let _: i32 = cmd.query(conn)?;
+        }
+        Ok(())
+    }
+
+    /// GEOADD of the new position. A renamed member is moved with ZREM of
+    /// the old one in the same transaction; in multi-key mode `old_data[0]`
+    /// is the key, not the member.
+    fn update(
+        &mut self,
+        conn: &mut dyn redis::ConnectionLike,
+        key_prefix: &str,
+        old_data: &[String],
+        new_data: &[String],
+    ) -> Result<(), redis::RedisError> {
+        let [member, longitude, latitude, ..] = new_data else {
+            return Ok(());
+        };
+        let parse = |value: &str| {
+            value.parse::<f64>().map_err(|e| {
+                redis::RedisError::from((
+                    redis::ErrorKind::InvalidClientConfig,
+                    "Invalid coordinate format",
+                    e.to_string(),
+                ))
+            })
+        };
+        let (longitude, latitude) = (parse(longitude)?, parse(latitude)?);
+
+        let mut pipe = redis::pipe();
+        pipe.atomic();
+        if let Some(old_member) = old_data
+            .first()
+            .filter(|old| !self.key_column && *old != member)
+        {
+            pipe.cmd("ZREM")
+                .arg(key_prefix)
+                .arg(self.member_arg(old_member))
+                .ignore();
+        }
+        pipe.cmd("GEOADD")
+            .arg(key_prefix)
+            .arg(longitude)
+            .arg(latitude)
+            .arg(self.member_arg(member))
+            .ignore();
+        pipe.query::<()>(conn)
+    }
+
+    fn supports_pushdown(&self, operator: &ComparisonOperator) -> bool {
+        matches!(
+            operator,
+            ComparisonOperator::Equal
+                | ComparisonOperator::In
+                | ComparisonOperator::GeoRadius { .. }
+                | ComparisonOperator::GeoBox { .. }
+        )
+    }
+
+    /// `score_column_index` is the longitude column; the latitude column is
+    /// taken to follow it unless the caller sets it afterwards. Columns
+    /// after the latitude are virtual: one named `geohash` holds the
+    /// geohash, any other the distance.
+    fn configure(
+        &mut self,
+        column_names: &[String],
+        pushdown_column_index: usize,
+        score_column_index: Option<usize>,
+    ) {
+        self.pushdown_column_index = pushdown_column_index;
+        self.longitude_column_index = score_column_index.unwrap_or(pushdown_column_index + 1);
+        self.latitude_column_index = self.longitude_column_index + 1;
+        let skip = usize::from(self.key_column) + 3;
+        self.extras = column_names
+            .iter()
+            .skip(skip)
+            .map(|name| {
+                if name.eq_ignore_ascii_case("geohash") {
+                    GeoExtra::Geohash
+                } else {
+                    GeoExtra::Distance
+                }
+            })
+            .collect();
+    }
+
+    fn set_column_encodings(&mut self, encodings: ColumnEncodings) {
+        self.encodings = encodings;
+    }
+
+    fn load_multi_key_data(
+        &mut self,
+        conn: &mut dyn redis::ConnectionLike,
+        keys: &[String],
+    ) -> Result<Vec<RowValue>, redis::RedisError> {
+        if keys.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut pipe = redis::pipe();
+        for key in keys {
+            pipe.cmd("ZRANGE").arg(key).arg(0).arg(-1);
+        }
+        let members: Vec<Vec<Vec<u8>>> = pipe.query(conn)?;
+        let groups = self.positions(conn, keys.iter().cloned().zip(members).collect())?;
+        self.rows(conn, groups)
+    }
+
+    fn clear(&mut self) {
+        self.dataset = DataSet::default();
+    }
+
+    fn redis_type_name(&self) -> &'static str {
+        "zset"
+    }
+
+    fn set_filtered_data(&mut self, data: Vec<RowValue>) {
+        self.dataset = DataSet::Filtered(data);
+    }
+
+    fn multi_key_columns_per_row(&self) -> usize {
+        1 + 3 + self.extras.len()
+    }
+
+    /// One GEOPOS for all `params`, which are members.
+    fn batch_parameterized_lookup(
+        &mut self,
+        conn: &mut dyn redis::ConnectionLike,
+        key_prefix: &str,
+        params: &[String],
+    ) -> Result<Vec<Option<Vec<RowValue>>>, redis::RedisError> {
+        let members: Vec<Vec<u8>> = params
+            .iter()
+            .map(|p| self.member_arg(p).into_owned())
+            .collect();
+        let mut groups = self.positions(conn, vec![(key_prefix.to_string(), members)])?;
+        self.add_geohashes(conn, &mut groups)?;
+        // The entries are the found members, in `params` order.
+        let mut entries = groups
+            .pop()
+            .map(|(_, entries)| entries)
+            .unwrap_or_default()
+            .into_iter()
+            .peekable();
+        params
+            .iter()
+            .map(|p| {
+                match entries.next_if(|e| e.member.as_slice() == self.member_arg(p).as_ref()) {
+                    Some(entry) => {
+                        let mut row = Vec::with_capacity(self.row_width());
+                        self.push_row(&mut row, key_prefix, entry)?;
+                        Ok(Some(row))
+                    }
+                    None => Ok(None),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::utils::cmd_args;
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn condition(operator: ComparisonOperator, value: &str) -> PushableCondition {
+        PushableCondition {
+            column_name: "longitude".to_string(),
+            column_index: 1,
+            operator,
+            value: value.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_geo_distance_matches_geodist() {
+        // GEODIST Sicily Palermo Catania, from the Redis documentation
+        let d = geo_distance(13.361389, 38.115556, 15.087269, 37.502669);
+        assert!((d - 166274.1516).abs() < 0.5, "{}", d);
+        assert_eq!(geo_distance(2.0, 45.0, 2.0, 45.0), 0.0);
+    }
+
+    #[test]
+    fn test_geo_within_box() {
+        // One degree of latitude is about 111 km.
+        assert!(geo_within_box(0.0, 0.9, 0.0, 0.0, 1.0, 250_000.0));
+        assert!(!geo_within_box(0.0, 0.9, 0.0, 0.0, 1.0, 150_000.0));
+        assert!(geo_within_box(0.9, 0.0, 0.0, 0.0, 250_000.0, 1.0));
+        assert!(!geo_within_box(0.9, 0.0, 0.0, 0.0, 150_000.0, 1.0));
+    }
+
+    #[test]
+    fn test_search_shape_requires_the_table_columns() {
+        let t = RedisGeoTable::new();
+        let radius = ComparisonOperator::GeoRadius { latitude_column: 2 };
+        assert_eq!(
+            t.search_shape(&condition(radius.clone(), "15,37,200000")),
+            Some(GeoShape::Radius {
+                longitude: 15.0,
+                latitude: 37.0,
+                radius: 200000.0
+            })
+        );
+        let swapped = ComparisonOperator::GeoRadius { latitude_column: 1 };
+        assert_eq!(t.search_shape(&condition(swapped, "15,37,200000")), None);
+        assert_eq!(t.search_shape(&condition(radius.clone(), "15,37,-1")), None);
+        assert_eq!(t.search_shape(&condition(radius, "15,89,10")), None);
+        let boxed = ComparisonOperator::GeoBox { latitude_column: 2 };
+        assert_eq!(
+            t.search_shape(&condition(boxed, "15,37,400,300")),
+            Some(GeoShape::Box {
+                longitude: 15.0,
+                latitude: 37.0,
+                width: 400.0,
+                height: 300.0
+            })
+        );
+    }
+
+    #[test]
+    fn test_search_shape_args() {
+        let mut cmd = redis::cmd("GEOSEARCH");
+        GeoShape::Box {
+            longitude: 15.0,
+            latitude: 37.0,
+            width: 400.0,
+            height: 300.0,
+        }
+        .add_args(&mut cmd);
+        assert_eq!(
+            cmd_args(&cmd)[1..],
+            strings(&["FROMLONLAT", "15.0", "37.0", "BYBOX", "400.0", "300.0", "m"])
+        );
+    }
+
+    #[test]
+    fn test_configure_virtual_columns() {
+        let mut t = RedisGeoTable::new();
+        t.key_column = true;
+        t.configure(
+            &strings(&["key", "name", "lon", "lat", "GeoHash", "dist"]),
+            1,
+            Some(2),
+        );
+        assert_eq!(t.extras, vec![GeoExtra::Geohash, GeoExtra::Distance]);
+        assert_eq!((t.longitude_column_index, t.latitude_column_index), (2, 3));
+        assert_eq!(t.multi_key_columns_per_row(), 6);
+    }
+
+    #[test]
+    fn test_insert_command() {
+        let t = RedisGeoTable::new();
+        let data = strings(&["Palermo", "13.361389", "38.115556", "NULL"]);
+        let cmd = t.insert_command("sicily", &data, InsertMode::Nx).unwrap();
+        assert_eq!(
+            cmd_args(&cmd),
+            strings(&[
+                "GEOADD",
+                "sicily",
+                "NX",
+                "13.361389",
+                "38.115556",
+                "Palermo"
+            ])
+        );
+        let cmd = t.insert_command("sicily", &data, InsertMode::Xx).unwrap();
+        assert_eq!(
+            cmd_args(&cmd)[2..],
+            strings(&["1", "sicily", "13.361389", "38.115556", "Palermo"])
+        );
+        let missing = strings(&["Palermo", "NULL", "38.115556"]);
+        assert!(t
+            .insert_command("sicily", &missing, InsertMode::Overwrite)
+            .is_none());
+    }
+
+    #[test]
+    fn test_push_row_fills_virtual_columns() {
+        let mut t = RedisGeoTable::new();
+        t.configure(
+            &strings(&["m", "lon", "lat", "distance", "geohash"]),
+            0,
+            None,
+        );
+        let mut rows = Vec::new();
+        let entry = GeoEntry {
+            member: b"Palermo".to_vec(),
+            longitude: "13.36".to_string(),
+            latitude: "38.11".to_string(),
+            distance: None,
+            geohash: Some("sqc8b49rny0".to_string()),
+        };
+        t.push_row(&mut rows, "sicily", entry).unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                Some(b"Palermo".to_vec()),
+                Some(b"13.36".to_vec()),
+                Some(b"38.11".to_vec()),
+                None,
+                Some(b"sqc8b49rny0".to_vec()),
+            ]
+        );
+    }
+}
@@ -8,25 +8,108 @@ use crate::{
     },
     tables::{
         interface::RedisTableOperations,
-        types::{DataContainer, DataSet, LoadDataResult, RowVec},
+        types::{
+            ColumnEncodings, DataContainer, DataSet, InsertMode, LoadDataResult, RowValue, RowVec,
+        },
     },
 };
 use smallvec::smallvec;
 
+/// HSET only when the field already exists; there is no HSETXX command.
+const HSET_XX_SCRIPT: &str = "if redis.call('HEXISTS', KEYS[1], ARGV[1]) == 1 then \
+     redis.call('HSET', KEYS[1], ARGV[1], ARGV[2]) return 1 end return 0";
+
 /// Redis Hash table type
 #[derive(Debug, Clone, Default)]
 pub struct RedisHashTable {
     pub dataset: DataSet,
     /// Raw attribute index of the field column (accounts for TTL position and multi-key offset)
     pub pushdown_column_index: usize,
+    /// Encodings of the field and value columns
+    pub encodings: ColumnEncodings,
 }
 
+/// A field/value pair as Redis returns it.
+type RawPair = (Vec<u8>, Vec<u8>);
+
 impl RedisHashTable {
     pub fn new() -> Self {
         Self {
             dataset: DataSet::Empty,
             pushdown_column_index: 0,
+            encodings: ColumnEncodings::default(),
+        }
+    }
+
+    /// Write command for one row of `[field, value, ...]` pairs. Overwrite
+    /// sets every pair with HSET; NX/XX apply to the first pair only (one
+    /// row = one field) via HSETNX or [`HSET_XX_SCRIPT`].
+    pub fn insert_command(
+        &self,
+        key: &str,
+        data: &[String],
+        mode: InsertMode,
+    ) -> Option<redis::Cmd> {
+        if data.len() < 2 {
+            return None;
         }
+        let cmd = match mode {
+            InsertMode::Overwrite => {
+                let mut cmd = redis::cmd("HSET");
+                cmd.arg(key);
+                for pair in data.chunks_exact(2) {
+                    cmd.arg(self.field_arg(&pair[0]))
+                        .arg(self.value_arg(&pair[1]));
+                }
+                cmd
+            }
+            InsertMode::Nx => {
+                let mut cmd = redis::cmd("HSETNX");
+                cmd.arg(key)
+                    .arg(self.field_arg(&data[0]))
+                    .arg(self.value_arg(&data[1]));
+                cmd
+            }
+            InsertMode::Xx => {
+                let mut cmd = redis::cmd("EVAL");
+                cmd.arg(HSET_XX_SCRIPT)
+                    .arg(1)
+                    .arg(key)
+                    .arg(self.field_arg(&data[0]))
+                    .arg(self.value_arg(&data[1]));
+                cmd
+            }
+        };
+        Some(cmd)
+    }
+
+    /// Bytes sent to Redis for a field name.
+    fn field_arg<'a>(&self, field: &'a str) -> Cow<'a, [u8]> {
+        self.encodings.get(0).encode(field)
+    }
+
+    /// Bytes sent to Redis for a value.
+    fn value_arg<'a>(&self, value: &'a str) -> Cow<'a, [u8]> {
+        self.encodings.get(1).encode(value)
+    }
+
+    fn decode_value(&self, value: Option<Vec<u8>>) -> Result<Option<Vec<u8>>, redis::RedisError> {
+        value.map(|v| self.encodings.get(1).decode(v)).transpose()
+    }
+
+    fn decode_pairs(&self, pairs: Vec<RawPair>) -> Result<Vec<RawPair>, redis::RedisError> {
+        let (field_encoding, value_encoding) = (self.encodings.get(0), self.encodings.get(1));
+        pairs
+            .into_iter()
+            .map(|(field, value)| {
+                Ok((field_encoding.decode(field)?, value_encoding.decode(value)?))
+            })
+            .collect()
+    }
+
+    /// Row of a field found by a parameterized lookup.
+    fn lookup_row(&self, field: &str, value: Vec<u8>) -> Vec<RowValue> {
+        vec![Some(self.field_arg(field).into_owned()), Some(value)]
     }
 
     fn load_with_scan_optimization(
@@ -40,10 +123,16 @@ impl RedisHashTable {
             let pattern_matcher = PatternMatcher::from_like_pattern(&pattern);
 
             if pattern_matcher.requires_scan() {
-                let matching_fields: Vec<String> = RedisScanBuilder::new_hash_scan(key_prefix)
+                // HSCAN replies [field, value, field, value, ...]
+                let raw: Vec<Vec<u8>> = RedisScanBuilder::new_hash_scan(key_prefix)
                     .with_pattern(pattern_matcher.get_pattern())
                     .with_limit(limit_offset.clone())
                     .execute_all(conn)?;
+                let matching_fields = raw
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, item)| self.encodings.get(i % 2).decode(item).map(Some))
+                    .collect::<Result<Vec<RowValue>, _>>()?;
 
                 if matching_fields.is_empty() {
                     self.dataset = DataSet::Empty;
@@ -65,10 +154,13 @@ impl RedisHashTable {
         key_prefix: &str,
         field: &str,
     ) -> Result<LoadDataResult, redis::RedisError> {
-        let value: Option<String> = redis::cmd("HGET").arg(key_prefix).arg(field).query(conn)?;
+        let value: Option<Vec<u8>> = redis::cmd("HGET")
+            .arg(key_prefix)
+            .arg(self.field_arg(field))
+            .query(conn)?;
 
-        if let Some(v) = value {
-            let result = vec![field.to_string(), v];
+        if let Some(v) = self.decode_value(value)? {
+            let result = vec![Some(self.field_arg(field).into_owned()), Some(v)];
             self.dataset = DataSet::Filtered(result);
             Ok(LoadDataResult::FullyLoaded)
         } else {
@@ -83,16 +175,18 @@ impl RedisHashTable {
         key_prefix: &str,
         fields: &[&str],
     ) -> Result<LoadDataResult, redis::RedisError> {
-        let values: Vec<Option<String>> = redis::cmd("HMGET")
-            .arg(key_prefix)
-            .arg(fields)
-            .query(conn)?;
+        let mut cmd = redis::cmd("HMGET");
+        cmd.arg(key_prefix);
+        for field in fields {
+            cmd.arg(self.field_arg(field));
+        }
+        let values: Vec<Option<Vec<u8>>> = cmd.query(conn)?;
 
         let mut result = Vec::new();
-        for (i, value) in values.iter().enumerate() {
-            if let Some(v) = value {
-                result.push(fields[i].to_string());
-                result.push(v.clone());
+        for (i, value) in values.into_iter().enumerate() {
+            if let Some(v) = self.decode_value(value)? {
+                result.push(Some(self.field_arg(fields[i]).into_owned()));
+                result.push(Some(v));
             }
         }
         self.dataset = DataSet::Filtered(result);
@@ -104,7 +198,8 @@ impl RedisHashTable {
         conn: &mut dyn redis::ConnectionLike,
         key_prefix: &str,
     ) -> Result<LoadDataResult, redis::RedisError> {
-        let data_vec: Vec<(String, String)> = redis::cmd("HGETALL").arg(key_prefix).query(conn)?;
+        let pairs: Vec<RawPair> = redis::cmd("HGETALL").arg(key_prefix).query(conn)?;
+        let data_vec = self.decode_pairs(pairs)?;
         self.dataset = DataSet::Complete(DataContainer::Hash(data_vec));
         Ok(LoadDataResult::FullyLoaded)
     }
@@ -171,8 +266,8 @@ impl RedisTableOperations for RedisHashTable {
                 let data_index = index * 2;
                 if data_index + 1 < data.len() {
                     Some(smallvec![
-                        Cow::Borrowed(data[data_index].as_str()),
-                        Cow::Borrowed(data[data_index + 1].as_str()),
+                        data[data_index].as_deref().map(Cow::Borrowed),
+                        data[data_index + 1].as_deref().map(Cow::Borrowed),
                     ])
                 } else {
                     None
@@ -196,25 +291,13 @@ impl RedisTableOperations for RedisHashTable {
         conn: &mut dyn redis::ConnectionLike,
         key_prefix: &str,
         data: &[String],
-    ) -> Result<(), redis::RedisError> {
-        let fields: Vec<(String, String)> = data
-            .chunks(2)
-            .filter_map(|chunk| {
-                if chunk.len() == 2 {
-                    Some((chunk[0].clone(), chunk[1].clone()))
-                } else {
-                    None
-                }
-            })
-            .collect();
-
-        if !fields.is_empty() {
-            let _: () = redis::cmd("HSET")
-                .arg(key_prefix)
-                .arg(&fields)
-                .query(conn)?;
-        }
-        Ok(())
+        mode: InsertMode,
+    ) -> Result<bool, redis::RedisError> {
+        let Some(cmd) = self.insert_command(key_prefix, data, mode) else {
+            return Ok(false);
+        };
+        let reply: redis::Value = cmd.query(conn)?;
+        Ok(mode == InsertMode::Overwrite || InsertMode::reply_applied(&reply))
     }
 
     fn delete(
@@ -224,7 +307,12 @@ impl RedisTableOperations for RedisHashTable {
         data: &[String],
     ) -> Result<(), redis::RedisError> {
         if !data.is_empty() {
-            let _: () = redis::cmd("HDEL").arg(key_prefix).arg(data).query(conn)?;
+            let mut cmd = redis::cmd("HDEL");
+            cmd.arg(key_prefix);
+            for field in data {
+                cmd.arg(self.field_arg(field));
+            }
+            let _: () = cmd.query(conn)?;
         }
         Ok(())
     }
@@ -247,11 +335,11 @@ impl RedisTableOperations for RedisHashTable {
                         .atomic()
                         .cmd("HDEL")
                         .arg(key_prefix)
-                        .arg(old_field)
+                        .arg(self.field_arg(old_field))
                         .cmd("HSET")
                         .arg(key_prefix)
-                        .arg(new_field)
-                        .arg(&new_data[1])
+                        .arg(self.field_arg(new_field))
+                        .arg(self.value_arg(&new_data[1]))
                         .query::<()>(conn)?;
                     return Ok(());
                 }
@@ -259,8 +347,8 @@ impl RedisTableOperations for RedisHashTable {
 
             let _: () = redis::cmd("HSET")
                 .arg(key_prefix)
-                .arg(new_field)
-                .arg(&new_data[1])
+                .arg(self.field_arg(new_field))
+                .arg(self.value_arg(&new_data[1]))
                 .query(conn)?;
         }
         Ok(())
@@ -310,12 +398,13 @@ impl RedisTableOperations for RedisHashTable {
         }
         cmd.arg("COUNT").arg(batch_size);
 
-        let (new_cursor, pairs): (u64, Vec<(String, String)>) = cmd.query(conn)?;
+        let (new_cursor, raw_pairs): (u64, Vec<RawPair>) = cmd.query(conn)?;
+        let pairs = self.decode_pairs(raw_pairs)?;
 
         // Apply field conditions as client-side post-filter
         // Note: Only the first LIKE condition was used for server-side MATCH;
         // all other conditions (including additional LIKEs) must be verified here
-        let filtered: Vec<(String, String)> = if let Some(conds) = field_conds {
+        let filtered: Vec<RawPair> = if let Some(conds) = field_conds {
             if conds.is_empty() {
                 pairs
             } else {
@@ -338,11 +427,11 @@ impl RedisTableOperations for RedisHashTable {
                         )
                     })
                     .collect();
-                let in_value_sets: Vec<Vec<&str>> = conds
+                let in_value_sets: Vec<Vec<Cow<[u8]>>> = conds
                     .iter()
                     .map(|c| {
                         if c.operator == ComparisonOperator::In {
-                            c.value.split(',').collect()
+                            c.value.split(',').map(|v| self.field_arg(v)).collect()
                         } else {
                             Vec::new()
                         }
@@ -352,9 +441,11 @@ impl RedisTableOperations for RedisHashTable {
                     .into_iter()
                     .filter(|(field, _)| {
                         conds.iter().enumerate().all(|(i, c)| match c.operator {
-                            ComparisonOperator::Equal => field == &c.value,
-                            ComparisonOperator::NotEqual => field != &c.value,
-                            ComparisonOperator::In => in_value_sets[i].contains(&field.as_str()),
+                            ComparisonOperator::Equal => *field == *self.field_arg(&c.value),
+                            ComparisonOperator::NotEqual => *field != *self.field_arg(&c.value),
+                            ComparisonOperator::In => {
+                                in_value_sets[i].iter().any(|v| **v == **field)
+                            }
                             ComparisonOperator::Like => {
                                 if Some(&c.value) == first_like_value {
                                     true // handled by MATCH
@@ -362,7 +453,9 @@ impl RedisTableOperations for RedisHashTable {
                                     extra_like_matchers
                                         .iter()
                                         .find(|(v, _)| *v == c.value.as_str())
-                                        .is_some_and(|(_, m)| m.matches(field))
+                                        .is_some_and(|(_, m)| {
+                                            m.matches(&String::from_utf8_lossy(field))
+                                        })
                                 }
                             }
                             _ => true,
@@ -396,10 +489,10 @@ impl RedisTableOperations for RedisHashTable {
         &mut self,
         conn: &mut dyn redis::ConnectionLike,
         keys: &[String],
-    ) -> Result<Vec<String>, redis::RedisError> {
+    ) -> Result<Vec<RowValue>, redis::RedisError> {
         const PER_KEY_WARN_THRESHOLD: usize = 200_000;
 
-        let pipe_result: Result<Vec<Vec<(String, String)>>, _> = {
+        let pipe_result: Result<Vec<Vec<RawPair>>, _> = {
             let mut pipe = redis::pipe();
             for key in keys {
                 pipe.cmd("HGETALL").arg(key);
@@ -412,7 +505,7 @@ impl RedisTableOperations for RedisHashTable {
             Err(_) => {
                 let mut results = Vec::with_capacity(keys.len());
                 for key in keys {
-                    let r: Vec<(String, String)> = redis::cmd("HGETALL").arg(key).query(conn)?;
+                    let r: Vec<RawPair> = redis::cmd("HGETALL").arg(key).query(conn)?;
                     results.push(r);
                 }
                 results
@@ -429,10 +522,10 @@ impl RedisTableOperations for RedisHashTable {
                     pairs.len()
                 );
             }
-            for (field, value) in pairs {
-                all_rows.push(key.clone());
-                all_rows.push(field);
-                all_rows.push(value);
+            for (field, value) in self.decode_pairs(pairs)? {
+                all_rows.push(Some(key.clone().into_bytes()));
+                all_rows.push(Some(field));
+                all_rows.push(Some(value));
             }
         }
         Ok(all_rows)
@@ -446,7 +539,7 @@ impl RedisTableOperations for RedisHashTable {
         "hash"
     }
 
-    fn set_filtered_data(&mut self, data: Vec<String>) {
+    fn set_filtered_data(&mut self, data: Vec<RowValue>) {
         self.dataset = DataSet::Filtered(data);
     }
 
@@ -454,12 +547,16 @@ impl RedisTableOperations for RedisHashTable {
         3
     }
 
+    fn set_column_encodings(&mut self, encodings: ColumnEncodings) {
+        self.encodings = encodings;
+    }
+
     fn batch_parameterized_lookup(
         &mut self,
         conn: &mut dyn redis::ConnectionLike,
         key_prefix: &str,
         params: &[String],
-    ) -> Result<Vec<Option<Vec<String>>>, redis::RedisError> {
+    ) -> Result<Vec<Option<Vec<RowValue>>>, redis::RedisError> {
         if params.is_empty() {
             return Ok(Vec::new());
         }
@@ -468,19 +565,31 @@ impl RedisTableOperations for RedisHashTable {
         // HGET is lighter than HMGET when there's only one field.
         if params.len() == 1 {
             let p = &params[0];
-            let v: Option<String> = redis::cmd("HGET").arg(key_prefix).arg(p).query(conn)?;
-            return Ok(vec![v.map(|val| vec![p.clone(), val])]);
+            let v: Option<Vec<u8>> = redis::cmd("HGET")
+                .arg(key_prefix)
+                .arg(self.field_arg(p))
+                .query(conn)?;
+            return Ok(vec![self
+                .decode_value(v)?
+                .map(|val| self.lookup_row(p, val))]);
         }
 
-        let pipeline_result: Result<Vec<Option<String>>, redis::RedisError> =
-            redis::cmd("HMGET").arg(key_prefix).arg(params).query(conn);
+        let mut hmget = redis::cmd("HMGET");
+        hmget.arg(key_prefix);
+        for p in params {
+            hmget.arg(self.field_arg(p));
+        }
+        let pipeline_result: Result<Vec<Option<Vec<u8>>>, redis::RedisError> = hmget.query(conn);
 
         match pipeline_result {
-            Ok(values) => Ok(values
+            Ok(values) => values
                 .into_iter()
                 .zip(params.iter())
-                .map(|(v, p)| v.map(|val| vec![p.clone(), val]))
-                .collect()),
+                .map(|(v, p)| {
+                    self.decode_value(v)
+                        .map(|v| v.map(|val| self.lookup_row(p, val)))
+                })
+                .collect(),
             Err(e) => {
                 pgrx::log!(
                     "redis_fdw: HMGET failed during batch parameterized lookup, falling back: {}",
@@ -488,9 +597,11 @@ impl RedisTableOperations for RedisHashTable {
                 );
                 let mut out = Vec::with_capacity(params.len());
                 for p in params {
-                    let v: Option<String> =
-                        redis::cmd("HGET").arg(key_prefix).arg(p).query(conn)?;
-                    out.push(v.map(|val| vec![p.clone(), val]));
+                    let v: Option<Vec<u8>> = redis::cmd("HGET")
+                        .arg(key_prefix)
+                        .arg(self.field_arg(p))
+                        .query(conn)?;
+                    out.push(self.decode_value(v)?.map(|val| self.lookup_row(p, val)));
                 }
                 Ok(out)
             }
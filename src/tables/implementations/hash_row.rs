@@ -0,0 +1,434 @@
+use std::borrow::Cow;
+
+use crate::{
+    query::{
+        limit::LimitOffsetInfo,
+        pushdown_types::{ComparisonOperator, PushableCondition},
+    },
+    tables::{
+        interface::RedisTableOperations,
+        types::{ColumnEncodings, DataSet, InsertMode, LoadDataResult, RowValue, RowVec},
+    },
+};
+
+/// HSET only when the key's existence matches the mode: ARGV[1] is 'nx' or
+/// 'xx', the rest are the field/value pairs.
+const HSET_ROW_SCRIPT: &str = "if (redis.call('EXISTS', KEYS[1]) == 1) == (ARGV[1] == 'xx') then \
+     redis.call('HSET', KEYS[1], unpack(ARGV, 2)) return 1 end return 0";
+
+/// Redis Hash table type in wide layout: each key is one row and each data
+/// column is one field of that hash.
+///
+/// A key is a row when it holds at least one of the mapped fields; fields
+/// the key lacks read as NULL, and fields without a column are left alone
+/// by every write.
+#[derive(Debug, Clone, Default)]
+pub struct RedisHashRowTable {
+    /// Rows as flat `[key?, value, ...]` chunks of [`Self::row_width`]
+    pub dataset: DataSet,
+    /// Hash field of each data column, in column order
+    pub fields: Vec<String>,
+    /// Whether rows start with the Redis key (multi-key mode)
+    pub key_column: bool,
+    /// Encodings of the data columns, in `fields` order
+    pub encodings: ColumnEncodings,
+}
+
+impl RedisHashRowTable {
+    pub fn new() -> Self {
+        Self {
+            dataset: DataSet::Empty,
+            fields: Vec::new(),
+            key_column: false,
+            encodings: ColumnEncodings::default(),
+        }
+    }
+
+    /// Number of values in one row: the fields plus the key column.
+    fn row_width(&self) -> usize {
+        self.fields.len() + usize::from(self.key_column)
+    }
+
+    /// Field/value arguments for the non-NULL values of `data`, which holds
+    /// one value per field.
+    fn field_value_args<'a>(&'a self, data: &'a [String]) -> Vec<(&'a str, Cow<'a, [u8]>)> {
+        self.fields
+            .iter()
+            .zip(data)
+            .enumerate()
+            .filter(|(_, (_, value))| value.as_str() != "NULL")
+            .map(|(i, (field, value))| (field.as_str(), self.encodings.get(i).encode(value)))
+            .collect()
+    }
+
+    /// Write command for one row: HSET of its non-NULL values. NX/XX write
+    /// the row only when the key is absent/present, via [`HSET_ROW_SCRIPT`].
+    pub fn insert_command(
+        &self,
+        key: &str,
+        data: &[String],
+        mode: InsertMode,
+    ) -> Option<redis::Cmd> {
+        let pairs = self.field_value_args(data);
+        if pairs.is_empty() {
+            return None;
+        }
+        let mut cmd = match mode {
+            InsertMode::Overwrite => {
+                let mut cmd = redis::cmd("HSET");
+                cmd.arg(key);
+                cmd
+            }
+            InsertMode::Nx | InsertMode::Xx => {
+                let mut cmd = redis::cmd("EVAL");
+                cmd.arg(HSET_ROW_SCRIPT)
+                    .arg(1)
+                    .arg(key)
+                    .arg(if mode == InsertMode::Nx { "nx" } else { "xx" });
+                cmd
+            }
+        };
+        for (field, value) in pairs {
+            cmd.arg(field).arg(value);
+        }
+        Some(cmd)
+    }
+
+    /// Queue an HMGET of the mapped fields of `key`.
+    fn add_hmget(&self, pipe: &mut redis::Pipeline, key: &str) {
+        pipe.cmd("HMGET").arg(key).arg(self.fields.as_slice());
+    }
+
+    /// One row from an HMGET reply, `None` when the key holds none of the
+    /// fields. Missing fields are NULL.
+    fn decode_row(
+        &self,
+        key: &str,
+        values: Vec<Option<Vec<u8>>>,
+    ) -> Result<Option<Vec<RowValue>>, redis::RedisError> {
+        if values.iter().all(Option::is_none) {
+            return Ok(None);
+        }
+        let mut row = Vec::with_capacity(self.row_width());
+        if self.key_column {
+            row.push(Some(key.as_bytes().to_vec()));
+        }
+        for (i, value) in values.into_iter().enumerate() {
+            row.push(value.map(|v| self.encodings.get(i).decode(v)).transpose()?);
+        }
+        Ok(Some(row))
+    }
+
+    /// The rows of `keys`, one pipelined HMGET per key.
+    fn fetch_rows(
+        &self,
+        conn: &mut dyn redis::ConnectionLike,
+        keys: &[String],
+    ) -> Result<Vec<Option<Vec<RowValue>>>, redis::RedisError> {
+        if keys.is_empty() || self.fields.is_empty() {
+            return Ok(vec![None; keys.len()]);
+        }
+        let mut pipe = redis::pipe();
+        for key in keys {
+            self.add_hmget(&mut pipe, key);
+        }
+        let replies: Vec<Vec<Option<Vec<u8>>>> = pipe.query(conn)?;
+        keys.iter()
+            .zip(replies)
+            .map(|(key, values)| self.decode_row(key, values))
+            .collect()
+    }
+}
+
+impl RedisTableOperations for RedisHashRowTable {
+    fn load_data(
+        &mut self,
+        conn: &mut dyn redis::ConnectionLike,
+        key_prefix: &str,
+        _conditions: Option<&[PushableCondition]>,
+        _limit_offset: &LimitOffsetInfo,
+    ) -> Result<LoadDataResult, redis::RedisError> {
+        // A single key is at most one row; PostgreSQL applies the quals.
+        let row = self
+            .fetch_rows(conn, &[key_prefix.to_string()])?
+            .pop()
+            .flatten();
+        match row {
+            Some(row) => {
+                self.dataset = DataSet::Filtered(row);
+                Ok(LoadDataResult::FullyLoaded)
+            }
+            None => {
+                self.dataset = DataSet::Empty;
+                Ok(LoadDataResult::Empty)
+            }
+        }
+    }
+
+    fn load_batch(
+        &mut self,
+        conn: &mut dyn redis::ConnectionLike,
+        key_prefix: &str,
+        _cursor: u64,
+        _batch_size: usize,
+        conditions: Option<&[PushableCondition]>,
+    ) -> Result<(u64, usize), redis::RedisError> {
+        self.load_data(conn, key_prefix, conditions, &LimitOffsetInfo::default())?;
+        Ok((0, self.data_len()))
+    }
+
+    fn get_dataset(&self) -> &DataSet {
+        &self.dataset
+    }
+
+    #[inline]
+    fn get_row(&self, index: usize) -> Option<RowVec<'_>> {
+        let width = self.row_width();
+        let data = self.dataset.as_filtered()?;
+        let start = index.checked_mul(width)?;
+        data.get(start..start + width).map(|row| {
+            row.iter()
+                .map(|v| v.as_deref().map(Cow::Borrowed))
+                .collect()
+        })
+    }
+
+    #[inline]
+    fn data_len(&self) -> usize {
+        match &self.dataset {
+            DataSet::Filtered(data) => data.len().checked_div(self.row_width()).unwrap_or(0),
+            _ => 0,
+        }
+    }
+
+    fn insert(
+        &mut self,
+        conn: &mut dyn redis::ConnectionLike,
+        key_prefix: &str,
+        data: &[String],
+        mode: InsertMode,
+    ) -> Result<bool, redis::RedisError> {
+        let Some(cmd) = self.insert_command(key_prefix, data, mode) else {
+            return Ok(false);
+        };
+        let reply: redis::Value = cmd.query(conn)?;
+        Ok(mode == InsertMode::Overwrite || InsertMode::reply_applied(&reply))
+    }
+
+    /// HDEL of the mapped fields; the key goes away once it has no fields
+    /// left.
+    fn delete(
+        &mut self,
+        conn: &mut dyn redis::ConnectionLike,
+        key_prefix: &str,
+        _data: &[String],
+    ) -> Result<(), redis::RedisError> {
+        if !self.fields.is_empty() {
+            let _: () = redis::cmd("HDEL")
+                .arg(key_prefix)
+                .arg(self.fields.as_slice())
+                .query(conn)?;
+        }
+        Ok(())
+    }
+
+    /// HSET of the non-NULL values and HDEL of the fields set to NULL. In
+    /// multi-key mode `old_data[0]` is the old key, renamed first when the
+    /// key column changed.
+    fn update(
+        &mut self,
+        conn: &mut dyn redis::ConnectionLike,
+        key_prefix: &str,
+        old_data: &[String],
+        new_data: &[String],
+    ) -> Result<(), redis::RedisError> {
+        let mut pipe = redis::pipe();
+        pipe.atomic();
+        if let Some(old_key) = old_data
+            .first()
+            .filter(|k| self.key_column && *k != key_prefix)
+        {
+            pipe.cmd("RENAME").arg(old_key).arg(key_prefix).ignore();
+        }
+        let pairs = self.field_value_args(new_data);
+        if !pairs.is_empty() {
+            pipe.cmd("HSET").arg(key_prefix);
+            for (field, value) in pairs {
+                pipe.arg(field).arg(value);
+            }
+            pipe.ignore();
+        }
+        let nulls: Vec<&str> = self
+            .fields
+            .iter()
+            .zip(new_data)
+            .filter(|(_, value)| value.as_str() == "NULL")
+            .map(|(field, _)| field.as_str())
+            .collect();
+        if !nulls.is_empty() {
+            pipe.cmd("HDEL").arg(key_prefix).arg(nulls).ignore();
+        }
+        pipe.query::<()>(conn)
+    }
+
+    /// Only conditions on the key column narrow anything: the multi-key scan
+    /// turns them into direct key lookups or a narrowed SCAN.
+    fn supports_pushdown(&self, operator: &ComparisonOperator) -> bool {
+        matches!(
+            operator,
+            ComparisonOperator::Equal | ComparisonOperator::In | ComparisonOperator::Like
+        )
+    }
+
+    fn configure(
+        &mut self,
+        column_names: &[String],
+        _pushdown_column_index: usize,
+        _score_column_index: Option<usize>,
+    ) {
+        let skip = usize::from(self.key_column).min(column_names.len());
+        self.fields = column_names[skip..].to_vec();
+    }
+
+    fn set_column_encodings(&mut self, encodings: ColumnEncodings) {
+        self.encodings = encodings;
+    }
+
+    fn load_multi_key_data(
+        &mut self,
+        conn: &mut dyn redis::ConnectionLike,
+        keys: &[String],
+    ) -> Result<Vec<RowValue>, redis::RedisError> {
+        Ok(self
+            .fetch_rows(conn, keys)?
+            .into_iter()
+            .flatten()
+            .flatten()
+            .collect())
+    }
+
+    fn clear(&mut self) {
+        self.dataset = DataSet::default();
+    }
+
+    fn redis_type_name(&self) -> &'static str {
+        "hash"
+    }
+
+    fn set_filtered_data(&mut self, data: Vec<RowValue>) {
+        self.dataset = DataSet::Filtered(data);
+    }
+
+    fn multi_key_columns_per_row(&self) -> usize {
+        self.fields.len() + 1
+    }
+
+    fn batch_parameterized_lookup(
+        &mut self,
+        conn: &mut dyn redis::ConnectionLike,
+        _key_prefix: &str,
+        params: &[String],
+    ) -> Result<Vec<Option<Vec<RowValue>>>, redis::RedisError> {
+        self.fetch_rows(conn, params)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tables::types::ValueEncoding;
+    use crate::tests::utils::{cmd_arg_bytes, row_text, row_values};
+
+    fn table(key_column: bool) -> RedisHashRowTable {
+        let mut t = RedisHashRowTable::new();
+        t.key_column = key_column;
+        let columns: Vec<String> = ["key", "name", "email", "plan"]
+            .iter()
+            .skip(usize::from(!key_column))
+            .map(|s| s.to_string())
+            .collect();
+        t.configure(&columns, 0, None);
+        t
+    }
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_configure_skips_key_column() {
+        assert_eq!(table(true).fields, strings(&["name", "email", "plan"]));
+        assert_eq!(table(false).fields, strings(&["name", "email", "plan"]));
+        assert_eq!(table(true).multi_key_columns_per_row(), 4);
+    }
+
+    #[test]
+    fn test_decode_row_marks_missing_fields() {
+        let t = table(true);
+        let row = t
+            .decode_row(
+                "user:1",
+                vec![Some(b"ann".to_vec()), None, Some(b"pro".to_vec())],
+            )
+            .unwrap();
+        assert_eq!(
+            row,
+            Some(vec![
+                Some(b"user:1".to_vec()),
+                Some(b"ann".to_vec()),
+                None,
+                Some(b"pro".to_vec()),
+            ])
+        );
+        assert_eq!(
+            t.decode_row("user:2", vec![None, None, None]).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_get_row_chunks_by_row_width() {
+        let mut t = table(true);
+        t.set_filtered_data(row_values(&["k1", "a", "b", "c", "k2", "d", "e", "f"]));
+        assert_eq!(t.data_len(), 2);
+        let row = t.get_row(1).unwrap();
+        assert_eq!(row_text(&row), ["k2", "d", "e", "f"]);
+        assert!(t.get_row(2).is_none());
+    }
+
+    #[test]
+    fn test_insert_command_skips_null_values() {
+        let mut t = table(false);
+        t.encodings = ColumnEncodings::new([
+            ValueEncoding::Text,
+            ValueEncoding::Text,
+            ValueEncoding::Binary,
+        ]);
+        let cmd = t
+            .insert_command(
+                "user:1",
+                &strings(&["ann", "NULL", "\\x00ff"]),
+                InsertMode::Overwrite,
+            )
+            .unwrap();
+        let args = cmd_arg_bytes(&cmd);
+        assert_eq!(
+            args,
+            vec![
+                b"HSET".to_vec(),
+                b"user:1".to_vec(),
+                b"name".to_vec(),
+                b"ann".to_vec(),
+                b"plan".to_vec(),
+                vec![0x00, 0xff],
+            ]
+        );
+        assert!(t
+            .insert_command(
+                "user:1",
+                &strings(&["NULL", "NULL", "NULL"]),
+                InsertMode::Nx
+            )
+            .is_none());
+    }
+}
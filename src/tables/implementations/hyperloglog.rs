@@ -0,0 +1,337 @@
+use std::borrow::Cow;
+
+use crate::{
+    query::{
+        limit::LimitOffsetInfo,
+        pushdown_types::{ComparisonOperator, PushableCondition},
+    },
+    tables::{
+        interface::RedisTableOperations,
+        types::{DataSet, InsertMode, LoadDataResult, RowValue, RowVec},
+    },
+};
+
+/// PFADD only when the key exists, reporting whether it did.
+const PFADD_XX_SCRIPT: &str = "if redis.call('EXISTS', KEYS[1]) == 1 then \
+     redis.call('PFADD', KEYS[1], unpack(ARGV)) return 1 end return 0";
+
+/// Redis HyperLogLog table type: each key is one row of `(approx_count)`,
+/// its PFCOUNT, with an optional write-only `element` column that INSERT
+/// adds with PFADD and that always reads as NULL.
+///
+/// Keys whose estimate is 0 (absent or empty) and keys that hold some other
+/// string have no row.
+#[derive(Debug, Clone, Default)]
+pub struct RedisHyperLogLogTable {
+    /// Rows as flat `[key?, approx_count, element?]` chunks
+    pub dataset: DataSet,
+    /// Whether rows start with the Redis key (multi-key mode)
+    pub key_column: bool,
+    /// Whether the table has the `element` column
+    pub element_column: bool,
+}
+
+impl RedisHyperLogLogTable {
+    pub fn new() -> Self {
+        Self {
+            dataset: DataSet::Empty,
+            key_column: false,
+            element_column: false,
+        }
+    }
+
+    /// Number of values in one row.
+    fn row_width(&self) -> usize {
+        usize::from(self.key_column) + 1 + usize::from(self.element_column)
+    }
+
+    /// Write command for one row of `[approx_count, element?]`: PFADD of the
+    /// element, or of nothing (creating an empty HyperLogLog) when it is
+    /// NULL or missing. The count is ignored. NX counts the row as written
+    /// only when PFADD reports a changed estimate, i.e. the element was
+    /// probably new; XX adds only to an existing key.
+    pub fn insert_command(&self, key: &str, data: &[String], mode: InsertMode) -> redis::Cmd {
+        let element = data.get(1).filter(|e| e.as_str() != "NULL");
+        let mut cmd = match mode {
+            InsertMode::Overwrite | InsertMode::Nx => {
+                let mut cmd = redis::cmd("PFADD");
+                cmd.arg(key);
+                cmd
+            }
+            InsertMode::Xx => {
+                let mut cmd = redis::cmd("EVAL");
+                cmd.arg(PFADD_XX_SCRIPT).arg(1).arg(key);
+                cmd
+            }
+        };
+        if let Some(element) = element {
+            cmd.arg(element);
+        }
+        cmd
+    }
+
+    /// The row of `key` with estimate `count`.
+    fn row(&self, key: &str, count: i64) -> Vec<RowValue> {
+        let mut row = Vec::with_capacity(self.row_width());
+        if self.key_column {
+            row.push(Some(key.as_bytes().to_vec()));
+        }
+        row.push(Some(count.to_string().into_bytes()));
+        if self.element_column {
+            row.push(None);
+        }
+        row
+    }
+
+    /// The rows of `keys`, one pipelined PFCOUNT per key. A key that is no
+    /// HyperLogLog fails its PFCOUNT alone and has no row.
+    fn fetch_rows(
+        &self,
+        conn: &mut dyn redis::ConnectionLike,
+        keys: &[String],
+    ) -> Result<Vec<Option<Vec<RowValue>>>, redis::RedisError> {
+        if keys.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut pipe = redis::pipe();
+        pipe.ignore_errors();
+        for key in keys {
+            pipe.cmd("PFCOUNT").arg(key);
+        }
+        let counts: Vec<redis::RedisResult<i64>> = pipe.query(conn)?;
+        Ok(keys
+            .iter()
+            .zip(counts)
+            .map(|(key, count)| match count {
+                Ok(count) if count > 0 => Some(self.row(key, count)),
+                _ => None,
+            })
+            .collect())
+    }
+}
+
+impl RedisTableOperations for RedisHyperLogLogTable {
+    fn load_data(
+        &mut self,
+        conn: &mut dyn redis::ConnectionLike,
+        key_prefix: &str,
+        _conditions: Option<&[PushableCondition]>,
+        limit_offset: &LimitOffsetInfo,
+    ) -> Result<LoadDataResult, redis::RedisError> {
+        // A single key is at most one row; PostgreSQL applies the quals.
+        let row = if limit_offset.limit == Some(0) {
+            None
+        } else {
+            self.fetch_rows(conn, &[key_prefix.to_string()])?
+                .pop()
+                .flatten()
+        };
+        match row {
+            Some(row) => {
+                self.dataset = DataSet::Filtered(row);
+                Ok(LoadDataResult::FullyLoaded)
+            }
+            None => {
+                self.dataset = DataSet::Empty;
+                Ok(LoadDataResult::Empty)
+            }
+        }
+    }
+
+    fn load_batch(
+        &mut self,
+        conn: &mut dyn redis::ConnectionLike,
+        key_prefix: &str,
+        _cursor: u64,
+        _batch_size: usize,
+        conditions: Option<&[PushableCondition]>,
+    ) -> Result<(u64, usize), redis::RedisError> {
+        self.load_data(conn, key_prefix, conditions, &LimitOffsetInfo::default())?;
+        Ok((0, self.data_len()))
+    }
+
+    fn get_dataset(&self) -> &DataSet {
+        &self.dataset
+    }
+
+    #[inline]
+    fn get_row(&self, index: usize) -> Option<RowVec<'_>> {
+        let width = self.row_width();
+        let data = self.dataset.as_filtered()?;
+        let start = index.checked_mul(width)?;
+        data.get(start..start + width).map(|row| {
+            row.iter()
+                .map(|v| v.as_deref().map(Cow::Borrowed))
+                .collect()
+        })
+    }
+
+    #[inline]
+    fn data_len(&self) -> usize {
+        match &self.dataset {
+            DataSet::Filtered(data) => data.len() / self.row_width(),
+            _ => 0,
+        }
+    }
+
+    fn insert(
+        &mut self,
+        conn: &mut dyn redis::ConnectionLike,
+        key_prefix: &str,
+        data: &[String],
+        mode: InsertMode,
+    ) -> Result<bool, redis::RedisError> {
+        let reply: redis::Value = self.insert_command(key_prefix, data, mode).query(conn)?;
+        Ok(mode == InsertMode::Overwrite || InsertMode::reply_applied(&reply))
+    }
+
+    /// Elements cannot be taken out of a HyperLogLog: DELETE drops the key.
+    fn delete(
+        &mut self,
+        conn: &mut dyn redis::ConnectionLike,
+        key_prefix: &str,
+        _data: &[String],
+    ) -> Result<(), redis::RedisError> {
+        let _: () = redis::cmd("DEL").arg(key_prefix).query(conn)?;
+        Ok(())
+    }
+
+    /// PFADD of the new element, if any. In multi-key mode `old_data[0]` is
+    /// the old key, renamed first when the key column changed.
+    fn update(
+        &mut self,
+        conn: &mut dyn redis::ConnectionLike,
+        key_prefix: &str,
+        old_data: &[String],
+        new_data: &[String],
+    ) -> Result<(), redis::RedisError> {
+        let mut pipe = redis::pipe();
+        pipe.atomic();
+        if let Some(old_key) = old_data
+            .first()
+            .filter(|k| self.key_column && *k != key_prefix)
+        {
+            pipe.cmd("RENAME").arg(old_key).arg(key_prefix).ignore();
+        }
+        if let Some(element) = new_data.get(1).filter(|e| e.as_str() != "NULL") {
+            pipe.cmd("PFADD").arg(key_prefix).arg(element).ignore();
+        }
+        pipe.query::<()>(conn)
+    }
+
+    /// Only conditions on the key column narrow anything: the multi-key scan
+    /// turns them into direct key lookups or a narrowed SCAN.
+    fn supports_pushdown(&self, operator: &ComparisonOperator) -> bool {
+        matches!(
+            operator,
+            ComparisonOperator::Equal | ComparisonOperator::In | ComparisonOperator::Like
+        )
+    }
+
+    fn configure(
+        &mut self,
+        column_names: &[String],
+        _pushdown_column_index: usize,
+        _score_column_index: Option<usize>,
+    ) {
+        self.element_column = column_names.len() > usize::from(self.key_column) + 1;
+    }
+
+    fn load_multi_key_data(
+        &mut self,
+        conn: &mut dyn redis::ConnectionLike,
+        keys: &[String],
+    ) -> Result<Vec<RowValue>, redis::RedisError> {
+        Ok(self
+            .fetch_rows(conn, keys)?
+            .into_iter()
+            .flatten()
+            .flatten()
+            .collect())
+    }
+
+    fn clear(&mut self) {
+        self.dataset = DataSet::default();
+    }
+
+    fn redis_type_name(&self) -> &'static str {
+        "string"
+    }
+
+    fn set_filtered_data(&mut self, data: Vec<RowValue>) {
+        self.dataset = DataSet::Filtered(data);
+    }
+
+    fn multi_key_columns_per_row(&self) -> usize {
+        1 + 1 + usize::from(self.element_column)
+    }
+
+    /// One pipelined PFCOUNT per param, each a key.
+    fn batch_parameterized_lookup(
+        &mut self,
+        conn: &mut dyn redis::ConnectionLike,
+        _key_prefix: &str,
+        params: &[String],
+    ) -> Result<Vec<Option<Vec<RowValue>>>, redis::RedisError> {
+        self.fetch_rows(conn, params)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::utils::cmd_args;
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn table(key_column: bool, columns: &[&str]) -> RedisHyperLogLogTable {
+        let mut t = RedisHyperLogLogTable::new();
+        t.key_column = key_column;
+        t.configure(&strings(columns), 0, None);
+        t
+    }
+
+    #[test]
+    fn test_configure_detects_element_column() {
+        assert!(!table(false, &["visitors"]).element_column);
+        assert!(table(false, &["visitors", "visitor"]).element_column);
+        assert!(!table(true, &["page", "visitors"]).element_column);
+        let t = table(true, &["page", "visitors", "visitor"]);
+        assert!(t.element_column);
+        assert_eq!(t.multi_key_columns_per_row(), 3);
+    }
+
+    #[test]
+    fn test_insert_command() {
+        let t = table(false, &["visitors", "visitor"]);
+        let cmd = t.insert_command("hll", &strings(&["NULL", "alice"]), InsertMode::Overwrite);
+        assert_eq!(cmd_args(&cmd), strings(&["PFADD", "hll", "alice"]));
+
+        let cmd = t.insert_command("hll", &strings(&["NULL", "NULL"]), InsertMode::Nx);
+        assert_eq!(cmd_args(&cmd), strings(&["PFADD", "hll"]));
+
+        let cmd = t.insert_command("hll", &strings(&["NULL", "bob"]), InsertMode::Xx);
+        assert_eq!(cmd_args(&cmd)[0], "EVAL");
+        assert_eq!(cmd_args(&cmd)[2..], strings(&["1", "hll", "bob"]));
+    }
+
+    #[test]
+    fn test_rows_with_key_and_element_columns() {
+        let mut t = table(true, &["page", "visitors", "visitor"]);
+        let mut rows = t.row("hll:page:home", 42);
+        rows.extend(t.row("hll:page:about", 7));
+        t.set_filtered_data(rows);
+        assert_eq!(t.data_len(), 2);
+        let row = t.get_row(1).unwrap();
+        assert_eq!(
+            row.as_slice(),
+            [
+                Some(b"hll:page:about".as_slice().into()),
+                Some(b"7".as_slice().into()),
+                None
+            ]
+        );
+    }
+}
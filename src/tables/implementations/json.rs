@@ -0,0 +1,550 @@
+use std::{borrow::Cow, collections::HashMap};
+
+use serde_json::value::RawValue;
+
+use crate::{
+    query::{
+        limit::LimitOffsetInfo,
+        pushdown_types::{ComparisonOperator, PushableCondition},
+    },
+    tables::{
+        interface::RedisTableOperations,
+        types::{
+            hex_to_bytes, ColumnEncodings, DataSet, InsertMode, LoadDataResult, RowValue, RowVec,
+            ValueEncoding,
+        },
+    },
+};
+
+/// Create the document `ARGV[2]` unless ARGV[1] ('nx', 'xx' or
+/// 'overwrite') rules the key out, then JSON.SET each path/value pair that
+/// follows.
+const JSON_INSERT_SCRIPT: &str = "local exists = redis.call('EXISTS', KEYS[1]) == 1 \
+     if (ARGV[1] == 'nx' and exists) or (ARGV[1] == 'xx' and not exists) then return 0 end \
+     redis.call('JSON.SET', KEYS[1], '$', ARGV[2]) \
+     for i = 3, #ARGV, 2 do redis.call('JSON.SET', KEYS[1], ARGV[i], ARGV[i + 1]) end \
+     return 1";
+
+/// JSONPath of the whole document.
+pub const ROOT_PATH: &str = "$";
+
+/// JSONPath a column maps to by default: `$.name`, or `$["name"]` when the
+/// name is not a plain identifier.
+pub fn default_json_path(column_name: &str) -> String {
+    let plain = column_name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && column_name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if plain {
+        format!("$.{}", column_name)
+    } else {
+        format!(
+            "$[{}]",
+            serde_json::to_string(column_name).unwrap_or_default()
+        )
+    }
+}
+
+fn json_error(e: serde_json::Error) -> redis::RedisError {
+    redis::RedisError::from((
+        redis::ErrorKind::Parse,
+        "Invalid RedisJSON reply",
+        e.to_string(),
+    ))
+}
+
+/// RedisJSON table type: each key is one document and one row, and each data
+/// column is the first match of a JSONPath in that document.
+///
+/// `json`/`jsonb`, numeric and boolean columns ([`ValueEncoding::Json`])
+/// carry the matched JSON value as-is; other columns read a JSON string as
+/// its contents and write their value as a JSON string.
+#[derive(Debug, Clone, Default)]
+pub struct RedisJsonTable {
+    /// Rows as flat `[key?, value, ...]` chunks of [`Self::row_width`]
+    pub dataset: DataSet,
+    /// JSONPath of each data column, in column order
+    pub paths: Vec<String>,
+    /// Whether rows start with the Redis key (multi-key mode)
+    pub key_column: bool,
+    /// Encodings of the data columns, in `paths` order
+    pub encodings: ColumnEncodings,
+}
+
+impl RedisJsonTable {
+    pub fn new() -> Self {
+        Self {
+            dataset: DataSet::Empty,
+            paths: Vec::new(),
+            key_column: false,
+            encodings: ColumnEncodings::default(),
+        }
+    }
+
+    /// Number of values in one row: the paths plus the key column.
+    fn row_width(&self) -> usize {
+        self.paths.len() + usize::from(self.key_column)
+    }
+
+    /// JSON text to store for a row value of data column `column`.
+    fn json_value(&self, column: usize, value: &str) -> String {
+        match self.encodings.get(column) {
+            ValueEncoding::Json => value.to_string(),
+            ValueEncoding::Text | ValueEncoding::Binary => {
+                serde_json::to_string(value).unwrap_or_default()
+            }
+        }
+    }
+
+    /// Row value of data column `column` for the first match of its path.
+    /// A `bytea` column stores its value as a JSON string in hex form.
+    fn row_value(
+        &self,
+        column: usize,
+        first: Option<&RawValue>,
+    ) -> Result<RowValue, redis::RedisError> {
+        let Some(raw) = first.map(RawValue::get).filter(|raw| *raw != "null") else {
+            return Ok(None);
+        };
+        let encoding = self.encodings.get(column);
+        if encoding != ValueEncoding::Json && raw.starts_with('"') {
+            let value = serde_json::from_str::<String>(raw).map_err(json_error)?;
+            if encoding == ValueEncoding::Binary {
+                if let Some(bytes) = hex_to_bytes(&value) {
+                    return Ok(Some(bytes));
+                }
+            }
+            return Ok(Some(value.into_bytes()));
+        }
+        Ok(Some(raw.as_bytes().to_vec()))
+    }
+
+    /// Write command for one row: a script that creates the document from
+    /// the root column (or `{}`) and then sets each non-NULL path column.
+    /// NX/XX skip the row when the key is present/absent.
+    pub fn insert_command(
+        &self,
+        key: &str,
+        data: &[String],
+        mode: InsertMode,
+    ) -> Option<redis::Cmd> {
+        let mode_arg = match mode {
+            InsertMode::Overwrite => "overwrite",
+            InsertMode::Nx => "nx",
+            InsertMode::Xx => "xx",
+        };
+        let values = || {
+            self.paths
+                .iter()
+                .zip(data)
+                .enumerate()
+                .filter(|(_, (_, value))| value.as_str() != "NULL")
+        };
+        let document = values()
+            .find(|(_, (path, _))| path.as_str() == ROOT_PATH)
+            .map_or_else(
+                || "{}".to_string(),
+                |(i, (_, value))| self.json_value(i, value),
+            );
+        let mut cmd = redis::cmd("EVAL");
+        cmd.arg(JSON_INSERT_SCRIPT)
+            .arg(1)
+            .arg(key)
+            .arg(mode_arg)
+            .arg(document);
+        for (i, (path, value)) in values().filter(|(_, (path, _))| path.as_str() != ROOT_PATH) {
+            cmd.arg(path).arg(self.json_value(i, value));
+        }
+        Some(cmd)
+    }
+
+    /// One row from a JSON.GET reply, `None` when the key does not exist.
+    /// Paths without a match are NULL.
+    fn decode_row(
+        &self,
+        key: &str,
+        reply: Option<String>,
+    ) -> Result<Option<Vec<RowValue>>, redis::RedisError> {
+        let Some(reply) = reply else {
+            return Ok(None);
+        };
+        // One path replies with its array of matches, several with an
+        // object of match arrays keyed by path.
+        let firsts: Vec<Option<&RawValue>> = if self.paths.len() == 1 {
+            let matches: Vec<&RawValue> = serde_json::from_str(&reply).map_err(json_error)?;
+            vec![matches.first().copied()]
+        } else {
+            let by_path: HashMap<String, Vec<&RawValue>> =
+                serde_json::from_str(&reply).map_err(json_error)?;
+            self.paths
+                .iter()
+                .map(|path| by_path.get(path).and_then(|m| m.first().copied()))
+                .collect()
+        };
+        let mut row = Vec::with_capacity(self.row_width());
+        if self.key_column {
+            row.push(Some(key.as_bytes().to_vec()));
+        }
+        for (i, first) in firsts.into_iter().enumerate() {
+            row.push(self.row_value(i, first)?);
+        }
+        Ok(Some(row))
+    }
+
+    /// The rows of `keys`: one pipelined JSON.GET of every path per key.
+    /// JSON.MGET would need all keys in one cluster slot.
+    fn fetch_rows(
+        &self,
+        conn: &mut dyn redis::ConnectionLike,
+        keys: &[String],
+    ) -> Result<Vec<Option<Vec<RowValue>>>, redis::RedisError> {
+        if keys.is_empty() || self.paths.is_empty() {
+            return Ok(vec![None; keys.len()]);
+        }
+        let mut pipe = redis::pipe();
+        for key in keys {
+            pipe.cmd("JSON.GET").arg(key).arg(self.paths.as_slice());
+        }
+        let replies: Vec<Option<String>> = pipe.query(conn)?;
+        keys.iter()
+            .zip(replies)
+            .map(|(key, reply)| self.decode_row(key, reply))
+            .collect()
+    }
+}
+
+impl RedisTableOperations for RedisJsonTable {
+    fn load_data(
+        &mut self,
+        conn: &mut dyn redis::ConnectionLike,
+        key_prefix: &str,
+        _conditions: Option<&[PushableCondition]>,
+        _limit_offset: &LimitOffsetInfo,
+    ) -> Result<LoadDataResult, redis::RedisError> {
+        // A single key is at most one row; PostgreSQL applies the quals.
+        let row = self
+            .fetch_rows(conn, &[key_prefix.to_string()])?
+            .pop()
+            .flatten();
+        match row {
+            Some(row) => {
+                self.dataset = DataSet::Filtered(row);
+                Ok(LoadDataResult::FullyLoaded)
+            }
+            None => {
+                self.dataset = DataSet::Empty;
+                Ok(LoadDataResult::Empty)
+            }
+        }
+    }
+
+    fn load_batch(
+        &mut self,
+        conn: &mut dyn redis::ConnectionLike,
+        key_prefix: &str,
+        _cursor: u64,
+        _batch_size: usize,
+        conditions: Option<&[PushableCondition]>,
+    ) -> Result<(u64, usize), redis::RedisError> {
+        self.load_data(conn, key_prefix, conditions, &LimitOffsetInfo::default())?;
+        Ok((0, self.data_len()))
+    }
+
+    fn get_dataset(&self) -> &DataSet {
+        &self.dataset
+    }
+
+    #[inline]
+    fn get_row(&self, index: usize) -> Option<RowVec<'_>> {
+        let width = self.row_width();
+        let data = self.dataset.as_filtered()?;
+        let start = index.checked_mul(width)?;
+        data.get(start..start + width).map(|row| {
+            row.iter()
+                .map(|v| v.as_deref().map(Cow::Borrowed))
+                .collect()
+        })
+    }
+
+    #[inline]
+    fn data_len(&self) -> usize {
+        match &self.dataset {
+            DataSet::Filtered(data) => data.len().checked_div(self.row_width()).unwrap_or(0),
+            _ => 0,
+        }
+    }
+
+    fn insert(
+        &mut self,
+        conn: &mut dyn redis::ConnectionLike,
+        key_prefix: &str,
+        data: &[String],
+        mode: InsertMode,
+    ) -> Result<bool, redis::RedisError> {
+        let Some(cmd) = self.insert_command(key_prefix, data, mode) else {
+            return Ok(false);
+        };
+        let reply: redis::Value = cmd.query(conn)?;
+        Ok(InsertMode::reply_applied(&reply))
+    }
+
+    /// A row is the whole document: DEL the key.
+    fn delete(
+        &mut self,
+        conn: &mut dyn redis::ConnectionLike,
+        key_prefix: &str,
+        _data: &[String],
+    ) -> Result<(), redis::RedisError> {
+        redis::cmd("DEL").arg(key_prefix).query(conn)
+    }
+
+    /// JSON.SET of the non-NULL values, root document first so the path
+    /// columns apply on top of it, and JSON.DEL of the paths set to NULL. In
+    /// multi-key mode `old_data[0]` is the old key, renamed first when the
+    /// key column changed.
+    fn update(
+        &mut self,
+        conn: &mut dyn redis::ConnectionLike,
+        key_prefix: &str,
+        old_data: &[String],
+        new_data: &[String],
+    ) -> Result<(), redis::RedisError> {
+        let mut pipe = redis::pipe();
+        pipe.atomic();
+        if let Some(old_key) = old_data
+            .first()
+            .filter(|k| self.key_column && *k != key_prefix)
+        {
+            pipe.cmd("RENAME").arg(old_key).arg(key_prefix).ignore();
+        }
+        let mut columns: Vec<(usize, &String, &String)> = self
+            .paths
+            .iter()
+            .zip(new_data)
+            .enumerate()
+            .map(|(i, (path, value))| (i, path, value))
+            .collect();
+        columns.sort_by_key(|(_, path, _)| path.as_str() != ROOT_PATH);
+        for (i, path, value) in columns {
+            if value.as_str() != "NULL" {
+                pipe.cmd("JSON.SET")
+                    .arg(key_prefix)
+                    .arg(path)
+                    .arg(self.json_value(i, value))
+                    .ignore();
+            } else if path.as_str() != ROOT_PATH {
+                // A NULL document keeps the key; DELETE removes it.
+                pipe.cmd("JSON.DEL").arg(key_prefix).arg(path).ignore();
+            }
+        }
+        pipe.query::<()>(conn)
+    }
+
+    /// Only conditions on the key column narrow anything: the multi-key scan
+    /// turns them into direct key lookups or a narrowed SCAN.
+    fn supports_pushdown(&self, operator: &ComparisonOperator) -> bool {
+        matches!(
+            operator,
+            ComparisonOperator::Equal | ComparisonOperator::In | ComparisonOperator::Like
+        )
+    }
+
+    /// `column_names` carry the `path` option where one is set; other
+    /// columns map to [`default_json_path`].
+    fn configure(
+        &mut self,
+        column_names: &[String],
+        _pushdown_column_index: usize,
+        _score_column_index: Option<usize>,
+    ) {
+        let skip = usize::from(self.key_column).min(column_names.len());
+        self.paths = column_names[skip..]
+            .iter()
+            .map(|name| {
+                if name.starts_with(ROOT_PATH) {
+                    name.clone()
+                } else {
+                    default_json_path(name)
+                }
+            })
+            .collect();
+    }
+
+    fn set_column_encodings(&mut self, encodings: ColumnEncodings) {
+        self.encodings = encodings;
+    }
+
+    fn load_multi_key_data(
+        &mut self,
+        conn: &mut dyn redis::ConnectionLike,
+        keys: &[String],
+    ) -> Result<Vec<RowValue>, redis::RedisError> {
+        Ok(self
+            .fetch_rows(conn, keys)?
+            .into_iter()
+            .flatten()
+            .flatten()
+            .collect())
+    }
+
+    fn clear(&mut self) {
+        self.dataset = DataSet::default();
+    }
+
+    fn redis_type_name(&self) -> &'static str {
+        "ReJSON-RL"
+    }
+
+    fn set_filtered_data(&mut self, data: Vec<RowValue>) {
+        self.dataset = DataSet::Filtered(data);
+    }
+
+    fn multi_key_columns_per_row(&self) -> usize {
+        self.paths.len() + 1
+    }
+
+    fn batch_parameterized_lookup(
+        &mut self,
+        conn: &mut dyn redis::ConnectionLike,
+        _key_prefix: &str,
+        params: &[String],
+    ) -> Result<Vec<Option<Vec<RowValue>>>, redis::RedisError> {
+        self.fetch_rows(conn, params)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::utils::{cmd_args, row_values};
+
+    fn table(columns: &[&str], encodings: &[ValueEncoding]) -> RedisJsonTable {
+        let mut t = RedisJsonTable::new();
+        t.key_column = true;
+        let columns: Vec<String> = std::iter::once("key")
+            .chain(columns.iter().copied())
+            .map(|s| s.to_string())
+            .collect();
+        t.configure(&columns, 0, None);
+        t.set_column_encodings(ColumnEncodings::new(encodings.iter().copied()));
+        t
+    }
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_default_json_path() {
+        assert_eq!(default_json_path("name"), "$.name");
+        assert_eq!(default_json_path("_id2"), "$._id2");
+        assert_eq!(default_json_path("first name"), "$[\"first name\"]");
+        assert_eq!(default_json_path("2nd"), "$[\"2nd\"]");
+    }
+
+    #[test]
+    fn test_configure_keeps_explicit_paths() {
+        let t = table(&["name", "$.address.city", "$"], &[]);
+        assert_eq!(t.paths, strings(&["$.name", "$.address.city", "$"]));
+        assert_eq!(t.multi_key_columns_per_row(), 4);
+    }
+
+    #[test]
+    fn test_decode_row_single_path() {
+        let t = table(&["name"], &[ValueEncoding::Text]);
+        let row = t
+            .decode_row("user:1", Some(r#"["ann"]"#.to_string()))
+            .unwrap();
+        assert_eq!(row, Some(row_values(&["user:1", "ann"])));
+        let row = t.decode_row("user:2", Some("[]".to_string())).unwrap();
+        assert_eq!(row, Some(vec![Some(b"user:2".to_vec()), None]));
+        assert_eq!(t.decode_row("user:3", None).unwrap(), None);
+    }
+
+    #[test]
+    fn test_decode_row_multiple_paths() {
+        let t = table(
+            &["name", "age", "tags", "$"],
+            &[
+                ValueEncoding::Text,
+                ValueEncoding::Json,
+                ValueEncoding::Text,
+                ValueEncoding::Json,
+            ],
+        );
+        let reply = r#"{"$.name":["ann"],"$.age":[41],"$.tags":[["a","b"]],"$":[{"name":"ann","age":41,"tags":["a","b"]}]}"#;
+        let row = t.decode_row("user:1", Some(reply.to_string())).unwrap();
+        assert_eq!(
+            row,
+            Some(row_values(&[
+                "user:1",
+                "ann",
+                "41",
+                r#"["a","b"]"#,
+                r#"{"name":"ann","age":41,"tags":["a","b"]}"#,
+            ]))
+        );
+        let reply = r#"{"$.name":[null],"$.age":[],"$.tags":[],"$":[{}]}"#;
+        let row = t.decode_row("user:2", Some(reply.to_string())).unwrap();
+        assert_eq!(
+            row,
+            Some(vec![
+                Some(b"user:2".to_vec()),
+                None,
+                None,
+                None,
+                Some(b"{}".to_vec()),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_insert_command_quotes_text_values() {
+        let t = table(
+            &["name", "age", "nick"],
+            &[
+                ValueEncoding::Text,
+                ValueEncoding::Json,
+                ValueEncoding::Text,
+            ],
+        );
+        let cmd = t
+            .insert_command(
+                "user:1",
+                &strings(&["ann \"a\"", "41", "NULL"]),
+                InsertMode::Nx,
+            )
+            .unwrap();
+        assert_eq!(
+            cmd_args(&cmd)[3..],
+            strings(&[
+                "user:1",
+                "nx",
+                "{}",
+                "$.name",
+                r#""ann \"a\"""#,
+                "$.age",
+                "41"
+            ])
+        );
+    }
+
+    #[test]
+    fn test_insert_command_uses_root_column_as_document() {
+        let t = table(&["$", "name"], &[ValueEncoding::Json, ValueEncoding::Text]);
+        let cmd = t
+            .insert_command(
+                "user:1",
+                &strings(&[r#"{"age":41}"#, "ann"]),
+                InsertMode::Overwrite,
+            )
+            .unwrap();
+        assert_eq!(
+            cmd_args(&cmd)[3..],
+            strings(&["user:1", "overwrite", r#"{"age":41}"#, "$.name", r#""ann""#])
+        );
+    }
+}
@@ -0,0 +1,404 @@
+use std::borrow::Cow;
+
+use crate::{
+    query::{
+        limit::LimitOffsetInfo,
+        pushdown_types::{ComparisonOperator, PushableCondition},
+    },
+    tables::{
+        interface::RedisTableOperations,
+        types::{DataSet, InsertMode, LoadDataResult, RowValue, RowVec},
+    },
+};
+
+/// Key types SCAN can filter on with its TYPE argument.
+const KEY_TYPES: &[&str] = &["string", "list", "set", "zset", "hash", "stream"];
+
+/// Commands behind the columns after `redis_type`, up to `idle_seconds`;
+/// each takes the key last.
+const ATTRIBUTE_COMMANDS: &[&[&str]] = &[
+    &["PTTL"],
+    &["MEMORY", "USAGE"],
+    &["OBJECT", "ENCODING"],
+    &["OBJECT", "IDLETIME"],
+];
+
+/// Most columns a row can have: key, redis_type, ttl_ms, memory_bytes,
+/// encoding, idle_seconds, length.
+const MAX_WIDTH: usize = 2 + ATTRIBUTE_COMMANDS.len() + 1;
+
+/// Redis keyspace table type: one row per key matching `table_key_prefix`,
+/// laid out as `(key, redis_type[, ttl_ms[, memory_bytes[, encoding[,
+/// idle_seconds[, length]]]]])`. Only the declared columns are fetched, with
+/// one pipeline of TYPE/PTTL/MEMORY USAGE/OBJECT per batch of keys and
+/// another of the type-specific length commands.
+///
+/// `ttl_ms` is NULL for keys without an expiry, `length` for types without
+/// a length command, and `idle_seconds` when the server's maxmemory policy
+/// tracks frequency instead. DELETE unlinks keys; the table is read-only
+/// otherwise.
+#[derive(Debug, Clone, Default)]
+pub struct RedisKeyspaceTable {
+    /// Rows as flat chunks of `width` values
+    pub dataset: DataSet,
+    /// Number of columns declared, key included
+    pub width: usize,
+    /// Raw attribute index of the `redis_type` column
+    pub type_column_index: usize,
+    /// Key type SCAN filters on (None = all types)
+    pub type_filter: Option<&'static str>,
+}
+
+impl RedisKeyspaceTable {
+    pub fn new() -> Self {
+        Self {
+            dataset: DataSet::Empty,
+            width: 2,
+            type_column_index: 1,
+            type_filter: None,
+        }
+    }
+
+    /// The SCAN TYPE among `conditions`: an `=` on `redis_type` naming a
+    /// core key type. PostgreSQL rechecks the condition either way.
+    pub fn scan_type(&self, conditions: &[PushableCondition]) -> Option<&'static str> {
+        conditions
+            .iter()
+            .filter(|c| {
+                c.column_index == self.type_column_index && c.operator == ComparisonOperator::Equal
+            })
+            .find_map(|c| KEY_TYPES.iter().copied().find(|t| *t == c.value))
+    }
+
+    /// Filter the SCAN of a multi-key scan by the key type in `conditions`.
+    pub fn set_type_filter(&mut self, conditions: &[PushableCondition]) {
+        self.type_filter = self.scan_type(conditions);
+    }
+
+    /// Number of attribute commands (PTTL onwards) each key needs.
+    fn attribute_count(&self) -> usize {
+        self.width.saturating_sub(2).min(ATTRIBUTE_COMMANDS.len())
+    }
+
+    /// The rows of `keys`; keys that no longer exist have none.
+    fn fetch_rows(
+        &self,
+        conn: &mut dyn redis::ConnectionLike,
+        keys: &[String],
+    ) -> Result<Vec<Option<Vec<RowValue>>>, redis::RedisError> {
+        if keys.is_empty() {
+            return Ok(Vec::new());
+        }
+        let attributes = &ATTRIBUTE_COMMANDS[..self.attribute_count()];
+        let mut pipe = redis::pipe();
+        pipe.ignore_errors();
+        for key in keys {
+            pipe.cmd("TYPE").arg(key);
+            for command in attributes {
+                pipe.cmd(command[0]);
+                for arg in &command[1..] {
+                    pipe.arg(*arg);
+                }
+                pipe.arg(key);
+            }
+        }
+        let replies: Vec<redis::RedisResult<redis::Value>> = pipe.query(conn)?;
+
+        let mut rows: Vec<Option<Vec<RowValue>>> = keys
+            .iter()
+            .zip(replies.chunks(1 + attributes.len()))
+            .map(|(key, replies)| {
+                let key_type = value_text(&replies[0]).filter(|t| t != b"none")?;
+                let mut row = Vec::with_capacity(self.width);
+                row.push(Some(key.clone().into_bytes()));
+                row.push(Some(key_type));
+                for (i, reply) in replies[1..].iter().enumerate() {
+                    row.push(match (i, reply) {
+                        // PTTL: -2 = gone since TYPE, -1 = no expiry
+                        (0, Ok(redis::Value::Int(-2))) => return None,
+                        (0, Ok(redis::Value::Int(-1))) => None,
+                        _ => value_text(reply),
+                    });
+                }
+                Some(row)
+            })
+            .collect();
+
+        if self.width == MAX_WIDTH {
+            self.push_lengths(conn, &mut rows)?;
+        }
+        Ok(rows)
+    }
+
+    /// Append the `length` column to `rows`, with one pipelined length
+    /// command per row whose type has one.
+    fn push_lengths(
+        &self,
+        conn: &mut dyn redis::ConnectionLike,
+        rows: &mut [Option<Vec<RowValue>>],
+    ) -> Result<(), redis::RedisError> {
+        let mut pipe = redis::pipe();
+        pipe.ignore_errors();
+        let mut measured = Vec::new();
+        for (i, row) in rows.iter().enumerate() {
+            let Some(row) = row else { continue };
+            let key_type = row[1].as_deref().and_then(|t| std::str::from_utf8(t).ok());
+            if let Some(command) = key_type.and_then(length_command) {
+                pipe.cmd(command).arg(&row[0]);
+                measured.push(i);
+            }
+        }
+        let lengths: Vec<redis::RedisResult<redis::Value>> = if measured.is_empty() {
+            Vec::new()
+        } else {
+            pipe.query(conn)?
+        };
+        let mut lengths = measured.into_iter().zip(lengths).peekable();
+        for (i, row) in rows.iter_mut().enumerate() {
+            let Some(row) = row else { continue };
+            match lengths.next_if(|(j, _)| *j == i) {
+                Some((_, length)) => row.push(value_text(&length)),
+                None => row.push(None),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Length command of key type `key_type`: elements, fields, entries or, for
+/// strings, bytes.
+fn length_command(key_type: &str) -> Option<&'static str> {
+    match key_type {
+        "string" => Some("STRLEN"),
+        "list" => Some("LLEN"),
+        "set" => Some("SCARD"),
+        "zset" => Some("ZCARD"),
+        "hash" => Some("HLEN"),
+        "stream" => Some("XLEN"),
+        _ => None,
+    }
+}
+
+/// A pipelined reply as column text; errors and nil read as NULL.
+fn value_text(reply: &redis::RedisResult<redis::Value>) -> RowValue {
+    match reply {
+        Ok(redis::Value::Int(n)) => Some(n.to_string().into_bytes()),
+        Ok(redis::Value::SimpleString(s)) => Some(s.clone().into_bytes()),
+        Ok(redis::Value::BulkString(bytes)) => Some(bytes.clone()),
+        _ => None,
+    }
+}
+
+impl RedisTableOperations for RedisKeyspaceTable {
+    fn load_data(
+        &mut self,
+        conn: &mut dyn redis::ConnectionLike,
+        key_prefix: &str,
+        _conditions: Option<&[PushableCondition]>,
+        _limit_offset: &LimitOffsetInfo,
+    ) -> Result<LoadDataResult, redis::RedisError> {
+        match self
+            .fetch_rows(conn, &[key_prefix.to_string()])?
+            .pop()
+            .flatten()
+        {
+            Some(row) => {
+                self.dataset = DataSet::Filtered(row);
+                Ok(LoadDataResult::FullyLoaded)
+            }
+            None => {
+                self.dataset = DataSet::Empty;
+                Ok(LoadDataResult::Empty)
+            }
+        }
+    }
+
+    fn load_batch(
+        &mut self,
+        conn: &mut dyn redis::ConnectionLike,
+        key_prefix: &str,
+        _cursor: u64,
+        _batch_size: usize,
+        conditions: Option<&[PushableCondition]>,
+    ) -> Result<(u64, usize), redis::RedisError> {
+        self.load_data(conn, key_prefix, conditions, &LimitOffsetInfo::default())?;
+        Ok((0, self.data_len()))
+    }
+
+    fn get_dataset(&self) -> &DataSet {
+        &self.dataset
+    }
+
+    #[inline]
+    fn get_row(&self, index: usize) -> Option<RowVec<'_>> {
+        let data = self.dataset.as_filtered()?;
+        let start = index.checked_mul(self.width)?;
+        data.get(start..start + self.width).map(|row| {
+            row.iter()
+                .map(|v| v.as_deref().map(Cow::Borrowed))
+                .collect()
+        })
+    }
+
+    #[inline]
+    fn data_len(&self) -> usize {
+        match &self.dataset {
+            DataSet::Filtered(data) => data.len() / self.width,
+            _ => 0,
+        }
+    }
+
+    fn insert(
+        &mut self,
+        _conn: &mut dyn redis::ConnectionLike,
+        _key_prefix: &str,
+        _data: &[String],
+        _mode: InsertMode,
+    ) -> Result<bool, redis::RedisError> {
+        Err(redis::RedisError::from((
+            redis::ErrorKind::InvalidClientConfig,
+            "INSERT is not supported for keyspace tables (read the keys through their own table type)",
+        )))
+    }
+
+    /// UNLINK the key; its memory is reclaimed in the background.
+    fn delete(
+        &mut self,
+        conn: &mut dyn redis::ConnectionLike,
+        key_prefix: &str,
+        _data: &[String],
+    ) -> Result<(), redis::RedisError> {
+        let _: () = redis::cmd("UNLINK").arg(key_prefix).query(conn)?;
+        Ok(())
+    }
+
+    fn update(
+        &mut self,
+        _conn: &mut dyn redis::ConnectionLike,
+        _key_prefix: &str,
+        _old_data: &[String],
+        _new_data: &[String],
+    ) -> Result<(), redis::RedisError> {
+        Err(redis::RedisError::from((
+            redis::ErrorKind::InvalidClientConfig,
+            "UPDATE is not supported for keyspace tables",
+        )))
+    }
+
+    /// Conditions on the key column turn into direct key lookups or a
+    /// narrowed SCAN, `redis_type = '...'` into SCAN TYPE.
+    fn supports_pushdown(&self, operator: &ComparisonOperator) -> bool {
+        matches!(
+            operator,
+            ComparisonOperator::Equal | ComparisonOperator::In | ComparisonOperator::Like
+        )
+    }
+
+    fn configure(
+        &mut self,
+        column_names: &[String],
+        pushdown_column_index: usize,
+        _score_column_index: Option<usize>,
+    ) {
+        self.width = column_names.len().clamp(2, MAX_WIDTH);
+        self.type_column_index = pushdown_column_index;
+    }
+
+    fn load_multi_key_data(
+        &mut self,
+        conn: &mut dyn redis::ConnectionLike,
+        keys: &[String],
+    ) -> Result<Vec<RowValue>, redis::RedisError> {
+        Ok(self
+            .fetch_rows(conn, keys)?
+            .into_iter()
+            .flatten()
+            .flatten()
+            .collect())
+    }
+
+    fn clear(&mut self) {
+        self.dataset = DataSet::default();
+    }
+
+    /// Any type, unless `redis_type = '...'` narrowed the scan.
+    fn redis_type_name(&self) -> &'static str {
+        self.type_filter.unwrap_or("")
+    }
+
+    fn set_filtered_data(&mut self, data: Vec<RowValue>) {
+        self.dataset = DataSet::Filtered(data);
+    }
+
+    fn multi_key_columns_per_row(&self) -> usize {
+        self.width
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cond(column_index: usize, operator: ComparisonOperator, value: &str) -> PushableCondition {
+        PushableCondition {
+            column_index,
+            column_name: String::new(),
+            operator,
+            value: value.to_string(),
+        }
+    }
+
+    fn table(columns: usize) -> RedisKeyspaceTable {
+        let names: Vec<String> = (0..columns).map(|i| format!("c{i}")).collect();
+        let mut t = RedisKeyspaceTable::new();
+        t.configure(&names, 1, None);
+        t
+    }
+
+    #[test]
+    fn test_scan_type_from_redis_type_equality() {
+        let t = table(7);
+        assert_eq!(
+            t.scan_type(&[cond(1, ComparisonOperator::Equal, "hash")]),
+            Some("hash")
+        );
+        assert_eq!(
+            t.scan_type(&[cond(0, ComparisonOperator::Equal, "hash")]),
+            None
+        );
+        assert_eq!(
+            t.scan_type(&[cond(1, ComparisonOperator::Like, "h%")]),
+            None
+        );
+        // Module types are not filtered by SCAN; PostgreSQL filters them.
+        assert_eq!(
+            t.scan_type(&[cond(1, ComparisonOperator::Equal, "ReJSON-RL")]),
+            None
+        );
+    }
+
+    #[test]
+    fn test_width_follows_declared_columns() {
+        assert_eq!(table(2).attribute_count(), 0);
+        assert_eq!(table(4).attribute_count(), 2);
+        let t = table(7);
+        assert_eq!(t.attribute_count(), 4);
+        assert_eq!(t.multi_key_columns_per_row(), MAX_WIDTH);
+    }
+
+    #[test]
+    fn test_value_text_and_length_command() {
+        assert_eq!(value_text(&Ok(redis::Value::Int(42))), Some(b"42".to_vec()));
+        assert_eq!(
+            value_text(&Ok(redis::Value::SimpleString("hash".to_string()))),
+            Some(b"hash".to_vec())
+        );
+        assert_eq!(
+            value_text(&Ok(redis::Value::BulkString(b"listpack".to_vec()))),
+            Some(b"listpack".to_vec())
+        );
+        assert_eq!(value_text(&Ok(redis::Value::Nil)), None);
+        assert_eq!(length_command("zset"), Some("ZCARD"));
+        assert_eq!(length_command("ReJSON-RL"), None);
+    }
+}
@@ -6,7 +6,10 @@ use crate::{
     },
     tables::{
         interface::RedisTableOperations,
-        types::{DataContainer, DataSet, LoadDataResult, RowVec},
+        types::{
+            ColumnEncodings, DataContainer, DataSet, InsertMode, LoadDataResult, RowValue, RowVec,
+            ValueEncoding,
+        },
     },
 };
 use smallvec::smallvec;
@@ -17,6 +20,8 @@ use std::borrow::Cow;
 pub struct RedisListTable {
     pub dataset: DataSet,
     pub include_index: bool,
+    /// Encodings of the data columns; the element column is the last one
+    pub encodings: ColumnEncodings,
 }
 
 impl RedisListTable {
@@ -24,9 +29,47 @@ impl RedisListTable {
         Self {
             dataset: DataSet::Empty,
             include_index: false,
+            encodings: ColumnEncodings::default(),
         }
     }
 
+    /// Write command for one row of values. Lists have no conflict target,
+    /// so NX appends like overwrite; XX appends only to an existing list
+    /// (RPUSHX).
+    pub fn insert_command(
+        &self,
+        key: &str,
+        data: &[String],
+        mode: InsertMode,
+    ) -> Option<redis::Cmd> {
+        if data.is_empty() {
+            return None;
+        }
+        let mut cmd = redis::cmd(match mode {
+            InsertMode::Overwrite | InsertMode::Nx => "RPUSH",
+            InsertMode::Xx => "RPUSHX",
+        });
+        cmd.arg(key);
+        for element in data {
+            cmd.arg(self.element_encoding().encode(element));
+        }
+        Some(cmd)
+    }
+
+    /// Encoding of the element column, which follows the index column when
+    /// the table has one.
+    fn element_encoding(&self) -> ValueEncoding {
+        self.encodings.last()
+    }
+
+    fn decode_elements(&self, items: Vec<Vec<u8>>) -> Result<Vec<Vec<u8>>, redis::RedisError> {
+        let encoding = self.element_encoding();
+        items
+            .into_iter()
+            .map(|item| encoding.decode(item))
+            .collect()
+    }
+
     fn load_with_pattern_optimization(
         &mut self,
         conn: &mut dyn redis::ConnectionLike,
@@ -35,11 +78,13 @@ impl RedisListTable {
         _limit_offset: &LimitOffsetInfo,
     ) -> Result<LoadDataResult, redis::RedisError> {
         // Load all list data first since Redis doesn't have LSCAN
-        let all_data: Vec<String> = redis::cmd("LRANGE")
+        let raw: Vec<Vec<u8>> = redis::cmd("LRANGE")
             .arg(key_prefix)
             .arg(0)
             .arg(-1)
             .query(conn)?;
+        let all_data = self.decode_elements(raw)?;
+        let encoding = self.element_encoding();
 
         // Apply pattern filtering on the client side
         let mut filtered_data = Vec::new();
@@ -52,7 +97,7 @@ impl RedisListTable {
                 match &condition.operator {
                     ComparisonOperator::Like => {
                         if let Some(matcher) = &scan_conditions.pattern_matcher {
-                            if !matcher.matches(&item) {
+                            if !matcher.matches(&String::from_utf8_lossy(&item)) {
                                 matches = false;
                                 break;
                             }
@@ -69,7 +114,7 @@ impl RedisListTable {
             for condition in &scan_conditions.exact_conditions {
                 match &condition.operator {
                     ComparisonOperator::Equal => {
-                        if item != condition.value {
+                        if *item != *encoding.encode(&condition.value) {
                             matches = false;
                             break;
                         }
@@ -82,7 +127,7 @@ impl RedisListTable {
             }
 
             if matches {
-                filtered_data.push(item);
+                filtered_data.push(Some(item));
             }
         }
 
@@ -125,26 +170,36 @@ impl RedisTableOperations for RedisListTable {
                         ComparisonOperator::Equal | ComparisonOperator::In
                     ) {
                         // For lists, load all data and filter by value
-                        let all_data: Vec<String> = redis::cmd("LRANGE")
+                        let raw: Vec<Vec<u8>> = redis::cmd("LRANGE")
                             .arg(key_prefix)
                             .arg(0)
                             .arg(-1)
                             .query(conn)?;
+                        let all_data = self.decode_elements(raw)?;
+                        let encoding = self.element_encoding();
 
-                        let filtered: Vec<String> = match condition.operator {
-                            ComparisonOperator::Equal => all_data
-                                .into_iter()
-                                .filter(|item| item == &condition.value)
-                                .collect(),
+                        let filtered: Vec<RowValue> = match condition.operator {
+                            ComparisonOperator::Equal => {
+                                let value = encoding.encode(&condition.value);
+                                all_data
+                                    .into_iter()
+                                    .filter(|item| *item == *value)
+                                    .map(Some)
+                                    .collect()
+                            }
                             ComparisonOperator::In => {
-                                let values: std::collections::HashSet<&str> =
-                                    condition.value.split(',').collect();
+                                let values: std::collections::HashSet<Cow<[u8]>> = condition
+                                    .value
+                                    .split(',')
+                                    .map(|v| encoding.encode(v))
+                                    .collect();
                                 all_data
                                     .into_iter()
-                                    .filter(|item| values.contains(item.as_str()))
+                                    .filter(|item| values.contains(item.as_slice()))
+                                    .map(Some)
                                     .collect()
                             }
-                            _ => all_data,
+                            _ => all_data.into_iter().map(Some).collect(),
                         };
 
                         return if filtered.is_empty() {
@@ -190,14 +245,15 @@ impl RedisTableOperations for RedisListTable {
             (0, -1)
         };
 
-        let data: Vec<String> = redis::cmd("LRANGE")
+        let raw: Vec<Vec<u8>> = redis::cmd("LRANGE")
             .arg(key_prefix)
             .arg(start)
             .arg(end)
             .query(conn)?;
+        let data = self.decode_elements(raw)?;
 
         if limit_offset.has_constraints() && !self.include_index {
-            self.dataset = DataSet::Filtered(data);
+            self.dataset = DataSet::Filtered(data.into_iter().map(Some).collect());
         } else {
             self.dataset = DataSet::Complete(DataContainer::List(data));
         }
@@ -212,10 +268,16 @@ impl RedisTableOperations for RedisListTable {
         if self.include_index {
             match &self.dataset {
                 DataSet::Complete(DataContainer::List(items)) => items.get(index).map(|item| {
-                    smallvec![Cow::Owned(index.to_string()), Cow::Borrowed(item.as_str())]
+                    smallvec![
+                        Some(Cow::Owned(index.to_string().into_bytes())),
+                        Some(Cow::Borrowed(item.as_slice())),
+                    ]
                 }),
                 DataSet::Filtered(items) => items.get(index).map(|item| {
-                    smallvec![Cow::Owned(index.to_string()), Cow::Borrowed(item.as_str())]
+                    smallvec![
+                        Some(Cow::Owned(index.to_string().into_bytes())),
+                        item.as_deref().map(Cow::Borrowed),
+                    ]
                 }),
                 _ => None,
             }
@@ -229,12 +291,13 @@ impl RedisTableOperations for RedisListTable {
         conn: &mut dyn redis::ConnectionLike,
         key_prefix: &str,
         data: &[String],
-    ) -> Result<(), redis::RedisError> {
-        if !data.is_empty() {
-            // Single RPUSH with all values (RPUSH supports multiple values)
-            let _: i32 = redis::cmd("RPUSH").arg(key_prefix).arg(data).query(conn)?;
-        }
-        Ok(())
+        mode: InsertMode,
+    ) -> Result<bool, redis::RedisError> {
+        let Some(cmd) = self.insert_command(key_prefix, data, mode) else {
+            return Ok(false);
+        };
+        let reply: redis::Value = cmd.query(conn)?;
+        Ok(mode == InsertMode::Overwrite || InsertMode::reply_applied(&reply))
     }
 
     fn delete(
@@ -249,7 +312,10 @@ impl RedisTableOperations for RedisListTable {
             let pipe_result: Result<Vec<i32>, _> = {
                 let mut pipe = redis::pipe();
                 for value in data {
-                    pipe.cmd("LREM").arg(key_prefix).arg(0).arg(value);
+                    pipe.cmd("LREM")
+                        .arg(key_prefix)
+                        .arg(0)
+                        .arg(self.element_encoding().encode(value));
                 }
                 pipe.query(conn)
             };
@@ -260,7 +326,7 @@ impl RedisTableOperations for RedisListTable {
                     let _: i32 = redis::cmd("LREM")
                         .arg(key_prefix)
                         .arg(0)
-                        .arg(value)
+                        .arg(self.element_encoding().encode(value))
                         .query(conn)?;
                 }
             }
@@ -280,9 +346,10 @@ impl RedisTableOperations for RedisListTable {
             if old_value == new_value {
                 return Ok(());
             }
+            let encoding = self.element_encoding();
             let positions: Vec<i64> = redis::cmd("LPOS")
                 .arg(key_prefix)
-                .arg(old_value)
+                .arg(encoding.encode(old_value))
                 .arg("COUNT")
                 .arg(1)
                 .query(conn)?;
@@ -291,7 +358,7 @@ impl RedisTableOperations for RedisListTable {
                 let _: () = redis::cmd("LSET")
                     .arg(key_prefix)
                     .arg(idx)
-                    .arg(new_value)
+                    .arg(encoding.encode(new_value))
                     .query(conn)?;
             }
         }
@@ -316,11 +383,12 @@ impl RedisTableOperations for RedisListTable {
         // Lists use offset-based pagination (cursor = offset index)
         let start = cursor as isize;
         let end = start + (batch_size as isize) - 1;
-        let data: Vec<String> = redis::cmd("LRANGE")
+        let raw: Vec<Vec<u8>> = redis::cmd("LRANGE")
             .arg(key_prefix)
             .arg(start)
             .arg(end)
             .query(conn)?;
+        let data = self.decode_elements(raw)?;
         let row_count = data.len();
         let new_cursor = if row_count < batch_size {
             0 // no more data
@@ -329,32 +397,33 @@ impl RedisTableOperations for RedisListTable {
         };
 
         // Apply conditions as client-side post-filter (no LSCAN in Redis)
-        let filtered: Vec<String> = if let Some(conds) = conditions {
+        let encoding = self.element_encoding();
+        let filtered: Vec<Vec<u8>> = if let Some(conds) = conditions {
             let like_matchers: Vec<(usize, PatternMatcher)> = conds
                 .iter()
                 .enumerate()
                 .filter(|(_, c)| c.operator == ComparisonOperator::Like)
                 .map(|(i, c)| (i, PatternMatcher::from_like_pattern(&c.value)))
                 .collect();
-            let in_sets: Vec<(usize, std::collections::HashSet<&str>)> = conds
+            let in_sets: Vec<(usize, std::collections::HashSet<Cow<[u8]>>)> = conds
                 .iter()
                 .enumerate()
                 .filter(|(_, c)| c.operator == ComparisonOperator::In)
-                .map(|(i, c)| (i, c.value.split(',').collect()))
+                .map(|(i, c)| (i, c.value.split(',').map(|v| encoding.encode(v)).collect()))
                 .collect();
             data.into_iter()
                 .filter(|item| {
                     conds.iter().enumerate().all(|(i, c)| match c.operator {
-                        ComparisonOperator::Equal => item == &c.value,
-                        ComparisonOperator::NotEqual => item != &c.value,
+                        ComparisonOperator::Equal => *item == *encoding.encode(&c.value),
+                        ComparisonOperator::NotEqual => *item != *encoding.encode(&c.value),
                         ComparisonOperator::Like => like_matchers
                             .iter()
                             .find(|(idx, _)| *idx == i)
-                            .is_some_and(|(_, m)| m.matches(item)),
+                            .is_some_and(|(_, m)| m.matches(&String::from_utf8_lossy(item))),
                         ComparisonOperator::In => in_sets
                             .iter()
                             .find(|(idx, _)| *idx == i)
-                            .is_some_and(|(_, set)| set.contains(item.as_str())),
+                            .is_some_and(|(_, set)| set.contains(item.as_slice())),
                         _ => true,
                     })
                 })
@@ -385,10 +454,10 @@ impl RedisTableOperations for RedisListTable {
         &mut self,
         conn: &mut dyn redis::ConnectionLike,
         keys: &[String],
-    ) -> Result<Vec<String>, redis::RedisError> {
+    ) -> Result<Vec<RowValue>, redis::RedisError> {
         const PER_KEY_WARN_THRESHOLD: usize = 200_000;
 
-        let pipe_result: Result<Vec<Vec<String>>, _> = {
+        let pipe_result: Result<Vec<Vec<Vec<u8>>>, _> = {
             let mut pipe = redis::pipe();
             for key in keys {
                 pipe.cmd("LRANGE").arg(key).arg(0i64).arg(-1i64);
@@ -401,7 +470,7 @@ impl RedisTableOperations for RedisListTable {
             Err(_) => {
                 let mut results = Vec::with_capacity(keys.len());
                 for key in keys {
-                    let r: Vec<String> = redis::cmd("LRANGE")
+                    let r: Vec<Vec<u8>> = redis::cmd("LRANGE")
                         .arg(key)
                         .arg(0i64)
                         .arg(-1i64)
@@ -422,9 +491,9 @@ impl RedisTableOperations for RedisListTable {
                     items.len()
                 );
             }
-            for item in items {
-                all_rows.push(key.clone());
-                all_rows.push(item);
+            for item in self.decode_elements(items)? {
+                all_rows.push(Some(key.clone().into_bytes()));
+                all_rows.push(Some(item));
             }
         }
         Ok(all_rows)
@@ -438,7 +507,11 @@ impl RedisTableOperations for RedisListTable {
         "list"
     }
 
-    fn set_filtered_data(&mut self, data: Vec<String>) {
+    fn set_filtered_data(&mut self, data: Vec<RowValue>) {
         self.dataset = DataSet::Filtered(data);
     }
+
+    fn set_column_encodings(&mut self, encodings: ColumnEncodings) {
+        self.encodings = encodings;
+    }
 }
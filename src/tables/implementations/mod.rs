@@ -2,16 +2,32 @@
 ///
 /// This module contains the actual implementations of different Redis data types
 /// as PostgreSQL foreign tables, with each data type having its own dedicated module.
+pub mod bitmap;
+pub mod function;
+pub mod geo;
 pub mod hash;
+pub mod hash_row;
+pub mod hyperloglog;
+pub mod json;
+pub mod keyspace;
 pub mod list;
+pub mod pubsub;
 pub mod set;
 pub mod stream;
 pub mod string;
 pub mod zset;
 
 // Re-export all table types for convenience
+pub use bitmap::RedisBitmapTable;
+pub use function::RedisFunctionTable;
+pub use geo::RedisGeoTable;
 pub use hash::RedisHashTable;
+pub use hash_row::RedisHashRowTable;
+pub use hyperloglog::RedisHyperLogLogTable;
+pub use json::RedisJsonTable;
+pub use keyspace::RedisKeyspaceTable;
 pub use list::RedisListTable;
+pub use pubsub::RedisPubSubTable;
 pub use set::RedisSetTable;
 pub use stream::RedisStreamTable;
 pub use string::RedisStringTable;
@@ -0,0 +1,193 @@
+use crate::{
+    query::{
+        limit::LimitOffsetInfo,
+        pushdown_types::{ComparisonOperator, PushableCondition},
+    },
+    tables::{
+        interface::RedisTableOperations,
+        types::{DataSet, InsertMode, LoadDataResult, RowValue},
+    },
+};
+
+/// Redis Pub/Sub table type: write-only, each INSERTed row of
+/// `(channel, message)`, or `(message)` with the `channel` table option,
+/// is published with PUBLISH, or SPUBLISH in cluster mode so the message
+/// stays on the shard that owns the channel.
+///
+/// Messages are not stored anywhere, so scans return no rows and UPDATE and
+/// DELETE are rejected.
+#[derive(Debug, Clone, Default)]
+pub struct RedisPubSubTable {
+    pub dataset: DataSet,
+    /// Channel every row goes to (`channel`); None = rows carry the channel
+    pub channel: Option<String>,
+    /// Whether to publish with SPUBLISH (cluster mode)
+    pub sharded: bool,
+}
+
+impl RedisPubSubTable {
+    pub fn new() -> Self {
+        Self {
+            dataset: DataSet::Empty,
+            channel: None,
+            sharded: false,
+        }
+    }
+
+    /// PUBLISH (or SPUBLISH) of one row of `[channel, message]`, or of
+    /// `[message]` to the fixed channel. None when the row lacks a channel
+    /// or message; a NULL message publishes an empty one.
+    pub fn publish_command(&self, data: &[String]) -> Option<redis::Cmd> {
+        let (channel, message) = match (&self.channel, data) {
+            (Some(channel), [message, ..]) => (channel.as_str(), message),
+            (None, [channel, message, ..]) if channel != "NULL" => (channel.as_str(), message),
+            _ => return None,
+        };
+        let mut cmd = redis::cmd(if self.sharded { "SPUBLISH" } else { "PUBLISH" });
+        cmd.arg(channel);
+        cmd.arg(if message == "NULL" {
+            ""
+        } else {
+            message.as_str()
+        });
+        Some(cmd)
+    }
+
+    fn unsupported(op: &str) -> redis::RedisError {
+        redis::RedisError::from((
+            redis::ErrorKind::InvalidClientConfig,
+            "operation is not supported for Redis Pub/Sub tables (messages are not stored)",
+            op.to_string(),
+        ))
+    }
+}
+
+impl RedisTableOperations for RedisPubSubTable {
+    fn load_data(
+        &mut self,
+        _conn: &mut dyn redis::ConnectionLike,
+        _key_prefix: &str,
+        _conditions: Option<&[PushableCondition]>,
+        _limit_offset: &LimitOffsetInfo,
+    ) -> Result<LoadDataResult, redis::RedisError> {
+        self.dataset = DataSet::Empty;
+        Ok(LoadDataResult::Empty)
+    }
+
+    fn load_batch(
+        &mut self,
+        _conn: &mut dyn redis::ConnectionLike,
+        _key_prefix: &str,
+        _cursor: u64,
+        _batch_size: usize,
+        _conditions: Option<&[PushableCondition]>,
+    ) -> Result<(u64, usize), redis::RedisError> {
+        self.dataset = DataSet::Empty;
+        Ok((0, 0))
+    }
+
+    fn get_dataset(&self) -> &DataSet {
+        &self.dataset
+    }
+
+    /// Publishing always happens; insert modes have nothing to compare
+    /// against, and a row without a channel is skipped.
+    fn insert(
+        &mut self,
+        conn: &mut dyn redis::ConnectionLike,
+        _key_prefix: &str,
+        data: &[String],
+        _mode: InsertMode,
+    ) -> Result<bool, redis::RedisError> {
+        match self.publish_command(data) {
+            Some(cmd) => {
+                let _: redis::Value = cmd.query(conn)?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    fn delete(
+        &mut self,
+        _conn: &mut dyn redis::ConnectionLike,
+        _key_prefix: &str,
+        _data: &[String],
+    ) -> Result<(), redis::RedisError> {
+        Err(Self::unsupported("DELETE"))
+    }
+
+    fn update(
+        &mut self,
+        _conn: &mut dyn redis::ConnectionLike,
+        _key_prefix: &str,
+        _old_data: &[String],
+        _new_data: &[String],
+    ) -> Result<(), redis::RedisError> {
+        Err(Self::unsupported("UPDATE"))
+    }
+
+    fn supports_pushdown(&self, _operator: &ComparisonOperator) -> bool {
+        false
+    }
+
+    fn configure(
+        &mut self,
+        _column_names: &[String],
+        _pushdown_column_index: usize,
+        _score_column_index: Option<usize>,
+    ) {
+    }
+
+    fn load_multi_key_data(
+        &mut self,
+        _conn: &mut dyn redis::ConnectionLike,
+        _keys: &[String],
+    ) -> Result<Vec<RowValue>, redis::RedisError> {
+        Ok(Vec::new())
+    }
+
+    fn clear(&mut self) {
+        self.dataset = DataSet::default();
+    }
+
+    /// Channels are no keys; there is nothing to SCAN for.
+    fn redis_type_name(&self) -> &'static str {
+        ""
+    }
+
+    fn set_filtered_data(&mut self, data: Vec<RowValue>) {
+        self.dataset = DataSet::Filtered(data);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::utils::cmd_args;
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_publish_command() {
+        let mut t = RedisPubSubTable::new();
+        let cmd = t.publish_command(&strings(&["news", "hello"])).unwrap();
+        assert_eq!(cmd_args(&cmd), strings(&["PUBLISH", "news", "hello"]));
+        assert!(t.publish_command(&strings(&["NULL", "hello"])).is_none());
+
+        t.sharded = true;
+        let cmd = t.publish_command(&strings(&["news", "NULL"])).unwrap();
+        assert_eq!(cmd_args(&cmd), strings(&["SPUBLISH", "news", ""]));
+    }
+
+    #[test]
+    fn test_publish_command_fixed_channel() {
+        let mut t = RedisPubSubTable::new();
+        t.channel = Some("alerts".to_string());
+        let cmd = t.publish_command(&strings(&["disk full"])).unwrap();
+        assert_eq!(cmd_args(&cmd), strings(&["PUBLISH", "alerts", "disk full"]));
+        assert!(t.publish_command(&[]).is_none());
+    }
+}
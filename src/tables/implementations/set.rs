@@ -6,31 +6,77 @@ use crate::{
     },
     tables::{
         interface::RedisTableOperations,
-        types::{DataSet, LoadDataResult},
+        types::{ColumnEncodings, DataSet, InsertMode, LoadDataResult, RowValue},
     },
 };
+use std::borrow::Cow;
+
 /// Redis Set table type
 #[derive(Debug, Clone, Default)]
 pub struct RedisSetTable {
     pub dataset: DataSet,
+    /// Encoding of the member column
+    pub encodings: ColumnEncodings,
 }
 
 impl RedisSetTable {
     pub fn new() -> Self {
         Self {
             dataset: DataSet::Empty,
+            encodings: ColumnEncodings::default(),
         }
     }
 
+    /// Write command for one row of members. SADD's added count tells NX
+    /// whether the member was new; XX has nothing to write for a set, so it
+    /// only checks membership with SISMEMBER.
+    pub fn insert_command(
+        &self,
+        key: &str,
+        data: &[String],
+        mode: InsertMode,
+    ) -> Option<redis::Cmd> {
+        let first = data.first()?;
+        let cmd = match mode {
+            InsertMode::Overwrite | InsertMode::Nx => {
+                let mut cmd = redis::cmd("SADD");
+                cmd.arg(key);
+                for member in data {
+                    cmd.arg(self.member_arg(member));
+                }
+                cmd
+            }
+            InsertMode::Xx => {
+                let mut cmd = redis::cmd("SISMEMBER");
+                cmd.arg(key).arg(self.member_arg(first));
+                cmd
+            }
+        };
+        Some(cmd)
+    }
+
+    /// Bytes sent to Redis for a member.
+    fn member_arg<'a>(&self, member: &'a str) -> Cow<'a, [u8]> {
+        self.encodings.get(0).encode(member)
+    }
+
+    fn decode_members(&self, members: Vec<Vec<u8>>) -> redis::RedisResult<Vec<Vec<u8>>> {
+        let encoding = self.encodings.get(0);
+        members.into_iter().map(|m| encoding.decode(m)).collect()
+    }
+
     fn match_equal(
         &self,
         conn: &mut dyn redis::ConnectionLike,
         key: &str,
         member: &str,
-    ) -> redis::RedisResult<Vec<String>> {
-        let exists: bool = redis::cmd("SISMEMBER").arg(key).arg(member).query(conn)?;
+    ) -> redis::RedisResult<Vec<Vec<u8>>> {
+        let exists: bool = redis::cmd("SISMEMBER")
+            .arg(key)
+            .arg(self.member_arg(member))
+            .query(conn)?;
         Ok(if exists {
-            vec![member.to_string()]
+            vec![self.member_arg(member).into_owned()]
         } else {
             vec![]
         })
@@ -41,22 +87,26 @@ impl RedisSetTable {
         conn: &mut dyn redis::ConnectionLike,
         key: &str,
         members: &[&str],
-    ) -> redis::RedisResult<Vec<String>> {
+    ) -> redis::RedisResult<Vec<Vec<u8>>> {
         if members.is_empty() {
             return Ok(vec![]);
         }
 
         // Try SMISMEMBER (Redis 6.2+) — single command, single round-trip
-        let smismember_result: Result<Vec<bool>, _> =
-            redis::cmd("SMISMEMBER").arg(key).arg(members).query(conn);
+        let mut smismember = redis::cmd("SMISMEMBER");
+        smismember.arg(key);
+        for m in members {
+            smismember.arg(self.member_arg(m));
+        }
+        let smismember_result: Result<Vec<bool>, _> = smismember.query(conn);
 
         match smismember_result {
             Ok(results) => {
-                let matched: Vec<String> = members
+                let matched: Vec<Vec<u8>> = members
                     .iter()
                     .zip(results.iter())
                     .filter(|(_, &exists)| exists)
-                    .map(|(&m, _)| m.to_string())
+                    .map(|(&m, _)| self.member_arg(m).into_owned())
                     .collect();
                 Ok(matched)
             }
@@ -65,18 +115,18 @@ impl RedisSetTable {
                 let pipe_result: Result<Vec<bool>, _> = {
                     let mut pipe = redis::pipe();
                     for m in members {
-                        pipe.cmd("SISMEMBER").arg(key).arg(*m);
+                        pipe.cmd("SISMEMBER").arg(key).arg(self.member_arg(m));
                     }
                     pipe.query(conn)
                 };
 
                 match pipe_result {
                     Ok(results) => {
-                        let matched: Vec<String> = members
+                        let matched: Vec<Vec<u8>> = members
                             .iter()
                             .zip(results.iter())
                             .filter(|(_, &exists)| exists)
-                            .map(|(&m, _)| m.to_string())
+                            .map(|(&m, _)| self.member_arg(m).into_owned())
                             .collect();
                         Ok(matched)
                     }
@@ -84,10 +134,12 @@ impl RedisSetTable {
                         // Final fallback: individual SISMEMBER commands (cluster mode)
                         let mut matched = Vec::new();
                         for m in members {
-                            let exists: bool =
-                                redis::cmd("SISMEMBER").arg(key).arg(*m).query(conn)?;
+                            let exists: bool = redis::cmd("SISMEMBER")
+                                .arg(key)
+                                .arg(self.member_arg(m))
+                                .query(conn)?;
                             if exists {
-                                matched.push(m.to_string());
+                                matched.push(self.member_arg(m).into_owned());
                             }
                         }
                         Ok(matched)
@@ -103,13 +155,14 @@ impl RedisSetTable {
         key: &str,
         pattern: &str,
         limit_offset: &LimitOffsetInfo,
-    ) -> redis::RedisResult<Vec<String>> {
+    ) -> redis::RedisResult<Vec<Vec<u8>>> {
         let matcher = PatternMatcher::from_like_pattern(pattern);
         if matcher.requires_scan() {
-            RedisScanBuilder::new_set_scan(key)
+            let members = RedisScanBuilder::new_set_scan(key)
                 .with_pattern(matcher.get_pattern())
                 .with_limit(limit_offset.clone())
-                .execute_all(conn)
+                .execute_all(conn)?;
+            self.decode_members(members)
         } else {
             self.match_equal(conn, key, pattern)
         }
@@ -119,8 +172,9 @@ impl RedisSetTable {
         &self,
         conn: &mut dyn redis::ConnectionLike,
         key: &str,
-    ) -> redis::RedisResult<Vec<String>> {
-        redis::cmd("SMEMBERS").arg(key).query(conn)
+    ) -> redis::RedisResult<Vec<Vec<u8>>> {
+        let members: Vec<Vec<u8>> = redis::cmd("SMEMBERS").arg(key).query(conn)?;
+        self.decode_members(members)
     }
 
     fn apply_conditions(
@@ -129,8 +183,8 @@ impl RedisSetTable {
         key: &str,
         conditions: &[PushableCondition],
         limit_offset: &LimitOffsetInfo,
-    ) -> redis::RedisResult<Vec<String>> {
-        let mut matched: Option<Vec<String>> = None;
+    ) -> redis::RedisResult<Vec<Vec<u8>>> {
+        let mut matched: Option<Vec<Vec<u8>>> = None;
 
         for cond in conditions {
             let matches = match cond.operator {
@@ -183,7 +237,7 @@ impl RedisTableOperations for RedisSetTable {
         self.dataset = if members.is_empty() {
             DataSet::Empty
         } else {
-            DataSet::Filtered(members)
+            DataSet::Filtered(members.into_iter().map(Some).collect())
         };
 
         Ok(if self.dataset.len() == 0 {
@@ -203,11 +257,13 @@ impl RedisTableOperations for RedisSetTable {
         conn: &mut dyn redis::ConnectionLike,
         key_prefix: &str,
         data: &[String],
-    ) -> Result<(), redis::RedisError> {
-        if !data.is_empty() {
-            let _added: i32 = redis::cmd("SADD").arg(key_prefix).arg(data).query(conn)?;
-        }
-        Ok(())
+        mode: InsertMode,
+    ) -> Result<bool, redis::RedisError> {
+        let Some(cmd) = self.insert_command(key_prefix, data, mode) else {
+            return Ok(false);
+        };
+        let reply: redis::Value = cmd.query(conn)?;
+        Ok(mode == InsertMode::Overwrite || InsertMode::reply_applied(&reply))
     }
 
     fn delete(
@@ -217,7 +273,12 @@ impl RedisTableOperations for RedisSetTable {
         data: &[String],
     ) -> Result<(), redis::RedisError> {
         if !data.is_empty() {
-            let _: i32 = redis::cmd("SREM").arg(key_prefix).arg(data).query(conn)?;
+            let mut cmd = redis::cmd("SREM");
+            cmd.arg(key_prefix);
+            for member in data {
+                cmd.arg(self.member_arg(member));
+            }
+            let _: i32 = cmd.query(conn)?;
         }
         Ok(())
     }
@@ -238,10 +299,10 @@ impl RedisTableOperations for RedisSetTable {
                 .atomic()
                 .cmd("SREM")
                 .arg(key_prefix)
-                .arg(old_member)
+                .arg(self.member_arg(old_member))
                 .cmd("SADD")
                 .arg(key_prefix)
-                .arg(new_member)
+                .arg(self.member_arg(new_member))
                 .query::<()>(conn)?;
         }
         Ok(())
@@ -280,12 +341,13 @@ impl RedisTableOperations for RedisSetTable {
         }
         cmd.arg("COUNT").arg(batch_size);
 
-        let (new_cursor, members): (u64, Vec<String>) = cmd.query(conn)?;
+        let (new_cursor, raw_members): (u64, Vec<Vec<u8>>) = cmd.query(conn)?;
+        let members = self.decode_members(raw_members)?;
 
         // Apply conditions as client-side post-filter
         // Note: Only the first LIKE condition was used for server-side MATCH;
         // all other conditions (including additional LIKEs) must be verified here
-        let filtered: Vec<String> = if let Some(conds) = conditions {
+        let filtered: Vec<Vec<u8>> = if let Some(conds) = conditions {
             if conds.is_empty() {
                 members
             } else {
@@ -308,11 +370,11 @@ impl RedisTableOperations for RedisSetTable {
                         )
                     })
                     .collect();
-                let in_value_sets: Vec<Vec<&str>> = conds
+                let in_value_sets: Vec<Vec<Cow<[u8]>>> = conds
                     .iter()
                     .map(|c| {
                         if c.operator == ComparisonOperator::In {
-                            c.value.split(',').collect()
+                            c.value.split(',').map(|v| self.member_arg(v)).collect()
                         } else {
                             Vec::new()
                         }
@@ -322,9 +384,11 @@ impl RedisTableOperations for RedisSetTable {
                     .into_iter()
                     .filter(|member| {
                         conds.iter().enumerate().all(|(i, c)| match c.operator {
-                            ComparisonOperator::Equal => member == &c.value,
-                            ComparisonOperator::NotEqual => member != &c.value,
-                            ComparisonOperator::In => in_value_sets[i].contains(&member.as_str()),
+                            ComparisonOperator::Equal => *member == *self.member_arg(&c.value),
+                            ComparisonOperator::NotEqual => *member != *self.member_arg(&c.value),
+                            ComparisonOperator::In => {
+                                in_value_sets[i].iter().any(|v| **v == **member)
+                            }
                             ComparisonOperator::Like => {
                                 if Some(&c.value) == first_like_value {
                                     true // handled by MATCH
@@ -332,7 +396,9 @@ impl RedisTableOperations for RedisSetTable {
                                     extra_like_matchers
                                         .iter()
                                         .find(|(v, _)| *v == c.value.as_str())
-                                        .is_some_and(|(_, m)| m.matches(member))
+                                        .is_some_and(|(_, m)| {
+                                            m.matches(&String::from_utf8_lossy(member))
+                                        })
                                 }
                             }
                             _ => true,
@@ -348,7 +414,7 @@ impl RedisTableOperations for RedisSetTable {
         self.dataset = if filtered.is_empty() {
             DataSet::Empty
         } else {
-            DataSet::Filtered(filtered)
+            DataSet::Filtered(filtered.into_iter().map(Some).collect())
         };
         Ok((new_cursor, row_count))
     }
@@ -357,10 +423,10 @@ impl RedisTableOperations for RedisSetTable {
         &mut self,
         conn: &mut dyn redis::ConnectionLike,
         keys: &[String],
-    ) -> Result<Vec<String>, redis::RedisError> {
+    ) -> Result<Vec<RowValue>, redis::RedisError> {
         const PER_KEY_WARN_THRESHOLD: usize = 200_000;
 
-        let pipe_result: Result<Vec<Vec<String>>, _> = {
+        let pipe_result: Result<Vec<Vec<Vec<u8>>>, _> = {
             let mut pipe = redis::pipe();
             for key in keys {
                 pipe.cmd("SMEMBERS").arg(key);
@@ -373,7 +439,7 @@ impl RedisTableOperations for RedisSetTable {
             Err(_) => {
                 let mut results = Vec::with_capacity(keys.len());
                 for key in keys {
-                    let r: Vec<String> = redis::cmd("SMEMBERS").arg(key).query(conn)?;
+                    let r: Vec<Vec<u8>> = redis::cmd("SMEMBERS").arg(key).query(conn)?;
                     results.push(r);
                 }
                 results
@@ -390,9 +456,9 @@ impl RedisTableOperations for RedisSetTable {
                     members.len()
                 );
             }
-            for member in members {
-                all_rows.push(key.clone());
-                all_rows.push(member);
+            for member in self.decode_members(members)? {
+                all_rows.push(Some(key.clone().into_bytes()));
+                all_rows.push(Some(member));
             }
         }
         Ok(all_rows)
@@ -406,16 +472,20 @@ impl RedisTableOperations for RedisSetTable {
         "set"
     }
 
-    fn set_filtered_data(&mut self, data: Vec<String>) {
+    fn set_filtered_data(&mut self, data: Vec<RowValue>) {
         self.dataset = DataSet::Filtered(data);
     }
 
+    fn set_column_encodings(&mut self, encodings: ColumnEncodings) {
+        self.encodings = encodings;
+    }
+
     fn batch_parameterized_lookup(
         &mut self,
         conn: &mut dyn redis::ConnectionLike,
         key_prefix: &str,
         params: &[String],
-    ) -> Result<Vec<Option<Vec<String>>>, redis::RedisError> {
+    ) -> Result<Vec<Option<Vec<RowValue>>>, redis::RedisError> {
         if params.is_empty() {
             return Ok(Vec::new());
         }
@@ -425,13 +495,20 @@ impl RedisTableOperations for RedisSetTable {
         // pipeline-not-supported failure on cluster) for the common case.
         if params.len() == 1 {
             let p = &params[0];
-            let hit: bool = redis::cmd("SISMEMBER").arg(key_prefix).arg(p).query(conn)?;
-            return Ok(vec![if hit { Some(vec![p.clone()]) } else { None }]);
+            let hit: bool = redis::cmd("SISMEMBER")
+                .arg(key_prefix)
+                .arg(self.member_arg(p))
+                .query(conn)?;
+            return Ok(vec![
+                hit.then(|| vec![Some(self.member_arg(p).into_owned())])
+            ]);
         }
 
         let mut pipe = redis::pipe();
         for p in params {
-            pipe.cmd("SISMEMBER").arg(key_prefix).arg(p);
+            pipe.cmd("SISMEMBER")
+                .arg(key_prefix)
+                .arg(self.member_arg(p));
         }
         let pipeline_result: Result<Vec<bool>, redis::RedisError> = pipe.query(conn);
 
@@ -444,7 +521,10 @@ impl RedisTableOperations for RedisSetTable {
                 );
                 let mut out = Vec::with_capacity(params.len());
                 for p in params {
-                    let hit: bool = redis::cmd("SISMEMBER").arg(key_prefix).arg(p).query(conn)?;
+                    let hit: bool = redis::cmd("SISMEMBER")
+                        .arg(key_prefix)
+                        .arg(self.member_arg(p))
+                        .query(conn)?;
                     out.push(hit);
                 }
                 out
@@ -454,7 +534,7 @@ impl RedisTableOperations for RedisSetTable {
         Ok(bools
             .into_iter()
             .zip(params.iter())
-            .map(|(hit, p)| if hit { Some(vec![p.clone()]) } else { None })
+            .map(|(hit, p)| hit.then(|| vec![Some(self.member_arg(p).into_owned())]))
             .collect())
     }
 }
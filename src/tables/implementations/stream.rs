@@ -1,4 +1,4 @@
-use std::borrow::Cow;
+use std::{borrow::Cow, collections::HashMap};
 
 use crate::{
     query::{
@@ -8,10 +8,118 @@ use crate::{
     },
     tables::{
         interface::RedisTableOperations,
-        types::{DataSet, LoadDataResult, RowVec},
+        types::{
+            ColumnEncodings, DataSet, InsertMode, LoadDataResult, RowValue, RowVec, ValueEncoding,
+        },
     },
 };
 
+/// One entry: ID and field/value pairs, values as raw bytes.
+pub(crate) type StreamEntry = (String, Vec<(String, Vec<u8>)>);
+/// XRANGE reply with field values checked against their column encoding.
+type StreamEntries = Vec<StreamEntry>;
+/// XRANGE reply with field values not yet checked.
+type RawStreamEntries = Vec<StreamEntry>;
+/// XREADGROUP reply: entries per stream. Pending entries deleted from the
+/// stream come back with nil fields.
+type GroupReadReply = Option<Vec<(String, Vec<(String, Option<Vec<(String, Vec<u8>)>>)>)>>;
+
+/// Virtual column with the number of times an entry was delivered.
+pub const DELIVERY_COUNT_FIELD: &str = "delivery_count";
+/// Virtual column with the milliseconds since the entry was last delivered.
+pub const IDLE_MS_FIELD: &str = "idle_ms";
+/// Virtual `timestamptz` column with the time encoded in the entry ID.
+pub const ENTRY_TIME_FIELD: &str = "entry_time";
+
+/// XADD only when the explicit ID is not in the stream yet; replies nil
+/// otherwise so `insert_mode 'nx'` can skip the row instead of erroring.
+/// ARGV is the ID followed by the XADD arguments after the key.
+const XADD_NX_SCRIPT: &str = "if #redis.call('XRANGE', KEYS[1], ARGV[1], ARGV[1]) == 0 then \
+     return redis.call('XADD', KEYS[1], unpack(ARGV, 2)) end return false";
+
+/// Consumer-group reading, set by the `consumer_group` table options. A
+/// scan then delivers entries to `consumer` with XREADGROUP instead of
+/// reading them with XRANGE, and DELETE acknowledges them with XACK.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StreamConsumer {
+    /// Consumer group (`consumer_group`)
+    pub group: String,
+    /// Consumer within the group (`consumer_name`)
+    pub consumer: String,
+    /// Create the group, and the stream, when missing (`create_group`)
+    pub create_group: bool,
+    /// Claim entries pending longer than this for other consumers
+    /// (`claim_idle_ms`)
+    pub claim_idle_ms: Option<u64>,
+}
+
+impl StreamConsumer {
+    /// Commands one scan sends, in order.
+    pub fn read_ops(&self) -> Vec<&'static str> {
+        let mut ops = Vec::with_capacity(4);
+        if self.create_group {
+            ops.push("XGROUP CREATE");
+        }
+        if self.claim_idle_ms.is_some() {
+            ops.push("XAUTOCLAIM");
+        }
+        ops.extend(["XREADGROUP", "XPENDING"]);
+        ops
+    }
+}
+
+/// Trimming policy, set by the `stream_maxlen` or `stream_minid_age` table
+/// option, that every XADD and `redis_xtrim` apply. A `~` before the value
+/// trims approximately, which Redis does in whole radix tree nodes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StreamTrim {
+    /// Keep at most `count` entries (`stream_maxlen`)
+    MaxLen { count: u64, approximate: bool },
+    /// Drop entries older than `ms` milliseconds (`stream_minid_age`)
+    MinIdAge { ms: u64, approximate: bool },
+}
+
+impl StreamTrim {
+    /// The policy in table options `opts`, if any.
+    pub fn from_options(opts: &HashMap<String, String>) -> Result<Option<Self>, String> {
+        let parse = |name: &str, value: &str| {
+            let (approximate, number) = match value.strip_prefix('~') {
+                Some(rest) => (true, rest.trim_start()),
+                None => (false, value),
+            };
+            number.parse::<u64>().map(|n| (n, approximate)).map_err(|_| {
+                format!("{name} must be a non-negative integer, optionally preceded by '~', got '{value}'")
+            })
+        };
+        match (opts.get("stream_maxlen"), opts.get("stream_minid_age")) {
+            (Some(_), Some(_)) => {
+                Err("stream_maxlen and stream_minid_age cannot be set together".to_string())
+            }
+            (Some(v), None) => parse("stream_maxlen", v)
+                .map(|(count, approximate)| Some(Self::MaxLen { count, approximate })),
+            (None, Some(v)) => parse("stream_minid_age", v)
+                .map(|(ms, approximate)| Some(Self::MinIdAge { ms, approximate })),
+            (None, None) => Ok(None),
+        }
+    }
+
+    /// XADD and XTRIM arguments for the policy, e.g. `MAXLEN ~ 1000`. The
+    /// MINID threshold is the current time less the age.
+    pub fn args(&self) -> Vec<String> {
+        let (strategy, approximate, threshold) = match self {
+            Self::MaxLen { count, approximate } => ("MAXLEN", approximate, count.to_string()),
+            Self::MinIdAge { ms, approximate } => {
+                let now_ms = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map_or(0, |d| d.as_millis() as u64);
+                ("MINID", approximate, now_ms.saturating_sub(*ms).to_string())
+            }
+        };
+        let op = if *approximate { "~" } else { "=" };
+        vec![strategy.to_string(), op.to_string(), threshold]
+    }
+}
+
 /// Redis Stream table type supporting large data sets with streaming and pagination
 ///
 /// Redis Streams are append-only log data structures that support:
@@ -20,11 +128,14 @@ use crate::{
 /// - Range queries by time or ID
 /// - Consumer groups for distributed processing
 /// - Efficient pagination with COUNT and ID cursors
+///
+/// In multi-key mode each stream key contributes one row per entry, laid out
+/// as `[key, stream_id, field values in column order...]`.
 #[derive(Debug, Clone, Default)]
 pub struct RedisStreamTable {
     pub dataset: DataSet,
-    /// Pre-split stream entries for zero-allocation row access
-    pub entries: Vec<Vec<String>>,
+    /// Entries of the last load, for zero-allocation row access
+    pub entries: StreamEntries,
     /// Last processed stream ID for pagination
     pub last_id: Option<String>,
     /// Batch size for streaming operations to handle large data sets
@@ -33,6 +144,18 @@ pub struct RedisStreamTable {
     pub column_names: Vec<String>,
     /// Raw attribute index of the stream ID column (accounts for TTL position)
     pub pushdown_column_index: usize,
+    /// ID Redis assigned to the entry written by the last `insert`
+    pub last_added_id: Option<String>,
+    /// Encodings of the columns in `column_names` order
+    pub encodings: ColumnEncodings,
+    /// Consumer group the table reads as (None = plain XRANGE reads)
+    pub consumer: Option<StreamConsumer>,
+    /// Whether rows start with the Redis key (multi-key mode)
+    pub key_column: bool,
+    /// XRANGE start and end used for every key of a multi-key scan
+    pub id_range: (String, String),
+    /// Trimming every XADD applies (None = streams grow unbounded)
+    pub trim: Option<StreamTrim>,
 }
 
 impl RedisStreamTable {
@@ -44,9 +167,131 @@ impl RedisStreamTable {
             batch_size,
             column_names: Vec::new(),
             pushdown_column_index: 0,
+            last_added_id: None,
+            encodings: ColumnEncodings::default(),
+            consumer: None,
+            key_column: false,
+            id_range: ("-".to_string(), "+".to_string()),
+            trim: None,
+        }
+    }
+
+    /// Number of values in one row of data given to `set_filtered_data`.
+    fn filtered_row_width(&self) -> usize {
+        if self.key_column {
+            self.multi_key_columns_per_row()
+        } else {
+            self.column_names.len().max(1)
         }
     }
 
+    /// Names of the columns after the stream ID, i.e. the stream fields.
+    fn field_columns(&self) -> &[String] {
+        let skip = (1 + usize::from(self.key_column)).min(self.column_names.len());
+        &self.column_names[skip..]
+    }
+
+    /// Narrow the XRANGE of every key in a multi-key scan to the stream ID
+    /// bounds among `conditions`. PostgreSQL rechecks all of them, so any
+    /// one bound per side is enough.
+    pub fn set_id_range(&mut self, conditions: &[PushableCondition]) {
+        self.id_range = self.xrange_bounds(conditions);
+    }
+
+    /// XRANGE start and end for the stream ID bounds among `conditions`,
+    /// `-` and `+` for a missing side.
+    pub fn xrange_bounds(&self, conditions: &[PushableCondition]) -> (String, String) {
+        let (mut start, mut end) = ("-".to_string(), "+".to_string());
+        for condition in conditions
+            .iter()
+            .filter(|c| c.column_index == self.pushdown_column_index)
+        {
+            let Some(v) = parse_stream_id_bound(&condition.value) else {
+                continue;
+            };
+            match condition.operator {
+                ComparisonOperator::Equal => {
+                    start = v.clone();
+                    end = v;
+                }
+                ComparisonOperator::GreaterThanOrEqual => start = v,
+                ComparisonOperator::GreaterThan => start = format!("({}", v),
+                ComparisonOperator::LessThanOrEqual => end = v,
+                ComparisonOperator::LessThan => end = format!("({}", v),
+                _ => {}
+            }
+        }
+        (start, end)
+    }
+
+    /// Value of column `column` of the entry `id` with `fields`: the entry
+    /// time for [`ENTRY_TIME_FIELD`], else the field of that name.
+    fn column_value<'a>(
+        &self,
+        column: &str,
+        id: &str,
+        fields: &'a [(String, Vec<u8>)],
+    ) -> Option<Cow<'a, [u8]>> {
+        if column == ENTRY_TIME_FIELD {
+            return entry_time(id).map(|time| Cow::Owned(time.into_bytes()));
+        }
+        fields
+            .iter()
+            .find(|(field, _)| field == column)
+            .map(|(_, value)| Cow::Borrowed(value.as_slice()))
+    }
+
+    /// ID and fields of a multi-key row to write: `[stream_id, values...]`
+    /// with the key already stripped. A NULL ID is generated by Redis and
+    /// NULL values leave their field out. `None` when no field is set.
+    pub fn multi_key_entry<'a>(
+        &self,
+        data: &'a [String],
+    ) -> Option<(&'a str, Vec<(String, String)>)> {
+        let id = data.first()?;
+        let id = if id == "NULL" { "*" } else { id.as_str() };
+        let fields: Vec<(String, String)> = self
+            .field_columns()
+            .iter()
+            .zip(&data[1..])
+            .filter(|(field, value)| value.as_str() != "NULL" && !self.is_virtual_field(field))
+            .map(|(field, value)| (field.clone(), value.clone()))
+            .collect();
+        (!fields.is_empty()).then_some((id, fields))
+    }
+
+    /// Whether `field` is derived from the entry ID or, for a consumer
+    /// group, the pending entries list rather than stored in the stream.
+    fn is_virtual_field(&self, field: &str) -> bool {
+        field == ENTRY_TIME_FIELD
+            || self.consumer.is_some() && (field == DELIVERY_COUNT_FIELD || field == IDLE_MS_FIELD)
+    }
+
+    /// Encoding of the column a stream field maps to; fields without a
+    /// column are text.
+    fn field_encoding(&self, field: &str) -> ValueEncoding {
+        let data_columns = usize::from(self.key_column).min(self.column_names.len());
+        self.column_names[data_columns..]
+            .iter()
+            .position(|name| name == field)
+            .map_or(ValueEncoding::Text, |i| self.encodings.get(i))
+    }
+
+    fn decode_entries(&self, raw: RawStreamEntries) -> redis::RedisResult<StreamEntries> {
+        raw.into_iter()
+            .map(|(id, fields)| {
+                let fields = fields
+                    .into_iter()
+                    .map(|(field, value)| {
+                        let value = self.field_encoding(&field).decode(value)?;
+                        Ok((field, value))
+                    })
+                    .collect::<redis::RedisResult<_>>()?;
+                Ok((id, fields))
+            })
+            .collect()
+    }
+
     fn next_start_id(&self) -> String {
         match &self.last_id {
             Some(id) => {
@@ -74,7 +319,7 @@ impl RedisStreamTable {
         count: Option<usize>,
     ) -> Result<LoadDataResult, redis::RedisError> {
         // Use XRANGE to get stream entries
-        let entries: Vec<(String, Vec<(String, String)>)> = match count {
+        let raw: RawStreamEntries = match count {
             Some(c) => redis::cmd("XRANGE")
                 .arg(key_prefix)
                 .arg(start_id)
@@ -88,6 +333,7 @@ impl RedisStreamTable {
                 .arg(end_id)
                 .query(conn)?,
         };
+        let entries = self.decode_entries(raw)?;
 
         if entries.is_empty() {
             return Ok(LoadDataResult::Empty);
@@ -96,27 +342,14 @@ impl RedisStreamTable {
         // Store last stream ID for pagination before processing entries
         let last_id = entries.last().map(|(id, _)| id.clone());
 
-        // Store as pre-split structured entries for zero-allocation row access
-        let mut structured_entries = Vec::with_capacity(entries.len());
-        let mut flat_data = Vec::with_capacity(entries.len());
-        for (stream_id, fields) in entries {
-            let mut row = vec![stream_id.clone()];
-            for (field, value) in fields {
-                row.push(field);
-                row.push(value);
-            }
-            flat_data.push(stream_id);
-            structured_entries.push(row);
-        }
-
         // Store last stream ID for pagination
         if let Some(id) = last_id {
             self.last_id = Some(id);
         }
 
-        self.entries = structured_entries;
-        // Store flat_data for DataSet compatibility
-        self.dataset = DataSet::Filtered(flat_data);
+        // Store the IDs for DataSet compatibility
+        self.dataset = DataSet::Filtered(entry_ids(&entries));
+        self.entries = entries;
         Ok(LoadDataResult::FullyLoaded)
     }
 
@@ -287,23 +520,22 @@ impl RedisStreamTable {
             let mut filtered_flat = Vec::with_capacity(self.entries.len());
 
             for entry in &self.entries {
+                let (id, fields) = entry;
                 let matches = non_id_conditions.iter().enumerate().all(|(i, cond)| {
                     let matcher = like_matchers[i].as_ref();
-                    // id-column conditions compare against entry[0] (the
-                    // stream_id), not the field-value chunks.
+                    // id-column conditions compare against the stream_id,
+                    // not the field values.
                     if cond.column_index == id_col_idx {
-                        return eval_condition(&entry[0], cond, matcher);
+                        return eval_condition(id.as_bytes(), ValueEncoding::Text, cond, matcher);
                     }
-                    entry[1..].chunks(2).any(|chunk| {
-                        if chunk.len() != 2 || chunk[0] != cond.column_name {
-                            return false;
-                        }
-                        eval_condition(&chunk[1], cond, matcher)
+                    fields.iter().any(|(field, value)| {
+                        *field == cond.column_name
+                            && eval_condition(value, self.field_encoding(field), cond, matcher)
                     })
                 });
 
                 if matches {
-                    filtered_flat.push(entry[0].clone());
+                    filtered_flat.push(Some(id.clone().into_bytes()));
                     filtered_entries.push(entry.clone());
                 }
             }
@@ -331,7 +563,154 @@ impl RedisStreamTable {
         Ok(result)
     }
 
-    /// Add a new entry to the stream
+    /// Delivery count and idle time, by ID, of the entries in
+    /// `start..=end` pending for `consumer`.
+    fn pending_info(
+        consumer: &StreamConsumer,
+        conn: &mut dyn redis::ConnectionLike,
+        key: &str,
+        start: &str,
+        end: &str,
+        count: usize,
+    ) -> redis::RedisResult<HashMap<String, (u64, u64)>> {
+        let pending: Vec<(String, String, u64, u64)> = redis::cmd("XPENDING")
+            .arg(key)
+            .arg(&consumer.group)
+            .arg(start)
+            .arg(end)
+            .arg(count)
+            .arg(&consumer.consumer)
+            .query(conn)?;
+        Ok(pending
+            .into_iter()
+            .map(|(id, _, idle, deliveries)| (id, (deliveries, idle)))
+            .collect())
+    }
+
+    /// One XREADGROUP of up to `count` entries after `id`: `0` for the
+    /// entries delivered to `consumer` but not acknowledged, `>` for new ones.
+    fn read_group(
+        consumer: &StreamConsumer,
+        conn: &mut dyn redis::ConnectionLike,
+        key: &str,
+        id: &str,
+        count: usize,
+    ) -> redis::RedisResult<RawStreamEntries> {
+        let reply: GroupReadReply = redis::cmd("XREADGROUP")
+            .arg("GROUP")
+            .arg(&consumer.group)
+            .arg(&consumer.consumer)
+            .arg("COUNT")
+            .arg(count)
+            .arg("STREAMS")
+            .arg(key)
+            .arg(id)
+            .query(conn)?;
+        Ok(reply
+            .into_iter()
+            .flatten()
+            .flat_map(|(_, entries)| entries)
+            .map(|(id, fields)| (id, fields.unwrap_or_default()))
+            .collect())
+    }
+
+    /// Deliver up to `count` entries to the table's consumer: the ones
+    /// already pending for it first (after claiming stalled ones with
+    /// XAUTOCLAIM when `claim_idle_ms` is set), then new ones. Every row
+    /// stays pending until a DELETE acknowledges it.
+    fn load_with_group(
+        &mut self,
+        conn: &mut dyn redis::ConnectionLike,
+        key: &str,
+        count: usize,
+    ) -> Result<LoadDataResult, redis::RedisError> {
+        self.entries.clear();
+        self.dataset = DataSet::Empty;
+        let Some(consumer) = self.consumer.clone() else {
+            return Ok(LoadDataResult::Empty);
+        };
+        if count == 0 {
+            return Ok(LoadDataResult::Empty);
+        }
+
+        if consumer.create_group {
+            let created: redis::RedisResult<()> = redis::cmd("XGROUP")
+                .arg("CREATE")
+                .arg(key)
+                .arg(&consumer.group)
+                .arg("0")
+                .arg("MKSTREAM")
+                .query(conn);
+            match created {
+                Err(e) if e.code() != Some("BUSYGROUP") => return Err(e),
+                _ => {}
+            }
+        }
+        if let Some(min_idle) = consumer.claim_idle_ms {
+            // The claimed entries join this consumer's pending list, which
+            // the first read below returns.
+            let _: redis::Value = redis::cmd("XAUTOCLAIM")
+                .arg(key)
+                .arg(&consumer.group)
+                .arg(&consumer.consumer)
+                .arg(min_idle)
+                .arg("0-0")
+                .arg("COUNT")
+                .arg(count)
+                .query(conn)?;
+        }
+
+        let mut raw = Self::read_group(&consumer, conn, key, "0", count)?;
+        if raw.len() < count {
+            raw.extend(Self::read_group(
+                &consumer,
+                conn,
+                key,
+                ">",
+                count - raw.len(),
+            )?);
+        }
+        let entries = self.decode_entries(raw)?;
+        let (Some((first, _)), Some((last, _))) = (entries.first(), entries.last()) else {
+            return Ok(LoadDataResult::Empty);
+        };
+        // The rows are the head of the consumer's pending list, so its
+        // range between the first and last row holds exactly them.
+        let pending = Self::pending_info(&consumer, conn, key, first, last, entries.len())?;
+
+        let entries: StreamEntries = entries
+            .into_iter()
+            .map(|(stream_id, fields)| {
+                let fields = with_pending(fields, pending.get(&stream_id));
+                (stream_id, fields)
+            })
+            .collect();
+        self.dataset = DataSet::Filtered(entry_ids(&entries));
+        self.entries = entries;
+        Ok(LoadDataResult::FullyLoaded)
+    }
+
+    /// `entry` with the consumer-group virtual columns filled in when it is
+    /// pending for the table's consumer.
+    fn with_pending_lookup(
+        &self,
+        conn: &mut dyn redis::ConnectionLike,
+        key: &str,
+        entry: Option<StreamEntry>,
+    ) -> redis::RedisResult<Option<StreamEntry>> {
+        match (&self.consumer, entry) {
+            (Some(consumer), Some((id, fields))) => {
+                let pending = Self::pending_info(consumer, conn, key, &id, &id, 1)?;
+                let fields = with_pending(fields, pending.get(&id));
+                Ok(Some((id, fields)))
+            }
+            (_, entry) => Ok(entry),
+        }
+    }
+
+    /// Add a new entry to the stream and return its ID. INSERT goes through
+    /// [`Self::xadd`]; this unconditional form is left for the tests.
+    #[cfg(test)]
     pub fn add_entry(
         &mut self,
         conn: &mut dyn redis::ConnectionLike,
@@ -339,16 +718,142 @@ impl RedisStreamTable {
         id: &str, // Use "*" for auto-generated ID
         fields: &[(String, String)],
     ) -> Result<String, redis::RedisError> {
-        let mut cmd = redis::cmd("XADD");
-        cmd.arg(key_prefix).arg(id);
+        self.xadd(conn, key_prefix, id, fields, InsertMode::Overwrite)?;
+        Ok(self.last_added_id.clone().unwrap_or_default())
+    }
+
+    /// Send the XADD of one entry, remembering the ID Redis assigned.
+    fn xadd(
+        &mut self,
+        conn: &mut dyn redis::ConnectionLike,
+        key: &str,
+        id: &str,
+        fields: &[(String, String)],
+        mode: InsertMode,
+    ) -> Result<bool, redis::RedisError> {
+        let reply: redis::Value = self.xadd_command(key, id, fields, mode).query(conn)?;
+        // Buffered writes reply with a placeholder integer; only a real
+        // XADD reply carries the entry ID.
+        self.last_added_id = match &reply {
+            redis::Value::BulkString(id) => Some(String::from_utf8_lossy(id).into_owned()),
+            _ => None,
+        };
+        Ok(InsertMode::reply_applied(&reply))
+    }
 
-        // Add field-value pairs
-        for (field, value) in fields {
-            cmd.arg(field).arg(value);
+    /// XADD for one entry. XX adds NOMKSTREAM (nil reply when the stream is
+    /// missing); NX with an explicit ID goes through [`XADD_NX_SCRIPT`].
+    /// Auto-generated IDs never collide, so NX with `*` is a plain XADD.
+    /// The table's trimming policy, if any, rides along on every one.
+    pub fn xadd_command(
+        &self,
+        key: &str,
+        id: &str,
+        fields: &[(String, String)],
+        mode: InsertMode,
+    ) -> redis::Cmd {
+        let mut cmd = if mode == InsertMode::Nx && id != "*" {
+            let mut cmd = redis::cmd("EVAL");
+            cmd.arg(XADD_NX_SCRIPT).arg(1).arg(key).arg(id);
+            cmd
+        } else {
+            let mut cmd = redis::cmd("XADD");
+            cmd.arg(key);
+            if mode == InsertMode::Xx {
+                cmd.arg("NOMKSTREAM");
+            }
+            cmd
+        };
+        if let Some(trim) = &self.trim {
+            cmd.arg(trim.args());
+        }
+        cmd.arg(id);
+        for (field, value) in fields.iter().filter(|(f, _)| !self.is_virtual_field(f)) {
+            cmd.arg(field).arg(self.field_encoding(field).encode(value));
         }
+        cmd
+    }
+}
 
-        let stream_id: String = cmd.query(conn)?;
-        Ok(stream_id)
+/// Filtered-data form of `entries`: their IDs.
+fn entry_ids(entries: &[StreamEntry]) -> Vec<RowValue> {
+    entries
+        .iter()
+        .map(|(id, _)| Some(id.clone().into_bytes()))
+        .collect()
+}
+
+/// `fields` preceded by the virtual delivery count and idle time columns,
+/// which thereby shadow stream fields of the same name. Entries not pending
+/// keep them NULL.
+fn with_pending(
+    fields: Vec<(String, Vec<u8>)>,
+    pending: Option<&(u64, u64)>,
+) -> Vec<(String, Vec<u8>)> {
+    let Some((deliveries, idle)) = pending else {
+        return fields;
+    };
+    let mut all = Vec::with_capacity(fields.len() + 2);
+    all.push((
+        DELIVERY_COUNT_FIELD.to_string(),
+        deliveries.to_string().into_bytes(),
+    ));
+    all.push((IDLE_MS_FIELD.to_string(), idle.to_string().into_bytes()));
+    all.extend(fields);
+    all
+}
+
+/// The entry time of stream ID `id` as `timestamptz` text in UTC, with
+/// millisecond precision: `1700000000000-0` is `2023-11-14 22:13:20.000+00`.
+pub fn entry_time(id: &str) -> Option<String> {
+    let ms: i64 = id.split('-').next()?.parse().ok()?;
+    let (days, ms_of_day) = (ms.div_euclid(86_400_000), ms.rem_euclid(86_400_000));
+    // Civil date of a day count since 1970-01-01 (proleptic Gregorian).
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    Some(format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}.{:03}+00",
+        year,
+        month,
+        day,
+        ms_of_day / 3_600_000,
+        ms_of_day / 60_000 % 60,
+        ms_of_day / 1000 % 60,
+        ms_of_day % 1000
+    ))
+}
+
+/// Stream ID bounds equivalent to `entry_time <operator> t`, `t` in
+/// microseconds since the Unix epoch. Entry times have millisecond
+/// precision, so a bound between two milliseconds rounds inward; a
+/// condition no entry can meet becomes `< 0-1`, below every valid ID.
+pub fn entry_time_id_bounds(
+    operator: &ComparisonOperator,
+    t: i64,
+) -> Vec<(ComparisonOperator, String)> {
+    let floor = t.div_euclid(1000);
+    let ceil = floor + i64::from(t.rem_euclid(1000) != 0);
+    let none_match = vec![(ComparisonOperator::LessThan, "0-1".to_string())];
+    let lower = |ms: i64| (ComparisonOperator::GreaterThanOrEqual, ms.to_string());
+    // An ID bound with only the milliseconds ends at its last sequence.
+    let upper = |ms: i64| (ComparisonOperator::LessThanOrEqual, ms.to_string());
+    match operator {
+        ComparisonOperator::GreaterThanOrEqual if ceil > 0 => vec![lower(ceil)],
+        ComparisonOperator::GreaterThan if floor >= 0 => vec![lower(floor + 1)],
+        ComparisonOperator::LessThanOrEqual if floor >= 0 => vec![upper(floor)],
+        ComparisonOperator::LessThanOrEqual => none_match,
+        ComparisonOperator::LessThan if ceil > 0 => vec![upper(ceil - 1)],
+        ComparisonOperator::LessThan => none_match,
+        ComparisonOperator::Equal if t >= 0 && floor == ceil => vec![lower(floor), upper(floor)],
+        ComparisonOperator::Equal => none_match,
+        _ => Vec::new(),
     }
 }
 
@@ -371,6 +876,9 @@ fn parse_stream_id_bound(value: &str) -> Option<String> {
 /// operator the FDW currently declares as pushable (Equal/NotEqual/Like/In/
 /// NotIn + the four range operators).
 ///
+/// Equality and IN compare the value with the bytes `encoding` sends to
+/// Redis for the condition; LIKE and ranges compare text.
+///
 /// Range comparisons try, in order:
 /// 1. Stream-id tuple `(ms, seq)` parse — correct for `ms-seq` values
 ///    (lexicographic compare would mis-order `1710000000000-10` vs `…-2`).
@@ -380,7 +888,8 @@ fn parse_stream_id_bound(value: &str) -> Option<String> {
 /// Mirrors row_matches_condition in state_manager.rs so client-side filtering
 /// on Stream is consistent with the join post-filter.
 fn eval_condition(
-    val: &str,
+    value: &[u8],
+    encoding: ValueEncoding,
     cond: &PushableCondition,
     matcher: Option<&crate::query::scan_ops::PatternMatcher>,
 ) -> bool {
@@ -394,12 +903,15 @@ fn eval_condition(
         Some((ms, seq))
     }
 
+    let text = String::from_utf8_lossy(value);
+    let val: &str = &text;
+    let is = |x: &str| value == &*encoding.encode(x);
     match cond.operator {
-        ComparisonOperator::Equal => val == cond.value,
-        ComparisonOperator::NotEqual => val != cond.value,
+        ComparisonOperator::Equal => is(&cond.value),
+        ComparisonOperator::NotEqual => !is(&cond.value),
         ComparisonOperator::Like => matcher.is_some_and(|m| m.matches(val)),
-        ComparisonOperator::In => cond.value.split(',').any(|x| x == val),
-        ComparisonOperator::NotIn => !cond.value.split(',').any(|x| x == val),
+        ComparisonOperator::In => cond.value.split(',').any(is),
+        ComparisonOperator::NotIn => !cond.value.split(',').any(is),
         ComparisonOperator::GreaterThan
         | ComparisonOperator::GreaterThanOrEqual
         | ComparisonOperator::LessThan
@@ -431,6 +943,8 @@ fn eval_condition(
                 },
             }
         }
+        // Geo searches never apply to streams; PostgreSQL evaluates them.
+        ComparisonOperator::GeoRadius { .. } | ComparisonOperator::GeoBox { .. } => true,
     }
 }
 
@@ -442,6 +956,16 @@ impl RedisTableOperations for RedisStreamTable {
         conditions: Option<&[PushableCondition]>,
         limit_offset: &LimitOffsetInfo,
     ) -> Result<LoadDataResult, redis::RedisError> {
+        if self.consumer.is_some() {
+            // A group read cannot filter: PostgreSQL applies the quals to
+            // what was delivered, so a LIMIT only caps reads without them.
+            let count = match (conditions, limit_offset.limit) {
+                (None, Some(limit)) => limit.saturating_add(limit_offset.offset.unwrap_or(0)),
+                _ => self.batch_size,
+            };
+            return self.load_with_group(conn, key_prefix, count);
+        }
+
         if let Some(conditions) = conditions {
             let scan_conditions = extract_scan_conditions(conditions);
 
@@ -498,6 +1022,8 @@ impl RedisTableOperations for RedisStreamTable {
     fn data_len(&self) -> usize {
         if !self.entries.is_empty() {
             self.entries.len()
+        } else if let DataSet::Filtered(data) = &self.dataset {
+            data.len() / self.filtered_row_width()
         } else {
             self.dataset.len()
         }
@@ -506,33 +1032,32 @@ impl RedisTableOperations for RedisStreamTable {
     #[inline]
     fn get_row(&self, index: usize) -> Option<RowVec<'_>> {
         if !self.entries.is_empty() {
-            self.entries.get(index).map(|row| {
-                // row = [stream_id, field1, val1, field2, val2, ...]
+            self.entries.get(index).map(|(id, fields)| {
+                let mut result = RowVec::with_capacity(self.column_names.len());
+                result.push(Some(Cow::Borrowed(id.as_bytes())));
                 if self.column_names.len() > 1 {
-                    let mut result = RowVec::with_capacity(self.column_names.len());
-                    result.push(Cow::Borrowed(row[0].as_str()));
-
                     for col_name in &self.column_names[1..] {
-                        let val = row[1..]
-                            .chunks(2)
-                            .find(|chunk| chunk.len() == 2 && chunk[0] == *col_name)
-                            .map(|chunk| Cow::Borrowed(chunk[1].as_str()))
-                            .unwrap_or(Cow::Borrowed("NULL"));
-                        result.push(val);
+                        result.push(self.column_value(col_name, id, fields));
                     }
-                    result
                 } else {
-                    row.iter().map(|s| Cow::Borrowed(s.as_str())).collect()
+                    for (field, value) in fields {
+                        result.push(Some(Cow::Borrowed(field.as_bytes())));
+                        result.push(Some(Cow::Borrowed(value.as_slice())));
+                    }
                 }
+                result
             })
         } else {
             match &self.dataset {
-                DataSet::Filtered(entries) => entries.get(index).map(|entry| {
-                    entry
-                        .split('\t')
-                        .map(|s| Cow::Owned(s.to_string()))
-                        .collect()
-                }),
+                DataSet::Filtered(data) => {
+                    let width = self.filtered_row_width();
+                    let start = index.checked_mul(width)?;
+                    data.get(start..start + width).map(|row| {
+                        row.iter()
+                            .map(|v| v.as_deref().map(Cow::Borrowed))
+                            .collect()
+                    })
+                }
                 DataSet::Complete(container) => container.get_row(index),
                 DataSet::Empty => None,
             }
@@ -544,15 +1069,26 @@ impl RedisTableOperations for RedisStreamTable {
         conn: &mut dyn redis::ConnectionLike,
         key_prefix: &str,
         data: &[String],
-    ) -> Result<(), redis::RedisError> {
+        mode: InsertMode,
+    ) -> Result<bool, redis::RedisError> {
         if data.is_empty() {
-            return Ok(());
+            return Ok(false);
+        }
+
+        if self.key_column {
+            let Some((id, fields)) = self.multi_key_entry(data) else {
+                return Ok(false);
+            };
+            return self.xadd(conn, key_prefix, id, &fields, mode);
         }
 
         // For streams, we expect data format: [id?, field1, value1, field2, value2, ...]
         // If first element looks like a stream ID, use it; otherwise auto-generate
         let (id, field_start) = if !data.is_empty() && (data[0] == "*" || data[0].contains('-')) {
             (data[0].as_str(), 1)
+        } else if data[0] == "NULL" {
+            // A NULL ID column: Redis generates the ID
+            ("*", 1)
         } else {
             ("*", 0) // Auto-generate ID
         };
@@ -565,11 +1101,11 @@ impl RedisTableOperations for RedisStreamTable {
             }
         }
 
-        if !fields.is_empty() {
-            self.add_entry(conn, key_prefix, id, &fields)?;
+        if fields.is_empty() {
+            return Ok(false);
         }
 
-        Ok(())
+        self.xadd(conn, key_prefix, id, &fields, mode)
     }
 
     fn delete(
@@ -584,6 +1120,16 @@ impl RedisTableOperations for RedisStreamTable {
 
         // For streams, data should contain stream IDs to delete
         let ids: Vec<&String> = data.iter().collect();
+        // A consumer-group table acknowledges the entries instead; they
+        // stay in the stream for other groups.
+        if let Some(consumer) = &self.consumer {
+            let _acked: usize = redis::cmd("XACK")
+                .arg(key_prefix)
+                .arg(&consumer.group)
+                .arg(&ids)
+                .query(conn)?;
+            return Ok(());
+        }
         let _deleted_count: usize = redis::cmd("XDEL").arg(key_prefix).arg(&ids).query(conn)?;
 
         Ok(())
@@ -629,6 +1175,11 @@ impl RedisTableOperations for RedisStreamTable {
         batch_size: usize,
         conditions: Option<&[PushableCondition]>,
     ) -> Result<(u64, usize), redis::RedisError> {
+        if self.consumer.is_some() {
+            self.load_with_group(conn, key_prefix, batch_size)?;
+            return Ok((0, self.data_len()));
+        }
+
         // Determine start/end IDs from ID-column conditions only
         let id_col_idx = self.pushdown_column_index;
         let (start_id, end_id) = if let Some(conds) = conditions {
@@ -649,13 +1200,14 @@ impl RedisTableOperations for RedisStreamTable {
             (self.next_start_id(), "+".to_string())
         };
 
-        let entries: Vec<(String, Vec<(String, String)>)> = redis::cmd("XRANGE")
+        let raw: RawStreamEntries = redis::cmd("XRANGE")
             .arg(key_prefix)
             .arg(&start_id)
             .arg(&end_id)
             .arg("COUNT")
             .arg(batch_size)
             .query(conn)?;
+        let entries = self.decode_entries(raw)?;
 
         let row_count = entries.len();
         let new_cursor = if row_count < batch_size { 0 } else { 1 };
@@ -692,36 +1244,27 @@ impl RedisTableOperations for RedisStreamTable {
             })
             .collect();
 
-        let mut structured_entries = Vec::with_capacity(entries.len());
-        let mut flat_data = Vec::with_capacity(entries.len());
-        for (stream_id, fields) in entries {
-            // Apply client-side filtering for non-ID conditions
-            if !non_id_conds.is_empty() {
-                let matches = non_id_conds.iter().enumerate().all(|(i, c)| {
+        // Apply client-side filtering for non-ID conditions
+        let entries: StreamEntries = entries
+            .into_iter()
+            .filter(|(_, fields)| {
+                non_id_conds.iter().enumerate().all(|(i, c)| {
                     fields.iter().any(|(f, v)| {
-                        if f != &c.column_name {
-                            return false;
-                        }
-                        eval_condition(v, c, like_matchers[i].as_ref())
+                        f == &c.column_name
+                            && eval_condition(
+                                v,
+                                self.field_encoding(f),
+                                c,
+                                like_matchers[i].as_ref(),
+                            )
                     })
-                });
-                if !matches {
-                    continue;
-                }
-            }
-
-            let mut row = vec![stream_id.clone()];
-            for (field, value) in fields {
-                row.push(field);
-                row.push(value);
-            }
-            flat_data.push(stream_id);
-            structured_entries.push(row);
-        }
+                })
+            })
+            .collect();
 
-        let filtered_count = structured_entries.len();
-        self.entries = structured_entries;
-        self.dataset = DataSet::Filtered(flat_data);
+        let filtered_count = entries.len();
+        self.dataset = DataSet::Filtered(entry_ids(&entries));
+        self.entries = entries;
         Ok((new_cursor, filtered_count))
     }
 
@@ -735,15 +1278,45 @@ impl RedisTableOperations for RedisStreamTable {
         self.pushdown_column_index = pushdown_column_index;
     }
 
+    fn set_column_encodings(&mut self, encodings: ColumnEncodings) {
+        self.encodings = encodings;
+    }
+
+    /// One pipelined XRANGE over `id_range` per key. A key that is no
+    /// stream fails its XRANGE alone and has no rows.
     fn load_multi_key_data(
         &mut self,
-        _conn: &mut dyn redis::ConnectionLike,
-        _keys: &[String],
-    ) -> Result<Vec<String>, redis::RedisError> {
-        Err(redis::RedisError::from((
-            redis::ErrorKind::InvalidClientConfig,
-            "Multi-key mode is not supported for Redis Stream",
-        )))
+        conn: &mut dyn redis::ConnectionLike,
+        keys: &[String],
+    ) -> Result<Vec<RowValue>, redis::RedisError> {
+        if keys.is_empty() {
+            return Ok(Vec::new());
+        }
+        let (start, end) = &self.id_range;
+        let mut pipe = redis::pipe();
+        pipe.ignore_errors();
+        for key in keys {
+            pipe.cmd("XRANGE").arg(key).arg(start).arg(end);
+        }
+        let replies: Vec<redis::RedisResult<RawStreamEntries>> = pipe.query(conn)?;
+
+        let width = self.multi_key_columns_per_row();
+        let mut rows = Vec::new();
+        for (key, reply) in keys.iter().zip(replies) {
+            let Ok(raw) = reply else {
+                continue;
+            };
+            for (stream_id, fields) in self.decode_entries(raw)? {
+                rows.reserve(width);
+                rows.push(Some(key.clone().into_bytes()));
+                rows.push(Some(stream_id.clone().into_bytes()));
+                for column in self.field_columns() {
+                    let value = self.column_value(column, &stream_id, &fields);
+                    rows.push(value.map(Cow::into_owned));
+                }
+            }
+        }
+        Ok(rows)
     }
 
     fn clear(&mut self) {
@@ -756,25 +1329,28 @@ impl RedisTableOperations for RedisStreamTable {
         "stream"
     }
 
-    fn set_filtered_data(&mut self, data: Vec<String>) {
+    fn set_filtered_data(&mut self, data: Vec<RowValue>) {
         // Called by parameterized_lookup with a single row's column values
-        // (e.g. [stream_id, val1, val2, ...]). get_row's DataSet::Filtered
-        // branch reconstructs columns via split('\t'), so we join here.
-        // Without this, only the first column survives — everything else
-        // comes back as NULL to PostgreSQL on JOIN.
+        // (e.g. [stream_id, val1, val2, ...]), and with multi-key rows. Both
+        // stay flat; get_row's DataSet::Filtered branch chunks them by
+        // `filtered_row_width`.
         //
         // Must clear self.entries: get_row prefers `entries` when non-empty,
         // so stale data from a previous scan would shadow the new row.
         self.entries.clear();
-        if data.is_empty() {
+        if data.is_empty() && !self.key_column {
             self.dataset = DataSet::Empty;
         } else {
-            self.dataset = DataSet::Filtered(vec![data.join("\t")]);
+            self.dataset = DataSet::Filtered(data);
         }
     }
 
     fn multi_key_columns_per_row(&self) -> usize {
-        4
+        if self.key_column {
+            2 + self.field_columns().len()
+        } else {
+            4
+        }
     }
 
     fn batch_parameterized_lookup(
@@ -782,7 +1358,7 @@ impl RedisTableOperations for RedisStreamTable {
         conn: &mut dyn redis::ConnectionLike,
         key_prefix: &str,
         params: &[String],
-    ) -> Result<Vec<Option<Vec<String>>>, redis::RedisError> {
+    ) -> Result<Vec<Option<Vec<RowValue>>>, redis::RedisError> {
         if params.is_empty() {
             return Ok(Vec::new());
         }
@@ -790,23 +1366,15 @@ impl RedisTableOperations for RedisStreamTable {
         // Convert a single XRANGE id-id result into the row shape configured
         // for this table (stream_id, then per-column lookup over the field-
         // value chunks). Returns None when the entry doesn't exist.
-        let entry_to_row = |entry: Option<(String, Vec<(String, String)>)>| -> Option<Vec<String>> {
+        let entry_to_row = |entry: Option<StreamEntry>| -> Option<Vec<RowValue>> {
             let (real_id, fields) = entry?;
-            if self.column_names.len() > 1 {
-                let mut row = Vec::with_capacity(self.column_names.len());
-                row.push(real_id);
-                for col_name in &self.column_names[1..] {
-                    let v = fields
-                        .iter()
-                        .find(|(f, _)| f == col_name)
-                        .map(|(_, v)| v.clone())
-                        .unwrap_or_else(|| "NULL".to_string());
-                    row.push(v);
-                }
-                Some(row)
-            } else {
-                Some(vec![real_id])
+            let mut row = Vec::with_capacity(self.column_names.len().max(1));
+            row.push(Some(real_id.as_bytes().to_vec()));
+            for col_name in self.column_names.iter().skip(1) {
+                let v = self.column_value(col_name, &real_id, &fields);
+                row.push(v.map(Cow::into_owned));
             }
+            Some(row)
         };
 
         // Fast path: single param — direct XRANGE id id.
@@ -817,12 +1385,14 @@ impl RedisTableOperations for RedisStreamTable {
             if parse_stream_id_bound(p).is_none() {
                 return Ok(vec![None]);
             }
-            let entries: Vec<(String, Vec<(String, String)>)> = redis::cmd("XRANGE")
+            let raw: RawStreamEntries = redis::cmd("XRANGE")
                 .arg(key_prefix)
                 .arg(p)
                 .arg(p)
                 .query(conn)?;
-            return Ok(vec![entry_to_row(entries.into_iter().next())]);
+            let entries = self.decode_entries(raw)?;
+            let entry = self.with_pending_lookup(conn, key_prefix, entries.into_iter().next())?;
+            return Ok(vec![entry_to_row(entry)]);
         }
 
         // Multi-param: try pipelined XRANGE id id; fall back per-key on cluster.
@@ -837,13 +1407,12 @@ impl RedisTableOperations for RedisStreamTable {
             }
         }
 
-        let mut results: Vec<Option<Vec<String>>> = vec![None; params.len()];
+        let mut results: Vec<Option<Vec<RowValue>>> = vec![None; params.len()];
         if valid_params.is_empty() {
             return Ok(results);
         }
 
-        type StreamEntries = Vec<(String, Vec<(String, String)>)>;
-        let pipe_result: Result<Vec<StreamEntries>, redis::RedisError> = {
+        let pipe_result: Result<Vec<RawStreamEntries>, redis::RedisError> = {
             let mut pipe = redis::pipe();
             for p in &valid_params {
                 pipe.cmd("XRANGE").arg(key_prefix).arg(*p).arg(*p);
@@ -851,7 +1420,7 @@ impl RedisTableOperations for RedisStreamTable {
             pipe.query(conn)
         };
 
-        let per_param_entries: Vec<StreamEntries> = match pipe_result {
+        let per_param_entries: Vec<RawStreamEntries> = match pipe_result {
             Ok(v) => v,
             Err(_e) => {
                 // Pipeline fails on ClusterConnection — fall through to per-key
@@ -859,7 +1428,7 @@ impl RedisTableOperations for RedisStreamTable {
                 // load_with_stream_optimization for the link-error rationale.
                 let mut out = Vec::with_capacity(valid_params.len());
                 for p in &valid_params {
-                    let r: Vec<(String, Vec<(String, String)>)> = redis::cmd("XRANGE")
+                    let r: RawStreamEntries = redis::cmd("XRANGE")
                         .arg(key_prefix)
                         .arg(*p)
                         .arg(*p)
@@ -870,10 +1439,299 @@ impl RedisTableOperations for RedisStreamTable {
             }
         };
 
-        for (idx, entries) in valid_indices.into_iter().zip(per_param_entries) {
-            results[idx] = entry_to_row(entries.into_iter().next());
+        for (idx, raw) in valid_indices.into_iter().zip(per_param_entries) {
+            let entries = self.decode_entries(raw)?;
+            let entry = self.with_pending_lookup(conn, key_prefix, entries.into_iter().next())?;
+            results[idx] = entry_to_row(entry);
         }
 
         Ok(results)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::utils::{cmd_args, row_text};
+
+    fn consumer(create_group: bool, claim_idle_ms: Option<u64>) -> StreamConsumer {
+        StreamConsumer {
+            group: "workers".to_string(),
+            consumer: "w1".to_string(),
+            create_group,
+            claim_idle_ms,
+        }
+    }
+
+    #[test]
+    fn test_read_ops() {
+        assert_eq!(
+            consumer(false, None).read_ops(),
+            vec!["XREADGROUP", "XPENDING"]
+        );
+        assert_eq!(
+            consumer(true, Some(60_000)).read_ops(),
+            vec!["XGROUP CREATE", "XAUTOCLAIM", "XREADGROUP", "XPENDING"]
+        );
+    }
+
+    #[test]
+    fn test_pending_columns_shadow_stream_fields() {
+        let mut t = RedisStreamTable::new(100);
+        t.consumer = Some(consumer(false, None));
+        t.configure(
+            &[
+                "stream_id".to_string(),
+                "event".to_string(),
+                DELIVERY_COUNT_FIELD.to_string(),
+                IDLE_MS_FIELD.to_string(),
+            ],
+            0,
+            None,
+        );
+        let fields = vec![
+            ("event".to_string(), b"signup".to_vec()),
+            (DELIVERY_COUNT_FIELD.to_string(), b"stored".to_vec()),
+        ];
+        t.entries = vec![("1-0".to_string(), with_pending(fields, Some(&(3, 1500))))];
+        let row = t.get_row(0).unwrap();
+        assert_eq!(row_text(&row), ["1-0", "signup", "3", "1500"]);
+    }
+
+    #[test]
+    fn test_xadd_skips_pending_columns() {
+        let mut t = RedisStreamTable::new(100);
+        t.consumer = Some(consumer(false, None));
+        let fields = vec![
+            ("event".to_string(), "signup".to_string()),
+            (DELIVERY_COUNT_FIELD.to_string(), "NULL".to_string()),
+            (IDLE_MS_FIELD.to_string(), "NULL".to_string()),
+        ];
+        let cmd = t.xadd_command("events", "*", &fields, InsertMode::Overwrite);
+        assert_eq!(
+            cmd_args(&cmd),
+            vec!["XADD", "events", "*", "event", "signup"]
+        );
+
+        t.consumer = None;
+        let cmd = t.xadd_command("events", "*", &fields, InsertMode::Overwrite);
+        assert_eq!(cmd_args(&cmd).len(), 9);
+    }
+
+    #[test]
+    fn test_stream_trim_options() {
+        let opts = |pairs: &[(&str, &str)]| -> HashMap<String, String> {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect()
+        };
+        assert_eq!(StreamTrim::from_options(&opts(&[])), Ok(None));
+        assert_eq!(
+            StreamTrim::from_options(&opts(&[("stream_maxlen", "~1000")])),
+            Ok(Some(StreamTrim::MaxLen {
+                count: 1000,
+                approximate: true
+            }))
+        );
+        assert_eq!(
+            StreamTrim::from_options(&opts(&[("stream_minid_age", "60000")])),
+            Ok(Some(StreamTrim::MinIdAge {
+                ms: 60_000,
+                approximate: false
+            }))
+        );
+        assert!(StreamTrim::from_options(&opts(&[("stream_maxlen", "-1")])).is_err());
+        assert!(StreamTrim::from_options(&opts(&[
+            ("stream_maxlen", "10"),
+            ("stream_minid_age", "10")
+        ]))
+        .is_err());
+    }
+
+    #[test]
+    fn test_xadd_applies_trim() {
+        let mut t = RedisStreamTable::new(100);
+        t.trim = Some(StreamTrim::MaxLen {
+            count: 2,
+            approximate: false,
+        });
+        let fields = vec![("event".to_string(), "signup".to_string())];
+        let cmd = t.xadd_command("events", "*", &fields, InsertMode::Xx);
+        assert_eq!(
+            cmd_args(&cmd),
+            vec![
+                "XADD",
+                "events",
+                "NOMKSTREAM",
+                "MAXLEN",
+                "=",
+                "2",
+                "*",
+                "event",
+                "signup"
+            ]
+        );
+
+        let cmd = t.xadd_command("events", "5-0", &fields, InsertMode::Nx);
+        assert_eq!(
+            cmd_args(&cmd)[2..],
+            ["1", "events", "5-0", "MAXLEN", "=", "2", "5-0", "event", "signup"]
+        );
+
+        t.trim = Some(StreamTrim::MinIdAge {
+            ms: 1000,
+            approximate: true,
+        });
+        let trim = t.trim.as_ref().unwrap().args();
+        assert_eq!(trim[..2], ["MINID", "~"]);
+        assert!(trim[2].parse::<u64>().unwrap() > 1_700_000_000_000);
+    }
+
+    #[test]
+    fn test_entry_time() {
+        assert_eq!(
+            entry_time("1700000000123-4").as_deref(),
+            Some("2023-11-14 22:13:20.123+00")
+        );
+        assert_eq!(
+            entry_time("0-1").as_deref(),
+            Some("1970-01-01 00:00:00.000+00")
+        );
+        assert_eq!(
+            entry_time("951782400000").as_deref(),
+            Some("2000-02-29 00:00:00.000+00")
+        );
+        assert_eq!(entry_time("not-an-id"), None);
+    }
+
+    #[test]
+    fn test_entry_time_id_bounds() {
+        use ComparisonOperator::*;
+        let bounds = |op, t| entry_time_id_bounds(&op, t);
+        assert_eq!(
+            bounds(GreaterThanOrEqual, 1_500),
+            vec![(GreaterThanOrEqual, "2".to_string())]
+        );
+        assert_eq!(
+            bounds(GreaterThan, 2_000),
+            vec![(GreaterThanOrEqual, "3".to_string())]
+        );
+        assert_eq!(
+            bounds(LessThan, 2_000),
+            vec![(LessThanOrEqual, "1".to_string())]
+        );
+        assert_eq!(
+            bounds(LessThanOrEqual, 2_500),
+            vec![(LessThanOrEqual, "2".to_string())]
+        );
+        assert_eq!(
+            bounds(Equal, 3_000),
+            vec![
+                (GreaterThanOrEqual, "3".to_string()),
+                (LessThanOrEqual, "3".to_string())
+            ]
+        );
+        assert_eq!(bounds(Equal, 3_001), vec![(LessThan, "0-1".to_string())]);
+        assert_eq!(
+            bounds(LessThan, -5_000),
+            vec![(LessThan, "0-1".to_string())]
+        );
+        assert!(bounds(GreaterThanOrEqual, -5_000).is_empty());
+        assert!(bounds(NotEqual, 3_000).is_empty());
+    }
+
+    #[test]
+    fn test_entry_time_column_is_virtual() {
+        let mut t = RedisStreamTable::new(100);
+        t.configure(
+            &[
+                "stream_id".to_string(),
+                "event".to_string(),
+                ENTRY_TIME_FIELD.to_string(),
+            ],
+            0,
+            None,
+        );
+        t.entries = vec![(
+            "1700000000000-0".to_string(),
+            vec![("event".to_string(), b"signup".to_vec())],
+        )];
+        let row = t.get_row(0).unwrap();
+        assert_eq!(
+            row_text(&row),
+            ["1700000000000-0", "signup", "2023-11-14 22:13:20.000+00"]
+        );
+
+        let fields = vec![
+            ("event".to_string(), "signup".to_string()),
+            (ENTRY_TIME_FIELD.to_string(), "NULL".to_string()),
+        ];
+        let cmd = t.xadd_command("events", "*", &fields, InsertMode::Overwrite);
+        assert_eq!(
+            cmd_args(&cmd),
+            vec!["XADD", "events", "*", "event", "signup"]
+        );
+    }
+
+    fn multi_key_table() -> RedisStreamTable {
+        let mut t = RedisStreamTable::new(100);
+        t.key_column = true;
+        t.configure(
+            &[
+                "key".to_string(),
+                "stream_id".to_string(),
+                "event".to_string(),
+                "user_id".to_string(),
+            ],
+            1,
+            None,
+        );
+        t
+    }
+
+    fn cond(column_index: usize, operator: ComparisonOperator, value: &str) -> PushableCondition {
+        PushableCondition {
+            column_name: String::new(),
+            column_index,
+            operator,
+            value: value.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_multi_key_layout() {
+        let t = multi_key_table();
+        assert_eq!(t.field_columns(), ["event", "user_id"]);
+        assert_eq!(t.multi_key_columns_per_row(), 4);
+    }
+
+    #[test]
+    fn test_set_id_range_from_id_column() {
+        let mut t = multi_key_table();
+        t.set_id_range(&[
+            cond(0, ComparisonOperator::Equal, "events:a"),
+            cond(1, ComparisonOperator::GreaterThan, "1700000000000-0"),
+            cond(1, ComparisonOperator::LessThanOrEqual, "1800000000000"),
+        ]);
+        assert_eq!(
+            t.id_range,
+            ("(1700000000000-0".to_string(), "1800000000000".to_string())
+        );
+
+        t.set_id_range(&[cond(1, ComparisonOperator::GreaterThan, "not-an-id")]);
+        assert_eq!(t.id_range, ("-".to_string(), "+".to_string()));
+    }
+
+    #[test]
+    fn test_multi_key_entry() {
+        let t = multi_key_table();
+        let data = vec!["NULL".to_string(), "signup".to_string(), "NULL".to_string()];
+        let (id, fields) = t.multi_key_entry(&data).unwrap();
+        assert_eq!(id, "*");
+        assert_eq!(fields, vec![("event".to_string(), "signup".to_string())]);
+
+        let data = vec!["5-1".to_string(), "NULL".to_string(), "NULL".to_string()];
+        assert!(t.multi_key_entry(&data).is_none());
+    }
+}
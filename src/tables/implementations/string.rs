@@ -6,7 +6,9 @@ use crate::{
     },
     tables::{
         interface::RedisTableOperations,
-        types::{DataContainer, DataSet, LoadDataResult, RowVec},
+        types::{
+            ColumnEncodings, DataContainer, DataSet, InsertMode, LoadDataResult, RowValue, RowVec,
+        },
     },
 };
 use smallvec::smallvec;
@@ -16,15 +18,84 @@ use std::borrow::Cow;
 #[derive(Debug, Clone, Default)]
 pub struct RedisStringTable {
     pub dataset: DataSet,
+    /// Encoding of the value column
+    pub encodings: ColumnEncodings,
 }
 
 impl RedisStringTable {
     pub fn new() -> Self {
         Self {
             dataset: DataSet::Empty,
+            encodings: ColumnEncodings::default(),
         }
     }
 
+    /// SET for one row: `data[0]` is the value. NX/XX map to the SET flags.
+    pub fn insert_command(
+        &self,
+        key: &str,
+        data: &[String],
+        mode: InsertMode,
+    ) -> Option<redis::Cmd> {
+        let value = data.first()?;
+        let mut cmd = redis::cmd("SET");
+        cmd.arg(key).arg(self.value_arg(value));
+        match mode {
+            InsertMode::Overwrite => {}
+            InsertMode::Nx => {
+                cmd.arg("NX");
+            }
+            InsertMode::Xx => {
+                cmd.arg("XX");
+            }
+        }
+        Some(cmd)
+    }
+
+    /// Bytes sent to Redis for a value.
+    fn value_arg<'a>(&self, value: &'a str) -> Cow<'a, [u8]> {
+        self.encodings.get(0).encode(value)
+    }
+
+    /// Whether a stored value satisfies one pushed-down condition; LIKE is
+    /// left to the caller.
+    fn value_matches(&self, value: &[u8], cond: &PushableCondition) -> bool {
+        match cond.operator {
+            ComparisonOperator::Equal => value == &*self.value_arg(&cond.value),
+            ComparisonOperator::NotEqual => value != &*self.value_arg(&cond.value),
+            ComparisonOperator::In => cond.value.split(',').any(|s| value == &*self.value_arg(s)),
+            _ => true,
+        }
+    }
+
+    /// Row of a key found by a parameterized lookup.
+    fn lookup_row(key: &str, value: Vec<u8>) -> Vec<RowValue> {
+        vec![Some(key.as_bytes().to_vec()), Some(value)]
+    }
+
+    /// GET one key, decoding the value with the value column's encoding.
+    fn get_value(
+        &self,
+        conn: &mut dyn redis::ConnectionLike,
+        key: &str,
+    ) -> Result<Option<Vec<u8>>, redis::RedisError> {
+        let value: Option<Vec<u8>> = redis::cmd("GET").arg(key).query(conn)?;
+        value.map(|v| self.encodings.get(0).decode(v)).transpose()
+    }
+
+    /// MGET `keys`, decoding the values like [`Self::get_value`].
+    fn mget_values(
+        &self,
+        conn: &mut dyn redis::ConnectionLike,
+        keys: &[String],
+    ) -> Result<Vec<Option<Vec<u8>>>, redis::RedisError> {
+        let values: Vec<Option<Vec<u8>>> = redis::cmd("MGET").arg(keys).query(conn)?;
+        values
+            .into_iter()
+            .map(|v| v.map(|v| self.encodings.get(0).decode(v)).transpose())
+            .collect()
+    }
+
     /// Load data with SCAN optimization for value matching
     fn load_with_scan_optimization(
         &mut self,
@@ -35,7 +106,7 @@ impl RedisStringTable {
     ) -> Result<LoadDataResult, redis::RedisError> {
         // For string tables, we need to check the stored value against conditions
         // Get the value from Redis
-        let stored_value: Option<String> = redis::cmd("GET").arg(key_prefix).query(conn)?;
+        let stored_value = self.get_value(conn, key_prefix)?;
 
         if let Some(value) = stored_value {
             // Check if the value matches any of the conditions
@@ -43,7 +114,9 @@ impl RedisStringTable {
 
             // Check exact match conditions
             for condition in &scan_conditions.exact_conditions {
-                if condition.operator == ComparisonOperator::Equal && value != condition.value {
+                if condition.operator == ComparisonOperator::Equal
+                    && !self.value_matches(&value, condition)
+                {
                     matches = false;
                     break;
                 }
@@ -54,7 +127,7 @@ impl RedisStringTable {
                 for condition in &scan_conditions.pattern_conditions {
                     if condition.operator == ComparisonOperator::Like {
                         let pattern_matcher = PatternMatcher::from_like_pattern(&condition.value);
-                        if !pattern_matcher.matches(&value) {
+                        if !pattern_matcher.matches(&String::from_utf8_lossy(&value)) {
                             matches = false;
                             break;
                         }
@@ -115,18 +188,11 @@ impl RedisTableOperations for RedisStringTable {
         }
 
         // Fallback: Load single key without optimization
-        let value: Option<String> = redis::cmd("GET").arg(key_prefix).query(conn)?;
+        let value = self.get_value(conn, key_prefix)?;
 
         // Apply pushdown conditions client-side for the fallback path
         let value = value.filter(|v| {
-            conditions.is_none_or(|conds| {
-                conds.iter().all(|c| match c.operator {
-                    ComparisonOperator::Equal => v == &c.value,
-                    ComparisonOperator::NotEqual => v != &c.value,
-                    ComparisonOperator::In => c.value.split(',').any(|s| s == v),
-                    _ => true,
-                })
-            })
+            conditions.is_none_or(|conds| conds.iter().all(|c| self.value_matches(v, c)))
         });
 
         // Apply LIMIT/OFFSET constraints - for string tables, OFFSET > 0 means no results
@@ -161,8 +227,8 @@ impl RedisTableOperations for RedisStringTable {
                 let data_index = index * 2;
                 if data_index + 1 < data.len() {
                     Some(smallvec![
-                        Cow::Borrowed(data[data_index].as_str()),
-                        Cow::Borrowed(data[data_index + 1].as_str()),
+                        data[data_index].as_deref().map(Cow::Borrowed),
+                        data[data_index + 1].as_deref().map(Cow::Borrowed),
                     ])
                 } else {
                     None
@@ -186,11 +252,13 @@ impl RedisTableOperations for RedisStringTable {
         conn: &mut dyn redis::ConnectionLike,
         key_prefix: &str,
         data: &[String],
-    ) -> Result<(), redis::RedisError> {
-        if let Some(value) = data.first() {
-            let _: () = redis::cmd("SET").arg(key_prefix).arg(value).query(conn)?;
-        }
-        Ok(())
+        mode: InsertMode,
+    ) -> Result<bool, redis::RedisError> {
+        let Some(cmd) = self.insert_command(key_prefix, data, mode) else {
+            return Ok(false);
+        };
+        let reply: redis::Value = cmd.query(conn)?;
+        Ok(InsertMode::reply_applied(&reply))
     }
 
     fn delete(
@@ -211,7 +279,10 @@ impl RedisTableOperations for RedisStringTable {
         new_data: &[String],
     ) -> Result<(), redis::RedisError> {
         if let Some(value) = new_data.first() {
-            let _: () = redis::cmd("SET").arg(key_prefix).arg(value).query(conn)?;
+            let _: () = redis::cmd("SET")
+                .arg(key_prefix)
+                .arg(self.value_arg(value))
+                .query(conn)?;
         }
         Ok(())
     }
@@ -232,7 +303,7 @@ impl RedisTableOperations for RedisStringTable {
         conditions: Option<&[PushableCondition]>,
     ) -> Result<(u64, usize), redis::RedisError> {
         // String type is always a single value — no cursor needed
-        let value: Option<String> = redis::cmd("GET").arg(key_prefix).query(conn)?;
+        let value = self.get_value(conn, key_prefix)?;
 
         // Apply conditions client-side on the single value
         let value = value.filter(|v| {
@@ -244,14 +315,11 @@ impl RedisTableOperations for RedisStringTable {
                     .map(|(i, c)| (i, PatternMatcher::from_like_pattern(&c.value)))
                     .collect();
                 conds.iter().enumerate().all(|(i, c)| match c.operator {
-                    ComparisonOperator::Equal => v == &c.value,
-                    ComparisonOperator::NotEqual => v != &c.value,
                     ComparisonOperator::Like => like_matchers
                         .iter()
                         .find(|(idx, _)| *idx == i)
-                        .is_some_and(|(_, m)| m.matches(v)),
-                    ComparisonOperator::In => c.value.split(',').any(|s| s == v),
-                    _ => true,
+                        .is_some_and(|(_, m)| m.matches(&String::from_utf8_lossy(v))),
+                    _ => self.value_matches(v, c),
                 })
             })
         });
@@ -265,13 +333,13 @@ impl RedisTableOperations for RedisStringTable {
         &mut self,
         conn: &mut dyn redis::ConnectionLike,
         keys: &[String],
-    ) -> Result<Vec<String>, redis::RedisError> {
-        let values: Vec<Option<String>> = redis::cmd("MGET").arg(keys).query(conn)?;
+    ) -> Result<Vec<RowValue>, redis::RedisError> {
+        let values = self.mget_values(conn, keys)?;
         let mut all_rows = Vec::with_capacity(keys.len() * self.multi_key_columns_per_row());
         for (key, value) in keys.iter().zip(values) {
             if let Some(v) = value {
-                all_rows.push(key.clone());
-                all_rows.push(v);
+                all_rows.push(Some(key.clone().into_bytes()));
+                all_rows.push(Some(v));
             }
         }
         Ok(all_rows)
@@ -285,16 +353,20 @@ impl RedisTableOperations for RedisStringTable {
         "string"
     }
 
-    fn set_filtered_data(&mut self, data: Vec<String>) {
+    fn set_filtered_data(&mut self, data: Vec<RowValue>) {
         self.dataset = DataSet::Filtered(data);
     }
 
+    fn set_column_encodings(&mut self, encodings: ColumnEncodings) {
+        self.encodings = encodings;
+    }
+
     fn batch_parameterized_lookup(
         &mut self,
         conn: &mut dyn redis::ConnectionLike,
         _key_prefix: &str,
         params: &[String],
-    ) -> Result<Vec<Option<Vec<String>>>, redis::RedisError> {
+    ) -> Result<Vec<Option<Vec<RowValue>>>, redis::RedisError> {
         if params.is_empty() {
             return Ok(Vec::new());
         }
@@ -303,25 +375,24 @@ impl RedisTableOperations for RedisStringTable {
         // GET is lighter than MGET when there's only one key.
         if params.len() == 1 {
             let p = &params[0];
-            let v: Option<String> = redis::cmd("GET").arg(p).query(conn)?;
-            return Ok(vec![v.map(|val| vec![p.clone(), val])]);
+            let v = self.get_value(conn, p)?;
+            return Ok(vec![v.map(|val| Self::lookup_row(p, val))]);
         }
 
-        let pipeline_result: Result<Vec<Option<String>>, redis::RedisError> =
-            redis::cmd("MGET").arg(params).query(conn);
+        let pipeline_result = self.mget_values(conn, params);
 
         match pipeline_result {
             Ok(values) => Ok(values
                 .into_iter()
                 .zip(params.iter())
-                .map(|(v, p)| v.map(|val| vec![p.clone(), val]))
+                .map(|(v, p)| v.map(|val| Self::lookup_row(p, val)))
                 .collect()),
             Err(e) => {
                 pgrx::log!("redis_fdw: MGET failed, falling back per-key: {}", e);
                 let mut out = Vec::with_capacity(params.len());
                 for p in params {
-                    let v: Option<String> = redis::cmd("GET").arg(p).query(conn)?;
-                    out.push(v.map(|val| vec![p.clone(), val]));
+                    let v = self.get_value(conn, p)?;
+                    out.push(v.map(|val| Self::lookup_row(p, val)));
                 }
                 Ok(out)
             }
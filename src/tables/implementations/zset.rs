@@ -8,11 +8,19 @@ use crate::{
     },
     tables::{
         interface::RedisTableOperations,
-        types::{DataContainer, DataSet, LoadDataResult, RowVec},
+        types::{
+            ColumnEncodings, DataContainer, DataSet, InsertMode, LoadDataResult, RowValue, RowVec,
+            ValueEncoding,
+        },
     },
 };
 use smallvec::smallvec;
 
+/// ZADD only when the member already exists, reporting whether it did.
+/// `ZADD XX` alone replies 0 either way, so it cannot tell a skip apart.
+const ZADD_XX_SCRIPT: &str = "if redis.call('ZSCORE', KEYS[1], ARGV[2]) then \
+     redis.call('ZADD', KEYS[1], ARGV[1], ARGV[2]) return 1 end return 0";
+
 /// Parse a ZRANGEBYSCORE bound string into f64 for comparison.
 fn parse_bound(s: &str, default: f64) -> f64 {
     let trimmed = s.strip_prefix('(').unwrap_or(s);
@@ -23,12 +31,19 @@ fn parse_bound(s: &str, default: f64) -> f64 {
     }
 }
 
+/// Member bytes with the score Redis replied.
+type ScoredMember = (Vec<u8>, f64);
+
 /// Redis Sorted Set table type
 #[derive(Debug, Clone, Default)]
 pub struct RedisZSetTable {
     pub dataset: DataSet,
     pub pushdown_column_index: usize,
     pub score_column_index: usize,
+    /// Encoding of the member column
+    pub encodings: ColumnEncodings,
+    /// Score sent by the last `insert`/`update` that Redis applied
+    pub last_written_score: Option<f64>,
 }
 
 impl RedisZSetTable {
@@ -37,9 +52,87 @@ impl RedisZSetTable {
             dataset: DataSet::Empty,
             pushdown_column_index: 0,
             score_column_index: 1,
+            encodings: ColumnEncodings::default(),
+            last_written_score: None,
         }
     }
 
+    /// Write command for one row of `[member, score, ...]` pairs; pairs with
+    /// an unparseable score are dropped. NX uses `ZADD NX`, XX applies the
+    /// first pair through [`ZADD_XX_SCRIPT`].
+    pub fn insert_command(
+        &self,
+        key: &str,
+        data: &[String],
+        mode: InsertMode,
+    ) -> Option<redis::Cmd> {
+        let items: Vec<(f64, &str)> = data
+            .chunks_exact(2)
+            .filter_map(|pair| pair[1].parse::<f64>().ok().map(|s| (s, pair[0].as_str())))
+            .collect();
+        let (first_score, first_member) = *items.first()?;
+
+        let cmd = match mode {
+            InsertMode::Overwrite | InsertMode::Nx => {
+                let mut cmd = redis::cmd("ZADD");
+                cmd.arg(key);
+                if mode == InsertMode::Nx {
+                    cmd.arg("NX");
+                }
+                for (score, member) in &items {
+                    cmd.arg(*score).arg(self.member_arg(member));
+                }
+                cmd
+            }
+            InsertMode::Xx => {
+                let mut cmd = redis::cmd("EVAL");
+                cmd.arg(ZADD_XX_SCRIPT)
+                    .arg(1)
+                    .arg(key)
+                    .arg(first_score)
+                    .arg(self.member_arg(first_member));
+                cmd
+            }
+        };
+        Some(cmd)
+    }
+
+    /// Bytes sent to Redis for a member.
+    fn member_arg<'a>(&self, member: &'a str) -> Cow<'a, [u8]> {
+        self.encodings.get(0).encode(member)
+    }
+
+    /// Filtered-data entries of a member and its score.
+    fn scored_row(&self, member: &str, score: f64) -> [RowValue; 2] {
+        [
+            Some(self.member_arg(member).into_owned()),
+            Some(score.to_string().into_bytes()),
+        ]
+    }
+
+    /// Decode a flat `[member, score, ...]` reply; scores are always text.
+    fn decode_flat(&self, flat: Vec<Vec<u8>>) -> redis::RedisResult<Vec<Vec<u8>>> {
+        let encoding = self.encodings.get(0);
+        flat.into_iter()
+            .enumerate()
+            .map(|(i, v)| {
+                if i % 2 == 0 {
+                    encoding.decode(v)
+                } else {
+                    ValueEncoding::Text.decode(v)
+                }
+            })
+            .collect()
+    }
+
+    fn decode_scored(&self, items: Vec<ScoredMember>) -> redis::RedisResult<Vec<ScoredMember>> {
+        let encoding = self.encodings.get(0);
+        items
+            .into_iter()
+            .map(|(member, score)| Ok((encoding.decode(member)?, score)))
+            .collect()
+    }
+
     fn load_with_score_range(
         &mut self,
         conn: &mut dyn redis::ConnectionLike,
@@ -128,13 +221,14 @@ impl RedisZSetTable {
             cmd.arg("LIMIT").arg(offset).arg(limit);
         }
 
-        let result: Vec<String> = cmd.query(conn)?;
+        let raw: Vec<Vec<u8>> = cmd.query(conn)?;
+        let result = self.decode_flat(raw)?;
 
         if result.is_empty() {
             self.dataset = DataSet::Empty;
             Ok(LoadDataResult::Empty)
         } else {
-            self.dataset = DataSet::Filtered(result);
+            self.dataset = DataSet::Filtered(result.into_iter().map(Some).collect());
             Ok(LoadDataResult::FullyLoaded)
         }
     }
@@ -156,9 +250,10 @@ impl RedisZSetTable {
         }
 
         // Add limit information to the scan builder
-        let all_members: Vec<String> = scan_builder
+        let raw_members: Vec<Vec<u8>> = scan_builder
             .with_limit(limit_offset.clone())
             .execute_all(conn)?;
+        let all_members = self.decode_flat(raw_members)?;
 
         let mut filtered_data = Vec::new();
 
@@ -175,7 +270,7 @@ impl RedisZSetTable {
                 for condition in &scan_conditions.pattern_conditions {
                     if condition.operator == ComparisonOperator::Like {
                         if let Some(matcher) = &scan_conditions.pattern_matcher {
-                            if !matcher.matches(member) {
+                            if !matcher.matches(&String::from_utf8_lossy(member)) {
                                 matches = false;
                                 break;
                             }
@@ -185,7 +280,8 @@ impl RedisZSetTable {
 
                 // Check exact conditions
                 for condition in &scan_conditions.exact_conditions {
-                    if condition.operator == ComparisonOperator::Equal && member != &condition.value
+                    if condition.operator == ComparisonOperator::Equal
+                        && *member != *self.member_arg(&condition.value)
                     {
                         matches = false;
                         break;
@@ -193,8 +289,8 @@ impl RedisZSetTable {
                 }
 
                 if matches {
-                    filtered_data.push(member.clone());
-                    filtered_data.push(score.clone());
+                    filtered_data.push(Some(member.clone()));
+                    filtered_data.push(Some(score.clone()));
                 }
             }
         }
@@ -245,13 +341,12 @@ impl RedisTableOperations for RedisZSetTable {
                             // Check if member exists and get its score
                             let score: Option<f64> = redis::cmd("ZSCORE")
                                 .arg(key_prefix)
-                                .arg(&condition.value)
+                                .arg(self.member_arg(&condition.value))
                                 .query(conn)?;
 
                             return if let Some(score) = score {
-                                let filtered_data =
-                                    vec![condition.value.clone(), score.to_string()];
-                                self.dataset = DataSet::Filtered(filtered_data);
+                                let filtered_data = self.scored_row(&condition.value, score);
+                                self.dataset = DataSet::Filtered(filtered_data.into());
                                 Ok(LoadDataResult::FullyLoaded)
                             } else {
                                 self.dataset = DataSet::Empty;
@@ -264,17 +359,18 @@ impl RedisTableOperations for RedisZSetTable {
                             let mut result = Vec::new();
 
                             // Try ZMSCORE first (single command, single round-trip)
-                            let zmscore_result: Result<Vec<Option<f64>>, _> = redis::cmd("ZMSCORE")
-                                .arg(key_prefix)
-                                .arg(&members)
-                                .query(conn);
+                            let mut zmscore = redis::cmd("ZMSCORE");
+                            zmscore.arg(key_prefix);
+                            for member in &members {
+                                zmscore.arg(self.member_arg(member));
+                            }
+                            let zmscore_result: Result<Vec<Option<f64>>, _> = zmscore.query(conn);
 
                             match zmscore_result {
                                 Ok(scores) => {
                                     for (member, score) in members.iter().zip(scores.iter()) {
                                         if let Some(s) = score {
-                                            result.push(member.to_string());
-                                            result.push(s.to_string());
+                                            result.extend(self.scored_row(member, *s));
                                         }
                                     }
                                 }
@@ -283,7 +379,9 @@ impl RedisTableOperations for RedisZSetTable {
                                     let pipe_result: Result<Vec<Option<f64>>, _> = {
                                         let mut pipe = redis::pipe();
                                         for member in &members {
-                                            pipe.cmd("ZSCORE").arg(key_prefix).arg(*member);
+                                            pipe.cmd("ZSCORE")
+                                                .arg(key_prefix)
+                                                .arg(self.member_arg(member));
                                         }
                                         pipe.query(conn)
                                     };
@@ -293,8 +391,7 @@ impl RedisTableOperations for RedisZSetTable {
                                             for (member, score) in members.iter().zip(scores.iter())
                                             {
                                                 if let Some(s) = score {
-                                                    result.push(member.to_string());
-                                                    result.push(s.to_string());
+                                                    result.extend(self.scored_row(member, *s));
                                                 }
                                             }
                                         }
@@ -303,11 +400,10 @@ impl RedisTableOperations for RedisZSetTable {
                                             for member in &members {
                                                 let score: Option<f64> = redis::cmd("ZSCORE")
                                                     .arg(key_prefix)
-                                                    .arg(*member)
+                                                    .arg(self.member_arg(member))
                                                     .query(conn)?;
                                                 if let Some(s) = score {
-                                                    result.push(member.to_string());
-                                                    result.push(s.to_string());
+                                                    result.extend(self.scored_row(member, s));
                                                 }
                                             }
                                         }
@@ -374,18 +470,19 @@ impl RedisTableOperations for RedisZSetTable {
             (0, -1)
         };
 
-        let result: Vec<(String, f64)> = redis::cmd("ZRANGE")
+        let raw: Vec<ScoredMember> = redis::cmd("ZRANGE")
             .arg(key_prefix)
             .arg(start)
             .arg(end)
             .arg("WITHSCORES")
             .query(conn)?;
+        let result = self.decode_scored(raw)?;
 
         if limit_offset.has_constraints() {
             // Convert to filtered format for efficient access
-            let flat_data: Vec<String> = result
+            let flat_data: Vec<RowValue> = result
                 .into_iter()
-                .flat_map(|(member, score)| vec![member, score.to_string()])
+                .flat_map(|(member, score)| [Some(member), Some(score.to_string().into_bytes())])
                 .collect();
             self.dataset = DataSet::Filtered(flat_data);
         } else {
@@ -407,8 +504,8 @@ impl RedisTableOperations for RedisZSetTable {
                 let data_index = index * 2;
                 if data_index + 1 < data.len() {
                     Some(smallvec![
-                        Cow::Borrowed(data[data_index].as_str()),
-                        Cow::Borrowed(data[data_index + 1].as_str()),
+                        data[data_index].as_deref().map(Cow::Borrowed),
+                        data[data_index + 1].as_deref().map(Cow::Borrowed),
                     ])
                 } else {
                     None
@@ -432,33 +529,20 @@ impl RedisTableOperations for RedisZSetTable {
         conn: &mut dyn redis::ConnectionLike,
         key_prefix: &str,
         data: &[String],
-    ) -> Result<(), redis::RedisError> {
-        // Expect data in pairs: [member1, score1, member2, score2, ...]
-        let items: Vec<(f64, &str)> = data
-            .chunks(2)
-            .filter_map(|chunk| {
-                if chunk.len() == 2 {
-                    if let Ok(score) = chunk[1].parse::<f64>() {
-                        Some((score, chunk[0].as_str()))
-                    } else {
-                        None
-                    }
-                } else {
-                    None
-                }
-            })
-            .collect();
-
-        if !items.is_empty() {
-            // Single ZADD with all score-member pairs
-            let mut cmd = redis::cmd("ZADD");
-            cmd.arg(key_prefix);
-            for (score, member) in &items {
-                cmd.arg(*score).arg(*member);
-            }
-            let _: () = cmd.query(conn)?;
+        mode: InsertMode,
+    ) -> Result<bool, redis::RedisError> {
+        self.last_written_score = None;
+        let Some(cmd) = self.insert_command(key_prefix, data, mode) else {
+            return Ok(false);
+        };
+        let reply: redis::Value = cmd.query(conn)?;
+        let applied = mode == InsertMode::Overwrite || InsertMode::reply_applied(&reply);
+        if applied {
+            // Redis stores the double as sent, so this is what ZSCORE would
+            // reply without a second round trip that could see a later write.
+            self.last_written_score = data.get(1).and_then(|s| s.parse().ok());
         }
-        Ok(())
+        Ok(applied)
     }
 
     fn delete(
@@ -469,7 +553,12 @@ impl RedisTableOperations for RedisZSetTable {
     ) -> Result<(), redis::RedisError> {
         if !data.is_empty() {
             // Single ZREM with all members
-            let _: i32 = redis::cmd("ZREM").arg(key_prefix).arg(data).query(conn)?;
+            let mut cmd = redis::cmd("ZREM");
+            cmd.arg(key_prefix);
+            for member in data {
+                cmd.arg(self.member_arg(member));
+            }
+            let _: i32 = cmd.query(conn)?;
         }
         Ok(())
     }
@@ -482,6 +571,7 @@ impl RedisTableOperations for RedisZSetTable {
         new_data: &[String],
     ) -> Result<(), redis::RedisError> {
         // old_data: [member], new_data: [member, score]
+        self.last_written_score = None;
         if new_data.len() >= 2 {
             let new_member = &new_data[0];
             let new_score: f64 = new_data[1]
@@ -501,12 +591,13 @@ impl RedisTableOperations for RedisZSetTable {
                         .atomic()
                         .cmd("ZREM")
                         .arg(key_prefix)
-                        .arg(old_member)
+                        .arg(self.member_arg(old_member))
                         .cmd("ZADD")
                         .arg(key_prefix)
                         .arg(new_score)
-                        .arg(new_member)
+                        .arg(self.member_arg(new_member))
                         .query::<()>(conn)?;
+                    self.last_written_score = Some(new_score);
                     return Ok(());
                 }
             }
@@ -515,8 +606,9 @@ impl RedisTableOperations for RedisZSetTable {
             let _: () = redis::cmd("ZADD")
                 .arg(key_prefix)
                 .arg(new_score)
-                .arg(new_member)
+                .arg(self.member_arg(new_member))
                 .query(conn)?;
+            self.last_written_score = Some(new_score);
         }
         Ok(())
     }
@@ -570,12 +662,13 @@ impl RedisTableOperations for RedisZSetTable {
         }
         cmd.arg("COUNT").arg(batch_size);
 
-        let (new_cursor, flat_data): (u64, Vec<String>) = cmd.query(conn)?;
+        let (new_cursor, raw_data): (u64, Vec<Vec<u8>>) = cmd.query(conn)?;
+        let flat_data = self.decode_flat(raw_data)?;
 
         // Apply member conditions as client-side post-filter
         // Note: Only the first LIKE condition was used for server-side MATCH;
         // all other conditions (including additional LIKEs) must be verified here
-        let filtered: Vec<String> = if let Some(conds) = member_conds {
+        let filtered: Vec<Vec<u8>> = if let Some(conds) = member_conds {
             if conds.is_empty() {
                 flat_data
             } else {
@@ -598,11 +691,11 @@ impl RedisTableOperations for RedisZSetTable {
                         )
                     })
                     .collect();
-                let in_value_sets: Vec<Vec<&str>> = conds
+                let in_value_sets: Vec<Vec<Cow<[u8]>>> = conds
                     .iter()
                     .map(|c| {
                         if c.operator == ComparisonOperator::In {
-                            c.value.split(',').collect()
+                            c.value.split(',').map(|v| self.member_arg(v)).collect()
                         } else {
                             Vec::new()
                         }
@@ -614,10 +707,12 @@ impl RedisTableOperations for RedisZSetTable {
                         if chunk.len() == 2 {
                             let member = &chunk[0];
                             conds.iter().enumerate().all(|(i, c)| match c.operator {
-                                ComparisonOperator::Equal => member == &c.value,
-                                ComparisonOperator::NotEqual => member != &c.value,
+                                ComparisonOperator::Equal => *member == *self.member_arg(&c.value),
+                                ComparisonOperator::NotEqual => {
+                                    *member != *self.member_arg(&c.value)
+                                }
                                 ComparisonOperator::In => {
-                                    in_value_sets[i].contains(&member.as_str())
+                                    in_value_sets[i].iter().any(|v| **v == **member)
                                 }
                                 ComparisonOperator::Like => {
                                     if Some(&c.value) == first_like_value {
@@ -626,7 +721,9 @@ impl RedisTableOperations for RedisZSetTable {
                                         extra_like_matchers
                                             .iter()
                                             .find(|(v, _)| *v == c.value.as_str())
-                                            .is_some_and(|(_, m)| m.matches(member))
+                                            .is_some_and(|(_, m)| {
+                                                m.matches(&String::from_utf8_lossy(member))
+                                            })
                                     }
                                 }
                                 _ => true,
@@ -646,7 +743,7 @@ impl RedisTableOperations for RedisZSetTable {
         self.dataset = if filtered.is_empty() {
             DataSet::Empty
         } else {
-            DataSet::Filtered(filtered)
+            DataSet::Filtered(filtered.into_iter().map(Some).collect())
         };
         Ok((new_cursor, row_count))
     }
@@ -665,10 +762,10 @@ impl RedisTableOperations for RedisZSetTable {
         &mut self,
         conn: &mut dyn redis::ConnectionLike,
         keys: &[String],
-    ) -> Result<Vec<String>, redis::RedisError> {
+    ) -> Result<Vec<RowValue>, redis::RedisError> {
         const PER_KEY_WARN_THRESHOLD: usize = 200_000;
 
-        let pipe_result: Result<Vec<Vec<(String, f64)>>, _> = {
+        let pipe_result: Result<Vec<Vec<ScoredMember>>, _> = {
             let mut pipe = redis::pipe();
             for key in keys {
                 pipe.cmd("ZRANGE")
@@ -685,7 +782,7 @@ impl RedisTableOperations for RedisZSetTable {
             Err(_) => {
                 let mut results = Vec::with_capacity(keys.len());
                 for key in keys {
-                    let r: Vec<(String, f64)> = redis::cmd("ZRANGE")
+                    let r: Vec<ScoredMember> = redis::cmd("ZRANGE")
                         .arg(key)
                         .arg(0i64)
                         .arg(-1i64)
@@ -707,10 +804,10 @@ impl RedisTableOperations for RedisZSetTable {
                     members.len()
                 );
             }
-            for (member, score) in members {
-                all_rows.push(key.clone());
-                all_rows.push(member);
-                all_rows.push(score.to_string());
+            for (member, score) in self.decode_scored(members)? {
+                all_rows.push(Some(key.clone().into_bytes()));
+                all_rows.push(Some(member));
+                all_rows.push(Some(score.to_string().into_bytes()));
             }
         }
         Ok(all_rows)
@@ -724,7 +821,7 @@ impl RedisTableOperations for RedisZSetTable {
         "zset"
     }
 
-    fn set_filtered_data(&mut self, data: Vec<String>) {
+    fn set_filtered_data(&mut self, data: Vec<RowValue>) {
         self.dataset = DataSet::Filtered(data);
     }
 
@@ -732,12 +829,16 @@ impl RedisTableOperations for RedisZSetTable {
         3
     }
 
+    fn set_column_encodings(&mut self, encodings: ColumnEncodings) {
+        self.encodings = encodings;
+    }
+
     fn batch_parameterized_lookup(
         &mut self,
         conn: &mut dyn redis::ConnectionLike,
         key_prefix: &str,
         params: &[String],
-    ) -> Result<Vec<Option<Vec<String>>>, redis::RedisError> {
+    ) -> Result<Vec<Option<Vec<RowValue>>>, redis::RedisError> {
         if params.is_empty() {
             return Ok(Vec::new());
         }
@@ -747,8 +848,11 @@ impl RedisTableOperations for RedisZSetTable {
         // failure on ClusterConnection.
         if params.len() == 1 {
             let p = &params[0];
-            let score: Option<f64> = redis::cmd("ZSCORE").arg(key_prefix).arg(p).query(conn)?;
-            return Ok(vec![score.map(|s| vec![p.clone(), s.to_string()])]);
+            let score: Option<f64> = redis::cmd("ZSCORE")
+                .arg(key_prefix)
+                .arg(self.member_arg(p))
+                .query(conn)?;
+            return Ok(vec![score.map(|s| self.scored_row(p, s).into())]);
         }
 
         // ZSCORE is O(1) per param. A score-range WHERE through the join is
@@ -758,7 +862,7 @@ impl RedisTableOperations for RedisZSetTable {
         // do NOT issue ZRANGEBYSCORE in the per-param join path.
         let mut pipe = redis::pipe();
         for p in params {
-            pipe.cmd("ZSCORE").arg(key_prefix).arg(p);
+            pipe.cmd("ZSCORE").arg(key_prefix).arg(self.member_arg(p));
         }
         let pipeline_result: Result<Vec<Option<f64>>, redis::RedisError> = pipe.query(conn);
 
@@ -771,7 +875,10 @@ impl RedisTableOperations for RedisZSetTable {
                 );
                 let mut out = Vec::with_capacity(params.len());
                 for p in params {
-                    let s: Option<f64> = redis::cmd("ZSCORE").arg(key_prefix).arg(p).query(conn)?;
+                    let s: Option<f64> = redis::cmd("ZSCORE")
+                        .arg(key_prefix)
+                        .arg(self.member_arg(p))
+                        .query(conn)?;
                     out.push(s);
                 }
                 out
@@ -781,7 +888,7 @@ impl RedisTableOperations for RedisZSetTable {
         Ok(scores
             .into_iter()
             .zip(params.iter())
-            .map(|(s, p)| s.map(|score| vec![p.clone(), score.to_string()]))
+            .map(|(s, p)| s.map(|score| self.scored_row(p, score).into()))
             .collect())
     }
 }
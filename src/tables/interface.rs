@@ -3,7 +3,7 @@ use crate::{
         limit::LimitOffsetInfo,
         pushdown_types::{ComparisonOperator, PushableCondition},
     },
-    tables::types::{DataSet, LoadDataResult, RowVec},
+    tables::types::{ColumnEncodings, DataSet, InsertMode, LoadDataResult, RowValue, RowVec},
 };
 
 /// Trait defining common operations for Redis table types
@@ -38,19 +38,22 @@ pub trait RedisTableOperations {
         self.get_dataset().len()
     }
 
-    /// Get a row at the specified index for iteration - returns borrowed strings to avoid cloning
+    /// Get a row at the specified index for iteration - returns borrowed values to avoid cloning
     #[inline]
     fn get_row(&self, index: usize) -> Option<RowVec<'_>> {
         self.get_dataset().get_row(index)
     }
 
-    /// Insert data into Redis
+    /// Insert data into Redis honoring `mode`.
+    /// Returns `false` when the row was skipped because of the mode (target
+    /// already present for `Nx`, absent for `Xx`).
     fn insert(
         &mut self,
         conn: &mut dyn redis::ConnectionLike,
         key_prefix: &str,
         data: &[String],
-    ) -> Result<(), redis::RedisError>;
+        mode: InsertMode,
+    ) -> Result<bool, redis::RedisError>;
 
     /// Delete data from Redis
     fn delete(
@@ -81,13 +84,17 @@ pub trait RedisTableOperations {
     ) {
     }
 
+    /// Set which data columns carry raw bytes. Reads decode and writes encode
+    /// the values of those columns with [`crate::tables::types::ValueEncoding`].
+    fn set_column_encodings(&mut self, _encodings: ColumnEncodings) {}
+
     /// Load data for multiple keys in multi-key mode.
-    /// Returns flat Vec<String> with [key, col1, col2, ...] repeated per row.
+    /// Returns flat values with [key, col1, col2, ...] repeated per row.
     fn load_multi_key_data(
         &mut self,
         conn: &mut dyn redis::ConnectionLike,
         keys: &[String],
-    ) -> Result<Vec<String>, redis::RedisError>;
+    ) -> Result<Vec<RowValue>, redis::RedisError>;
 
     /// Reset internal dataset state for rescan.
     fn clear(&mut self);
@@ -96,7 +103,7 @@ pub trait RedisTableOperations {
     fn redis_type_name(&self) -> &'static str;
 
     /// Set flat multi-key filtered data directly.
-    fn set_filtered_data(&mut self, data: Vec<String>);
+    fn set_filtered_data(&mut self, data: Vec<RowValue>);
 
     /// Get number of columns per row in multi-key flat format.
     fn multi_key_columns_per_row(&self) -> usize {
@@ -115,7 +122,7 @@ pub trait RedisTableOperations {
     /// `RedisFdwState` routes the per-key fallback explicitly when a type
     /// opts out — the trait does not call back into the single-lookup path.
     ///
-    /// Returns `Ok(Vec<Option<Vec<String>>>)` of the same length as `params`:
+    /// Returns `Ok(Vec<Option<Vec<RowValue>>>)` of the same length as `params`:
     /// each element is `Some(row)` on hit, `None` on miss. Row layout matches
     /// the single-row dataset layout for that table type. On Redis error,
     /// returns `Err` so the caller can decide whether to abort the transaction
@@ -126,7 +133,7 @@ pub trait RedisTableOperations {
         conn: &mut dyn redis::ConnectionLike,
         key_prefix: &str,
         params: &[String],
-    ) -> Result<Vec<Option<Vec<String>>>, redis::RedisError> {
+    ) -> Result<Vec<Option<Vec<RowValue>>>, redis::RedisError> {
         let _ = (conn, key_prefix);
         Ok(vec![None; params.len()])
     }
@@ -174,7 +181,8 @@ mod batch_lookup_trait_tests {
             _conn: &mut dyn redis::ConnectionLike,
             _key_prefix: &str,
             _data: &[String],
-        ) -> Result<(), redis::RedisError> {
+            _mode: InsertMode,
+        ) -> Result<bool, redis::RedisError> {
             unreachable!("stub")
         }
         fn delete(
@@ -201,14 +209,14 @@ mod batch_lookup_trait_tests {
             &mut self,
             _conn: &mut dyn redis::ConnectionLike,
             _keys: &[String],
-        ) -> Result<Vec<String>, redis::RedisError> {
+        ) -> Result<Vec<RowValue>, redis::RedisError> {
             unreachable!("stub")
         }
         fn clear(&mut self) {}
         fn redis_type_name(&self) -> &'static str {
             "stub"
         }
-        fn set_filtered_data(&mut self, _data: Vec<String>) {}
+        fn set_filtered_data(&mut self, _data: Vec<RowValue>) {}
         // batch_parameterized_lookup intentionally NOT overridden — that's the SUT.
     }
 
@@ -11,6 +11,14 @@ macro_rules! table_dispatch {
             crate::tables::types::RedisTableType::Set(table) => table.$method($($args),*),
             crate::tables::types::RedisTableType::ZSet(table) => table.$method($($args),*),
             crate::tables::types::RedisTableType::Stream(table) => table.$method($($args),*),
+            crate::tables::types::RedisTableType::HashRow(table) => table.$method($($args),*),
+            crate::tables::types::RedisTableType::Json(table) => table.$method($($args),*),
+            crate::tables::types::RedisTableType::Geo(table) => table.$method($($args),*),
+            crate::tables::types::RedisTableType::Bitmap(table) => table.$method($($args),*),
+            crate::tables::types::RedisTableType::HyperLogLog(table) => table.$method($($args),*),
+            crate::tables::types::RedisTableType::PubSub(table) => table.$method($($args),*),
+            crate::tables::types::RedisTableType::Keyspace(table) => table.$method($($args),*),
+            crate::tables::types::RedisTableType::Function(table) => table.$method($($args),*),
             crate::tables::types::RedisTableType::None => {
                 Default::default()
             }
@@ -24,6 +32,14 @@ macro_rules! table_dispatch {
             crate::tables::types::RedisTableType::Set(table) => table.$method($($args),*),
             crate::tables::types::RedisTableType::ZSet(table) => table.$method($($args),*),
             crate::tables::types::RedisTableType::Stream(table) => table.$method($($args),*),
+            crate::tables::types::RedisTableType::HashRow(table) => table.$method($($args),*),
+            crate::tables::types::RedisTableType::Json(table) => table.$method($($args),*),
+            crate::tables::types::RedisTableType::Geo(table) => table.$method($($args),*),
+            crate::tables::types::RedisTableType::Bitmap(table) => table.$method($($args),*),
+            crate::tables::types::RedisTableType::HyperLogLog(table) => table.$method($($args),*),
+            crate::tables::types::RedisTableType::PubSub(table) => table.$method($($args),*),
+            crate::tables::types::RedisTableType::Keyspace(table) => table.$method($($args),*),
+            crate::tables::types::RedisTableType::Function(table) => table.$method($($args),*),
             crate::tables::types::RedisTableType::None => $default,
         }
     };
@@ -39,6 +55,14 @@ macro_rules! table_dispatch_mut_result {
             crate::tables::types::RedisTableType::Set(table) => table.$method($($args),*),
             crate::tables::types::RedisTableType::ZSet(table) => table.$method($($args),*),
             crate::tables::types::RedisTableType::Stream(table) => table.$method($($args),*),
+            crate::tables::types::RedisTableType::HashRow(table) => table.$method($($args),*),
+            crate::tables::types::RedisTableType::Json(table) => table.$method($($args),*),
+            crate::tables::types::RedisTableType::Geo(table) => table.$method($($args),*),
+            crate::tables::types::RedisTableType::Bitmap(table) => table.$method($($args),*),
+            crate::tables::types::RedisTableType::HyperLogLog(table) => table.$method($($args),*),
+            crate::tables::types::RedisTableType::PubSub(table) => table.$method($($args),*),
+            crate::tables::types::RedisTableType::Keyspace(table) => table.$method($($args),*),
+            crate::tables::types::RedisTableType::Function(table) => table.$method($($args),*),
             crate::tables::types::RedisTableType::None => Ok(crate::tables::types::LoadDataResult::Empty),
         }
     };
@@ -50,6 +74,14 @@ macro_rules! table_dispatch_mut_result {
             crate::tables::types::RedisTableType::Set(table) => table.$method($($args),*),
             crate::tables::types::RedisTableType::ZSet(table) => table.$method($($args),*),
             crate::tables::types::RedisTableType::Stream(table) => table.$method($($args),*),
+            crate::tables::types::RedisTableType::HashRow(table) => table.$method($($args),*),
+            crate::tables::types::RedisTableType::Json(table) => table.$method($($args),*),
+            crate::tables::types::RedisTableType::Geo(table) => table.$method($($args),*),
+            crate::tables::types::RedisTableType::Bitmap(table) => table.$method($($args),*),
+            crate::tables::types::RedisTableType::HyperLogLog(table) => table.$method($($args),*),
+            crate::tables::types::RedisTableType::PubSub(table) => table.$method($($args),*),
+            crate::tables::types::RedisTableType::Keyspace(table) => table.$method($($args),*),
+            crate::tables::types::RedisTableType::Function(table) => table.$method($($args),*),
             crate::tables::types::RedisTableType::None => $default,
         }
     };
@@ -65,6 +97,14 @@ macro_rules! table_dispatch_mut_void {
             crate::tables::types::RedisTableType::Set(table) => table.$method($($args),*),
             crate::tables::types::RedisTableType::ZSet(table) => table.$method($($args),*),
             crate::tables::types::RedisTableType::Stream(table) => table.$method($($args),*),
+            crate::tables::types::RedisTableType::HashRow(table) => table.$method($($args),*),
+            crate::tables::types::RedisTableType::Json(table) => table.$method($($args),*),
+            crate::tables::types::RedisTableType::Geo(table) => table.$method($($args),*),
+            crate::tables::types::RedisTableType::Bitmap(table) => table.$method($($args),*),
+            crate::tables::types::RedisTableType::HyperLogLog(table) => table.$method($($args),*),
+            crate::tables::types::RedisTableType::PubSub(table) => table.$method($($args),*),
+            crate::tables::types::RedisTableType::Keyspace(table) => table.$method($($args),*),
+            crate::tables::types::RedisTableType::Function(table) => table.$method($($args),*),
             crate::tables::types::RedisTableType::None => {}
         }
     };
@@ -7,8 +7,9 @@ use crate::{
     },
     tables::{
         implementations::{
-            RedisHashTable, RedisListTable, RedisSetTable, RedisStreamTable, RedisStringTable,
-            RedisZSetTable,
+            RedisBitmapTable, RedisFunctionTable, RedisGeoTable, RedisHashRowTable, RedisHashTable,
+            RedisHyperLogLogTable, RedisJsonTable, RedisKeyspaceTable, RedisListTable,
+            RedisPubSubTable, RedisSetTable, RedisStreamTable, RedisStringTable, RedisZSetTable,
         },
         interface::RedisTableOperations,
         macros::{table_dispatch, table_dispatch_mut_result, table_dispatch_mut_void},
@@ -17,7 +18,96 @@ use crate::{
 use smallvec::{smallvec, SmallVec};
 use std::borrow::Cow;
 
-pub type RowVec<'a> = SmallVec<[Cow<'a, str>; 4]>;
+/// One column value as carried from Redis: its raw bytes, or `None` for SQL
+/// NULL (a field, path or attribute the entry does not have).
+pub type RowValue = Option<Vec<u8>>;
+
+/// The values of one row, borrowed from the dataset where possible.
+pub type RowVec<'a> = SmallVec<[Option<Cow<'a, [u8]>>; 4]>;
+
+/// How the values of one data column are carried between Redis and `DataSet`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ValueEncoding {
+    /// UTF-8 text; a Redis value that is not valid UTF-8 is an error
+    #[default]
+    Text,
+    /// Raw bytes of a `bytea` column, carried unchanged
+    Binary,
+    /// Text whose form is a JSON value (`json`/`jsonb`, numeric and boolean
+    /// columns); a RedisJSON table carries it as JSON instead of as a string
+    Json,
+}
+
+impl ValueEncoding {
+    /// Carry a Redis bulk string as a row value.
+    pub fn decode(self, bytes: Vec<u8>) -> Result<Vec<u8>, redis::RedisError> {
+        match self {
+            ValueEncoding::Text | ValueEncoding::Json => String::from_utf8(bytes)
+                .map(String::into_bytes)
+                .map_err(|e| redis::RedisError::from(redis::ParsingError::from(e))),
+            ValueEncoding::Binary => Ok(bytes),
+        }
+    }
+
+    /// The bytes to send to Redis for a row value.
+    pub fn encode(self, value: &str) -> Cow<'_, [u8]> {
+        match self {
+            ValueEncoding::Binary => match hex_to_bytes(value) {
+                Some(bytes) => Cow::Owned(bytes),
+                None => Cow::Borrowed(value.as_bytes()),
+            },
+            ValueEncoding::Text | ValueEncoding::Json => Cow::Borrowed(value.as_bytes()),
+        }
+    }
+}
+
+/// [`ValueEncoding`] of each data column of a table, in `get_row` order
+/// without the multi-key key column and the TTL column. Columns past the end
+/// are text.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ColumnEncodings(SmallVec<[ValueEncoding; 4]>);
+
+impl ColumnEncodings {
+    pub fn new(encodings: impl IntoIterator<Item = ValueEncoding>) -> Self {
+        ColumnEncodings(encodings.into_iter().collect())
+    }
+
+    #[inline]
+    pub fn get(&self, column: usize) -> ValueEncoding {
+        self.0.get(column).copied().unwrap_or_default()
+    }
+
+    /// Encoding of the last column.
+    #[inline]
+    pub fn last(&self) -> ValueEncoding {
+        self.0.last().copied().unwrap_or_default()
+    }
+}
+
+/// PostgreSQL hex form of `bytes`: `\x` followed by two lowercase digits per byte.
+pub fn bytes_to_hex(bytes: &[u8]) -> String {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+    let mut out = String::with_capacity(2 + bytes.len() * 2);
+    out.push_str("\\x");
+    for &b in bytes {
+        out.push(DIGITS[(b >> 4) as usize] as char);
+        out.push(DIGITS[(b & 0x0f) as usize] as char);
+    }
+    out
+}
+
+/// Inverse of [`bytes_to_hex`]; `None` when `value` is not in hex form.
+pub fn hex_to_bytes(value: &str) -> Option<Vec<u8>> {
+    let digits = value.strip_prefix("\\x")?.as_bytes();
+    if digits.len() % 2 != 0 {
+        return None;
+    }
+    let nibble = |c: u8| (c as char).to_digit(16).map(|d| d as u8);
+    digits
+        .chunks_exact(2)
+        .map(|pair| Some((nibble(pair[0])? << 4) | nibble(pair[1])?))
+        .collect()
+}
 
 /// Enum representing different Redis table types with their implementations
 #[derive(Debug, Clone)]
@@ -28,6 +118,14 @@ pub enum RedisTableType {
     Set(RedisSetTable),
     ZSet(RedisZSetTable),
     Stream(RedisStreamTable),
+    HashRow(RedisHashRowTable),
+    Json(RedisJsonTable),
+    Geo(RedisGeoTable),
+    Bitmap(RedisBitmapTable),
+    HyperLogLog(RedisHyperLogLogTable),
+    PubSub(RedisPubSubTable),
+    Keyspace(RedisKeyspaceTable),
+    Function(RedisFunctionTable),
     None,
 }
 
@@ -40,6 +138,14 @@ impl RedisTableType {
             "set" => RedisTableType::Set(RedisSetTable::new()),
             "zset" => RedisTableType::ZSet(RedisZSetTable::new()),
             "stream" => RedisTableType::Stream(RedisStreamTable::new(1000)),
+            "hash_row" => RedisTableType::HashRow(RedisHashRowTable::new()),
+            "json" => RedisTableType::Json(RedisJsonTable::new()),
+            "geo" => RedisTableType::Geo(RedisGeoTable::new()),
+            "bitmap" => RedisTableType::Bitmap(RedisBitmapTable::new()),
+            "hyperloglog" => RedisTableType::HyperLogLog(RedisHyperLogLogTable::new()),
+            "pubsub" => RedisTableType::PubSub(RedisPubSubTable::new()),
+            "keyspace" => RedisTableType::Keyspace(RedisKeyspaceTable::new()),
+            "function" => RedisTableType::Function(RedisFunctionTable::new()),
             _ => RedisTableType::None,
         }
     }
@@ -84,8 +190,9 @@ impl RedisTableType {
         conn: &mut dyn redis::ConnectionLike,
         key_prefix: &str,
         data: &[String],
-    ) -> Result<(), redis::RedisError> {
-        table_dispatch_mut_result!(self, insert(conn, key_prefix, data) -> Result<(), redis::RedisError>, Ok(()))
+        mode: InsertMode,
+    ) -> Result<bool, redis::RedisError> {
+        table_dispatch_mut_result!(self, insert(conn, key_prefix, data, mode) -> Result<bool, redis::RedisError>, Ok(false))
     }
 
     pub fn delete(
@@ -113,7 +220,7 @@ impl RedisTableType {
     }
 
     /// Store flat multi-key data (used by multi-key mode)
-    pub fn set_multi_key_data(&mut self, data: Vec<String>) {
+    pub fn set_multi_key_data(&mut self, data: Vec<RowValue>) {
         table_dispatch_mut_void!(self, set_filtered_data(data));
     }
 
@@ -122,6 +229,21 @@ impl RedisTableType {
         table_dispatch!(self, redis_type_name() -> "")
     }
 
+    /// The `table_type` option value this table was created with.
+    pub fn table_type_name(&self) -> &'static str {
+        match self {
+            RedisTableType::HashRow(_) => "hash_row",
+            RedisTableType::Json(_) => "json",
+            RedisTableType::Geo(_) => "geo",
+            RedisTableType::Bitmap(_) => "bitmap",
+            RedisTableType::HyperLogLog(_) => "hyperloglog",
+            RedisTableType::PubSub(_) => "pubsub",
+            RedisTableType::Keyspace(_) => "keyspace",
+            RedisTableType::Function(_) => "function",
+            other => other.redis_type_name(),
+        }
+    }
+
     /// Get a reference to the dataset (for multi-key mode)
     pub fn get_dataset_ref(&self) -> &DataSet {
         table_dispatch!(self, get_dataset() -> &DataSet::Empty)
@@ -140,13 +262,18 @@ impl RedisTableType {
         );
     }
 
+    /// Set which data columns carry raw bytes (`bytea`).
+    pub fn set_column_encodings(&mut self, encodings: ColumnEncodings) {
+        table_dispatch_mut_void!(self, set_column_encodings(encodings));
+    }
+
     /// Load data for multiple keys in multi-key mode.
     pub fn load_multi_key_data(
         &mut self,
         conn: &mut dyn redis::ConnectionLike,
         keys: &[String],
-    ) -> Result<Vec<String>, redis::RedisError> {
-        table_dispatch_mut_result!(self, load_multi_key_data(conn, keys) -> Result<Vec<String>, redis::RedisError>, Ok(Vec::new()))
+    ) -> Result<Vec<RowValue>, redis::RedisError> {
+        table_dispatch_mut_result!(self, load_multi_key_data(conn, keys) -> Result<Vec<RowValue>, redis::RedisError>, Ok(Vec::new()))
     }
 
     /// Get number of columns per row in multi-key flat format.
@@ -164,14 +291,53 @@ pub enum LoadDataResult {
     Empty,
 }
 
-/// Represents the different states of data in a Redis table
+/// How INSERT treats a row whose key/field/member already exists.
+///
+/// Set by the `insert_mode` table option; `INSERT ... ON CONFLICT DO NOTHING`
+/// selects `Nx` for that statement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InsertMode {
+    /// Always write (SET, HSET, ZADD, SADD, RPUSH, XADD)
+    #[default]
+    Overwrite,
+    /// Write only if absent (SET NX, HSETNX, ZADD NX, SADD, XADD with an unused ID)
+    Nx,
+    /// Write only if present (SET XX, ZADD XX, RPUSHX, XADD NOMKSTREAM)
+    Xx,
+}
+
+impl InsertMode {
+    /// Parse the `insert_mode` option value (case-insensitive).
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "overwrite" => Some(InsertMode::Overwrite),
+            "nx" => Some(InsertMode::Nx),
+            "xx" => Some(InsertMode::Xx),
+            _ => None,
+        }
+    }
+
+    /// Whether the reply to a conditional insert command means the row was
+    /// written. Nil (SET NX/XX, XADD NOMKSTREAM) and a zero count (HSETNX,
+    /// ZADD NX, SADD, RPUSHX) mean skipped; anything else means written.
+    pub fn reply_applied(reply: &redis::Value) -> bool {
+        match reply {
+            redis::Value::Nil => false,
+            redis::Value::Int(n) => *n > 0,
+            _ => true,
+        }
+    }
+}
+
+/// Represents the different states of data in a Redis table.
+/// Values are held as the bytes Redis returned, see [`ValueEncoding`].
 #[derive(Debug, Clone, Default)]
 pub enum DataSet {
     /// No data has been loaded yet
     #[default]
     Empty,
     /// Data loaded with pushdown optimization applied
-    Filtered(Vec<String>),
+    Filtered(Vec<RowValue>),
     /// All data loaded without filtering
     Complete(DataContainer),
 }
@@ -180,13 +346,13 @@ pub enum DataSet {
 #[derive(Debug, Clone)]
 pub enum DataContainer {
     /// Single string value (Redis String type)
-    String(Option<String>),
+    String(Option<Vec<u8>>),
     /// Key-value pairs (Redis Hash type)
-    Hash(Vec<(String, String)>),
+    Hash(Vec<(Vec<u8>, Vec<u8>)>),
     /// Ordered list of values (Redis List type)
-    List(Vec<String>),
+    List(Vec<Vec<u8>>),
     /// Sorted set with scores (Redis ZSet type)
-    ZSet(Vec<(String, f64)>),
+    ZSet(Vec<(Vec<u8>, f64)>),
 }
 
 impl DataSet {
@@ -214,13 +380,13 @@ impl DataSet {
             DataSet::Filtered(data) => {
                 // Generic implementation - each element is a row
                 data.get(index)
-                    .map(|item| smallvec![Cow::Borrowed(item.as_str())])
+                    .map(|item| smallvec![item.as_deref().map(Cow::Borrowed)])
             }
             DataSet::Complete(container) => container.get_row(index),
         }
     }
 
-    pub fn as_filtered(&self) -> Option<&Vec<String>> {
+    pub fn as_filtered(&self) -> Option<&Vec<RowValue>> {
         match self {
             DataSet::Filtered(data) => Some(data),
             _ => None,
@@ -246,27 +412,31 @@ impl DataContainer {
         }
     }
 
-    /// Get a row at the specified index - returns borrowed strings to avoid cloning
+    /// Get a row at the specified index - returns borrowed values to avoid cloning
     #[inline]
     pub fn get_row(&self, index: usize) -> Option<RowVec<'_>> {
         match self {
             DataContainer::String(opt) => {
                 if index == 0 && opt.is_some() {
-                    opt.as_ref().map(|s| smallvec![Cow::Borrowed(s.as_str())])
+                    opt.as_ref()
+                        .map(|s| smallvec![Some(Cow::Borrowed(s.as_slice()))])
                 } else {
                     None
                 }
             }
-            DataContainer::Hash(pairs) => pairs
-                .get(index)
-                .map(|(k, v)| smallvec![Cow::Borrowed(k.as_str()), Cow::Borrowed(v.as_str())]),
+            DataContainer::Hash(pairs) => pairs.get(index).map(|(k, v)| {
+                smallvec![
+                    Some(Cow::Borrowed(k.as_slice())),
+                    Some(Cow::Borrowed(v.as_slice())),
+                ]
+            }),
             DataContainer::List(items) => items
                 .get(index)
-                .map(|item| smallvec![Cow::Borrowed(item.as_str())]),
+                .map(|item| smallvec![Some(Cow::Borrowed(item.as_slice()))]),
             DataContainer::ZSet(items) => items.get(index).map(|(member, score)| {
                 smallvec![
-                    Cow::Borrowed(member.as_str()),
-                    Cow::Owned(score.to_string()),
+                    Some(Cow::Borrowed(member.as_slice())),
+                    Some(Cow::Owned(score.to_string().into_bytes())),
                 ]
             }),
         }
@@ -276,6 +446,7 @@ impl DataContainer {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::tests::utils::{row_text, row_values};
 
     #[test]
     fn test_redis_table_type_from_str() {
@@ -303,6 +474,38 @@ mod tests {
             RedisTableType::from_str("stream"),
             RedisTableType::Stream(_)
         ));
+        assert!(matches!(
+            RedisTableType::from_str("hash_row"),
+            RedisTableType::HashRow(_)
+        ));
+        assert!(matches!(
+            RedisTableType::from_str("json"),
+            RedisTableType::Json(_)
+        ));
+        assert!(matches!(
+            RedisTableType::from_str("geo"),
+            RedisTableType::Geo(_)
+        ));
+        assert!(matches!(
+            RedisTableType::from_str("bitmap"),
+            RedisTableType::Bitmap(_)
+        ));
+        assert!(matches!(
+            RedisTableType::from_str("hyperloglog"),
+            RedisTableType::HyperLogLog(_)
+        ));
+        assert!(matches!(
+            RedisTableType::from_str("pubsub"),
+            RedisTableType::PubSub(_)
+        ));
+        assert!(matches!(
+            RedisTableType::from_str("keyspace"),
+            RedisTableType::Keyspace(_)
+        ));
+        assert!(matches!(
+            RedisTableType::from_str("function"),
+            RedisTableType::Function(_)
+        ));
         assert!(matches!(
             RedisTableType::from_str("unknown"),
             RedisTableType::None
@@ -317,6 +520,61 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_insert_mode_parse() {
+        assert_eq!(InsertMode::parse("nx"), Some(InsertMode::Nx));
+        assert_eq!(InsertMode::parse("XX"), Some(InsertMode::Xx));
+        assert_eq!(InsertMode::parse("Overwrite"), Some(InsertMode::Overwrite));
+        assert_eq!(InsertMode::parse("upsert"), None);
+        assert_eq!(InsertMode::default(), InsertMode::Overwrite);
+    }
+
+    #[test]
+    fn test_insert_mode_reply_applied() {
+        assert!(InsertMode::reply_applied(&redis::Value::Okay));
+        assert!(InsertMode::reply_applied(&redis::Value::Int(1)));
+        assert!(InsertMode::reply_applied(&redis::Value::BulkString(
+            b"1-0".to_vec()
+        )));
+        assert!(!InsertMode::reply_applied(&redis::Value::Nil));
+        assert!(!InsertMode::reply_applied(&redis::Value::Int(0)));
+    }
+
+    #[test]
+    fn test_value_encoding_binary_round_trip() {
+        let raw = vec![0x00, 0xff, 0x80, b'\\', b'a'];
+        assert_eq!(ValueEncoding::Binary.decode(raw.clone()).unwrap(), raw);
+        let hex = bytes_to_hex(&raw);
+        assert_eq!(hex, "\\x00ff805c61");
+        assert_eq!(ValueEncoding::Binary.encode(&hex).as_ref(), raw.as_slice());
+        assert_eq!(bytes_to_hex(&[]), "\\x");
+        assert_eq!(hex_to_bytes("\\x"), Some(Vec::new()));
+    }
+
+    #[test]
+    fn test_value_encoding_text() {
+        assert_eq!(ValueEncoding::Text.decode(b"abc".to_vec()).unwrap(), b"abc");
+        assert!(ValueEncoding::Text.decode(vec![0xff, 0xfe]).is_err());
+        // Text that looks like hex is still sent as written.
+        assert_eq!(ValueEncoding::Text.encode("\\x41").as_ref(), b"\\x41");
+    }
+
+    #[test]
+    fn test_hex_to_bytes_rejects_non_hex() {
+        assert_eq!(hex_to_bytes("\\x4"), None);
+        assert_eq!(hex_to_bytes("\\xzz"), None);
+        assert_eq!(hex_to_bytes("41"), None);
+        assert_eq!(ValueEncoding::Binary.encode("plain").as_ref(), b"plain");
+    }
+
+    #[test]
+    fn test_column_encodings_default_to_text() {
+        let encodings = ColumnEncodings::new([ValueEncoding::Text, ValueEncoding::Binary]);
+        assert_eq!(encodings.get(1), ValueEncoding::Binary);
+        assert_eq!(encodings.get(5), ValueEncoding::Text);
+        assert_eq!(encodings.last(), ValueEncoding::Binary);
+    }
+
     #[test]
     fn test_dataset_empty() {
         let ds = DataSet::Empty;
@@ -326,21 +584,21 @@ mod tests {
 
     #[test]
     fn test_dataset_filtered() {
-        let ds = DataSet::Filtered(vec!["a".to_string(), "b".to_string(), "c".to_string()]);
+        let ds = DataSet::Filtered(row_values(&["a", "b", "c"]));
         assert_eq!(ds.len(), 3);
         let row = ds.get_row(0).unwrap();
-        assert_eq!(row.as_slice(), &[Cow::Borrowed("a")]);
+        assert_eq!(row_text(&row), ["a"]);
         let row2 = ds.get_row(2).unwrap();
-        assert_eq!(row2.as_slice(), &[Cow::Borrowed("c")]);
+        assert_eq!(row_text(&row2), ["c"]);
         assert!(ds.get_row(3).is_none());
     }
 
     #[test]
     fn test_dataset_complete_string() {
-        let ds = DataSet::Complete(DataContainer::String(Some("hello".to_string())));
+        let ds = DataSet::Complete(DataContainer::String(Some(b"hello".to_vec())));
         assert_eq!(ds.len(), 1);
         let row = ds.get_row(0).unwrap();
-        assert_eq!(row.as_slice(), &[Cow::Borrowed("hello")]);
+        assert_eq!(row_text(&row), ["hello"]);
         assert!(ds.get_row(1).is_none());
     }
 
@@ -354,45 +612,36 @@ mod tests {
     #[test]
     fn test_data_container_hash() {
         let container = DataContainer::Hash(vec![
-            ("key1".to_string(), "val1".to_string()),
-            ("key2".to_string(), "val2".to_string()),
+            (b"key1".to_vec(), b"val1".to_vec()),
+            (b"key2".to_vec(), b"val2".to_vec()),
         ]);
         assert_eq!(container.len(), 2);
         let row = container.get_row(0).unwrap();
-        assert_eq!(
-            row.as_slice(),
-            &[Cow::Borrowed("key1"), Cow::Borrowed("val1")]
-        );
+        assert_eq!(row_text(&row), ["key1", "val1"]);
         let row2 = container.get_row(1).unwrap();
-        assert_eq!(
-            row2.as_slice(),
-            &[Cow::Borrowed("key2"), Cow::Borrowed("val2")]
-        );
+        assert_eq!(row_text(&row2), ["key2", "val2"]);
         assert!(container.get_row(2).is_none());
     }
 
     #[test]
     fn test_data_container_list() {
         let container = DataContainer::List(vec![
-            "item1".to_string(),
-            "item2".to_string(),
-            "item3".to_string(),
+            b"item1".to_vec(),
+            b"item2".to_vec(),
+            b"item3".to_vec(),
         ]);
         assert_eq!(container.len(), 3);
         let row = container.get_row(1).unwrap();
-        assert_eq!(row.as_slice(), &[Cow::Borrowed("item2")]);
+        assert_eq!(row_text(&row), ["item2"]);
     }
 
     #[test]
     fn test_data_container_zset() {
-        let container = DataContainer::ZSet(vec![
-            ("member1".to_string(), 1.5),
-            ("member2".to_string(), 2.7),
-        ]);
+        let container =
+            DataContainer::ZSet(vec![(b"member1".to_vec(), 1.5), (b"member2".to_vec(), 2.7)]);
         assert_eq!(container.len(), 2);
         let row = container.get_row(0).unwrap();
-        assert_eq!(row[0], Cow::Borrowed("member1"));
-        assert_eq!(row[1], Cow::<str>::Owned("1.5".to_string()));
+        assert_eq!(row_text(&row), ["member1", "1.5"]);
     }
 
     #[test]
@@ -429,9 +678,58 @@ mod tests {
             RedisTableType::from_str("stream").redis_type_name(),
             "stream"
         );
+        assert_eq!(
+            RedisTableType::from_str("hash_row").redis_type_name(),
+            "hash"
+        );
+        assert_eq!(
+            RedisTableType::from_str("json").redis_type_name(),
+            "ReJSON-RL"
+        );
+        assert_eq!(RedisTableType::from_str("geo").redis_type_name(), "zset");
+        assert_eq!(
+            RedisTableType::from_str("bitmap").redis_type_name(),
+            "string"
+        );
+        assert_eq!(
+            RedisTableType::from_str("hyperloglog").redis_type_name(),
+            "string"
+        );
+        assert_eq!(RedisTableType::from_str("keyspace").redis_type_name(), "");
         assert_eq!(RedisTableType::None.redis_type_name(), "");
     }
 
+    #[test]
+    fn test_table_type_name() {
+        assert_eq!(
+            RedisTableType::from_str("hash_row").table_type_name(),
+            "hash_row"
+        );
+        assert_eq!(RedisTableType::from_str("json").table_type_name(), "json");
+        assert_eq!(RedisTableType::from_str("geo").table_type_name(), "geo");
+        assert_eq!(
+            RedisTableType::from_str("bitmap").table_type_name(),
+            "bitmap"
+        );
+        assert_eq!(
+            RedisTableType::from_str("hyperloglog").table_type_name(),
+            "hyperloglog"
+        );
+        assert_eq!(
+            RedisTableType::from_str("pubsub").table_type_name(),
+            "pubsub"
+        );
+        assert_eq!(
+            RedisTableType::from_str("keyspace").table_type_name(),
+            "keyspace"
+        );
+        assert_eq!(
+            RedisTableType::from_str("function").table_type_name(),
+            "function"
+        );
+        assert_eq!(RedisTableType::from_str("zset").table_type_name(), "zset");
+    }
+
     #[test]
     fn test_multi_key_columns_per_row() {
         assert_eq!(
@@ -458,13 +756,25 @@ mod tests {
             RedisTableType::from_str("stream").multi_key_columns_per_row(),
             4
         );
+        assert_eq!(
+            RedisTableType::from_str("geo").multi_key_columns_per_row(),
+            4
+        );
+        assert_eq!(
+            RedisTableType::from_str("bitmap").multi_key_columns_per_row(),
+            3
+        );
+        assert_eq!(
+            RedisTableType::from_str("hyperloglog").multi_key_columns_per_row(),
+            2
+        );
         assert_eq!(RedisTableType::None.multi_key_columns_per_row(), 0);
     }
 
     #[test]
     fn test_clear_data() {
         let mut table = RedisTableType::from_str("set");
-        table.set_multi_key_data(vec!["a".to_string(), "b".to_string()]);
+        table.set_multi_key_data(row_values(&["a", "b"]));
         assert!(table.data_len() > 0);
         table.clear_data();
         assert_eq!(table.data_len(), 0);
@@ -498,7 +808,7 @@ mod tests {
     #[test]
     fn test_set_multi_key_data_and_get_dataset_ref() {
         let mut table = RedisTableType::from_str("set");
-        table.set_multi_key_data(vec!["k1".to_string(), "m1".to_string()]);
+        table.set_multi_key_data(row_values(&["k1", "m1"]));
         match table.get_dataset_ref() {
             DataSet::Filtered(data) => assert_eq!(data.len(), 2),
             _ => panic!("expected Filtered dataset"),
@@ -14,12 +14,20 @@ mod tests {
                 RedisHashTable, RedisListTable, RedisSetTable, RedisStringTable, RedisZSetTable,
             },
             interface::RedisTableOperations,
-            types::{DataContainer, DataSet, RedisTableType, RowVec},
+            types::{DataContainer, DataSet, RedisTableType, RowValue, RowVec},
         },
     };
 
     fn cow_vec_to_string_vec(row: Option<RowVec<'_>>) -> Option<Vec<String>> {
-        row.map(|v| v.into_iter().map(|c| c.into_owned()).collect())
+        row.map(|v| {
+            v.into_iter()
+                .map(|c| String::from_utf8(c.expect("NULL value").into_owned()).unwrap())
+                .collect()
+        })
+    }
+
+    fn filtered(values: Vec<String>) -> Vec<RowValue> {
+        values.into_iter().map(|v| Some(v.into_bytes())).collect()
     }
 
     /// Helper utilities for integration testing
@@ -29,35 +37,45 @@ mod tests {
         /// Setup a string table with test data
         pub fn setup_string_table_with_data(value: Option<String>) -> RedisStringTable {
             let mut table = RedisStringTable::new();
-            table.dataset = DataSet::Complete(DataContainer::String(value));
+            table.dataset = DataSet::Complete(DataContainer::String(value.map(String::into_bytes)));
             table
         }
 
         /// Setup a hash table with test data
         pub fn setup_hash_table_with_data(data: Vec<(String, String)>) -> RedisHashTable {
             let mut table = RedisHashTable::new();
-            table.dataset = DataSet::Complete(DataContainer::Hash(data));
+            table.dataset = DataSet::Complete(DataContainer::Hash(
+                data.into_iter()
+                    .map(|(field, value)| (field.into_bytes(), value.into_bytes()))
+                    .collect(),
+            ));
             table
         }
 
         /// Setup a list table with test data
         pub fn setup_list_table_with_data(data: Vec<String>) -> RedisListTable {
             let mut table = RedisListTable::new();
-            table.dataset = DataSet::Complete(DataContainer::List(data));
+            table.dataset = DataSet::Complete(DataContainer::List(
+                data.into_iter().map(String::into_bytes).collect(),
+            ));
             table
         }
 
         /// Setup a set table with test data
         pub fn setup_set_table_with_data(data: Vec<String>) -> RedisSetTable {
             let mut table = RedisSetTable::new();
-            table.dataset = DataSet::Filtered(data);
+            table.dataset = DataSet::Filtered(filtered(data));
             table
         }
 
         /// Setup a zset table with test data
         pub fn setup_zset_table_with_data(data: Vec<(String, f64)>) -> RedisZSetTable {
             let mut table = RedisZSetTable::new();
-            table.dataset = DataSet::Complete(DataContainer::ZSet(data));
+            table.dataset = DataSet::Complete(DataContainer::ZSet(
+                data.into_iter()
+                    .map(|(member, score)| (member.into_bytes(), score))
+                    .collect(),
+            ));
             table
         }
     }
@@ -159,9 +177,9 @@ mod tests {
         // Check that all field-value pairs are accessible
         let mut found_pairs = std::collections::HashSet::new();
         for i in 0..table.data_len() {
-            if let Some(row) = table.get_row(i) {
+            if let Some(row) = cow_vec_to_string_vec(table.get_row(i)) {
                 assert_eq!(row.len(), 2); // field, value
-                found_pairs.insert((row[0].to_string(), row[1].to_string()));
+                found_pairs.insert((row[0].clone(), row[1].clone()));
             }
         }
 
@@ -214,8 +232,8 @@ mod tests {
         // Verify complex data is preserved
         let mut found_pairs = std::collections::HashSet::new();
         for i in 0..table.data_len() {
-            if let Some(row) = table.get_row(i) {
-                found_pairs.insert((row[0].to_string(), row[1].to_string()));
+            if let Some(row) = cow_vec_to_string_vec(table.get_row(i)) {
+                found_pairs.insert((row[0].clone(), row[1].clone()));
             }
         }
 
@@ -310,9 +328,9 @@ mod tests {
         // Collect all members
         let mut retrieved_members = std::collections::HashSet::new();
         for i in 0..table.data_len() {
-            if let Some(row) = table.get_row(i) {
+            if let Some(row) = cow_vec_to_string_vec(table.get_row(i)) {
                 assert_eq!(row.len(), 1);
-                retrieved_members.insert(row[0].to_string());
+                retrieved_members.insert(row[0].clone());
             }
         }
 
@@ -347,8 +365,8 @@ mod tests {
         // Verify no duplicates
         let mut seen_members = std::collections::HashSet::new();
         for i in 0..table.data_len() {
-            if let Some(row) = table.get_row(i) {
-                let member_str = row[0].to_string();
+            if let Some(row) = cow_vec_to_string_vec(table.get_row(i)) {
+                let member_str = row[0].clone();
                 assert!(!seen_members.contains(&member_str));
                 seen_members.insert(member_str);
             }
@@ -360,7 +378,7 @@ mod tests {
     fn test_set_table_filtered_data() {
         let mut table = RedisSetTable::new();
         let filtered_data = vec!["filtered1".to_string(), "filtered2".to_string()];
-        table.dataset = DataSet::Filtered(filtered_data.clone());
+        table.dataset = DataSet::Filtered(filtered(filtered_data.clone()));
 
         assert_eq!(table.data_len(), 2);
 
@@ -389,9 +407,9 @@ mod tests {
         // Check that all members are accessible with their scores
         let mut found_members = std::collections::HashMap::new();
         for i in 0..table.data_len() {
-            if let Some(row) = table.get_row(i) {
+            if let Some(row) = cow_vec_to_string_vec(table.get_row(i)) {
                 assert_eq!(row.len(), 2); // member, score
-                let member = row[0].to_string();
+                let member = row[0].clone();
                 let score = row[1].parse::<f64>().unwrap();
                 found_members.insert(member, score);
             }
@@ -428,7 +446,7 @@ mod tests {
 
         // Check that scores are properly formatted and parseable
         for i in 0..table.data_len() {
-            if let Some(row) = table.get_row(i) {
+            if let Some(row) = cow_vec_to_string_vec(table.get_row(i)) {
                 assert_eq!(row.len(), 2);
                 let score_str = &row[1];
                 // Should be able to parse back to float
@@ -447,7 +465,7 @@ mod tests {
             "player5".to_string(),
             "130.5".to_string(),
         ];
-        table.dataset = DataSet::Filtered(filtered_data);
+        table.dataset = DataSet::Filtered(filtered(filtered_data));
 
         assert_eq!(table.data_len(), 2); // 2 member-score pairs
 
@@ -637,11 +655,11 @@ mod tests {
         assert_eq!(table.data_len(), 0);
 
         // Transition to Complete
-        table.dataset = DataSet::Complete(DataContainer::String(Some("test".to_string())));
+        table.dataset = DataSet::Complete(DataContainer::String(Some(b"test".to_vec())));
         assert_eq!(table.data_len(), 1);
 
         // Transition to Filtered
-        table.dataset = DataSet::Filtered(vec!["filtered".to_string()]);
+        table.dataset = DataSet::Filtered(filtered(vec!["filtered".to_string()]));
         assert_eq!(table.data_len(), 1);
 
         // Transition back to Empty
@@ -0,0 +1,298 @@
+#[cfg(any(test, feature = "pg_test"))]
+#[pgrx::pg_schema]
+mod tests {
+    use crate::tables::implementations::stream::StreamEntry;
+    use pgrx::prelude::*;
+
+    const TEST_DATABASE: &str = "15";
+    const FDW_NAME: &str = "redis_binary_fdw";
+    const SERVER_NAME: &str = "redis_binary_server";
+
+    /// Not valid UTF-8, with a NUL byte in the middle.
+    const RAW: &[u8] = &[0xde, 0xad, 0x00, 0xbe, 0xef, 0xff];
+    const RAW_HEX: &str = "\\xdead00beefff";
+
+    fn setup_fdw() {
+        let _ = Spi::run(&format!(
+            "DROP FOREIGN DATA WRAPPER IF EXISTS {} CASCADE;",
+            FDW_NAME
+        ));
+        Spi::run(&format!(
+            "CREATE FOREIGN DATA WRAPPER {} HANDLER redis_fdw_handler VALIDATOR redis_fdw_validator;",
+            FDW_NAME
+        ))
+        .unwrap();
+        Spi::run(&format!(
+            "CREATE SERVER {} FOREIGN DATA WRAPPER {} OPTIONS (host_port '127.0.0.1:8899');",
+            SERVER_NAME, FDW_NAME
+        ))
+        .unwrap();
+    }
+
+    fn cleanup() {
+        let _ = Spi::run(&format!(
+            "DROP FOREIGN DATA WRAPPER IF EXISTS {} CASCADE;",
+            FDW_NAME
+        ));
+    }
+
+    fn redis_conn() -> redis::Connection {
+        redis::Client::open("redis://127.0.0.1:8899/15")
+            .unwrap()
+            .get_connection()
+            .unwrap()
+    }
+
+    fn cleanup_redis_key(key: &str) {
+        let _: Result<(), _> = redis::cmd("DEL").arg(key).query(&mut redis_conn());
+    }
+
+    fn create_table(name: &str, columns: &str, table_type: &str, key: &str) {
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE {} ({}) SERVER {} OPTIONS (
+                database '{}', table_type '{}', table_key_prefix '{}'
+            );",
+            name, columns, SERVER_NAME, TEST_DATABASE, table_type, key
+        ))
+        .unwrap();
+    }
+
+    #[pg_test]
+    fn test_string_bytea_round_trip() {
+        setup_fdw();
+        let key = "bin_test:string";
+        cleanup_redis_key(key);
+        create_table("bin_string", "value bytea", "string", key);
+
+        let _: () = redis::cmd("SET")
+            .arg(key)
+            .arg(RAW)
+            .query(&mut redis_conn())
+            .unwrap();
+        let value = Spi::get_one::<Vec<u8>>("SELECT value FROM bin_string;")
+            .unwrap()
+            .unwrap();
+        assert_eq!(value, RAW);
+
+        Spi::run("INSERT INTO bin_string VALUES ('\\x00ff80'::bytea);").unwrap();
+        let stored: Vec<u8> = redis::cmd("GET").arg(key).query(&mut redis_conn()).unwrap();
+        assert_eq!(stored, vec![0x00, 0xff, 0x80]);
+
+        cleanup_redis_key(key);
+        cleanup();
+    }
+
+    #[pg_test]
+    fn test_hash_bytea_value_with_text_field() {
+        setup_fdw();
+        let key = "bin_test:hash";
+        cleanup_redis_key(key);
+        create_table("bin_hash", "field text, value bytea", "hash", key);
+
+        let _: () = redis::cmd("HSET")
+            .arg(key)
+            .arg("blob")
+            .arg(RAW)
+            .query(&mut redis_conn())
+            .unwrap();
+        let value = Spi::get_one::<Vec<u8>>("SELECT value FROM bin_hash WHERE field = 'blob';")
+            .unwrap()
+            .unwrap();
+        assert_eq!(value, RAW);
+
+        Spi::run(&format!(
+            "INSERT INTO bin_hash VALUES ('copy', '{}'::bytea);",
+            RAW_HEX
+        ))
+        .unwrap();
+        let stored: Vec<u8> = redis::cmd("HGET")
+            .arg(key)
+            .arg("copy")
+            .query(&mut redis_conn())
+            .unwrap();
+        assert_eq!(stored, RAW);
+
+        Spi::run("UPDATE bin_hash SET value = '\\x01'::bytea WHERE field = 'copy';").unwrap();
+        let stored: Vec<u8> = redis::cmd("HGET")
+            .arg(key)
+            .arg("copy")
+            .query(&mut redis_conn())
+            .unwrap();
+        assert_eq!(stored, vec![0x01]);
+
+        cleanup_redis_key(key);
+        cleanup();
+    }
+
+    #[pg_test]
+    fn test_list_bytea_elements() {
+        setup_fdw();
+        let key = "bin_test:list";
+        cleanup_redis_key(key);
+        create_table("bin_list", "element bytea", "list", key);
+
+        let _: () = redis::cmd("RPUSH")
+            .arg(key)
+            .arg(RAW)
+            .query(&mut redis_conn())
+            .unwrap();
+        Spi::run("INSERT INTO bin_list VALUES ('\\xfffe'::bytea);").unwrap();
+
+        let stored: Vec<Vec<u8>> = redis::cmd("LRANGE")
+            .arg(key)
+            .arg(0)
+            .arg(-1)
+            .query(&mut redis_conn())
+            .unwrap();
+        assert_eq!(stored, vec![RAW.to_vec(), vec![0xff, 0xfe]]);
+
+        let count = Spi::get_one::<i64>(&format!(
+            "SELECT count(*) FROM bin_list WHERE element = '{}'::bytea;",
+            RAW_HEX
+        ))
+        .unwrap()
+        .unwrap();
+        assert_eq!(count, 1);
+
+        cleanup_redis_key(key);
+        cleanup();
+    }
+
+    #[pg_test]
+    fn test_set_bytea_members_delete() {
+        setup_fdw();
+        let key = "bin_test:set";
+        cleanup_redis_key(key);
+        create_table("bin_set", "member bytea", "set", key);
+
+        let _: () = redis::cmd("SADD")
+            .arg(key)
+            .arg(RAW)
+            .arg(&[0x80u8][..])
+            .query(&mut redis_conn())
+            .unwrap();
+        let count = Spi::get_one::<i64>("SELECT count(*) FROM bin_set;")
+            .unwrap()
+            .unwrap();
+        assert_eq!(count, 2);
+
+        Spi::run(&format!(
+            "DELETE FROM bin_set WHERE member = '{}'::bytea;",
+            RAW_HEX
+        ))
+        .unwrap();
+        let members: Vec<Vec<u8>> = redis::cmd("SMEMBERS")
+            .arg(key)
+            .query(&mut redis_conn())
+            .unwrap();
+        assert_eq!(members, vec![vec![0x80]]);
+
+        cleanup_redis_key(key);
+        cleanup();
+    }
+
+    #[pg_test]
+    fn test_zset_bytea_member() {
+        setup_fdw();
+        let key = "bin_test:zset";
+        cleanup_redis_key(key);
+        create_table("bin_zset", "member bytea, score float8", "zset", key);
+
+        Spi::run(&format!(
+            "INSERT INTO bin_zset VALUES ('{}'::bytea, 2.5);",
+            RAW_HEX
+        ))
+        .unwrap();
+        let score: Option<f64> = redis::cmd("ZSCORE")
+            .arg(key)
+            .arg(RAW)
+            .query(&mut redis_conn())
+            .unwrap();
+        assert_eq!(score, Some(2.5));
+
+        let member = Spi::get_one::<Vec<u8>>("SELECT member FROM bin_zset WHERE score > 1;")
+            .unwrap()
+            .unwrap();
+        assert_eq!(member, RAW);
+
+        cleanup_redis_key(key);
+        cleanup();
+    }
+
+    #[pg_test]
+    fn test_stream_bytea_field() {
+        setup_fdw();
+        let key = "bin_test:stream";
+        cleanup_redis_key(key);
+        create_table(
+            "bin_stream",
+            "id text, label text, payload bytea",
+            "stream",
+            key,
+        );
+
+        let _: String = redis::cmd("XADD")
+            .arg(key)
+            .arg("1-1")
+            .arg("label")
+            .arg("first")
+            .arg("payload")
+            .arg(RAW)
+            .query(&mut redis_conn())
+            .unwrap();
+        let payload =
+            Spi::get_one::<Vec<u8>>("SELECT payload FROM bin_stream WHERE label = 'first';")
+                .unwrap()
+                .unwrap();
+        assert_eq!(payload, RAW);
+
+        Spi::run("INSERT INTO bin_stream VALUES ('2-1', 'second', '\\x00ff'::bytea);").unwrap();
+        let entries: Vec<StreamEntry> = redis::cmd("XRANGE")
+            .arg(key)
+            .arg("2-1")
+            .arg("2-1")
+            .query(&mut redis_conn())
+            .unwrap();
+        let payload = entries[0]
+            .1
+            .iter()
+            .find(|(field, _)| field == "payload")
+            .map(|(_, value)| value.clone());
+        assert_eq!(payload, Some(vec![0x00, 0xff]));
+
+        cleanup_redis_key(key);
+        cleanup();
+    }
+
+    #[pg_test]
+    fn test_multi_key_bytea_values_keep_text_keys() {
+        setup_fdw();
+        let keys = ["bin_test:mk:a", "bin_test:mk:b"];
+        for key in keys {
+            cleanup_redis_key(key);
+        }
+        create_table("bin_mk", "key text, value bytea", "string", "bin_test:mk:*");
+
+        let _: () = redis::cmd("MSET")
+            .arg(keys[0])
+            .arg(RAW)
+            .arg(keys[1])
+            .arg(&[0xc3u8, 0x28][..])
+            .query(&mut redis_conn())
+            .unwrap();
+        let value =
+            Spi::get_one::<Vec<u8>>("SELECT value FROM bin_mk WHERE key = 'bin_test:mk:b';")
+                .unwrap()
+                .unwrap();
+        assert_eq!(value, vec![0xc3, 0x28]);
+        let count = Spi::get_one::<i64>("SELECT count(*) FROM bin_mk;")
+            .unwrap()
+            .unwrap();
+        assert_eq!(count, 2);
+
+        for key in keys {
+            cleanup_redis_key(key);
+        }
+        cleanup();
+    }
+}
@@ -0,0 +1,178 @@
+#[cfg(any(test, feature = "pg_test"))]
+#[pgrx::pg_schema]
+mod tests {
+    use pgrx::prelude::*;
+
+    const TEST_DATABASE: &str = "15";
+    const FDW_NAME: &str = "redis_bitmap_fdw";
+    const SERVER_NAME: &str = "redis_bitmap_server";
+
+    fn setup_fdw() {
+        let _ = Spi::run(&format!(
+            "DROP FOREIGN DATA WRAPPER IF EXISTS {} CASCADE;",
+            FDW_NAME
+        ));
+        Spi::run(&format!(
+            "CREATE FOREIGN DATA WRAPPER {} HANDLER redis_fdw_handler VALIDATOR redis_fdw_validator;",
+            FDW_NAME
+        ))
+        .unwrap();
+        Spi::run(&format!(
+            "CREATE SERVER {} FOREIGN DATA WRAPPER {} OPTIONS (host_port '127.0.0.1:8899');",
+            SERVER_NAME, FDW_NAME
+        ))
+        .unwrap();
+    }
+
+    fn cleanup() {
+        let _ = Spi::run(&format!(
+            "DROP FOREIGN DATA WRAPPER IF EXISTS {} CASCADE;",
+            FDW_NAME
+        ));
+    }
+
+    fn redis_conn() -> redis::Connection {
+        redis::Client::open("redis://127.0.0.1:8899/15")
+            .unwrap()
+            .get_connection()
+            .unwrap()
+    }
+
+    fn cleanup_redis_key(key: &str) {
+        let _: Result<(), _> = redis::cmd("DEL").arg(key).query(&mut redis_conn());
+    }
+
+    fn create_table(name: &str, key: &str) {
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE {} (day bigint, active boolean) SERVER {} OPTIONS (
+                database '{}', table_type 'bitmap', table_key_prefix '{}', batch_size '100'
+            );",
+            name, SERVER_NAME, TEST_DATABASE, key
+        ))
+        .unwrap();
+    }
+
+    fn set_bits(key: &str, offsets: &[u64]) {
+        cleanup_redis_key(key);
+        let mut pipe = redis::pipe();
+        for offset in offsets {
+            pipe.cmd("SETBIT").arg(key).arg(offset).arg(1).ignore();
+        }
+        pipe.query::<()>(&mut redis_conn()).unwrap();
+    }
+
+    fn offsets(sql: &str) -> Vec<i64> {
+        Spi::connect(|client| {
+            client
+                .select(sql, None, &[])
+                .unwrap()
+                .map(|row| row.get::<i64>(1).unwrap().unwrap())
+                .collect()
+        })
+    }
+
+    fn explain(sql: &str) -> String {
+        let q = format!("EXPLAIN (FORMAT TEXT) {sql}");
+        Spi::connect(|client| {
+            let mut out = String::new();
+            for row in client.select(&q, None, &[]).unwrap() {
+                if let Some(line) = row.get::<&str>(1).unwrap() {
+                    out.push_str(line);
+                    out.push('\n');
+                }
+            }
+            out
+        })
+    }
+
+    #[pg_test]
+    fn test_scan_returns_set_bits() {
+        setup_fdw();
+        let key = "bitmap_test:scan";
+        // Far apart, so the scan spans several BITPOS/GETRANGE chunks.
+        set_bits(key, &[0, 7, 500, 100_000]);
+        create_table("bitmap_scan", key);
+
+        assert_eq!(
+            offsets("SELECT day FROM bitmap_scan;"),
+            vec![0, 7, 500, 100_000]
+        );
+        let all_true = Spi::get_one::<bool>("SELECT bool_and(active) FROM bitmap_scan;").unwrap();
+        assert_eq!(all_true, Some(true));
+
+        assert_eq!(
+            offsets("SELECT day FROM bitmap_scan WHERE day = 500;"),
+            vec![500]
+        );
+        assert!(offsets("SELECT day FROM bitmap_scan WHERE day = 501;").is_empty());
+        assert_eq!(
+            offsets("SELECT day FROM bitmap_scan WHERE day IN (1, 7, 100000) ORDER BY day;"),
+            vec![7, 100_000]
+        );
+
+        cleanup_redis_key(key);
+        cleanup();
+    }
+
+    #[pg_test]
+    fn test_count_star_uses_bitcount() {
+        setup_fdw();
+        let key = "bitmap_test:count";
+        set_bits(key, &[1, 2, 3, 40_000]);
+        create_table("bitmap_count", key);
+
+        let sql = "SELECT count(*) FROM bitmap_count";
+        let plan = explain(sql);
+        assert!(plan.contains("BITCOUNT"), "{}", plan);
+        assert!(!plan.contains("Aggregate"), "{}", plan);
+        assert_eq!(Spi::get_one::<i64>(sql).unwrap(), Some(4));
+
+        // A WHERE clause keeps the aggregate in PostgreSQL.
+        let sql = "SELECT count(*) FROM bitmap_count WHERE day > 2";
+        assert!(explain(sql).contains("Aggregate"));
+        assert_eq!(Spi::get_one::<i64>(sql).unwrap(), Some(2));
+
+        cleanup_redis_key(key);
+        cleanup();
+    }
+
+    #[pg_test]
+    fn test_bitmap_insert_update_delete() {
+        setup_fdw();
+        let key = "bitmap_test:write";
+        cleanup_redis_key(key);
+        create_table("bitmap_write", key);
+
+        Spi::run("INSERT INTO bitmap_write VALUES (5, true), (9, NULL);").unwrap();
+        assert_eq!(offsets("SELECT day FROM bitmap_write;"), vec![5, 9]);
+
+        Spi::run("UPDATE bitmap_write SET day = 6 WHERE day = 5;").unwrap();
+        assert_eq!(offsets("SELECT day FROM bitmap_write;"), vec![6, 9]);
+
+        Spi::run("UPDATE bitmap_write SET active = false WHERE day = 9;").unwrap();
+        assert_eq!(offsets("SELECT day FROM bitmap_write;"), vec![6]);
+
+        Spi::run("DELETE FROM bitmap_write WHERE day = 6;").unwrap();
+        let count: i64 = redis::cmd("BITCOUNT")
+            .arg(key)
+            .query(&mut redis_conn())
+            .unwrap();
+        assert_eq!(count, 0);
+
+        cleanup_redis_key(key);
+        cleanup();
+    }
+
+    #[pg_test]
+    #[should_panic(expected = "requires exactly 2 data column(s)")]
+    fn test_wrong_column_count_rejected() {
+        setup_fdw();
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE bitmap_bad (day bigint) SERVER {} OPTIONS (
+                table_type 'bitmap', table_key_prefix 'bitmap_test:bad'
+            );",
+            SERVER_NAME
+        ))
+        .unwrap();
+    }
+}
@@ -0,0 +1,165 @@
+#[cfg(any(test, feature = "pg_test"))]
+#[pgrx::pg_schema]
+mod tests {
+    use pgrx::prelude::*;
+    use pgrx::JsonB;
+    use serde_json::json;
+
+    const FDW_NAME: &str = "redis_command_fdw";
+    const SERVER_NAME: &str = "redis_command_server";
+
+    fn setup_fdw(server_options: &str) {
+        let _ = Spi::run(&format!(
+            "DROP FOREIGN DATA WRAPPER IF EXISTS {} CASCADE;",
+            FDW_NAME
+        ));
+        Spi::run(&format!(
+            "CREATE FOREIGN DATA WRAPPER {} HANDLER redis_fdw_handler VALIDATOR redis_fdw_validator;",
+            FDW_NAME
+        ))
+        .unwrap();
+        Spi::run(&format!(
+            "CREATE SERVER {} FOREIGN DATA WRAPPER {} OPTIONS (host_port '127.0.0.1:8899'{});",
+            SERVER_NAME, FDW_NAME, server_options
+        ))
+        .unwrap();
+    }
+
+    fn cleanup() {
+        let _ = Spi::run(&format!(
+            "DROP FOREIGN DATA WRAPPER IF EXISTS {} CASCADE;",
+            FDW_NAME
+        ));
+    }
+
+    fn redis_conn() -> redis::Connection {
+        redis::Client::open("redis://127.0.0.1:8899/15")
+            .unwrap()
+            .get_connection()
+            .unwrap()
+    }
+
+    fn jsonb(sql: &str) -> serde_json::Value {
+        Spi::get_one::<JsonB>(sql).unwrap().unwrap().0
+    }
+
+    #[pg_test]
+    fn test_redis_command_replies() {
+        setup_fdw("");
+        let key = "command_test:hash";
+        let _: Result<(), _> = redis::cmd("DEL").arg(key).query(&mut redis_conn());
+
+        assert_eq!(
+            jsonb(&format!(
+                "SELECT redis_command('{}', ARRAY['HSET', '{}', 'a', '1', 'b', '2'], 15);",
+                SERVER_NAME, key
+            )),
+            json!(2)
+        );
+        assert_eq!(
+            jsonb(&format!(
+                "SELECT redis_command('{}', ARRAY['HMGET', '{}', 'a', 'missing'], 15);",
+                SERVER_NAME, key
+            )),
+            json!(["1", null])
+        );
+        // listpack or ziplist, depending on the Redis version
+        assert!(jsonb(&format!(
+            "SELECT redis_command('{}', ARRAY['OBJECT', 'ENCODING', '{}'], 15);",
+            SERVER_NAME, key
+        ))
+        .is_string());
+
+        let _: Result<(), _> = redis::cmd("DEL").arg(key).query(&mut redis_conn());
+        cleanup();
+    }
+
+    #[pg_test]
+    fn test_redis_pipeline_reports_errors_per_command() {
+        setup_fdw("");
+        let key = "command_test:counter";
+        let _: Result<(), _> = redis::cmd("DEL").arg(key).query(&mut redis_conn());
+
+        let replies = jsonb(&format!(
+            r#"SELECT redis_pipeline('{}', '[["SET", "{k}", 1], ["INCRBY", "{k}", 5], ["HGET", "{k}", "f"], ["GET", "{k}"]]', 15);"#,
+            SERVER_NAME,
+            k = key
+        ));
+        assert_eq!(replies[0], json!("OK"));
+        assert_eq!(replies[1], json!(6));
+        assert!(replies[2]["error"]
+            .as_str()
+            .is_some_and(|e| e.starts_with("WRONGTYPE")));
+        assert_eq!(replies[3], json!("6"));
+
+        let _: Result<(), _> = redis::cmd("DEL").arg(key).query(&mut redis_conn());
+        cleanup();
+    }
+
+    #[pg_test]
+    #[should_panic(expected = "FLUSHALL is not in allowed_commands")]
+    fn test_allowed_commands_blocks_others() {
+        setup_fdw(", allowed_commands 'get,object|freq'");
+        Spi::run(&format!(
+            "SELECT redis_command('{}', ARRAY['GET', 'command_test:missing'], 15);",
+            SERVER_NAME
+        ))
+        .unwrap();
+        Spi::run(&format!(
+            "SELECT redis_command('{}', ARRAY['FLUSHALL'], 15);",
+            SERVER_NAME
+        ))
+        .unwrap();
+    }
+
+    #[pg_test]
+    #[should_panic(expected = "SELECT is not allowed")]
+    fn test_connection_state_commands_rejected() {
+        setup_fdw("");
+        Spi::run(&format!(
+            "SELECT redis_pipeline('{}', '[[\"PING\"], [\"SELECT\", \"3\"]]');",
+            SERVER_NAME
+        ))
+        .unwrap();
+    }
+
+    #[pg_test]
+    #[should_panic(expected = "CLIENT REPLY is not allowed")]
+    fn test_client_reply_rejected_even_when_allowed() {
+        setup_fdw(", allowed_commands 'client,readonly'");
+        Spi::run(&format!(
+            "SELECT redis_command('{}', ARRAY['CLIENT', 'REPLY', 'OFF'], 15);",
+            SERVER_NAME
+        ))
+        .unwrap();
+    }
+
+    #[pg_test]
+    #[should_panic(expected = "redis_command arguments cannot be NULL")]
+    fn test_redis_command_rejects_null_arguments() {
+        setup_fdw("");
+        Spi::run(&format!(
+            "SELECT redis_command('{}', ARRAY['SET', 'command_test:null', NULL], 15);",
+            SERVER_NAME
+        ))
+        .unwrap();
+    }
+
+    #[pg_test]
+    #[should_panic(expected = "permission denied for function redis_command")]
+    fn test_redis_command_requires_admin() {
+        setup_fdw("");
+        Spi::run("CREATE ROLE redis_command_test_user;").unwrap();
+        Spi::run(&format!(
+            "GRANT USAGE ON FOREIGN SERVER {} TO redis_command_test_user;",
+            SERVER_NAME
+        ))
+        .unwrap();
+        Spi::run("SET ROLE redis_command_test_user;").unwrap();
+        Spi::run(&format!(
+            "SELECT redis_command('{}', ARRAY['PING']);",
+            SERVER_NAME
+        ))
+        .unwrap();
+    }
+}
@@ -101,8 +101,8 @@ mod tests {
         // Test Hash type with data
         let mut hash_table = RedisHashTable::new();
         hash_table.dataset = DataSet::Complete(DataContainer::Hash(vec![
-            ("key1".to_string(), "value1".to_string()),
-            ("key2".to_string(), "value2".to_string()),
+            (b"key1".to_vec(), b"value1".to_vec()),
+            (b"key2".to_vec(), b"value2".to_vec()),
         ]));
         state.table_type = RedisTableType::Hash(hash_table);
         assert_eq!(state.data_len(), 2);
@@ -110,9 +110,9 @@ mod tests {
         // Test List type with data
         let mut list_table = RedisListTable::new();
         list_table.dataset = DataSet::Complete(DataContainer::List(vec![
-            "item1".to_string(),
-            "item2".to_string(),
-            "item3".to_string(),
+            b"item1".to_vec(),
+            b"item2".to_vec(),
+            b"item3".to_vec(),
         ]));
         state.table_type = RedisTableType::List(list_table);
         assert_eq!(state.data_len(), 3);
@@ -137,8 +137,8 @@ mod tests {
         // Test with hash data
         let mut hash_table = RedisHashTable::new();
         hash_table.dataset = DataSet::Complete(DataContainer::Hash(vec![
-            ("key1".to_string(), "value1".to_string()),
-            ("key2".to_string(), "value2".to_string()),
+            (b"key1".to_vec(), b"value1".to_vec()),
+            (b"key2".to_vec(), b"value2".to_vec()),
         ]));
         state.table_type = RedisTableType::Hash(hash_table);
         state.row_count = 0;
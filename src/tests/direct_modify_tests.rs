@@ -0,0 +1,670 @@
+#[cfg(any(test, feature = "pg_test"))]
+#[pgrx::pg_schema]
+mod tests {
+    use pgrx::prelude::*;
+
+    const TEST_DATABASE: &str = "15";
+    const FDW_NAME: &str = "redis_direct_modify_fdw";
+    const SERVER_NAME: &str = "redis_direct_modify_server";
+
+    fn setup_fdw() {
+        let _ = Spi::run(&format!(
+            "DROP FOREIGN DATA WRAPPER IF EXISTS {} CASCADE;",
+            FDW_NAME
+        ));
+        Spi::run(&format!(
+            "CREATE FOREIGN DATA WRAPPER {} HANDLER redis_fdw_handler VALIDATOR redis_fdw_validator;",
+            FDW_NAME
+        ))
+        .unwrap();
+        Spi::run(&format!(
+            "CREATE SERVER {} FOREIGN DATA WRAPPER {} OPTIONS (host_port '127.0.0.1:8899');",
+            SERVER_NAME, FDW_NAME
+        ))
+        .unwrap();
+    }
+
+    fn cleanup() {
+        let _ = Spi::run(&format!(
+            "DROP FOREIGN DATA WRAPPER IF EXISTS {} CASCADE;",
+            FDW_NAME
+        ));
+    }
+
+    fn redis_conn() -> redis::Connection {
+        redis::Client::open("redis://127.0.0.1:8899/15")
+            .unwrap()
+            .get_connection()
+            .unwrap()
+    }
+
+    fn cleanup_redis_keys(keys: &[&str]) {
+        let _: Result<(), _> = redis::cmd("DEL").arg(keys).query(&mut redis_conn());
+    }
+
+    fn explain_text(sql: &str) -> String {
+        let q = format!("EXPLAIN (FORMAT TEXT) {sql}");
+        Spi::connect(|client| {
+            let mut out = String::new();
+            let result = client.select(&q, None, &[]).unwrap();
+            for row in result {
+                if let Some(line) = row.get::<&str>(1).unwrap() {
+                    out.push_str(line);
+                    out.push('\n');
+                }
+            }
+            out
+        })
+    }
+
+    #[pg_test]
+    fn test_delete_by_key_unlinks_without_scan() {
+        setup_fdw();
+        let keys = ["dm_test:s1", "dm_test:s2", "dm_test:s3"];
+        cleanup_redis_keys(&keys);
+        for key in keys {
+            let _: () = redis::cmd("HSET")
+                .arg(key)
+                .arg("user")
+                .arg("alice")
+                .query(&mut redis_conn())
+                .unwrap();
+        }
+
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE dm_sessions (key text, field text, value text) SERVER {} OPTIONS (
+                database '{}', table_type 'hash', table_key_prefix 'dm_test:*'
+            );",
+            SERVER_NAME, TEST_DATABASE
+        ))
+        .unwrap();
+
+        let plan = explain_text(
+            "DELETE FROM dm_sessions WHERE key IN ('dm_test:s1', 'dm_test:s2', 'other:s1')",
+        );
+        assert!(
+            plan.contains("Direct Modify: UNLINK"),
+            "expected direct UNLINK, got:\n{plan}"
+        );
+
+        Spi::connect_mut(|client| {
+            let result = client
+                .update(
+                    "DELETE FROM dm_sessions WHERE key IN ('dm_test:s1', 'dm_test:s2', 'other:s1')",
+                    None,
+                    &[],
+                )
+                .unwrap();
+            assert_eq!(result.len(), 2);
+        });
+
+        let remaining: i64 = redis::cmd("EXISTS")
+            .arg(&keys)
+            .query(&mut redis_conn())
+            .unwrap();
+        assert_eq!(remaining, 1);
+
+        cleanup_redis_keys(&keys);
+        cleanup();
+    }
+
+    #[pg_test]
+    fn test_delete_key_with_comma_keeps_scan_path() {
+        setup_fdw();
+        let keys = ["dm_test:a", "dm_test:b", "dm_test:a,dm_test:b"];
+        cleanup_redis_keys(&keys);
+        for key in keys {
+            let _: () = redis::cmd("SET")
+                .arg(key)
+                .arg("v")
+                .query(&mut redis_conn())
+                .unwrap();
+        }
+
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE dm_comma (key text, value text) SERVER {} OPTIONS (
+                database '{}', table_type 'string', table_key_prefix 'dm_test:*'
+            );",
+            SERVER_NAME, TEST_DATABASE
+        ))
+        .unwrap();
+
+        let sql = "DELETE FROM dm_comma WHERE key IN ('dm_test:a,dm_test:b')";
+        let plan = explain_text(sql);
+        assert!(
+            !plan.contains("Direct Modify"),
+            "a key with a comma must not use direct modify:\n{plan}"
+        );
+
+        Spi::connect_mut(|client| {
+            let result = client.update(sql, None, &[]).unwrap();
+            assert_eq!(result.len(), 1);
+        });
+
+        let remaining: i64 = redis::cmd("EXISTS")
+            .arg(&keys)
+            .query(&mut redis_conn())
+            .unwrap();
+        assert_eq!(remaining, 2);
+        let gone: i64 = redis::cmd("EXISTS")
+            .arg("dm_test:a,dm_test:b")
+            .query(&mut redis_conn())
+            .unwrap();
+        assert_eq!(gone, 0);
+
+        cleanup_redis_keys(&keys);
+        cleanup();
+    }
+
+    #[pg_test]
+    fn test_delete_hash_fields_counts_redis_reply() {
+        setup_fdw();
+        let key = "dm_test:hash";
+        cleanup_redis_keys(&[key]);
+        let _: () = redis::cmd("HSET")
+            .arg(key)
+            .arg("a")
+            .arg("1")
+            .arg("b")
+            .arg("2")
+            .query(&mut redis_conn())
+            .unwrap();
+
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE dm_hash (field text, value text) SERVER {} OPTIONS (
+                database '{}', table_type 'hash', table_key_prefix '{}'
+            );",
+            SERVER_NAME, TEST_DATABASE, key
+        ))
+        .unwrap();
+
+        let plan = explain_text("DELETE FROM dm_hash WHERE field IN ('a', 'missing')");
+        assert!(
+            plan.contains("Direct Modify: HDEL"),
+            "expected direct HDEL, got:\n{plan}"
+        );
+
+        Spi::connect_mut(|client| {
+            let result = client
+                .update(
+                    "DELETE FROM dm_hash WHERE field IN ('a', 'missing')",
+                    None,
+                    &[],
+                )
+                .unwrap();
+            assert_eq!(result.len(), 1);
+        });
+
+        let fields: Vec<String> = redis::cmd("HKEYS")
+            .arg(key)
+            .query(&mut redis_conn())
+            .unwrap();
+        assert_eq!(fields, vec!["b".to_string()]);
+
+        cleanup_redis_keys(&[key]);
+        cleanup();
+    }
+
+    #[pg_test]
+    fn test_update_hash_value_only_touches_existing_fields() {
+        setup_fdw();
+        let key = "dm_test:update";
+        cleanup_redis_keys(&[key]);
+        let _: () = redis::cmd("HSET")
+            .arg(key)
+            .arg("a")
+            .arg("old")
+            .query(&mut redis_conn())
+            .unwrap();
+
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE dm_update (field text, value text) SERVER {} OPTIONS (
+                database '{}', table_type 'hash', table_key_prefix '{}'
+            );",
+            SERVER_NAME, TEST_DATABASE, key
+        ))
+        .unwrap();
+
+        let plan = explain_text("UPDATE dm_update SET value = 'new' WHERE field IN ('a', 'b')");
+        assert!(
+            plan.contains("Direct Modify: HSET"),
+            "expected direct HSET, got:\n{plan}"
+        );
+
+        Spi::connect_mut(|client| {
+            let result = client
+                .update(
+                    "UPDATE dm_update SET value = 'new' WHERE field IN ('a', 'b')",
+                    None,
+                    &[],
+                )
+                .unwrap();
+            assert_eq!(result.len(), 1);
+        });
+
+        let a: Option<String> = redis::cmd("HGET")
+            .arg(key)
+            .arg("a")
+            .query(&mut redis_conn())
+            .unwrap();
+        let b: Option<String> = redis::cmd("HGET")
+            .arg(key)
+            .arg("b")
+            .query(&mut redis_conn())
+            .unwrap();
+        assert_eq!(a.as_deref(), Some("new"));
+        assert_eq!(b, None);
+
+        cleanup_redis_keys(&[key]);
+        cleanup();
+    }
+
+    #[pg_test]
+    fn test_update_hash_counter_uses_hincrby() {
+        setup_fdw();
+        let key = "dm_test:counters";
+        cleanup_redis_keys(&[key]);
+        let _: () = redis::cmd("HSET")
+            .arg(key)
+            .arg("hits")
+            .arg(10)
+            .query(&mut redis_conn())
+            .unwrap();
+
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE dm_counters (field text, value bigint) SERVER {} OPTIONS (
+                database '{}', table_type 'hash', table_key_prefix '{}'
+            );",
+            SERVER_NAME, TEST_DATABASE, key
+        ))
+        .unwrap();
+
+        let plan = explain_text(
+            "UPDATE dm_counters SET value = value + 5 WHERE field IN ('hits', 'misses')",
+        );
+        assert!(
+            plan.contains("Direct Modify: HINCRBY"),
+            "expected direct HINCRBY, got:\n{plan}"
+        );
+
+        Spi::connect_mut(|client| {
+            let result = client
+                .update(
+                    "UPDATE dm_counters SET value = value + 5 WHERE field IN ('hits', 'misses')",
+                    None,
+                    &[],
+                )
+                .unwrap();
+            assert_eq!(result.len(), 1);
+            client
+                .update(
+                    "UPDATE dm_counters SET value = value - 2 WHERE field = 'hits'",
+                    None,
+                    &[],
+                )
+                .unwrap();
+        });
+
+        let hits: Option<i64> = redis::cmd("HGET")
+            .arg(key)
+            .arg("hits")
+            .query(&mut redis_conn())
+            .unwrap();
+        let misses: Option<i64> = redis::cmd("HGET")
+            .arg(key)
+            .arg("misses")
+            .query(&mut redis_conn())
+            .unwrap();
+        assert_eq!(hits, Some(13));
+        assert_eq!(misses, None);
+
+        cleanup_redis_keys(&[key]);
+        cleanup();
+    }
+
+    #[pg_test]
+    fn test_update_string_keys_increment_and_append() {
+        setup_fdw();
+        let keys = ["dm_test:str:n", "dm_test:str:s"];
+        cleanup_redis_keys(&keys);
+        let _: () = redis::cmd("MSET")
+            .arg(keys[0])
+            .arg("1")
+            .arg(keys[1])
+            .arg("ab")
+            .query(&mut redis_conn())
+            .unwrap();
+
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE dm_num (key text, value float8) SERVER {server} OPTIONS (
+                database '{db}', table_type 'string', table_key_prefix 'dm_test:str:*'
+            );
+            CREATE FOREIGN TABLE dm_str (key text, value text) SERVER {server} OPTIONS (
+                database '{db}', table_type 'string', table_key_prefix 'dm_test:str:*'
+            );",
+            server = SERVER_NAME,
+            db = TEST_DATABASE
+        ))
+        .unwrap();
+
+        let plan =
+            explain_text("UPDATE dm_num SET value = value + 0.5 WHERE key = 'dm_test:str:n'");
+        assert!(
+            plan.contains("Direct Modify: INCRBYFLOAT"),
+            "expected direct INCRBYFLOAT, got:\n{plan}"
+        );
+        let plan =
+            explain_text("UPDATE dm_str SET value = value || 'c' WHERE key = 'dm_test:str:s'");
+        assert!(
+            plan.contains("Direct Modify: APPEND"),
+            "expected direct APPEND, got:\n{plan}"
+        );
+
+        Spi::run("UPDATE dm_num SET value = value + 0.5 WHERE key = 'dm_test:str:n'").unwrap();
+        Spi::run("UPDATE dm_str SET value = value || 'c' WHERE key = 'dm_test:str:s'").unwrap();
+
+        let values: Vec<String> = redis::cmd("MGET")
+            .arg(&keys)
+            .query(&mut redis_conn())
+            .unwrap();
+        assert_eq!(values, vec!["1.5".to_string(), "abc".to_string()]);
+
+        cleanup_redis_keys(&keys);
+        cleanup();
+    }
+
+    #[pg_test]
+    fn test_update_zset_score_uses_zincrby() {
+        setup_fdw();
+        let key = "dm_test:scores";
+        cleanup_redis_keys(&[key]);
+        let _: () = redis::cmd("ZADD")
+            .arg(key)
+            .arg(10)
+            .arg("alice")
+            .query(&mut redis_conn())
+            .unwrap();
+
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE dm_scores (member text, score float8) SERVER {} OPTIONS (
+                database '{}', table_type 'zset', table_key_prefix '{}'
+            );",
+            SERVER_NAME, TEST_DATABASE, key
+        ))
+        .unwrap();
+
+        let plan = explain_text(
+            "UPDATE dm_scores SET score = score + 2.5 WHERE member IN ('alice', 'bob')",
+        );
+        assert!(
+            plan.contains("Direct Modify: ZINCRBY"),
+            "expected direct ZINCRBY, got:\n{plan}"
+        );
+
+        Spi::connect_mut(|client| {
+            let result = client
+                .update(
+                    "UPDATE dm_scores SET score = score + 2.5 WHERE member IN ('alice', 'bob')",
+                    None,
+                    &[],
+                )
+                .unwrap();
+            assert_eq!(result.len(), 1);
+        });
+
+        let members: Vec<(String, f64)> = redis::cmd("ZRANGE")
+            .arg(key)
+            .arg(0)
+            .arg(-1)
+            .arg("WITHSCORES")
+            .query(&mut redis_conn())
+            .unwrap();
+        assert_eq!(members, vec![("alice".to_string(), 12.5)]);
+
+        cleanup_redis_keys(&[key]);
+        cleanup();
+    }
+
+    #[pg_test]
+    fn test_delete_zset_score_range_uses_zremrangebyscore() {
+        setup_fdw();
+        let key = "dm_test:zset";
+        cleanup_redis_keys(&[key]);
+        let _: () = redis::cmd("ZADD")
+            .arg(key)
+            .arg(50)
+            .arg("low")
+            .arg(100)
+            .arg("edge")
+            .arg(150)
+            .arg("high")
+            .query(&mut redis_conn())
+            .unwrap();
+
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE dm_zset (member text, score float8) SERVER {} OPTIONS (
+                database '{}', table_type 'zset', table_key_prefix '{}'
+            );",
+            SERVER_NAME, TEST_DATABASE, key
+        ))
+        .unwrap();
+
+        let plan = explain_text("DELETE FROM dm_zset WHERE score < 100");
+        assert!(
+            plan.contains("Direct Modify: ZREMRANGEBYSCORE"),
+            "expected direct ZREMRANGEBYSCORE, got:\n{plan}"
+        );
+
+        Spi::connect_mut(|client| {
+            let result = client
+                .update("DELETE FROM dm_zset WHERE 100 > score", None, &[])
+                .unwrap();
+            assert_eq!(result.len(), 1);
+        });
+
+        let members: Vec<String> = redis::cmd("ZRANGE")
+            .arg(key)
+            .arg(0)
+            .arg(-1)
+            .query(&mut redis_conn())
+            .unwrap();
+        assert_eq!(members, vec!["edge".to_string(), "high".to_string()]);
+
+        cleanup_redis_keys(&[key]);
+        cleanup();
+    }
+
+    #[pg_test]
+    fn test_delete_zset_member_range_uses_zremrangebylex() {
+        setup_fdw();
+        let key = "dm_test:zset_lex";
+        cleanup_redis_keys(&[key]);
+        let _: () = redis::cmd("ZADD")
+            .arg(key)
+            .arg(0)
+            .arg("apple")
+            .arg(0)
+            .arg("banana")
+            .arg(0)
+            .arg("Cherry")
+            .arg(0)
+            .arg("date")
+            .query(&mut redis_conn())
+            .unwrap();
+
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE dm_zset_lex (member text COLLATE \"C\", score float8) SERVER {} OPTIONS (
+                database '{}', table_type 'zset', table_key_prefix '{}'
+            );",
+            SERVER_NAME, TEST_DATABASE, key
+        ))
+        .unwrap();
+
+        let plan = explain_text("DELETE FROM dm_zset_lex WHERE member < 'banana'");
+        assert!(
+            plan.contains("Direct Modify: ZREMRANGEBYLEX"),
+            "expected direct ZREMRANGEBYLEX, got:\n{plan}"
+        );
+
+        // Bytewise, 'Cherry' sorts before 'apple'.
+        Spi::connect_mut(|client| {
+            let result = client
+                .update("DELETE FROM dm_zset_lex WHERE member < 'banana'", None, &[])
+                .unwrap();
+            assert_eq!(result.len(), 2);
+        });
+
+        // Different scores: the members are compared one by one instead.
+        let _: () = redis::cmd("ZADD")
+            .arg(key)
+            .arg(5)
+            .arg("elderberry")
+            .query(&mut redis_conn())
+            .unwrap();
+        Spi::connect_mut(|client| {
+            let result = client
+                .update("DELETE FROM dm_zset_lex WHERE member >= 'd'", None, &[])
+                .unwrap();
+            assert_eq!(result.len(), 2);
+        });
+
+        let members: Vec<String> = redis::cmd("ZRANGE")
+            .arg(key)
+            .arg(0)
+            .arg(-1)
+            .query(&mut redis_conn())
+            .unwrap();
+        assert_eq!(members, vec!["banana".to_string()]);
+
+        cleanup_redis_keys(&[key]);
+        cleanup();
+    }
+
+    #[pg_test]
+    fn test_delete_stream_id_ranges() {
+        setup_fdw();
+        let key = "dm_test:stream";
+        cleanup_redis_keys(&[key]);
+        for id in ["1000-0", "2000-0", "3000-0", "4000-0"] {
+            let _: String = redis::cmd("XADD")
+                .arg(key)
+                .arg(id)
+                .arg("event")
+                .arg("e")
+                .query(&mut redis_conn())
+                .unwrap();
+        }
+
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE dm_stream (stream_id text, event text) SERVER {} OPTIONS (
+                database '{}', table_type 'stream', table_key_prefix '{}'
+            );",
+            SERVER_NAME, TEST_DATABASE, key
+        ))
+        .unwrap();
+
+        let plan = explain_text("DELETE FROM dm_stream WHERE stream_id < '2000-0'");
+        assert!(
+            plan.contains("Direct Modify: XTRIM MINID"),
+            "expected direct XTRIM, got:\n{plan}"
+        );
+
+        Spi::connect_mut(|client| {
+            let trimmed = client
+                .update(
+                    "DELETE FROM dm_stream WHERE stream_id < '2000-0'",
+                    None,
+                    &[],
+                )
+                .unwrap();
+            assert_eq!(trimmed.len(), 1);
+            let deleted = client
+                .update(
+                    "DELETE FROM dm_stream WHERE stream_id > '2000-0' AND stream_id <= '3000-0'",
+                    None,
+                    &[],
+                )
+                .unwrap();
+            assert_eq!(deleted.len(), 1);
+        });
+
+        let entries: Vec<(String, Vec<String>)> = redis::cmd("XRANGE")
+            .arg(key)
+            .arg("-")
+            .arg("+")
+            .query(&mut redis_conn())
+            .unwrap();
+        let ids: Vec<&str> = entries.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(ids, vec!["2000-0", "4000-0"]);
+
+        cleanup_redis_keys(&[key]);
+        cleanup();
+    }
+
+    #[pg_test]
+    fn test_delete_list_index_range_uses_ltrim() {
+        setup_fdw();
+        let key = "dm_test:list";
+        cleanup_redis_keys(&[key]);
+        let _: () = redis::cmd("RPUSH")
+            .arg(key)
+            .arg(&["a", "b", "c", "d", "e"])
+            .query(&mut redis_conn())
+            .unwrap();
+
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE dm_list (index bigint, element text) SERVER {} OPTIONS (
+                database '{}', table_type 'list', table_key_prefix '{}'
+            );",
+            SERVER_NAME, TEST_DATABASE, key
+        ))
+        .unwrap();
+
+        let plan = explain_text("DELETE FROM dm_list WHERE index < 2");
+        assert!(
+            plan.contains("Direct Modify: LTRIM"),
+            "expected direct LTRIM, got:\n{plan}"
+        );
+
+        Spi::connect_mut(|client| {
+            let result = client
+                .update("DELETE FROM dm_list WHERE index < 2", None, &[])
+                .unwrap();
+            assert_eq!(result.len(), 2);
+        });
+
+        let items: Vec<String> = redis::cmd("LRANGE")
+            .arg(key)
+            .arg(0)
+            .arg(-1)
+            .query(&mut redis_conn())
+            .unwrap();
+        assert_eq!(items, vec!["c", "d", "e"]);
+
+        cleanup_redis_keys(&[key]);
+        cleanup();
+    }
+
+    #[pg_test]
+    fn test_non_key_condition_keeps_scan_path() {
+        setup_fdw();
+        let key = "dm_test:fallback";
+        cleanup_redis_keys(&[key]);
+
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE dm_fallback (field text, value text) SERVER {} OPTIONS (
+                database '{}', table_type 'hash', table_key_prefix '{}'
+            );",
+            SERVER_NAME, TEST_DATABASE, key
+        ))
+        .unwrap();
+
+        let plan = explain_text("DELETE FROM dm_fallback WHERE value = 'x'");
+        assert!(
+            !plan.contains("Direct Modify"),
+            "value filter must not use direct modify:\n{plan}"
+        );
+
+        cleanup();
+    }
+}
@@ -0,0 +1,188 @@
+#[cfg(any(test, feature = "pg_test"))]
+#[pgrx::pg_schema]
+mod tests {
+    use pgrx::prelude::*;
+
+    const TEST_DATABASE: &str = "15";
+    const FDW_NAME: &str = "redis_function_fdw";
+    const SERVER_NAME: &str = "redis_function_server";
+
+    /// Library keeping `id -> title` rows in the hash KEYS[1].
+    const LIBRARY: &str = r#"#!lua name=redis_fdw_function_test
+redis.register_function('fdw_test_read', function(keys, args)
+    local id
+    for i = 1, #args, 2 do
+        if args[i] == 'id' then id = args[i + 1] end
+    end
+    local rows = {}
+    if id then
+        local title = redis.call('HGET', keys[1], id)
+        if title then rows[1] = {id, title} end
+        return rows
+    end
+    local all = redis.call('HGETALL', keys[1])
+    for i = 1, #all, 2 do rows[#rows + 1] = {all[i], all[i + 1]} end
+    return rows
+end)
+redis.register_function('fdw_test_write', function(keys, args)
+    if args[1] == 'insert' then
+        return redis.call('HSET', keys[1], args[2], args[3])
+    elseif args[1] == 'update' then
+        redis.call('HDEL', keys[1], args[2])
+        return redis.call('HSET', keys[1], args[3], args[4])
+    elseif args[1] == 'delete' then
+        return redis.call('HDEL', keys[1], args[2])
+    end
+    return redis.error_reply('unknown operation ' .. tostring(args[1]))
+end)
+"#;
+
+    fn setup_fdw() {
+        let _ = Spi::run(&format!(
+            "DROP FOREIGN DATA WRAPPER IF EXISTS {} CASCADE;",
+            FDW_NAME
+        ));
+        Spi::run(&format!(
+            "CREATE FOREIGN DATA WRAPPER {} HANDLER redis_fdw_handler VALIDATOR redis_fdw_validator;",
+            FDW_NAME
+        ))
+        .unwrap();
+        Spi::run(&format!(
+            "CREATE SERVER {} FOREIGN DATA WRAPPER {} OPTIONS (host_port '127.0.0.1:8899');",
+            SERVER_NAME, FDW_NAME
+        ))
+        .unwrap();
+    }
+
+    fn cleanup() {
+        let _ = Spi::run(&format!(
+            "DROP FOREIGN DATA WRAPPER IF EXISTS {} CASCADE;",
+            FDW_NAME
+        ));
+    }
+
+    fn redis_conn() -> redis::Connection {
+        redis::Client::open("redis://127.0.0.1:8899/15")
+            .unwrap()
+            .get_connection()
+            .unwrap()
+    }
+
+    #[pg_test]
+    fn test_function_table_round_trip() {
+        setup_fdw();
+        let key = "function_test:tasks";
+        let mut conn = redis_conn();
+        let _: String = redis::cmd("FUNCTION")
+            .arg("LOAD")
+            .arg("REPLACE")
+            .arg(LIBRARY)
+            .query(&mut conn)
+            .unwrap();
+        let _: Result<(), _> = redis::cmd("DEL").arg(key).query(&mut conn);
+
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE function_tasks (id text, title text) SERVER {} OPTIONS (
+                database '{}', table_type 'function', table_key_prefix '{}',
+                read_function 'fdw_test_read', write_function 'fdw_test_write'
+            );",
+            SERVER_NAME, TEST_DATABASE, key
+        ))
+        .unwrap();
+
+        Spi::run("INSERT INTO function_tasks VALUES ('1', 'write docs'), ('2', 'ship');").unwrap();
+        assert_eq!(
+            Spi::get_one::<i64>("SELECT count(*) FROM function_tasks;").unwrap(),
+            Some(2)
+        );
+        assert_eq!(
+            Spi::get_one::<String>("SELECT title FROM function_tasks WHERE id = '2';").unwrap(),
+            Some("ship".to_string())
+        );
+
+        Spi::run("UPDATE function_tasks SET id = '3', title = 'review' WHERE id = '1';").unwrap();
+        Spi::run("DELETE FROM function_tasks WHERE id = '2';").unwrap();
+        let stored: Vec<(String, String)> =
+            redis::cmd("HGETALL").arg(key).query(&mut conn).unwrap();
+        assert_eq!(stored, vec![("3".to_string(), "review".to_string())]);
+
+        let _: Result<(), _> = redis::cmd("DEL").arg(key).query(&mut conn);
+        let _: Result<(), _> = redis::cmd("FUNCTION")
+            .arg("DELETE")
+            .arg("redis_fdw_function_test")
+            .query(&mut conn);
+        cleanup();
+    }
+
+    #[pg_test]
+    fn test_script_table_arguments_and_nulls() {
+        setup_fdw();
+        let key = "function_test:log";
+        let mut conn = redis_conn();
+        let _: Result<(), _> = redis::cmd("DEL").arg(key).query(&mut conn);
+
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE function_log (k text, arg text, extra text) SERVER {} OPTIONS (
+                database '{}', table_type 'function', table_key_prefix '{}',
+                read_script $$return {{{{KEYS[1], ARGV[2]}}, {{'second'}}}}$$,
+                write_script $$return redis.call('RPUSH', KEYS[1], table.concat(ARGV, ':'))$$
+            );",
+            SERVER_NAME, TEST_DATABASE, key
+        ))
+        .unwrap();
+
+        // The pushed-down value comes back as `arg`; short rows are padded
+        // with NULLs.
+        let first = Spi::get_three::<String, String, String>(
+            "SELECT k, arg, extra FROM function_log WHERE arg = 'x';",
+        )
+        .unwrap();
+        assert_eq!(first, (Some(key.to_string()), Some("x".to_string()), None));
+        assert_eq!(
+            Spi::get_one::<i64>("SELECT count(*) FROM function_log WHERE arg IS NULL;").unwrap(),
+            Some(2)
+        );
+
+        let _: () = redis::cmd("SCRIPT").arg("FLUSH").query(&mut conn).unwrap();
+        Spi::run("INSERT INTO function_log VALUES ('a', 'b', 'c');").unwrap();
+        Spi::run("DELETE FROM function_log WHERE k = 'second';").unwrap();
+        let calls: Vec<String> = redis::cmd("LRANGE")
+            .arg(key)
+            .arg(0)
+            .arg(-1)
+            .query(&mut conn)
+            .unwrap();
+        assert_eq!(calls, vec!["insert:a:b:c", "delete:second"]);
+
+        let _: Result<(), _> = redis::cmd("DEL").arg(key).query(&mut conn);
+        cleanup();
+    }
+
+    #[pg_test]
+    #[should_panic(expected = "needs write_function or write_script")]
+    fn test_read_only_function_table_rejects_insert() {
+        setup_fdw();
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE function_read_only (v text) SERVER {} OPTIONS (
+                database '{}', table_type 'function', table_key_prefix 'function_test:ro',
+                read_script 'return {{}}'
+            );",
+            SERVER_NAME, TEST_DATABASE
+        ))
+        .unwrap();
+        Spi::run("INSERT INTO function_read_only VALUES ('x');").unwrap();
+    }
+
+    #[pg_test]
+    #[should_panic(expected = "function tables need read_function or read_script")]
+    fn test_function_table_requires_callable() {
+        setup_fdw();
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE function_none (v text) SERVER {} OPTIONS (
+                table_type 'function', table_key_prefix 'function_test:none'
+            );",
+            SERVER_NAME
+        ))
+        .unwrap();
+    }
+}
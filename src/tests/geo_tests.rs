@@ -0,0 +1,206 @@
+#[cfg(any(test, feature = "pg_test"))]
+#[pgrx::pg_schema]
+mod tests {
+    use pgrx::prelude::*;
+
+    const TEST_DATABASE: &str = "15";
+    const FDW_NAME: &str = "redis_geo_fdw";
+    const SERVER_NAME: &str = "redis_geo_server";
+
+    fn setup_fdw() {
+        let _ = Spi::run(&format!(
+            "DROP FOREIGN DATA WRAPPER IF EXISTS {} CASCADE;",
+            FDW_NAME
+        ));
+        Spi::run(&format!(
+            "CREATE FOREIGN DATA WRAPPER {} HANDLER redis_fdw_handler VALIDATOR redis_fdw_validator;",
+            FDW_NAME
+        ))
+        .unwrap();
+        Spi::run(&format!(
+            "CREATE SERVER {} FOREIGN DATA WRAPPER {} OPTIONS (host_port '127.0.0.1:8899');",
+            SERVER_NAME, FDW_NAME
+        ))
+        .unwrap();
+    }
+
+    fn cleanup() {
+        let _ = Spi::run(&format!(
+            "DROP FOREIGN DATA WRAPPER IF EXISTS {} CASCADE;",
+            FDW_NAME
+        ));
+    }
+
+    fn redis_conn() -> redis::Connection {
+        redis::Client::open("redis://127.0.0.1:8899/15")
+            .unwrap()
+            .get_connection()
+            .unwrap()
+    }
+
+    fn cleanup_redis_key(key: &str) {
+        let _: Result<(), _> = redis::cmd("DEL").arg(key).query(&mut redis_conn());
+    }
+
+    fn create_table(name: &str, columns: &str, key: &str) {
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE {} ({}) SERVER {} OPTIONS (
+                database '{}', table_type 'geo', table_key_prefix '{}'
+            );",
+            name, columns, SERVER_NAME, TEST_DATABASE, key
+        ))
+        .unwrap();
+    }
+
+    /// Palermo and Catania from the Redis GEOSEARCH documentation, plus
+    /// Rome well outside Sicily.
+    fn load_sicily(key: &str) {
+        cleanup_redis_key(key);
+        let _: i64 = redis::cmd("GEOADD")
+            .arg(key)
+            .arg(&["13.361389", "38.115556", "Palermo"])
+            .arg(&["15.087269", "37.502669", "Catania"])
+            .arg(&["12.496366", "41.902783", "Rome"])
+            .query(&mut redis_conn())
+            .unwrap();
+    }
+
+    fn names(sql: &str) -> Vec<String> {
+        Spi::connect(|client| {
+            client
+                .select(sql, None, &[])
+                .unwrap()
+                .map(|row| row.get::<String>(1).unwrap().unwrap())
+                .collect()
+        })
+    }
+
+    fn explain(sql: &str) -> String {
+        let q = format!("EXPLAIN (FORMAT TEXT) {sql}");
+        Spi::connect(|client| {
+            let mut out = String::new();
+            for row in client.select(&q, None, &[]).unwrap() {
+                if let Some(line) = row.get::<&str>(1).unwrap() {
+                    out.push_str(line);
+                    out.push('\n');
+                }
+            }
+            out
+        })
+    }
+
+    #[pg_test]
+    fn test_radius_and_box_search() {
+        setup_fdw();
+        let key = "geo_test:sicily";
+        load_sicily(key);
+        create_table(
+            "geo_places",
+            "name text, longitude float8, latitude float8, distance float8",
+            key,
+        );
+
+        let found = names(
+            "SELECT name FROM geo_places
+             WHERE redis_geo_within(longitude, latitude, 15, 37, 200000) ORDER BY name;",
+        );
+        assert_eq!(found, vec!["Catania", "Palermo"]);
+
+        let found = names(
+            "SELECT name FROM geo_places
+             WHERE redis_geo_within_box(longitude, latitude, 15, 37, 250000, 250000);",
+        );
+        assert_eq!(found, vec!["Catania"]);
+
+        let distance = Spi::get_one::<f64>(
+            "SELECT distance FROM geo_places
+             WHERE redis_geo_within(longitude, latitude, 15, 37, 200000) AND name = 'Palermo';",
+        )
+        .unwrap()
+        .unwrap();
+        assert!((distance - 190442.6).abs() < 1.0, "{}", distance);
+
+        let no_search =
+            Spi::get_one::<bool>("SELECT distance IS NULL FROM geo_places WHERE name = 'Rome';")
+                .unwrap();
+        assert_eq!(no_search, Some(true));
+
+        cleanup_redis_key(key);
+        cleanup();
+    }
+
+    #[pg_test]
+    fn test_order_by_distance_uses_search_order() {
+        setup_fdw();
+        let key = "geo_test:nearest";
+        load_sicily(key);
+        create_table(
+            "geo_nearest",
+            "name text, longitude float8, latitude float8, distance float8",
+            key,
+        );
+
+        let sql = "SELECT name FROM geo_nearest
+                   WHERE redis_geo_within(longitude, latitude, 15, 37, 1000000)
+                   ORDER BY distance LIMIT 2";
+        assert!(!explain(sql).contains("Sort"), "{}", explain(sql));
+        assert_eq!(names(sql), vec!["Catania", "Palermo"]);
+
+        let far = names(
+            "SELECT name FROM geo_nearest
+             WHERE redis_geo_within(longitude, latitude, 15, 37, 1000000)
+             ORDER BY distance DESC LIMIT 1;",
+        );
+        assert_eq!(far, vec!["Rome"]);
+
+        cleanup_redis_key(key);
+        cleanup();
+    }
+
+    #[pg_test]
+    fn test_insert_update_delete() {
+        setup_fdw();
+        let key = "geo_test:write";
+        cleanup_redis_key(key);
+        create_table(
+            "geo_write",
+            "name text, longitude float8, latitude float8, geohash text",
+            key,
+        );
+
+        Spi::run("INSERT INTO geo_write (name, longitude, latitude) VALUES ('Palermo', 13.361389, 38.115556);")
+            .unwrap();
+        let geohash = Spi::get_one::<String>("SELECT geohash FROM geo_write;").unwrap();
+        assert_eq!(geohash.as_deref(), Some("sqc8b49rny0"));
+
+        Spi::run("UPDATE geo_write SET longitude = 15.087269, latitude = 37.502669 WHERE name = 'Palermo';")
+            .unwrap();
+        let moved: Vec<Option<(String, String)>> = redis::cmd("GEOPOS")
+            .arg(key)
+            .arg("Palermo")
+            .query(&mut redis_conn())
+            .unwrap();
+        let (longitude, _) = moved[0].clone().unwrap();
+        assert!(longitude.starts_with("15.0872"), "{}", longitude);
+
+        Spi::run("UPDATE geo_write SET name = 'Catania' WHERE name = 'Palermo';").unwrap();
+        assert_eq!(names("SELECT name FROM geo_write;"), vec!["Catania"]);
+
+        Spi::run("DELETE FROM geo_write WHERE name = 'Catania';").unwrap();
+        let count: i64 = redis::cmd("ZCARD")
+            .arg(key)
+            .query(&mut redis_conn())
+            .unwrap();
+        assert_eq!(count, 0);
+
+        cleanup_redis_key(key);
+        cleanup();
+    }
+
+    #[pg_test]
+    #[should_panic(expected = "requires 3-5 data column(s)")]
+    fn test_too_few_columns_rejected() {
+        setup_fdw();
+        create_table("geo_bad", "name text, longitude float8", "geo_test:bad");
+    }
+}
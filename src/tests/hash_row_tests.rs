@@ -0,0 +1,200 @@
+#[cfg(any(test, feature = "pg_test"))]
+#[pgrx::pg_schema]
+mod tests {
+    use pgrx::prelude::*;
+    use std::collections::HashMap;
+
+    const TEST_DATABASE: &str = "15";
+    const FDW_NAME: &str = "redis_hash_row_fdw";
+    const SERVER_NAME: &str = "redis_hash_row_server";
+
+    fn setup_fdw() {
+        let _ = Spi::run(&format!(
+            "DROP FOREIGN DATA WRAPPER IF EXISTS {} CASCADE;",
+            FDW_NAME
+        ));
+        Spi::run(&format!(
+            "CREATE FOREIGN DATA WRAPPER {} HANDLER redis_fdw_handler VALIDATOR redis_fdw_validator;",
+            FDW_NAME
+        ))
+        .unwrap();
+        Spi::run(&format!(
+            "CREATE SERVER {} FOREIGN DATA WRAPPER {} OPTIONS (host_port '127.0.0.1:8899');",
+            SERVER_NAME, FDW_NAME
+        ))
+        .unwrap();
+    }
+
+    fn cleanup() {
+        let _ = Spi::run(&format!(
+            "DROP FOREIGN DATA WRAPPER IF EXISTS {} CASCADE;",
+            FDW_NAME
+        ));
+    }
+
+    fn redis_conn() -> redis::Connection {
+        redis::Client::open("redis://127.0.0.1:8899/15")
+            .unwrap()
+            .get_connection()
+            .unwrap()
+    }
+
+    fn cleanup_redis_key(key: &str) {
+        let _: Result<(), _> = redis::cmd("DEL").arg(key).query(&mut redis_conn());
+    }
+
+    fn create_table(name: &str, columns: &str, key: &str) {
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE {} ({}) SERVER {} OPTIONS (
+                database '{}', table_type 'hash_row', table_key_prefix '{}'
+            );",
+            name, columns, SERVER_NAME, TEST_DATABASE, key
+        ))
+        .unwrap();
+    }
+
+    fn hset(key: &str, pairs: &[(&str, &str)]) {
+        let _: () = redis::cmd("HSET")
+            .arg(key)
+            .arg(pairs)
+            .query(&mut redis_conn())
+            .unwrap();
+    }
+
+    fn hgetall(key: &str) -> HashMap<String, String> {
+        redis::cmd("HGETALL")
+            .arg(key)
+            .query(&mut redis_conn())
+            .unwrap()
+    }
+
+    #[pg_test]
+    fn test_single_key_reads_one_row() {
+        setup_fdw();
+        let key = "hash_row_test:profile";
+        cleanup_redis_key(key);
+        hset(key, &[("name", "alice"), ("age", "30"), ("extra", "x")]);
+        create_table("hr_profile", "name text, email text, age int", key);
+
+        let name = Spi::get_one::<String>("SELECT name FROM hr_profile;").unwrap();
+        assert_eq!(name.as_deref(), Some("alice"));
+        let age = Spi::get_one::<i32>("SELECT age FROM hr_profile;").unwrap();
+        assert_eq!(age, Some(30));
+        let email_is_null = Spi::get_one::<bool>("SELECT email IS NULL FROM hr_profile;")
+            .unwrap()
+            .unwrap();
+        assert!(email_is_null);
+
+        cleanup_redis_key(key);
+        cleanup();
+    }
+
+    #[pg_test]
+    fn test_multi_key_scan_and_key_lookup() {
+        setup_fdw();
+        let keys = ["hash_row_test:user:1", "hash_row_test:user:2"];
+        for key in keys {
+            cleanup_redis_key(key);
+        }
+        hset(keys[0], &[("name", "alice"), ("age", "30")]);
+        hset(keys[1], &[("name", "bob")]);
+        create_table(
+            "hr_users",
+            "key text, name text, age int",
+            "hash_row_test:user:*",
+        );
+
+        let count = Spi::get_one::<i64>("SELECT count(*) FROM hr_users;")
+            .unwrap()
+            .unwrap();
+        assert_eq!(count, 2);
+        let missing = Spi::get_one::<i64>("SELECT count(*) FROM hr_users WHERE age IS NULL;")
+            .unwrap()
+            .unwrap();
+        assert_eq!(missing, 1);
+        let name = Spi::get_one::<String>(&format!(
+            "SELECT name FROM hr_users WHERE key = '{}';",
+            keys[0]
+        ))
+        .unwrap();
+        assert_eq!(name.as_deref(), Some("alice"));
+
+        for key in keys {
+            cleanup_redis_key(key);
+        }
+        cleanup();
+    }
+
+    #[pg_test]
+    fn test_multi_key_insert_update_delete() {
+        setup_fdw();
+        let key = "hash_row_test:item:1";
+        cleanup_redis_key(key);
+        create_table(
+            "hr_items",
+            "key text, label text, price numeric",
+            "hash_row_test:item:*",
+        );
+
+        Spi::run(&format!(
+            "INSERT INTO hr_items VALUES ('{}', 'pen', 1.5);",
+            key
+        ))
+        .unwrap();
+        let stored = hgetall(key);
+        assert_eq!(stored.get("label").map(String::as_str), Some("pen"));
+        assert_eq!(stored.get("price").map(String::as_str), Some("1.5"));
+
+        hset(key, &[("untracked", "keep")]);
+        Spi::run(&format!(
+            "UPDATE hr_items SET price = NULL, label = 'ink' WHERE key = '{}';",
+            key
+        ))
+        .unwrap();
+        let stored = hgetall(key);
+        assert_eq!(stored.get("label").map(String::as_str), Some("ink"));
+        assert!(!stored.contains_key("price"));
+
+        Spi::run(&format!("DELETE FROM hr_items WHERE key = '{}';", key)).unwrap();
+        let stored = hgetall(key);
+        assert_eq!(stored.len(), 1);
+        assert_eq!(stored.get("untracked").map(String::as_str), Some("keep"));
+
+        cleanup_redis_key(key);
+        cleanup();
+    }
+
+    #[pg_test]
+    fn test_field_column_option() {
+        setup_fdw();
+        let key = "hash_row_test:renamed";
+        cleanup_redis_key(key);
+        hset(key, &[("e-mail", "a@example.com")]);
+        create_table("hr_renamed", "email text OPTIONS (field 'e-mail')", key);
+
+        let email = Spi::get_one::<String>("SELECT email FROM hr_renamed;").unwrap();
+        assert_eq!(email.as_deref(), Some("a@example.com"));
+
+        Spi::run("UPDATE hr_renamed SET email = 'b@example.com';").unwrap();
+        let stored = hgetall(key);
+        assert_eq!(
+            stored.get("e-mail").map(String::as_str),
+            Some("b@example.com")
+        );
+        assert!(!stored.contains_key("email"));
+
+        cleanup_redis_key(key);
+        cleanup();
+    }
+
+    #[pg_test]
+    #[should_panic(expected = "field must not be empty")]
+    fn test_empty_field_option_rejected() {
+        setup_fdw();
+        create_table(
+            "hr_bad",
+            "email text OPTIONS (field '')",
+            "hash_row_test:bad",
+        );
+    }
+}
@@ -0,0 +1,177 @@
+#[cfg(any(test, feature = "pg_test"))]
+#[pgrx::pg_schema]
+mod tests {
+    use pgrx::prelude::*;
+
+    const TEST_DATABASE: &str = "15";
+    const FDW_NAME: &str = "redis_hll_fdw";
+    const SERVER_NAME: &str = "redis_hll_server";
+
+    fn setup_fdw() {
+        let _ = Spi::run(&format!(
+            "DROP FOREIGN DATA WRAPPER IF EXISTS {} CASCADE;",
+            FDW_NAME
+        ));
+        Spi::run(&format!(
+            "CREATE FOREIGN DATA WRAPPER {} HANDLER redis_fdw_handler VALIDATOR redis_fdw_validator;",
+            FDW_NAME
+        ))
+        .unwrap();
+        Spi::run(&format!(
+            "CREATE SERVER {} FOREIGN DATA WRAPPER {} OPTIONS (host_port '127.0.0.1:8899');",
+            SERVER_NAME, FDW_NAME
+        ))
+        .unwrap();
+    }
+
+    fn cleanup() {
+        let _ = Spi::run(&format!(
+            "DROP FOREIGN DATA WRAPPER IF EXISTS {} CASCADE;",
+            FDW_NAME
+        ));
+    }
+
+    fn redis_conn() -> redis::Connection {
+        redis::Client::open("redis://127.0.0.1:8899/15")
+            .unwrap()
+            .get_connection()
+            .unwrap()
+    }
+
+    fn cleanup_redis_keys(keys: &[&str]) {
+        let _: Result<(), _> = redis::cmd("DEL").arg(keys).query(&mut redis_conn());
+    }
+
+    fn pfadd(key: &str, elements: &[&str]) {
+        let _: i64 = redis::cmd("PFADD")
+            .arg(key)
+            .arg(elements)
+            .query(&mut redis_conn())
+            .unwrap();
+    }
+
+    #[pg_test]
+    fn test_single_key_insert_and_count() {
+        setup_fdw();
+        let key = "hll_test:single";
+        cleanup_redis_keys(&[key]);
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE hll_single (visitors bigint, visitor text) SERVER {} OPTIONS (
+                database '{}', table_type 'hyperloglog', table_key_prefix '{}'
+            );",
+            SERVER_NAME, TEST_DATABASE, key
+        ))
+        .unwrap();
+
+        assert_eq!(
+            Spi::get_one::<i64>("SELECT count(*) FROM hll_single;").unwrap(),
+            Some(0)
+        );
+
+        Spi::run(
+            "INSERT INTO hll_single (visitor) VALUES ('alice'), ('bob'), ('alice'), ('carol');",
+        )
+        .unwrap();
+        assert_eq!(
+            Spi::get_one::<i64>("SELECT visitors FROM hll_single;").unwrap(),
+            Some(3)
+        );
+        assert_eq!(
+            Spi::get_one::<bool>("SELECT visitor IS NULL FROM hll_single;").unwrap(),
+            Some(true)
+        );
+
+        Spi::run("DELETE FROM hll_single;").unwrap();
+        let exists: i64 = redis::cmd("EXISTS")
+            .arg(key)
+            .query(&mut redis_conn())
+            .unwrap();
+        assert_eq!(exists, 0);
+
+        cleanup();
+    }
+
+    #[pg_test]
+    fn test_multi_key_counts_and_union() {
+        setup_fdw();
+        let keys = [
+            "hll_test:page:home",
+            "hll_test:page:about",
+            "hll_test:page:plain",
+        ];
+        cleanup_redis_keys(&keys);
+        pfadd(keys[0], &["alice", "bob", "carol"]);
+        pfadd(keys[1], &["alice", "dave"]);
+        // A plain string the pattern also matches has no row.
+        let _: () = redis::cmd("SET")
+            .arg(keys[2])
+            .arg("not an hll")
+            .query(&mut redis_conn())
+            .unwrap();
+
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE hll_pages (page text, visitors bigint, visitor text) SERVER {} OPTIONS (
+                database '{}', table_type 'hyperloglog', table_key_prefix 'hll_test:page:*'
+            );",
+            SERVER_NAME, TEST_DATABASE
+        ))
+        .unwrap();
+
+        let rows: Vec<(String, i64)> = Spi::connect(|client| {
+            client
+                .select(
+                    "SELECT page, visitors FROM hll_pages ORDER BY page;",
+                    None,
+                    &[],
+                )
+                .unwrap()
+                .map(|row| {
+                    (
+                        row.get::<String>(1).unwrap().unwrap(),
+                        row.get::<i64>(2).unwrap().unwrap(),
+                    )
+                })
+                .collect()
+        });
+        assert_eq!(
+            rows,
+            vec![
+                ("hll_test:page:about".to_string(), 2),
+                ("hll_test:page:home".to_string(), 3),
+            ]
+        );
+
+        Spi::run("INSERT INTO hll_pages (page, visitor) VALUES ('hll_test:page:about', 'erin');")
+            .unwrap();
+        assert_eq!(
+            Spi::get_one::<i64>(
+                "SELECT visitors FROM hll_pages WHERE page = 'hll_test:page:about';"
+            )
+            .unwrap(),
+            Some(3)
+        );
+
+        let union = Spi::get_one::<i64>(&format!(
+            "SELECT redis_pfcount('{}', ARRAY['{}', '{}', 'hll_test:page:missing'], {});",
+            SERVER_NAME, keys[0], keys[1], TEST_DATABASE
+        ))
+        .unwrap();
+        assert_eq!(union, Some(5));
+
+        cleanup_redis_keys(&keys);
+        cleanup();
+    }
+
+    #[pg_test]
+    #[should_panic(expected = "requires 1-2 data column(s)")]
+    fn test_hyperloglog_wrong_column_count_rejected() {
+        setup_fdw();
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE hll_bad (visitors bigint, visitor text, extra text) SERVER {} OPTIONS (
+                table_type 'hyperloglog', table_key_prefix 'hll_test:bad'
+            );",
+            SERVER_NAME
+        ))
+        .unwrap();
+    }
+}
@@ -0,0 +1,238 @@
+#[cfg(any(test, feature = "pg_test"))]
+#[pgrx::pg_schema]
+mod tests {
+    use pgrx::prelude::*;
+
+    const TEST_DATABASE: &str = "15";
+    const FDW_NAME: &str = "redis_insert_mode_fdw";
+    const SERVER_NAME: &str = "redis_insert_mode_server";
+
+    fn setup_fdw() {
+        let _ = Spi::run(&format!(
+            "DROP FOREIGN DATA WRAPPER IF EXISTS {} CASCADE;",
+            FDW_NAME
+        ));
+        Spi::run(&format!(
+            "CREATE FOREIGN DATA WRAPPER {} HANDLER redis_fdw_handler VALIDATOR redis_fdw_validator;",
+            FDW_NAME
+        ))
+        .unwrap();
+        Spi::run(&format!(
+            "CREATE SERVER {} FOREIGN DATA WRAPPER {} OPTIONS (host_port '127.0.0.1:8899');",
+            SERVER_NAME, FDW_NAME
+        ))
+        .unwrap();
+    }
+
+    fn cleanup() {
+        let _ = Spi::run(&format!(
+            "DROP FOREIGN DATA WRAPPER IF EXISTS {} CASCADE;",
+            FDW_NAME
+        ));
+    }
+
+    fn redis_conn() -> redis::Connection {
+        redis::Client::open("redis://127.0.0.1:8899/15")
+            .unwrap()
+            .get_connection()
+            .unwrap()
+    }
+
+    fn cleanup_redis_key(key: &str) {
+        let _: Result<(), _> = redis::cmd("DEL").arg(key).query(&mut redis_conn());
+    }
+
+    /// Rows reported by an INSERT, counted through RETURNING.
+    fn inserted_rows(insert: &str) -> i64 {
+        Spi::get_one::<i64>(&format!(
+            "WITH ins AS ({} RETURNING 1) SELECT count(*) FROM ins;",
+            insert
+        ))
+        .unwrap()
+        .unwrap()
+    }
+
+    #[pg_test]
+    fn test_on_conflict_do_nothing_hash_keeps_existing_field() {
+        setup_fdw();
+        let key = "insert_mode_test:hash:nx";
+        cleanup_redis_key(key);
+        let _: () = redis::cmd("HSET")
+            .arg(key)
+            .arg("f1")
+            .arg("old")
+            .query(&mut redis_conn())
+            .unwrap();
+
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE im_hash (field text, value text) SERVER {} OPTIONS (
+                database '{}', table_type 'hash', table_key_prefix '{}'
+            );",
+            SERVER_NAME, TEST_DATABASE, key
+        ))
+        .unwrap();
+
+        let count = inserted_rows(
+            "INSERT INTO im_hash VALUES ('f1', 'new'), ('f2', 'v2') ON CONFLICT DO NOTHING",
+        );
+        assert_eq!(count, 1);
+
+        let (f1, f2): (Option<String>, Option<String>) = redis::pipe()
+            .cmd("HGET")
+            .arg(key)
+            .arg("f1")
+            .cmd("HGET")
+            .arg(key)
+            .arg("f2")
+            .query(&mut redis_conn())
+            .unwrap();
+        assert_eq!(f1.as_deref(), Some("old"));
+        assert_eq!(f2.as_deref(), Some("v2"));
+
+        cleanup_redis_key(key);
+        cleanup();
+    }
+
+    #[pg_test]
+    fn test_insert_mode_nx_string_multi_key() {
+        setup_fdw();
+        let existing = "insert_mode_test:str:existing";
+        let fresh = "insert_mode_test:str:fresh";
+        cleanup_redis_key(existing);
+        cleanup_redis_key(fresh);
+        let _: () = redis::cmd("SET")
+            .arg(existing)
+            .arg("old")
+            .query(&mut redis_conn())
+            .unwrap();
+
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE im_str (key text, value text) SERVER {} OPTIONS (
+                database '{}', table_type 'string', table_key_prefix 'insert_mode_test:str:*',
+                insert_mode 'nx'
+            );",
+            SERVER_NAME, TEST_DATABASE
+        ))
+        .unwrap();
+
+        let count = inserted_rows(&format!(
+            "INSERT INTO im_str VALUES ('{}', 'new'), ('{}', 'v')",
+            existing, fresh
+        ));
+        assert_eq!(count, 1);
+
+        let value: Option<String> = redis::cmd("GET")
+            .arg(existing)
+            .query(&mut redis_conn())
+            .unwrap();
+        assert_eq!(value.as_deref(), Some("old"));
+        let value: Option<String> = redis::cmd("GET")
+            .arg(fresh)
+            .query(&mut redis_conn())
+            .unwrap();
+        assert_eq!(value.as_deref(), Some("v"));
+
+        cleanup_redis_key(existing);
+        cleanup_redis_key(fresh);
+        cleanup();
+    }
+
+    #[pg_test]
+    fn test_insert_mode_xx_zset_updates_only_existing_members() {
+        setup_fdw();
+        let key = "insert_mode_test:zset:xx";
+        cleanup_redis_key(key);
+        let _: () = redis::cmd("ZADD")
+            .arg(key)
+            .arg(1)
+            .arg("a")
+            .query(&mut redis_conn())
+            .unwrap();
+
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE im_zset (member text, score float8) SERVER {} OPTIONS (
+                database '{}', table_type 'zset', table_key_prefix '{}', insert_mode 'xx'
+            );",
+            SERVER_NAME, TEST_DATABASE, key
+        ))
+        .unwrap();
+
+        let count = inserted_rows("INSERT INTO im_zset VALUES ('a', 5), ('b', 7)");
+        assert_eq!(count, 1);
+
+        let (a, b): (Option<f64>, Option<f64>) = redis::pipe()
+            .cmd("ZSCORE")
+            .arg(key)
+            .arg("a")
+            .cmd("ZSCORE")
+            .arg(key)
+            .arg("b")
+            .query(&mut redis_conn())
+            .unwrap();
+        assert_eq!(a, Some(5.0));
+        assert_eq!(b, None);
+
+        cleanup_redis_key(key);
+        cleanup();
+    }
+
+    #[pg_test]
+    fn test_insert_mode_nx_across_batches() {
+        setup_fdw();
+        let key = "insert_mode_test:hash:batches";
+        cleanup_redis_key(key);
+        let mut seed = redis::cmd("HSET");
+        seed.arg(key);
+        for i in (0..250).step_by(3) {
+            seed.arg(format!("f{}", i)).arg("old");
+        }
+        let _: () = seed.query(&mut redis_conn()).unwrap();
+
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE im_batches (field text, value text) SERVER {} OPTIONS (
+                database '{}', table_type 'hash', table_key_prefix '{}',
+                insert_mode 'nx', batch_size '100'
+            );",
+            SERVER_NAME, TEST_DATABASE, key
+        ))
+        .unwrap();
+
+        // Three batches, each with skipped rows; the executor reuses its
+        // slots from one batch to the next.
+        Spi::run(
+            "INSERT INTO im_batches SELECT 'f' || g, 'new' || g FROM generate_series(0, 249) g;",
+        )
+        .unwrap();
+
+        let stored: std::collections::HashMap<String, String> = redis::cmd("HGETALL")
+            .arg(key)
+            .query(&mut redis_conn())
+            .unwrap();
+        assert_eq!(stored.len(), 250);
+        for i in 0..250 {
+            let expected = if i % 3 == 0 {
+                "old".to_string()
+            } else {
+                format!("new{}", i)
+            };
+            assert_eq!(stored.get(&format!("f{}", i)), Some(&expected));
+        }
+
+        cleanup_redis_key(key);
+        cleanup();
+    }
+
+    #[pg_test]
+    #[should_panic(expected = "insert_mode must be")]
+    fn test_invalid_insert_mode_rejected() {
+        setup_fdw();
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE im_bad (value text) SERVER {} OPTIONS (
+                database '{}', table_type 'string', table_key_prefix 'insert_mode_test:bad',
+                insert_mode 'upsert'
+            );",
+            SERVER_NAME, TEST_DATABASE
+        ))
+        .unwrap();
+    }
+}
@@ -0,0 +1,136 @@
+#[cfg(any(test, feature = "pg_test"))]
+#[pgrx::pg_schema]
+mod tests {
+    use pgrx::prelude::*;
+
+    const FDW_NAME: &str = "redis_introspection_fdw";
+    const SERVER_NAME: &str = "redis_introspection_server";
+    const HOST_PORT: &str = "127.0.0.1:8899";
+
+    fn setup_fdw() {
+        let _ = Spi::run(&format!(
+            "DROP FOREIGN DATA WRAPPER IF EXISTS {} CASCADE;",
+            FDW_NAME
+        ));
+        Spi::run(&format!(
+            "CREATE FOREIGN DATA WRAPPER {} HANDLER redis_fdw_handler VALIDATOR redis_fdw_validator;",
+            FDW_NAME
+        ))
+        .unwrap();
+        Spi::run(&format!(
+            "CREATE SERVER {} FOREIGN DATA WRAPPER {} OPTIONS (host_port '{}');",
+            SERVER_NAME, FDW_NAME, HOST_PORT
+        ))
+        .unwrap();
+    }
+
+    fn cleanup() {
+        let _ = Spi::run(&format!(
+            "DROP FOREIGN DATA WRAPPER IF EXISTS {} CASCADE;",
+            FDW_NAME
+        ));
+    }
+
+    fn redis_conn() -> redis::Connection {
+        redis::Client::open(format!("redis://{}/15", HOST_PORT))
+            .unwrap()
+            .get_connection()
+            .unwrap()
+    }
+
+    #[pg_test]
+    fn test_redis_info() {
+        setup_fdw();
+        let row = Spi::get_three::<String, String, String>(&format!(
+            "SELECT section, value, node FROM redis_info('{}', 'server')
+             WHERE key = 'redis_version';",
+            SERVER_NAME
+        ))
+        .unwrap();
+        assert_eq!(row.0.as_deref(), Some("server"));
+        assert!(row.1.is_some_and(|v| !v.is_empty()));
+        assert_eq!(row.2.as_deref(), Some(HOST_PORT));
+
+        let other_sections = Spi::get_one::<i64>(&format!(
+            "SELECT count(*) FROM redis_info('{}', 'server') WHERE section <> 'server';",
+            SERVER_NAME
+        ))
+        .unwrap();
+        assert_eq!(other_sections, Some(0));
+        cleanup();
+    }
+
+    #[pg_test]
+    fn test_redis_slowlog() {
+        setup_fdw();
+        let mut conn = redis_conn();
+        let threshold: Vec<String> = redis::cmd("CONFIG")
+            .arg("GET")
+            .arg("slowlog-log-slower-than")
+            .query(&mut conn)
+            .unwrap();
+        let _: () = redis::cmd("CONFIG")
+            .arg("SET")
+            .arg("slowlog-log-slower-than")
+            .arg(0)
+            .query(&mut conn)
+            .unwrap();
+        let _: () = redis::cmd("SLOWLOG").arg("RESET").query(&mut conn).unwrap();
+        let _: Option<String> = redis::cmd("GET")
+            .arg("introspection_test:slow")
+            .query(&mut conn)
+            .unwrap();
+        let _: () = redis::cmd("CONFIG")
+            .arg("SET")
+            .arg("slowlog-log-slower-than")
+            .arg(&threshold[1])
+            .query(&mut conn)
+            .unwrap();
+
+        let logged = Spi::get_one::<bool>(&format!(
+            "SELECT bool_or(command = ARRAY['GET', 'introspection_test:slow']
+                            AND duration_us >= 0
+                            AND start_time > now() - interval '1 hour')
+             FROM redis_slowlog('{}', 128);",
+            SERVER_NAME
+        ))
+        .unwrap();
+        assert_eq!(logged, Some(true));
+        cleanup();
+    }
+
+    #[pg_test]
+    fn test_redis_client_list_and_config_get() {
+        setup_fdw();
+        let _conn = redis_conn();
+        let clients_on_db15 = Spi::get_one::<i64>(&format!(
+            "SELECT count(*) FROM redis_client_list('{}') WHERE db = 15 AND id IS NOT NULL;",
+            SERVER_NAME
+        ))
+        .unwrap();
+        assert!(clients_on_db15.is_some_and(|n| n >= 1));
+
+        let rows = Spi::get_two::<String, String>(&format!(
+            "SELECT name, node FROM redis_config_get('{}', 'maxmemory-polic*');",
+            SERVER_NAME
+        ))
+        .unwrap();
+        assert_eq!(rows.0.as_deref(), Some("maxmemory-policy"));
+        assert_eq!(rows.1.as_deref(), Some(HOST_PORT));
+
+        let password = Spi::get_one::<String>(&format!(
+            "SELECT value FROM redis_config_get('{}', 'requirepass');",
+            SERVER_NAME
+        ))
+        .unwrap();
+        assert_eq!(password, None);
+
+        // The latency monitor is usually off; the call must still succeed.
+        Spi::get_one::<i64>(&format!(
+            "SELECT count(*) FROM redis_latency_latest('{}');",
+            SERVER_NAME
+        ))
+        .unwrap();
+        cleanup();
+    }
+}
@@ -0,0 +1,185 @@
+#[cfg(any(test, feature = "pg_test"))]
+#[pgrx::pg_schema]
+mod tests {
+    use pgrx::prelude::*;
+
+    const TEST_DATABASE: &str = "15";
+    const FDW_NAME: &str = "redis_json_fdw";
+    const SERVER_NAME: &str = "redis_json_server";
+
+    fn setup_fdw() {
+        let _ = Spi::run(&format!(
+            "DROP FOREIGN DATA WRAPPER IF EXISTS {} CASCADE;",
+            FDW_NAME
+        ));
+        Spi::run(&format!(
+            "CREATE FOREIGN DATA WRAPPER {} HANDLER redis_fdw_handler VALIDATOR redis_fdw_validator;",
+            FDW_NAME
+        ))
+        .unwrap();
+        Spi::run(&format!(
+            "CREATE SERVER {} FOREIGN DATA WRAPPER {} OPTIONS (host_port '127.0.0.1:8899');",
+            SERVER_NAME, FDW_NAME
+        ))
+        .unwrap();
+    }
+
+    fn cleanup() {
+        let _ = Spi::run(&format!(
+            "DROP FOREIGN DATA WRAPPER IF EXISTS {} CASCADE;",
+            FDW_NAME
+        ));
+    }
+
+    fn redis_conn() -> redis::Connection {
+        redis::Client::open("redis://127.0.0.1:8899/15")
+            .unwrap()
+            .get_connection()
+            .unwrap()
+    }
+
+    fn cleanup_redis_key(key: &str) {
+        let _: Result<(), _> = redis::cmd("DEL").arg(key).query(&mut redis_conn());
+    }
+
+    /// The RedisJSON module ships with redis-stack, not plain Redis; these
+    /// tests pass trivially without it.
+    fn json_module_loaded() -> bool {
+        let probe = "json_test:probe";
+        let loaded = redis::cmd("JSON.SET")
+            .arg(probe)
+            .arg("$")
+            .arg("1")
+            .query::<()>(&mut redis_conn())
+            .is_ok();
+        cleanup_redis_key(probe);
+        loaded
+    }
+
+    fn create_table(name: &str, columns: &str, key: &str) {
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE {} ({}) SERVER {} OPTIONS (
+                database '{}', table_type 'json', table_key_prefix '{}'
+            );",
+            name, columns, SERVER_NAME, TEST_DATABASE, key
+        ))
+        .unwrap();
+    }
+
+    fn json_set(key: &str, document: &str) {
+        let _: () = redis::cmd("JSON.SET")
+            .arg(key)
+            .arg("$")
+            .arg(document)
+            .query(&mut redis_conn())
+            .unwrap();
+    }
+
+    fn json_get(key: &str, path: &str) -> Option<String> {
+        redis::cmd("JSON.GET")
+            .arg(key)
+            .arg(path)
+            .query(&mut redis_conn())
+            .unwrap()
+    }
+
+    #[pg_test]
+    fn test_paths_map_to_columns() {
+        if !json_module_loaded() {
+            return;
+        }
+        setup_fdw();
+        let key = "json_test:profile";
+        cleanup_redis_key(key);
+        json_set(
+            key,
+            r#"{"name":"ann","age":41,"address":{"city":"Oslo"},"tags":["a"]}"#,
+        );
+        create_table(
+            "js_profile",
+            "name text, age int, city text OPTIONS (path '$.address.city'), nick text, doc jsonb OPTIONS (path '$')",
+            key,
+        );
+
+        let name = Spi::get_one::<String>("SELECT name FROM js_profile;").unwrap();
+        assert_eq!(name.as_deref(), Some("ann"));
+        let age = Spi::get_one::<i32>("SELECT age FROM js_profile;").unwrap();
+        assert_eq!(age, Some(41));
+        let city = Spi::get_one::<String>("SELECT city FROM js_profile;").unwrap();
+        assert_eq!(city.as_deref(), Some("Oslo"));
+        let nick_is_null = Spi::get_one::<bool>("SELECT nick IS NULL FROM js_profile;")
+            .unwrap()
+            .unwrap();
+        assert!(nick_is_null);
+        let tag = Spi::get_one::<String>("SELECT doc->'tags'->>0 FROM js_profile;").unwrap();
+        assert_eq!(tag.as_deref(), Some("a"));
+
+        cleanup_redis_key(key);
+        cleanup();
+    }
+
+    #[pg_test]
+    fn test_json_multi_key_insert_update_delete() {
+        if !json_module_loaded() {
+            return;
+        }
+        setup_fdw();
+        let key = "json_test:user:1";
+        cleanup_redis_key(key);
+        create_table(
+            "js_users",
+            "key text, name text, visits int, active boolean",
+            "json_test:user:*",
+        );
+
+        Spi::run(&format!(
+            "INSERT INTO js_users VALUES ('{}', 'bob', 1, true);",
+            key
+        ))
+        .unwrap();
+        assert_eq!(json_get(key, "$.name").as_deref(), Some(r#"["bob"]"#));
+        assert_eq!(json_get(key, "$.visits").as_deref(), Some("[1]"));
+        assert_eq!(json_get(key, "$.active").as_deref(), Some("[true]"));
+
+        Spi::run(&format!(
+            "UPDATE js_users SET visits = visits + 2 WHERE key = '{}';",
+            key
+        ))
+        .unwrap();
+        assert_eq!(json_get(key, "$.visits").as_deref(), Some("[3]"));
+
+        Spi::run(&format!(
+            "UPDATE js_users SET name = NULL, active = false WHERE key = '{}';",
+            key
+        ))
+        .unwrap();
+        assert_eq!(json_get(key, "$.name").as_deref(), Some("[]"));
+        assert_eq!(json_get(key, "$.active").as_deref(), Some("[false]"));
+
+        let count = Spi::get_one::<i64>("SELECT count(*) FROM js_users WHERE name IS NULL;")
+            .unwrap()
+            .unwrap();
+        assert_eq!(count, 1);
+
+        Spi::run(&format!("DELETE FROM js_users WHERE key = '{}';", key)).unwrap();
+        let exists: i64 = redis::cmd("EXISTS")
+            .arg(key)
+            .query(&mut redis_conn())
+            .unwrap();
+        assert_eq!(exists, 0);
+
+        cleanup_redis_key(key);
+        cleanup();
+    }
+
+    #[pg_test]
+    #[should_panic(expected = "path must be a JSONPath")]
+    fn test_legacy_path_rejected() {
+        setup_fdw();
+        create_table(
+            "js_bad",
+            "name text OPTIONS (path '.name')",
+            "json_test:bad",
+        );
+    }
+}
@@ -0,0 +1,226 @@
+#[cfg(any(test, feature = "pg_test"))]
+#[pgrx::pg_schema]
+mod tests {
+    use pgrx::prelude::*;
+
+    const TEST_DATABASE: &str = "15";
+    const FDW_NAME: &str = "redis_keyspace_fdw";
+    const SERVER_NAME: &str = "redis_keyspace_server";
+    const KEYS: [&str; 4] = [
+        "keyspace_test:greeting",
+        "keyspace_test:profile",
+        "keyspace_test:queue",
+        "keyspace_test:tags",
+    ];
+
+    fn setup_fdw() {
+        let _ = Spi::run(&format!(
+            "DROP FOREIGN DATA WRAPPER IF EXISTS {} CASCADE;",
+            FDW_NAME
+        ));
+        Spi::run(&format!(
+            "CREATE FOREIGN DATA WRAPPER {} HANDLER redis_fdw_handler VALIDATOR redis_fdw_validator;",
+            FDW_NAME
+        ))
+        .unwrap();
+        Spi::run(&format!(
+            "CREATE SERVER {} FOREIGN DATA WRAPPER {} OPTIONS (host_port '127.0.0.1:8899');",
+            SERVER_NAME, FDW_NAME
+        ))
+        .unwrap();
+    }
+
+    fn cleanup() {
+        let _ = Spi::run(&format!(
+            "DROP FOREIGN DATA WRAPPER IF EXISTS {} CASCADE;",
+            FDW_NAME
+        ));
+    }
+
+    fn redis_conn() -> redis::Connection {
+        redis::Client::open("redis://127.0.0.1:8899/15")
+            .unwrap()
+            .get_connection()
+            .unwrap()
+    }
+
+    fn seed_keys() {
+        let mut conn = redis_conn();
+        let _: Result<(), _> = redis::cmd("DEL").arg(&KEYS).query(&mut conn);
+        let _: () = redis::cmd("SET")
+            .arg(KEYS[0])
+            .arg("hello")
+            .arg("PX")
+            .arg(600_000)
+            .query(&mut conn)
+            .unwrap();
+        let _: i64 = redis::cmd("HSET")
+            .arg(KEYS[1])
+            .arg(&["name", "alice", "plan", "pro"])
+            .query(&mut conn)
+            .unwrap();
+        let _: i64 = redis::cmd("RPUSH")
+            .arg(KEYS[2])
+            .arg(&["a", "b", "c"])
+            .query(&mut conn)
+            .unwrap();
+        let _: i64 = redis::cmd("SADD")
+            .arg(KEYS[3])
+            .arg(&["x", "y", "z", "w"])
+            .query(&mut conn)
+            .unwrap();
+    }
+
+    fn create_table(name: &str) {
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE {} (
+                key text, redis_type text, ttl_ms bigint, memory_bytes bigint,
+                encoding text, idle_seconds bigint, length bigint
+            ) SERVER {} OPTIONS (
+                database '{}', table_type 'keyspace', table_key_prefix 'keyspace_test:*'
+            );",
+            name, SERVER_NAME, TEST_DATABASE
+        ))
+        .unwrap();
+    }
+
+    fn keys(sql: &str) -> Vec<String> {
+        Spi::connect(|client| {
+            client
+                .select(sql, None, &[])
+                .unwrap()
+                .map(|row| row.get::<String>(1).unwrap().unwrap())
+                .collect()
+        })
+    }
+
+    #[pg_test]
+    fn test_keyspace_reads_key_attributes() {
+        setup_fdw();
+        seed_keys();
+        create_table("keyspace_all");
+
+        let rows: Vec<(String, String, Option<i64>, Option<i64>)> = Spi::connect(|client| {
+            client
+                .select(
+                    "SELECT key, redis_type, ttl_ms, length FROM keyspace_all ORDER BY key;",
+                    None,
+                    &[],
+                )
+                .unwrap()
+                .map(|row| {
+                    (
+                        row.get::<String>(1).unwrap().unwrap(),
+                        row.get::<String>(2).unwrap().unwrap(),
+                        row.get::<i64>(3).unwrap(),
+                        row.get::<i64>(4).unwrap(),
+                    )
+                })
+                .collect()
+        });
+        let types: Vec<(&str, &str)> = rows
+            .iter()
+            .map(|(k, t, _, _)| (k.as_str(), t.as_str()))
+            .collect();
+        assert_eq!(
+            types,
+            vec![
+                (KEYS[0], "string"),
+                (KEYS[1], "hash"),
+                (KEYS[2], "list"),
+                (KEYS[3], "set"),
+            ]
+        );
+        assert!(rows[0].2.is_some_and(|ttl| ttl > 0 && ttl <= 600_000));
+        assert!(rows[1..].iter().all(|r| r.2.is_none()));
+        let lengths: Vec<Option<i64>> = rows.iter().map(|r| r.3).collect();
+        assert_eq!(lengths, vec![Some(5), Some(2), Some(3), Some(4)]);
+
+        let attributes_present = Spi::get_one::<bool>(
+            "SELECT bool_and(memory_bytes > 0 AND encoding IS NOT NULL AND idle_seconds >= 0)
+             FROM keyspace_all;",
+        )
+        .unwrap();
+        assert_eq!(attributes_present, Some(true));
+
+        let _: Result<(), _> = redis::cmd("DEL").arg(&KEYS).query(&mut redis_conn());
+        cleanup();
+    }
+
+    #[pg_test]
+    fn test_keyspace_type_filter_uses_scan_type() {
+        setup_fdw();
+        seed_keys();
+        create_table("keyspace_types");
+
+        assert_eq!(
+            keys("SELECT key FROM keyspace_types WHERE redis_type = 'hash';"),
+            vec![KEYS[1]]
+        );
+        assert_eq!(
+            keys("SELECT key FROM keyspace_types WHERE redis_type = 'zset';"),
+            Vec::<String>::new()
+        );
+
+        let plan = Spi::connect(|client| {
+            client
+                .select(
+                    "EXPLAIN SELECT * FROM keyspace_types WHERE redis_type = 'hash';",
+                    None,
+                    &[],
+                )
+                .unwrap()
+                .map(|row| row.get::<String>(1).unwrap().unwrap())
+                .collect::<Vec<_>>()
+                .join("\n")
+        });
+        assert!(
+            plan.contains("Scan Type: hash"),
+            "expected the SCAN TYPE in EXPLAIN, got:\n{plan}"
+        );
+
+        let _: Result<(), _> = redis::cmd("DEL").arg(&KEYS).query(&mut redis_conn());
+        cleanup();
+    }
+
+    #[pg_test]
+    fn test_keyspace_delete_unlinks_keys() {
+        setup_fdw();
+        seed_keys();
+        create_table("keyspace_cleanup");
+
+        Spi::run("DELETE FROM keyspace_cleanup WHERE redis_type = 'list' AND idle_seconds >= 0;")
+            .unwrap();
+        Spi::run(&format!(
+            "DELETE FROM keyspace_cleanup WHERE key = '{}';",
+            KEYS[3]
+        ))
+        .unwrap();
+
+        assert_eq!(
+            keys("SELECT key FROM keyspace_cleanup ORDER BY key;"),
+            vec![KEYS[0], KEYS[1]]
+        );
+        let exists: i64 = redis::cmd("EXISTS")
+            .arg(&KEYS[2..])
+            .query(&mut redis_conn())
+            .unwrap();
+        assert_eq!(exists, 0);
+
+        let _: Result<(), _> = redis::cmd("DEL").arg(&KEYS).query(&mut redis_conn());
+        cleanup();
+    }
+
+    #[pg_test]
+    #[should_panic(expected = "keyspace tables need a glob table_key_prefix")]
+    fn test_keyspace_requires_pattern() {
+        setup_fdw();
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE keyspace_bad (key text, redis_type text) SERVER {} OPTIONS (
+                table_type 'keyspace', table_key_prefix 'keyspace_test:greeting'
+            );",
+            SERVER_NAME
+        ))
+        .unwrap();
+    }
+}
@@ -88,3 +88,66 @@ pub mod explain_tests;
 
 #[cfg(any(test, feature = "pg_test"))]
 pub mod where_limit_order_tests;
+
+#[cfg(any(test, feature = "pg_test"))]
+pub mod transaction_tests;
+
+#[cfg(any(test, feature = "pg_test"))]
+pub mod optimistic_tests;
+
+#[cfg(any(test, feature = "pg_test"))]
+pub mod insert_mode_tests;
+
+#[cfg(any(test, feature = "pg_test"))]
+pub mod returning_tests;
+
+#[cfg(any(test, feature = "pg_test"))]
+pub mod direct_modify_tests;
+
+#[cfg(any(test, feature = "pg_test"))]
+pub mod type_mapping_tests;
+
+#[cfg(any(test, feature = "pg_test"))]
+pub mod binary_tests;
+
+#[cfg(any(test, feature = "pg_test"))]
+pub mod hash_row_tests;
+
+#[cfg(any(test, feature = "pg_test"))]
+pub mod json_tests;
+
+#[cfg(any(test, feature = "pg_test"))]
+pub mod geo_tests;
+
+#[cfg(any(test, feature = "pg_test"))]
+pub mod bitmap_tests;
+
+#[cfg(any(test, feature = "pg_test"))]
+pub mod hyperloglog_tests;
+
+#[cfg(any(test, feature = "pg_test"))]
+pub mod stream_group_tests;
+
+#[cfg(any(test, feature = "pg_test"))]
+pub mod stream_entry_time_tests;
+
+#[cfg(any(test, feature = "pg_test"))]
+pub mod stream_trim_tests;
+
+#[cfg(any(test, feature = "pg_test"))]
+pub mod pubsub_tests;
+
+#[cfg(any(test, feature = "pg_test"))]
+pub mod keyspace_tests;
+
+#[cfg(any(test, feature = "pg_test"))]
+pub mod introspection_tests;
+
+#[cfg(any(test, feature = "pg_test"))]
+pub mod command_tests;
+
+#[cfg(any(test, feature = "pg_test"))]
+pub mod function_tests;
+
+#[cfg(any(test, feature = "pg_test"))]
+pub mod utils;
@@ -209,6 +209,68 @@ mod tests {
         cleanup();
     }
 
+    #[pg_test]
+    fn test_multi_key_stream_select_and_insert() {
+        setup_fdw();
+        let keys = ["mk_test_stream:a", "mk_test_stream:b"];
+
+        let mut conn = redis::Client::open("redis://127.0.0.1:8899/15")
+            .unwrap()
+            .get_connection()
+            .unwrap();
+        let _: Result<(), _> = redis::cmd("DEL").arg(&keys).query(&mut conn);
+        for (key, id, event) in [
+            (keys[0], "1-0", "signup"),
+            (keys[0], "2-0", "login"),
+            (keys[1], "1-0", "logout"),
+        ] {
+            let _: String = redis::cmd("XADD")
+                .arg(key)
+                .arg(id)
+                .arg("event")
+                .arg(event)
+                .query(&mut conn)
+                .unwrap();
+        }
+
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE mk_stream (key text, stream_id text, event text) SERVER {} OPTIONS (
+                database '{}', table_type 'stream', table_key_prefix 'mk_test_stream:*'
+            );",
+            SERVER_NAME, TEST_DATABASE
+        ))
+        .unwrap();
+
+        let count = Spi::get_one::<i64>("SELECT COUNT(*) FROM mk_stream;").unwrap();
+        assert_eq!(count, Some(3));
+
+        let event = Spi::get_one::<String>(&format!(
+            "SELECT event FROM mk_stream WHERE key = '{}' AND stream_id > '1-0';",
+            keys[0]
+        ))
+        .unwrap();
+        assert_eq!(event, Some("login".to_string()));
+
+        let count = Spi::get_one::<i64>(&format!(
+            "SELECT COUNT(*) FROM mk_stream WHERE key IN ('{}', '{}') AND stream_id = '1-0';",
+            keys[0], keys[1]
+        ))
+        .unwrap();
+        assert_eq!(count, Some(2));
+
+        Spi::run(&format!(
+            "INSERT INTO mk_stream (key, event) VALUES ('{}', 'refund');",
+            keys[1]
+        ))
+        .unwrap();
+        let len: i64 = redis::cmd("XLEN").arg(keys[1]).query(&mut conn).unwrap();
+        assert_eq!(len, 2);
+
+        Spi::run("DROP FOREIGN TABLE mk_stream;").unwrap();
+        let _: Result<(), _> = redis::cmd("DEL").arg(&keys).query(&mut conn);
+        cleanup();
+    }
+
     #[pg_test]
     fn test_multi_key_with_ttl_on_insert() {
         setup_fdw();
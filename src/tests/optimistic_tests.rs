@@ -0,0 +1,190 @@
+#[cfg(any(test, feature = "pg_test"))]
+#[pgrx::pg_schema]
+mod tests {
+    use pgrx::prelude::*;
+
+    const TEST_DATABASE: &str = "15";
+    const FDW_NAME: &str = "redis_occ_fdw";
+    const SERVER_NAME: &str = "redis_occ_server";
+
+    fn setup_fdw() {
+        let _ = Spi::run(&format!(
+            "DROP FOREIGN DATA WRAPPER IF EXISTS {} CASCADE;",
+            FDW_NAME
+        ));
+        Spi::run(&format!(
+            "CREATE FOREIGN DATA WRAPPER {} HANDLER redis_fdw_handler VALIDATOR redis_fdw_validator;",
+            FDW_NAME
+        ))
+        .unwrap();
+        Spi::run(&format!(
+            "CREATE SERVER {} FOREIGN DATA WRAPPER {} OPTIONS (host_port '127.0.0.1:8899');",
+            SERVER_NAME, FDW_NAME
+        ))
+        .unwrap();
+    }
+
+    fn cleanup() {
+        let _ = Spi::run(&format!(
+            "DROP FOREIGN DATA WRAPPER IF EXISTS {} CASCADE;",
+            FDW_NAME
+        ));
+    }
+
+    fn redis_conn() -> redis::Connection {
+        redis::Client::open("redis://127.0.0.1:8899/15")
+            .unwrap()
+            .get_connection()
+            .unwrap()
+    }
+
+    fn cleanup_redis_key(key: &str) {
+        let _: Result<(), _> = redis::cmd("DEL").arg(key).query(&mut redis_conn());
+    }
+
+    #[pg_test]
+    fn test_optimistic_update_applies_without_conflict() {
+        setup_fdw();
+        let key = "occ_test:hash:update";
+        cleanup_redis_key(key);
+        let _: () = redis::cmd("HSET")
+            .arg(key)
+            .arg("f1")
+            .arg("v1")
+            .query(&mut redis_conn())
+            .unwrap();
+
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE occ_hash (field text, value text) SERVER {} OPTIONS (
+                database '{}', table_type 'hash', table_key_prefix '{}', concurrency 'optimistic'
+            );",
+            SERVER_NAME, TEST_DATABASE, key
+        ))
+        .unwrap();
+
+        Spi::run("UPDATE occ_hash SET value = 'v2' WHERE field = 'f1';").unwrap();
+
+        let value: Option<String> = redis::cmd("HGET")
+            .arg(key)
+            .arg("f1")
+            .query(&mut redis_conn())
+            .unwrap();
+        assert_eq!(value.as_deref(), Some("v2"));
+
+        cleanup_redis_key(key);
+        cleanup();
+    }
+
+    /// Overwrite a hash field from a connection of its own, so it can be
+    /// called from a qual after the scan has WATCHed the key.
+    #[pg_extern]
+    fn occ_touch(key: &str) -> bool {
+        let _: () = redis::cmd("HSET")
+            .arg(key)
+            .arg("f1")
+            .arg("changed")
+            .query(&mut redis_conn())
+            .unwrap();
+        true
+    }
+
+    #[pg_test]
+    #[should_panic(expected = "could not serialize access due to concurrent update")]
+    fn test_optimistic_update_conflict_is_serialization_failure() {
+        setup_fdw();
+        let key = "occ_test:hash:conflict";
+        cleanup_redis_key(key);
+        let _: () = redis::cmd("HSET")
+            .arg(key)
+            .arg("f1")
+            .arg("v1")
+            .query(&mut redis_conn())
+            .unwrap();
+
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE occ_conflict (field text, value text) SERVER {} OPTIONS (
+                database '{}', table_type 'hash', table_key_prefix '{}', concurrency 'optimistic'
+            );",
+            SERVER_NAME, TEST_DATABASE, key
+        ))
+        .unwrap();
+
+        // The qual rewrites the watched field mid-scan, so EXEC is aborted
+        Spi::run(&format!(
+            "UPDATE occ_conflict SET value = 'v2' WHERE field = 'f1' AND tests.occ_touch('{}');",
+            key
+        ))
+        .unwrap();
+    }
+
+    #[pg_test]
+    #[should_panic(expected = "failed to apply optimistic writes")]
+    fn test_optimistic_exec_reports_wrongtype() {
+        setup_fdw();
+        let (a, b, string_key) = ("occ_test:mixed:a", "occ_test:mixed:b", "occ_test:mixed:str");
+        for key in [a, b, string_key] {
+            cleanup_redis_key(key);
+        }
+        for key in [a, b] {
+            let _: () = redis::cmd("HSET")
+                .arg(key)
+                .arg("f1")
+                .arg("v1")
+                .query(&mut redis_conn())
+                .unwrap();
+        }
+        let _: () = redis::cmd("SET")
+            .arg(string_key)
+            .arg("plain")
+            .query(&mut redis_conn())
+            .unwrap();
+
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE occ_mixed (key text, field text, value text) SERVER {} OPTIONS (
+                database '{}', table_type 'hash', table_key_prefix 'occ_test:mixed:*',
+                concurrency 'optimistic'
+            );",
+            SERVER_NAME, TEST_DATABASE
+        ))
+        .unwrap();
+
+        // The row of `a` stays in its hash; the row of `b` moves to a key
+        // holding a string, so one command of the EXEC fails with WRONGTYPE
+        Spi::run(&format!(
+            "UPDATE occ_mixed SET value = 'v2',
+                key = CASE key WHEN '{a}' THEN key ELSE '{string_key}' END
+             WHERE key IN ('{a}', '{b}');"
+        ))
+        .unwrap();
+    }
+
+    #[pg_test]
+    #[should_panic(expected = "requires a key = or key IN condition")]
+    fn test_optimistic_multi_key_requires_key_condition() {
+        setup_fdw();
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE occ_multi (key text, value text) SERVER {} OPTIONS (
+                database '{}', table_type 'string', table_key_prefix 'occ_test:multi:*',
+                concurrency 'optimistic'
+            );",
+            SERVER_NAME, TEST_DATABASE
+        ))
+        .unwrap();
+
+        Spi::run("DELETE FROM occ_multi;").unwrap();
+    }
+
+    #[pg_test]
+    #[should_panic(expected = "cannot be combined with transactional")]
+    fn test_optimistic_rejects_transactional() {
+        setup_fdw();
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE occ_bad (value text) SERVER {} OPTIONS (
+                table_type 'string', table_key_prefix 'occ_test:bad',
+                concurrency 'optimistic', transactional 'true'
+            );",
+            SERVER_NAME
+        ))
+        .unwrap();
+    }
+}
@@ -0,0 +1,152 @@
+#[cfg(any(test, feature = "pg_test"))]
+#[pgrx::pg_schema]
+mod tests {
+    use pgrx::prelude::*;
+    use std::time::Duration;
+
+    const FDW_NAME: &str = "redis_pubsub_fdw";
+    const SERVER_NAME: &str = "redis_pubsub_server";
+
+    fn setup_fdw(server_options: &str) {
+        let _ = Spi::run(&format!(
+            "DROP FOREIGN DATA WRAPPER IF EXISTS {} CASCADE;",
+            FDW_NAME
+        ));
+        Spi::run(&format!(
+            "CREATE FOREIGN DATA WRAPPER {} HANDLER redis_fdw_handler VALIDATOR redis_fdw_validator;",
+            FDW_NAME
+        ))
+        .unwrap();
+        Spi::run(&format!(
+            "CREATE SERVER {} FOREIGN DATA WRAPPER {} OPTIONS (host_port '127.0.0.1:8899'{});",
+            SERVER_NAME, FDW_NAME, server_options
+        ))
+        .unwrap();
+    }
+
+    fn cleanup() {
+        let _ = Spi::run(&format!(
+            "DROP FOREIGN DATA WRAPPER IF EXISTS {} CASCADE;",
+            FDW_NAME
+        ));
+    }
+
+    fn redis_conn() -> redis::Connection {
+        redis::Client::open("redis://127.0.0.1:8899/15")
+            .unwrap()
+            .get_connection()
+            .unwrap()
+    }
+
+    /// Messages published on `channels` while `publish` runs, as
+    /// `(channel, payload)`, until none arrives for a while.
+    fn received(channels: &[&str], publish: impl FnOnce()) -> Vec<(String, String)> {
+        let mut conn = redis_conn();
+        let mut pubsub = conn.as_pubsub();
+        pubsub.subscribe(channels).unwrap();
+        pubsub
+            .set_read_timeout(Some(Duration::from_millis(300)))
+            .unwrap();
+        publish();
+        let mut messages = Vec::new();
+        while let Ok(msg) = pubsub.get_message() {
+            messages.push((
+                msg.get_channel_name().to_string(),
+                msg.get_payload::<String>().unwrap(),
+            ));
+        }
+        messages
+    }
+
+    fn pair(channel: &str, message: &str) -> (String, String) {
+        (channel.to_string(), message.to_string())
+    }
+
+    #[pg_test]
+    fn test_insert_publishes() {
+        setup_fdw("");
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE ps_any (channel text, message text) SERVER {}
+             OPTIONS (table_type 'pubsub');
+             CREATE FOREIGN TABLE ps_fixed (message text) SERVER {}
+             OPTIONS (table_type 'pubsub', channel 'pubsub_test:fixed');",
+            SERVER_NAME, SERVER_NAME
+        ))
+        .unwrap();
+
+        let messages = received(&["pubsub_test:a", "pubsub_test:fixed"], || {
+            Spi::run(
+                "INSERT INTO ps_any VALUES ('pubsub_test:a', 'one'), ('pubsub_test:a', 'two');",
+            )
+            .unwrap();
+            Spi::run("INSERT INTO ps_fixed VALUES ('three');").unwrap();
+        });
+        assert_eq!(
+            messages,
+            vec![
+                pair("pubsub_test:a", "one"),
+                pair("pubsub_test:a", "two"),
+                pair("pubsub_test:fixed", "three"),
+            ]
+        );
+
+        assert_eq!(
+            Spi::get_one::<i64>("SELECT count(*) FROM ps_any;").unwrap(),
+            Some(0)
+        );
+
+        let receivers = std::cell::Cell::new(None);
+        let messages = received(&["pubsub_test:fn"], || {
+            receivers.set(
+                Spi::get_one::<i64>(&format!(
+                    "SELECT redis_publish('{}', 'pubsub_test:fn', 'hello');",
+                    SERVER_NAME
+                ))
+                .unwrap(),
+            );
+        });
+        assert_eq!(receivers.get(), Some(1));
+        assert_eq!(messages, vec![pair("pubsub_test:fn", "hello")]);
+
+        cleanup();
+    }
+
+    #[pg_test]
+    fn test_transactional_publish_waits_for_commit() {
+        setup_fdw(", transactional 'true'");
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE ps_tx (message text) SERVER {}
+             OPTIONS (table_type 'pubsub', channel 'pubsub_test:tx');",
+            SERVER_NAME
+        ))
+        .unwrap();
+
+        let receivers = std::cell::Cell::new(Some(-1));
+        let messages = received(&["pubsub_test:tx"], || {
+            Spi::run("INSERT INTO ps_tx VALUES ('queued');").unwrap();
+            receivers.set(
+                Spi::get_one::<i64>(&format!(
+                    "SELECT redis_publish('{}', 'pubsub_test:tx', 'also queued');",
+                    SERVER_NAME
+                ))
+                .unwrap(),
+            );
+        });
+        // pg_test runs inside a transaction that never commits.
+        assert_eq!(messages, Vec::new());
+        assert_eq!(receivers.get(), None);
+
+        cleanup();
+    }
+
+    #[pg_test]
+    #[should_panic(expected = "requires exactly 2 data column(s)")]
+    fn test_single_column_needs_channel_option() {
+        setup_fdw("");
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE ps_bad (message text) SERVER {} OPTIONS (table_type 'pubsub');",
+            SERVER_NAME
+        ))
+        .unwrap();
+    }
+}
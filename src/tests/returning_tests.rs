@@ -0,0 +1,217 @@
+#[cfg(any(test, feature = "pg_test"))]
+#[pgrx::pg_schema]
+mod tests {
+    use pgrx::prelude::*;
+
+    const TEST_DATABASE: &str = "15";
+    const FDW_NAME: &str = "redis_returning_fdw";
+    const SERVER_NAME: &str = "redis_returning_server";
+
+    fn setup_fdw() {
+        let _ = Spi::run(&format!(
+            "DROP FOREIGN DATA WRAPPER IF EXISTS {} CASCADE;",
+            FDW_NAME
+        ));
+        Spi::run(&format!(
+            "CREATE FOREIGN DATA WRAPPER {} HANDLER redis_fdw_handler VALIDATOR redis_fdw_validator;",
+            FDW_NAME
+        ))
+        .unwrap();
+        Spi::run(&format!(
+            "CREATE SERVER {} FOREIGN DATA WRAPPER {} OPTIONS (host_port '127.0.0.1:8899');",
+            SERVER_NAME, FDW_NAME
+        ))
+        .unwrap();
+    }
+
+    fn cleanup() {
+        let _ = Spi::run(&format!(
+            "DROP FOREIGN DATA WRAPPER IF EXISTS {} CASCADE;",
+            FDW_NAME
+        ));
+    }
+
+    fn redis_conn() -> redis::Connection {
+        redis::Client::open("redis://127.0.0.1:8899/15")
+            .unwrap()
+            .get_connection()
+            .unwrap()
+    }
+
+    fn cleanup_redis_key(key: &str) {
+        let _: Result<(), _> = redis::cmd("DEL").arg(key).query(&mut redis_conn());
+    }
+
+    #[pg_test]
+    fn test_insert_returning_generated_stream_id() {
+        setup_fdw();
+        let key = "returning_test:stream";
+        cleanup_redis_key(key);
+
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE ret_stream (stream_id text, event text) SERVER {} OPTIONS (
+                database '{}', table_type 'stream', table_key_prefix '{}'
+            );",
+            SERVER_NAME, TEST_DATABASE, key
+        ))
+        .unwrap();
+
+        let returned = Spi::get_one::<String>(
+            "INSERT INTO ret_stream VALUES ('*', 'login') RETURNING stream_id;",
+        )
+        .unwrap()
+        .unwrap();
+
+        let entries: Vec<(String, Vec<(String, String)>)> = redis::cmd("XRANGE")
+            .arg(key)
+            .arg("-")
+            .arg("+")
+            .query(&mut redis_conn())
+            .unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(returned, entries[0].0);
+
+        cleanup_redis_key(key);
+        cleanup();
+    }
+
+    #[pg_test]
+    fn test_multi_row_insert_returning_with_default_batch_size() {
+        setup_fdw();
+        let key = "returning_test:stream_rows";
+        cleanup_redis_key(key);
+
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE ret_stream_rows (stream_id text, event text) SERVER {} OPTIONS (
+                database '{}', table_type 'stream', table_key_prefix '{}'
+            );",
+            SERVER_NAME, TEST_DATABASE, key
+        ))
+        .unwrap();
+
+        // Every row gets its own generated ID back, even though the table
+        // would batch a plain INSERT of these rows.
+        let returned = Spi::get_one::<String>(
+            "WITH ins AS (
+                INSERT INTO ret_stream_rows VALUES ('*', 'a'), ('*', 'b'), ('*', 'c')
+                RETURNING stream_id
+            ) SELECT string_agg(stream_id, ',' ORDER BY stream_id) FROM ins;",
+        )
+        .unwrap()
+        .unwrap();
+
+        let entries: Vec<(String, Vec<(String, String)>)> = redis::cmd("XRANGE")
+            .arg(key)
+            .arg("-")
+            .arg("+")
+            .query(&mut redis_conn())
+            .unwrap();
+        let ids: Vec<String> = entries.into_iter().map(|(id, _)| id).collect();
+        assert_eq!(ids.len(), 3);
+        assert_eq!(returned, ids.join(","));
+
+        cleanup_redis_key(key);
+        cleanup();
+    }
+
+    #[pg_test]
+    fn test_insert_returning_stored_zset_score() {
+        setup_fdw();
+        let key = "returning_test:zset";
+        cleanup_redis_key(key);
+
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE ret_zset (member text, score text) SERVER {} OPTIONS (
+                database '{}', table_type 'zset', table_key_prefix '{}'
+            );",
+            SERVER_NAME, TEST_DATABASE, key
+        ))
+        .unwrap();
+
+        let returned =
+            Spi::get_one::<String>("INSERT INTO ret_zset VALUES ('a', '1e1') RETURNING score;")
+                .unwrap()
+                .unwrap();
+        assert_eq!(returned, "10");
+
+        cleanup_redis_key(key);
+        cleanup();
+    }
+
+    #[pg_test]
+    #[should_panic(expected = "RETURNING is not supported with transactional")]
+    fn test_insert_returning_rejected_when_transactional() {
+        setup_fdw();
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE ret_tx_zset (member text, score float8) SERVER {} OPTIONS (
+                database '{}', table_type 'zset', table_key_prefix 'returning_test:tx_zset',
+                transactional 'true'
+            );",
+            SERVER_NAME, TEST_DATABASE
+        ))
+        .unwrap();
+
+        Spi::run("INSERT INTO ret_tx_zset VALUES ('a', 1) RETURNING score;").unwrap();
+    }
+
+    #[pg_test]
+    fn test_insert_returning_remaining_ttl() {
+        setup_fdw();
+        let key = "returning_test:ttl";
+        cleanup_redis_key(key);
+
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE ret_ttl (field text, value text, ttl bigint) SERVER {} OPTIONS (
+                database '{}', table_type 'hash', table_key_prefix '{}'
+            );",
+            SERVER_NAME, TEST_DATABASE, key
+        ))
+        .unwrap();
+
+        let returned =
+            Spi::get_one::<i64>("INSERT INTO ret_ttl VALUES ('f', 'v', 300) RETURNING ttl;")
+                .unwrap()
+                .unwrap();
+        assert!(returned > 0 && returned <= 300, "got ttl {}", returned);
+
+        cleanup_redis_key(key);
+        cleanup();
+    }
+
+    #[pg_test]
+    fn test_delete_returning_old_hash_value() {
+        setup_fdw();
+        let key = "returning_test:delete";
+        cleanup_redis_key(key);
+        let _: () = redis::cmd("HSET")
+            .arg(key)
+            .arg("session")
+            .arg("alice")
+            .query(&mut redis_conn())
+            .unwrap();
+
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE ret_del (field text, value text) SERVER {} OPTIONS (
+                database '{}', table_type 'hash', table_key_prefix '{}'
+            );",
+            SERVER_NAME, TEST_DATABASE, key
+        ))
+        .unwrap();
+
+        let returned =
+            Spi::get_one::<String>("DELETE FROM ret_del WHERE field = 'session' RETURNING value;")
+                .unwrap()
+                .unwrap();
+        assert_eq!(returned, "alice");
+
+        let exists: bool = redis::cmd("HEXISTS")
+            .arg(key)
+            .arg("session")
+            .query(&mut redis_conn())
+            .unwrap();
+        assert!(!exists);
+
+        cleanup_redis_key(key);
+        cleanup();
+    }
+}
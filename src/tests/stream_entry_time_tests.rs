@@ -0,0 +1,182 @@
+#[cfg(any(test, feature = "pg_test"))]
+#[pgrx::pg_schema]
+mod tests {
+    use pgrx::prelude::*;
+
+    const TEST_DATABASE: &str = "15";
+    const FDW_NAME: &str = "redis_stream_time_fdw";
+    const SERVER_NAME: &str = "redis_stream_time_server";
+
+    fn setup_fdw() {
+        let _ = Spi::run(&format!(
+            "DROP FOREIGN DATA WRAPPER IF EXISTS {} CASCADE;",
+            FDW_NAME
+        ));
+        Spi::run(&format!(
+            "CREATE FOREIGN DATA WRAPPER {} HANDLER redis_fdw_handler VALIDATOR redis_fdw_validator;",
+            FDW_NAME
+        ))
+        .unwrap();
+        Spi::run(&format!(
+            "CREATE SERVER {} FOREIGN DATA WRAPPER {} OPTIONS (host_port '127.0.0.1:8899');",
+            SERVER_NAME, FDW_NAME
+        ))
+        .unwrap();
+    }
+
+    fn cleanup() {
+        let _ = Spi::run(&format!(
+            "DROP FOREIGN DATA WRAPPER IF EXISTS {} CASCADE;",
+            FDW_NAME
+        ));
+    }
+
+    fn redis_conn() -> redis::Connection {
+        redis::Client::open("redis://127.0.0.1:8899/15")
+            .unwrap()
+            .get_connection()
+            .unwrap()
+    }
+
+    fn seed_stream(key: &str, entries: &[(&str, &str)]) {
+        let mut conn = redis_conn();
+        let _: Result<(), _> = redis::cmd("DEL").arg(key).query(&mut conn);
+        for (id, event) in entries {
+            let _: String = redis::cmd("XADD")
+                .arg(key)
+                .arg(id)
+                .arg("event")
+                .arg(event)
+                .query(&mut conn)
+                .unwrap();
+        }
+    }
+
+    fn create_table(name: &str, key: &str) {
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE {} (stream_id text, event text, entry_time timestamptz)
+             SERVER {} OPTIONS (database '{}', table_type 'stream', table_key_prefix '{}');",
+            name, SERVER_NAME, TEST_DATABASE, key
+        ))
+        .unwrap();
+    }
+
+    fn events(sql: &str) -> Vec<String> {
+        Spi::connect(|client| {
+            client
+                .select(sql, None, &[])
+                .unwrap()
+                .map(|row| row.get::<String>(1).unwrap().unwrap())
+                .collect()
+        })
+    }
+
+    #[pg_test]
+    fn test_entry_time_reads_and_filters() {
+        setup_fdw();
+        let key = "stream_time_test:events";
+        // 2023-11-14 22:13:20 UTC, one second and one hour later
+        seed_stream(
+            key,
+            &[
+                ("1700000000000-0", "signup"),
+                ("1700000001000-0", "login"),
+                ("1700000001000-1", "click"),
+                ("1700003600000-0", "logout"),
+            ],
+        );
+        create_table("stream_time", key);
+
+        let first = Spi::get_one::<bool>(
+            "SELECT entry_time = '2023-11-14 22:13:20+00'::timestamptz
+             FROM stream_time WHERE stream_id = '1700000000000-0';",
+        )
+        .unwrap();
+        assert_eq!(first, Some(true));
+
+        assert_eq!(
+            events(
+                "SELECT event FROM stream_time
+                 WHERE entry_time >= '2023-11-14 22:13:21+00' AND entry_time < '2023-11-14 23:00:00+00'
+                 ORDER BY stream_id;"
+            ),
+            vec!["login", "click"]
+        );
+        assert_eq!(
+            events(
+                "SELECT event FROM stream_time
+                 WHERE entry_time = '2023-11-14 22:13:21+00' ORDER BY stream_id;"
+            ),
+            vec!["login", "click"]
+        );
+        assert_eq!(
+            events(
+                "SELECT event FROM stream_time
+                 WHERE entry_time > '2023-11-14 22:13:21+00'::timestamptz + interval '1 minute';"
+            ),
+            vec!["logout"]
+        );
+
+        // The column is read-only: INSERT leaves it out of the entry.
+        Spi::run("INSERT INTO stream_time (event, entry_time) VALUES ('refund', now());").unwrap();
+        let fields: Vec<(String, Vec<(String, String)>)> = redis::cmd("XREVRANGE")
+            .arg(key)
+            .arg("+")
+            .arg("-")
+            .arg("COUNT")
+            .arg(1)
+            .query(&mut redis_conn())
+            .unwrap();
+        assert_eq!(
+            fields[0].1,
+            vec![("event".to_string(), "refund".to_string())]
+        );
+
+        let _: Result<(), _> = redis::cmd("DEL").arg(key).query(&mut redis_conn());
+        cleanup();
+    }
+
+    #[pg_test]
+    fn test_entry_time_stable_bound_in_explain() {
+        setup_fdw();
+        let key = "stream_time_test:explain";
+        seed_stream(key, &[("1700000000000-0", "old")]);
+        create_table("stream_time_explain", key);
+
+        let plan = Spi::connect(|client| {
+            client
+                .select(
+                    "EXPLAIN SELECT * FROM stream_time_explain
+                     WHERE entry_time >= now() - interval '1 hour';",
+                    None,
+                    &[],
+                )
+                .unwrap()
+                .map(|row| row.get::<String>(1).unwrap().unwrap())
+                .collect::<Vec<_>>()
+                .join("\n")
+        });
+        assert!(
+            plan.contains("Stream ID Range:"),
+            "expected the computed ID range in EXPLAIN, got:\n{plan}"
+        );
+
+        assert_eq!(
+            events(
+                "SELECT event FROM stream_time_explain
+                 WHERE entry_time >= now() - interval '1 hour';"
+            ),
+            Vec::<String>::new()
+        );
+        assert_eq!(
+            events(
+                "SELECT event FROM stream_time_explain
+                 WHERE entry_time < now() - interval '1 hour';"
+            ),
+            vec!["old"]
+        );
+
+        let _: Result<(), _> = redis::cmd("DEL").arg(key).query(&mut redis_conn());
+        cleanup();
+    }
+}
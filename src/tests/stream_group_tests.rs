@@ -0,0 +1,179 @@
+#[cfg(any(test, feature = "pg_test"))]
+#[pgrx::pg_schema]
+mod tests {
+    use pgrx::prelude::*;
+
+    const TEST_DATABASE: &str = "15";
+    const FDW_NAME: &str = "redis_stream_group_fdw";
+    const SERVER_NAME: &str = "redis_stream_group_server";
+
+    fn setup_fdw() {
+        let _ = Spi::run(&format!(
+            "DROP FOREIGN DATA WRAPPER IF EXISTS {} CASCADE;",
+            FDW_NAME
+        ));
+        Spi::run(&format!(
+            "CREATE FOREIGN DATA WRAPPER {} HANDLER redis_fdw_handler VALIDATOR redis_fdw_validator;",
+            FDW_NAME
+        ))
+        .unwrap();
+        Spi::run(&format!(
+            "CREATE SERVER {} FOREIGN DATA WRAPPER {} OPTIONS (host_port '127.0.0.1:8899');",
+            SERVER_NAME, FDW_NAME
+        ))
+        .unwrap();
+    }
+
+    fn cleanup() {
+        let _ = Spi::run(&format!(
+            "DROP FOREIGN DATA WRAPPER IF EXISTS {} CASCADE;",
+            FDW_NAME
+        ));
+    }
+
+    fn redis_conn() -> redis::Connection {
+        redis::Client::open("redis://127.0.0.1:8899/15")
+            .unwrap()
+            .get_connection()
+            .unwrap()
+    }
+
+    fn cleanup_redis_key(key: &str) {
+        let _: Result<(), _> = redis::cmd("DEL").arg(key).query(&mut redis_conn());
+    }
+
+    fn xadd(key: &str, event: &str) -> String {
+        redis::cmd("XADD")
+            .arg(key)
+            .arg("*")
+            .arg("event")
+            .arg(event)
+            .query(&mut redis_conn())
+            .unwrap()
+    }
+
+    fn pending_count(key: &str, group: &str) -> i64 {
+        let summary: (i64, redis::Value, redis::Value, redis::Value) = redis::cmd("XPENDING")
+            .arg(key)
+            .arg(group)
+            .query(&mut redis_conn())
+            .unwrap();
+        summary.0
+    }
+
+    fn create_table(name: &str, key: &str, consumer: &str, extra_options: &str) {
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE {} (stream_id text, event text, delivery_count bigint, idle_ms bigint)
+             SERVER {} OPTIONS (
+                database '{}', table_type 'stream', table_key_prefix '{}',
+                consumer_group 'workers', consumer_name '{}'{}
+            );",
+            name, SERVER_NAME, TEST_DATABASE, key, consumer, extra_options
+        ))
+        .unwrap();
+    }
+
+    #[pg_test]
+    fn test_group_read_redelivers_until_acked() {
+        setup_fdw();
+        let key = "stream_group_test:events";
+        cleanup_redis_key(key);
+        create_table("group_events", key, "w1", ", create_group 'true'");
+
+        // The group is created, with the stream, on the first scan.
+        assert_eq!(
+            Spi::get_one::<i64>("SELECT count(*) FROM group_events;").unwrap(),
+            Some(0)
+        );
+        xadd(key, "signup");
+        xadd(key, "login");
+        xadd(key, "logout");
+
+        let read = "SELECT count(*) FROM group_events WHERE delivery_count = 1 AND idle_ms >= 0;";
+        assert_eq!(Spi::get_one::<i64>(read).unwrap(), Some(3));
+        // Unacknowledged entries come back on the next scan.
+        assert_eq!(Spi::get_one::<i64>(read).unwrap(), Some(3));
+        assert_eq!(pending_count(key, "workers"), 3);
+
+        Spi::run("DELETE FROM group_events WHERE event = 'login';").unwrap();
+        assert_eq!(pending_count(key, "workers"), 2);
+        let len: i64 = redis::cmd("XLEN")
+            .arg(key)
+            .query(&mut redis_conn())
+            .unwrap();
+        assert_eq!(len, 3);
+
+        xadd(key, "purchase");
+        let events: Vec<String> = Spi::connect(|client| {
+            client
+                .select("SELECT event FROM group_events;", None, &[])
+                .unwrap()
+                .map(|row| row.get::<String>(1).unwrap().unwrap())
+                .collect()
+        });
+        assert_eq!(events, vec!["signup", "logout", "purchase"]);
+
+        Spi::run("DELETE FROM group_events;").unwrap();
+        assert_eq!(pending_count(key, "workers"), 0);
+
+        cleanup_redis_key(key);
+        cleanup();
+    }
+
+    #[pg_test]
+    fn test_claim_idle_takes_over_stalled_entries() {
+        setup_fdw();
+        let key = "stream_group_test:claim";
+        cleanup_redis_key(key);
+        let _: () = redis::cmd("XGROUP")
+            .arg("CREATE")
+            .arg(key)
+            .arg("workers")
+            .arg("0")
+            .arg("MKSTREAM")
+            .query(&mut redis_conn())
+            .unwrap();
+        xadd(key, "job1");
+        xadd(key, "job2");
+        // A worker that read both entries and then stalled.
+        let _: redis::Value = redis::cmd("XREADGROUP")
+            .arg("GROUP")
+            .arg("workers")
+            .arg("stalled")
+            .arg("STREAMS")
+            .arg(key)
+            .arg(">")
+            .query(&mut redis_conn())
+            .unwrap();
+
+        create_table("group_plain", key, "w2", "");
+        assert_eq!(
+            Spi::get_one::<i64>("SELECT count(*) FROM group_plain;").unwrap(),
+            Some(0)
+        );
+
+        create_table("group_claim", key, "w3", ", claim_idle_ms '0'");
+        assert_eq!(
+            Spi::get_one::<i64>("SELECT count(*) FROM group_claim WHERE delivery_count = 2;")
+                .unwrap(),
+            Some(2)
+        );
+
+        cleanup_redis_key(key);
+        cleanup();
+    }
+
+    #[pg_test]
+    #[should_panic(expected = "consumer_group and consumer_name must be set together")]
+    fn test_consumer_group_requires_consumer_name() {
+        setup_fdw();
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE group_bad (stream_id text, event text) SERVER {} OPTIONS (
+                table_type 'stream', table_key_prefix 'stream_group_test:bad',
+                consumer_group 'workers'
+            );",
+            SERVER_NAME
+        ))
+        .unwrap();
+    }
+}
@@ -5,8 +5,11 @@ mod tests {
     use crate::{
         query::{limit::LimitOffsetInfo, pushdown_types::ComparisonOperator},
         tables::{
-            implementations::RedisStreamTable, interface::RedisTableOperations, types::DataSet,
+            implementations::RedisStreamTable,
+            interface::RedisTableOperations,
+            types::{DataSet, InsertMode},
         },
+        tests::utils::row_text,
     };
 
     #[test]
@@ -164,7 +167,7 @@ mod tests {
                 assert!(row1_data.len() >= 4); // stream_id + at least 3 field/value pairs
 
                 // Verify the row contains expected data
-                let row1_str = row1_data.join(",");
+                let row1_str = row_text(&row1_data).join(",");
                 assert!(
                     row1_str.contains("user_login")
                         || row1_str.contains("page_view")
@@ -241,7 +244,7 @@ mod tests {
             "999".to_string(),
         ];
 
-        let insert_result = table.insert(&mut conn, test_key, &insert_data);
+        let insert_result = table.insert(&mut conn, test_key, &insert_data, InsertMode::Overwrite);
         assert!(insert_result.is_ok());
 
         // Verify entry was inserted
@@ -393,7 +396,7 @@ mod tests {
         }
 
         // Test insert with empty data
-        let empty_insert = table.insert(&mut conn, "test:empty", &[]);
+        let empty_insert = table.insert(&mut conn, "test:empty", &[], InsertMode::Overwrite);
         assert!(empty_insert.is_ok()); // Should handle gracefully
 
         // Test delete with empty data
@@ -460,7 +463,7 @@ mod tests {
             "XRANGE by stream_id should return 1 row"
         );
         let row = table2.get_row(0).unwrap();
-        assert_eq!(row[0].as_ref(), id1.as_str());
+        assert_eq!(row_text(&row)[0], id1.as_str());
 
         // WHERE user_id = 'user:alice' should NOT be treated as stream ID
         let condition2 = PushableCondition {
@@ -481,7 +484,7 @@ mod tests {
         assert!(result2.is_ok(), "WHERE on non-ID column must not error");
         assert_eq!(table3.data_len(), 1, "Expected 1 row for user:alice");
         let row = table3.get_row(0).unwrap();
-        assert_eq!(row[1].as_ref(), "user:alice");
+        assert_eq!(row_text(&row)[1], "user:alice");
 
         cleanup_test_stream(&mut conn, test_key);
     }
@@ -512,7 +515,9 @@ mod tests {
             "resource".to_string(),
             "project:alpha".to_string(),
         ];
-        table.insert(&mut conn, test_key, &data).unwrap();
+        table
+            .insert(&mut conn, test_key, &data, InsertMode::Overwrite)
+            .unwrap();
 
         // Load data (same path as begin_foreign_scan → iterate)
         let result = table.load_data(&mut conn, test_key, None, &LimitOffsetInfo::default());
@@ -525,13 +530,13 @@ mod tests {
         eprintln!("row_data = {:?}", row_data);
         assert_eq!(row_data.len(), 4, "Expected 4 columns, got {:?}", row_data);
         assert_eq!(
-            row_data[1].as_ref(),
+            row_text(&row_data)[1],
             "user:alice",
             "user_id column mismatch"
         );
-        assert_eq!(row_data[2].as_ref(), "CREATE", "action column mismatch");
+        assert_eq!(row_text(&row_data)[2], "CREATE", "action column mismatch");
         assert_eq!(
-            row_data[3].as_ref(),
+            row_text(&row_data)[3],
             "project:alpha",
             "resource column mismatch"
         );
@@ -570,7 +575,9 @@ mod tests {
                 "resource".to_string(),
                 resource.to_string(),
             ];
-            table.insert(&mut conn, test_key, &data).unwrap();
+            table
+                .insert(&mut conn, test_key, &data, InsertMode::Overwrite)
+                .unwrap();
         }
 
         // Test WHERE user_id = 'user:alice' via load_data (optimized path)
@@ -598,9 +605,9 @@ mod tests {
 
         // Verify filtered rows
         let row0 = table2.get_row(0).unwrap();
-        assert_eq!(row0[1].as_ref(), "user:alice");
+        assert_eq!(row_text(&row0)[1], "user:alice");
         let row1 = table2.get_row(1).unwrap();
-        assert_eq!(row1[1].as_ref(), "user:alice");
+        assert_eq!(row_text(&row1)[1], "user:alice");
 
         // Test WHERE action = 'CREATE'
         let condition2 = PushableCondition {
@@ -625,9 +632,9 @@ mod tests {
         assert_eq!(table3.data_len(), 2, "Expected 2 rows for action=CREATE");
 
         let row0 = table3.get_row(0).unwrap();
-        assert_eq!(row0[2].as_ref(), "CREATE");
+        assert_eq!(row_text(&row0)[2], "CREATE");
         let row1 = table3.get_row(1).unwrap();
-        assert_eq!(row1[2].as_ref(), "CREATE");
+        assert_eq!(row_text(&row1)[2], "CREATE");
 
         cleanup_test_stream(&mut conn, test_key);
     }
@@ -663,7 +670,9 @@ mod tests {
                 "resource".to_string(),
                 resource.to_string(),
             ];
-            table.insert(&mut conn, test_key, &data).unwrap();
+            table
+                .insert(&mut conn, test_key, &data, InsertMode::Overwrite)
+                .unwrap();
         }
 
         // Test WHERE user_id = 'user:alice' via load_batch (streaming path)
@@ -684,9 +693,9 @@ mod tests {
         assert_eq!(rows, 2, "Expected 2 rows for user:alice");
 
         let row0 = table2.get_row(0).unwrap();
-        assert_eq!(row0[1].as_ref(), "user:alice");
+        assert_eq!(row_text(&row0)[1], "user:alice");
         let row1 = table2.get_row(1).unwrap();
-        assert_eq!(row1[1].as_ref(), "user:alice");
+        assert_eq!(row_text(&row1)[1], "user:alice");
 
         // Test WHERE action = 'CREATE' via load_batch
         let condition2 = PushableCondition {
@@ -705,9 +714,9 @@ mod tests {
         assert_eq!(rows2, 2, "Expected 2 rows for action=CREATE");
 
         let row0 = table3.get_row(0).unwrap();
-        assert_eq!(row0[2].as_ref(), "CREATE");
+        assert_eq!(row_text(&row0)[2], "CREATE");
         let row1 = table3.get_row(1).unwrap();
-        assert_eq!(row1[2].as_ref(), "CREATE");
+        assert_eq!(row_text(&row1)[2], "CREATE");
 
         cleanup_test_stream(&mut conn, test_key);
     }
@@ -767,8 +776,8 @@ mod tests {
         assert!(result.is_ok());
         assert_eq!(table2.data_len(), 1);
         let row = table2.get_row(0).unwrap();
-        assert_eq!(row[0].as_ref(), id1.as_str());
-        assert_eq!(row[1].as_ref(), "user:alice");
+        assert_eq!(row_text(&row)[0], id1.as_str());
+        assert_eq!(row_text(&row)[1], "user:alice");
 
         cleanup_test_stream(&mut conn, test_key);
     }
@@ -799,7 +808,9 @@ mod tests {
             "resource".to_string(),
             "file:readme".to_string(),
         ];
-        table.insert(&mut conn, test_key, &data).unwrap();
+        table
+            .insert(&mut conn, test_key, &data, InsertMode::Overwrite)
+            .unwrap();
 
         // Use load_batch (the streaming path used in iterate_foreign_scan)
         let (cursor, rows) = table.load_batch(&mut conn, test_key, 0, 100, None).unwrap();
@@ -812,10 +823,14 @@ mod tests {
         let row_data = row.unwrap();
         eprintln!("row_data after load_batch = {:?}", row_data);
         assert_eq!(row_data.len(), 4, "Expected 4 columns, got {:?}", row_data);
-        assert_eq!(row_data[1].as_ref(), "user:bob", "user_id column mismatch");
-        assert_eq!(row_data[2].as_ref(), "UPDATE", "action column mismatch");
         assert_eq!(
-            row_data[3].as_ref(),
+            row_text(&row_data)[1],
+            "user:bob",
+            "user_id column mismatch"
+        );
+        assert_eq!(row_text(&row_data)[2], "UPDATE", "action column mismatch");
+        assert_eq!(
+            row_text(&row_data)[3],
             "file:readme",
             "resource column mismatch"
         );
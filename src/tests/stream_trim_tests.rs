@@ -0,0 +1,202 @@
+#[cfg(any(test, feature = "pg_test"))]
+#[pgrx::pg_schema]
+mod tests {
+    use pgrx::prelude::*;
+
+    const TEST_DATABASE: &str = "15";
+    const FDW_NAME: &str = "redis_stream_trim_fdw";
+    const SERVER_NAME: &str = "redis_stream_trim_server";
+
+    fn setup_fdw() {
+        let _ = Spi::run(&format!(
+            "DROP FOREIGN DATA WRAPPER IF EXISTS {} CASCADE;",
+            FDW_NAME
+        ));
+        Spi::run(&format!(
+            "CREATE FOREIGN DATA WRAPPER {} HANDLER redis_fdw_handler VALIDATOR redis_fdw_validator;",
+            FDW_NAME
+        ))
+        .unwrap();
+        Spi::run(&format!(
+            "CREATE SERVER {} FOREIGN DATA WRAPPER {} OPTIONS (host_port '127.0.0.1:8899');",
+            SERVER_NAME, FDW_NAME
+        ))
+        .unwrap();
+    }
+
+    fn cleanup() {
+        let _ = Spi::run(&format!(
+            "DROP FOREIGN DATA WRAPPER IF EXISTS {} CASCADE;",
+            FDW_NAME
+        ));
+    }
+
+    fn redis_conn() -> redis::Connection {
+        redis::Client::open("redis://127.0.0.1:8899/15")
+            .unwrap()
+            .get_connection()
+            .unwrap()
+    }
+
+    fn cleanup_redis_keys(keys: &[&str]) {
+        let _: Result<(), _> = redis::cmd("DEL").arg(keys).query(&mut redis_conn());
+    }
+
+    fn xlen(key: &str) -> i64 {
+        redis::cmd("XLEN")
+            .arg(key)
+            .query(&mut redis_conn())
+            .unwrap()
+    }
+
+    #[pg_test]
+    fn test_maxlen_trims_every_insert() {
+        setup_fdw();
+        let key = "stream_trim_test:maxlen";
+        cleanup_redis_keys(&[key]);
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE trim_maxlen (stream_id text, event text) SERVER {} OPTIONS (
+                database '{}', table_type 'stream', table_key_prefix '{}', stream_maxlen '2'
+            );",
+            SERVER_NAME, TEST_DATABASE, key
+        ))
+        .unwrap();
+
+        for event in ["a", "b", "c"] {
+            Spi::run(&format!(
+                "INSERT INTO trim_maxlen (event) VALUES ('{}');",
+                event
+            ))
+            .unwrap();
+        }
+        assert_eq!(xlen(key), 2);
+
+        // The pipelined batch path trims too.
+        Spi::run("INSERT INTO trim_maxlen (event) SELECT 'e' || g FROM generate_series(1, 50) g;")
+            .unwrap();
+        assert_eq!(xlen(key), 2);
+        assert_eq!(
+            Spi::get_one::<String>(
+                "SELECT string_agg(event, ',' ORDER BY stream_id) FROM trim_maxlen;"
+            )
+            .unwrap(),
+            Some("e49,e50".to_string())
+        );
+
+        cleanup_redis_keys(&[key]);
+        cleanup();
+    }
+
+    #[pg_test]
+    fn test_redis_xtrim_on_demand() {
+        setup_fdw();
+        let keys = ["stream_trim_test:multi:a", "stream_trim_test:multi:b"];
+        cleanup_redis_keys(&keys);
+        let mut conn = redis_conn();
+        for key in keys {
+            for i in 0..5 {
+                let _: String = redis::cmd("XADD")
+                    .arg(key)
+                    .arg("*")
+                    .arg("event")
+                    .arg(i)
+                    .query(&mut conn)
+                    .unwrap();
+            }
+        }
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE trim_multi (key text, stream_id text, event text) SERVER {} OPTIONS (
+                database '{}', table_type 'stream', table_key_prefix 'stream_trim_test:multi:*',
+                stream_maxlen '3'
+            );",
+            SERVER_NAME, TEST_DATABASE
+        ))
+        .unwrap();
+        assert_eq!(
+            Spi::get_one::<i64>("SELECT redis_xtrim('trim_multi');").unwrap(),
+            Some(4)
+        );
+        assert_eq!(xlen(keys[0]), 3);
+        assert_eq!(xlen(keys[1]), 3);
+
+        let key = "stream_trim_test:age";
+        cleanup_redis_keys(&[key]);
+        // Entries from 2001, far older than a day.
+        for id in ["1000000000000-0", "1000000000001-0"] {
+            let _: String = redis::cmd("XADD")
+                .arg(key)
+                .arg(id)
+                .arg("event")
+                .arg("old")
+                .query(&mut conn)
+                .unwrap();
+        }
+        let _: String = redis::cmd("XADD")
+            .arg(key)
+            .arg("*")
+            .arg("event")
+            .arg("new")
+            .query(&mut conn)
+            .unwrap();
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE trim_age (stream_id text, event text) SERVER {} OPTIONS (
+                database '{}', table_type 'stream', table_key_prefix '{}',
+                stream_minid_age '86400000'
+            );",
+            SERVER_NAME, TEST_DATABASE, key
+        ))
+        .unwrap();
+        assert_eq!(
+            Spi::get_one::<i64>("SELECT redis_xtrim('trim_age');").unwrap(),
+            Some(2)
+        );
+        assert_eq!(
+            Spi::get_one::<String>("SELECT event FROM trim_age;").unwrap(),
+            Some("new".to_string())
+        );
+
+        cleanup_redis_keys(&keys);
+        cleanup_redis_keys(&[key]);
+        cleanup();
+    }
+
+    #[pg_test]
+    #[should_panic(expected = "holds a string, not a stream")]
+    fn test_redis_xtrim_rejects_other_types() {
+        setup_fdw();
+        let key = "stream_trim_test:not_stream";
+        cleanup_redis_keys(&[key]);
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE trim_other (stream_id text, event text) SERVER {} OPTIONS (
+                database '{}', table_type 'stream', table_key_prefix '{}', stream_maxlen '3'
+            );",
+            SERVER_NAME, TEST_DATABASE, key
+        ))
+        .unwrap();
+        // A missing stream has nothing to trim and is not created
+        assert_eq!(
+            Spi::get_one::<i64>("SELECT redis_xtrim('trim_other');").unwrap(),
+            Some(0)
+        );
+        let _: () = redis::cmd("SET")
+            .arg(key)
+            .arg("plain")
+            .query(&mut redis_conn())
+            .unwrap();
+        Spi::run("SELECT redis_xtrim('trim_other');").unwrap();
+    }
+
+    #[pg_test]
+    #[should_panic(expected = "cannot be set together")]
+    fn test_both_trim_options_rejected() {
+        setup_fdw();
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE trim_bad (stream_id text, event text) SERVER {} OPTIONS (
+                table_type 'stream', table_key_prefix 'stream_trim_test:bad',
+                stream_maxlen '10', stream_minid_age '1000'
+            );",
+            SERVER_NAME
+        ))
+        .unwrap();
+    }
+}
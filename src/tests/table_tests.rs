@@ -11,11 +11,19 @@ mod tests {
             RedisHashTable, RedisListTable, RedisSetTable, RedisStringTable, RedisZSetTable,
         },
         interface::RedisTableOperations,
-        types::{DataContainer, DataSet, RedisTableType, RowVec},
+        types::{DataContainer, DataSet, RedisTableType, RowValue, RowVec},
     };
 
     fn cow_vec_to_string_vec(row: Option<RowVec<'_>>) -> Option<Vec<String>> {
-        row.map(|v| v.into_iter().map(|c| c.into_owned()).collect())
+        row.map(|v| {
+            v.into_iter()
+                .map(|c| String::from_utf8(c.expect("NULL value").into_owned()).unwrap())
+                .collect()
+        })
+    }
+
+    fn filtered_values(values: Vec<String>) -> Vec<RowValue> {
+        values.into_iter().map(|v| Some(v.into_bytes())).collect()
     }
 
     #[test]
@@ -28,7 +36,7 @@ mod tests {
 
         // Simulate setting data
         string_table.dataset =
-            DataSet::Complete(DataContainer::String(Some("Hello, World!".to_string())));
+            DataSet::Complete(DataContainer::String(Some("Hello, World!".into())));
 
         // Now has data
         assert_eq!(string_table.data_len(), 1);
@@ -39,15 +47,14 @@ mod tests {
         assert_eq!(string_table.get_row(1), None);
 
         // Test with different values
-        string_table.dataset =
-            DataSet::Complete(DataContainer::String(Some("Test String".to_string())));
+        string_table.dataset = DataSet::Complete(DataContainer::String(Some("Test String".into())));
         assert_eq!(
             cow_vec_to_string_vec(string_table.get_row(0)),
             Some(vec!["Test String".to_string()])
         );
 
         // Test empty string
-        string_table.dataset = DataSet::Complete(DataContainer::String(Some("".to_string())));
+        string_table.dataset = DataSet::Complete(DataContainer::String(Some("".into())));
         assert_eq!(string_table.data_len(), 1);
         assert_eq!(
             cow_vec_to_string_vec(string_table.get_row(0)),
@@ -69,9 +76,9 @@ mod tests {
 
         // Add some data
         hash_table.dataset = DataSet::Complete(DataContainer::Hash(vec![
-            ("name".to_string(), "John".to_string()),
-            ("age".to_string(), "30".to_string()),
-            ("city".to_string(), "New York".to_string()),
+            ("name".into(), "John".into()),
+            ("age".into(), "30".into()),
+            ("city".into(), "New York".into()),
         ]));
 
         // Check data
@@ -100,9 +107,9 @@ mod tests {
 
         // Add some data
         list_table.dataset = DataSet::Complete(DataContainer::List(vec![
-            "apple".to_string(),
-            "banana".to_string(),
-            "cherry".to_string(),
+            "apple".into(),
+            "banana".into(),
+            "cherry".into(),
         ]));
 
         // Check data
@@ -131,11 +138,11 @@ mod tests {
         assert_eq!(set_table.get_row(0), None);
 
         // Add some data
-        set_table.dataset = DataSet::Filtered(vec![
+        set_table.dataset = DataSet::Filtered(filtered_values(vec![
             "red".to_string(),
             "green".to_string(),
             "blue".to_string(),
-        ]);
+        ]));
 
         // Check data
         assert_eq!(set_table.data_len(), 3);
@@ -155,7 +162,7 @@ mod tests {
 
         // Test with filtered data
         let filtered_data = vec!["yellow".to_string(), "purple".to_string()];
-        set_table.dataset = DataSet::Filtered(filtered_data);
+        set_table.dataset = DataSet::Filtered(filtered_values(filtered_data));
         assert_eq!(set_table.data_len(), 2);
         assert_eq!(
             cow_vec_to_string_vec(set_table.get_row(0)),
@@ -183,9 +190,9 @@ mod tests {
 
         // Add some data
         zset_table.dataset = DataSet::Complete(DataContainer::ZSet(vec![
-            ("player1".to_string(), 100.5),
-            ("player2".to_string(), 95.0),
-            ("player3".to_string(), 110.2),
+            ("player1".into(), 100.5),
+            ("player2".into(), 95.0),
+            ("player3".into(), 110.2),
         ]));
 
         // Check data
@@ -211,7 +218,7 @@ mod tests {
             "player5".to_string(),
             "130.5".to_string(),
         ];
-        zset_table.dataset = DataSet::Filtered(filtered_data);
+        zset_table.dataset = DataSet::Filtered(filtered_values(filtered_data));
         assert_eq!(zset_table.data_len(), 2);
         assert_eq!(
             cow_vec_to_string_vec(zset_table.get_row(0)),
@@ -224,8 +231,7 @@ mod tests {
         assert_eq!(zset_table.get_row(2), None);
 
         // Test edge cases
-        zset_table.dataset =
-            DataSet::Complete(DataContainer::ZSet(vec![("single".to_string(), 0.0)]));
+        zset_table.dataset = DataSet::Complete(DataContainer::ZSet(vec![("single".into(), 0.0)]));
         assert_eq!(zset_table.data_len(), 1);
         assert_eq!(
             cow_vec_to_string_vec(zset_table.get_row(0)),
@@ -303,13 +309,13 @@ mod tests {
 
         // Test with various data types as strings
         hash_table.dataset = DataSet::Complete(DataContainer::Hash(vec![
-            ("user_id".to_string(), "12345".to_string()),
-            ("score".to_string(), "98.5".to_string()),
-            ("active".to_string(), "true".to_string()),
-            ("name".to_string(), "John O'Connor".to_string()),
+            ("user_id".into(), "12345".into()),
+            ("score".into(), "98.5".into()),
+            ("active".into(), "true".into()),
+            ("name".into(), "John O'Connor".into()),
             (
-                "description".to_string(),
-                "User with special chars: @#$%^&*()".to_string(),
+                "description".into(),
+                "User with special chars: @#$%^&*()".into(),
             ),
         ]));
 
@@ -330,8 +336,8 @@ mod tests {
 
         // Test with unicode fields and values
         hash_table.dataset = DataSet::Complete(DataContainer::Hash(vec![
-            ("用户名".to_string(), "张三".to_string()),
-            ("🎯".to_string(), "target".to_string()),
+            ("用户名".into(), "张三".into()),
+            ("🎯".into(), "target".into()),
         ]));
         assert_eq!(hash_table.data_len(), 2);
         assert_eq!(
@@ -350,12 +356,12 @@ mod tests {
 
         // Test with mixed content
         list_table.dataset = DataSet::Complete(DataContainer::List(vec![
-            "first".to_string(),
-            "".to_string(),            // empty string
-            "123".to_string(),         // numeric string
-            "hello world".to_string(), // space in string
-            "special@#$%".to_string(), // special characters
-            "🚀🌟".to_string(),        // unicode emojis
+            "first".into(),
+            "".into(),            // empty string
+            "123".into(),         // numeric string
+            "hello world".into(), // space in string
+            "special@#$%".into(), // special characters
+            "🚀🌟".into(),        // unicode emojis
         ]));
 
         assert_eq!(list_table.data_len(), 6);
@@ -374,7 +380,7 @@ mod tests {
         assert_eq!(list_table.get_row(6), None);
 
         // Test large list simulation
-        let large_list: Vec<String> = (0..1000).map(|i| format!("item_{}", i)).collect();
+        let large_list: Vec<Vec<u8>> = (0..1000).map(|i| format!("item_{}", i).into()).collect();
         list_table.dataset = DataSet::Complete(DataContainer::List(large_list));
         assert_eq!(list_table.data_len(), 1000);
         assert_eq!(
@@ -393,7 +399,7 @@ mod tests {
         let mut set_table = RedisSetTable::new();
 
         // Test with various member types
-        set_table.dataset = DataSet::Filtered(vec![
+        set_table.dataset = DataSet::Filtered(filtered_values(vec![
             "user:123".to_string(),
             "tag:urgent".to_string(),
             "category:work".to_string(),
@@ -401,7 +407,7 @@ mod tests {
             "123".to_string(),
             "true".to_string(),
             "".to_string(), // empty string member
-        ]);
+        ]));
 
         assert_eq!(set_table.data_len(), 7);
         assert_eq!(
@@ -420,7 +426,7 @@ mod tests {
             "user".to_string(),
             "guest".to_string(),
         ];
-        set_table.dataset = DataSet::Filtered(filtered);
+        set_table.dataset = DataSet::Filtered(filtered_values(filtered));
         assert_eq!(set_table.data_len(), 4);
         assert_eq!(
             cow_vec_to_string_vec(set_table.get_row(0)),
@@ -439,12 +445,12 @@ mod tests {
 
         // Test with various score scenarios
         zset_table.dataset = DataSet::Complete(DataContainer::ZSet(vec![
-            ("user1".to_string(), 100.0),
-            ("user2".to_string(), -50.5),     // negative score
-            ("user3".to_string(), 0.0),       // zero score
-            ("user4".to_string(), 999999.99), // large score
-            ("user5".to_string(), 0.001),     // small positive score
-            ("特殊用户".to_string(), 88.8),   // unicode member
+            ("user1".into(), 100.0),
+            ("user2".into(), -50.5),     // negative score
+            ("user3".into(), 0.0),       // zero score
+            ("user4".into(), 999999.99), // large score
+            ("user5".into(), 0.001),     // small positive score
+            ("特殊用户".into(), 88.8),   // unicode member
         ]));
 
         assert_eq!(zset_table.data_len(), 6);
@@ -467,8 +473,8 @@ mod tests {
 
         // Test edge case with same member name
         zset_table.dataset = DataSet::Complete(DataContainer::ZSet(vec![
-            ("duplicate".to_string(), 10.0),
-            ("duplicate".to_string(), 20.0), // Redis would overwrite, but our structure allows it
+            ("duplicate".into(), 10.0),
+            ("duplicate".into(), 20.0), // Redis would overwrite, but our structure allows it
         ]));
         assert_eq!(zset_table.data_len(), 2);
         assert_eq!(
@@ -500,7 +506,7 @@ mod tests {
 
         for test_string in test_strings {
             string_table.dataset =
-                DataSet::Complete(DataContainer::String(Some(test_string.to_string())));
+                DataSet::Complete(DataContainer::String(Some(test_string.into())));
             assert_eq!(string_table.data_len(), 1);
             assert_eq!(
                 cow_vec_to_string_vec(string_table.get_row(0)),
@@ -511,7 +517,7 @@ mod tests {
 
         // Test with filtered data
         let filtered = vec!["filtered_value".to_string()];
-        string_table.dataset = DataSet::Filtered(filtered);
+        string_table.dataset = DataSet::Filtered(filtered_values(filtered));
         assert_eq!(string_table.data_len(), 1);
         assert_eq!(
             cow_vec_to_string_vec(string_table.get_row(0)),
@@ -561,10 +567,8 @@ mod tests {
         assert_eq!(hash_table.get_row(0), None);
 
         // Test with single entry
-        hash_table.dataset = DataSet::Complete(DataContainer::Hash(vec![(
-            "key1".to_string(),
-            "value1".to_string(),
-        )]));
+        hash_table.dataset =
+            DataSet::Complete(DataContainer::Hash(vec![("key1".into(), "value1".into())]));
         assert_eq!(hash_table.data_len(), 1);
         assert_eq!(
             cow_vec_to_string_vec(hash_table.get_row(0)),
@@ -572,8 +576,7 @@ mod tests {
         );
 
         // Test with empty strings
-        hash_table.dataset =
-            DataSet::Complete(DataContainer::Hash(vec![("".to_string(), "".to_string())]));
+        hash_table.dataset = DataSet::Complete(DataContainer::Hash(vec![("".into(), "".into())]));
         assert_eq!(hash_table.data_len(), 1);
         assert_eq!(
             cow_vec_to_string_vec(hash_table.get_row(0)),
@@ -582,8 +585,8 @@ mod tests {
 
         // Test with special characters
         hash_table.dataset = DataSet::Complete(DataContainer::Hash(vec![(
-            "key@#$".to_string(),
-            "value!@#$%^&*()".to_string(),
+            "key@#$".into(),
+            "value!@#$%^&*()".into(),
         )]));
         assert_eq!(
             cow_vec_to_string_vec(hash_table.get_row(0)),
@@ -596,7 +599,7 @@ mod tests {
         let mut list_table = RedisListTable::new();
 
         // Test with single element
-        list_table.dataset = DataSet::Complete(DataContainer::List(vec!["single".to_string()]));
+        list_table.dataset = DataSet::Complete(DataContainer::List(vec!["single".into()]));
         assert_eq!(list_table.data_len(), 1);
         assert_eq!(
             cow_vec_to_string_vec(list_table.get_row(0)),
@@ -605,9 +608,9 @@ mod tests {
 
         // Test with empty strings in list
         list_table.dataset = DataSet::Complete(DataContainer::List(vec![
-            "".to_string(),
-            "non-empty".to_string(),
-            "".to_string(),
+            "".into(),
+            "non-empty".into(),
+            "".into(),
         ]));
         assert_eq!(list_table.data_len(), 3);
         assert_eq!(
@@ -625,9 +628,9 @@ mod tests {
 
         // Test with unicode characters
         list_table.dataset = DataSet::Complete(DataContainer::List(vec![
-            "🚀".to_string(),
-            "测试".to_string(),
-            "עברית".to_string(),
+            "🚀".into(),
+            "测试".into(),
+            "עברית".into(),
         ]));
         assert_eq!(list_table.data_len(), 3);
         assert_eq!(
@@ -649,7 +652,10 @@ mod tests {
         let mut set_table = RedisSetTable::new();
 
         // Test with duplicate-like values (sets should handle uniqueness in Redis, but we test the structure)
-        set_table.dataset = DataSet::Filtered(vec!["value".to_string(), "value".to_string()]);
+        set_table.dataset = DataSet::Filtered(filtered_values(vec![
+            "value".to_string(),
+            "value".to_string(),
+        ]));
         assert_eq!(set_table.data_len(), 2); // Our structure doesn't enforce uniqueness, Redis does
         assert_eq!(
             cow_vec_to_string_vec(set_table.get_row(0)),
@@ -661,8 +667,11 @@ mod tests {
         );
 
         // Test with numeric strings
-        set_table.dataset =
-            DataSet::Filtered(vec!["1".to_string(), "2.5".to_string(), "-10".to_string()]);
+        set_table.dataset = DataSet::Filtered(filtered_values(vec![
+            "1".to_string(),
+            "2.5".to_string(),
+            "-10".to_string(),
+        ]));
         assert_eq!(set_table.data_len(), 3);
         assert_eq!(
             cow_vec_to_string_vec(set_table.get_row(0)),
@@ -684,8 +693,8 @@ mod tests {
 
         // Test with same scores
         zset_table.dataset = DataSet::Complete(DataContainer::ZSet(vec![
-            ("member1".to_string(), 100.0),
-            ("member2".to_string(), 100.0),
+            ("member1".into(), 100.0),
+            ("member2".into(), 100.0),
         ]));
         assert_eq!(zset_table.data_len(), 2);
         assert_eq!(
@@ -699,8 +708,8 @@ mod tests {
 
         // Test with negative scores
         zset_table.dataset = DataSet::Complete(DataContainer::ZSet(vec![
-            ("negative".to_string(), -50.5),
-            ("positive".to_string(), 50.5),
+            ("negative".into(), -50.5),
+            ("positive".into(), 50.5),
         ]));
         assert_eq!(
             cow_vec_to_string_vec(zset_table.get_row(0)),
@@ -712,8 +721,7 @@ mod tests {
         );
 
         // Test with zero score
-        zset_table.dataset =
-            DataSet::Complete(DataContainer::ZSet(vec![("zero".to_string(), 0.0)]));
+        zset_table.dataset = DataSet::Complete(DataContainer::ZSet(vec![("zero".into(), 0.0)]));
         assert_eq!(
             cow_vec_to_string_vec(zset_table.get_row(0)),
             Some(vec!["zero".to_string(), "0".to_string()])
@@ -721,7 +729,7 @@ mod tests {
 
         // Test with very large numbers
         zset_table.dataset =
-            DataSet::Complete(DataContainer::ZSet(vec![("large".to_string(), f64::MAX)]));
+            DataSet::Complete(DataContainer::ZSet(vec![("large".into(), f64::MAX)]));
         assert_eq!(
             cow_vec_to_string_vec(zset_table.get_row(0)),
             Some(vec!["large".to_string(), f64::MAX.to_string()])
@@ -744,7 +752,7 @@ mod tests {
             "field2".to_string(),
             "value2".to_string(),
         ];
-        hash_table.dataset = DataSet::Filtered(hash_filtered);
+        hash_table.dataset = DataSet::Filtered(filtered_values(hash_filtered));
         assert_eq!(hash_table.data_len(), 2);
         assert_eq!(
             cow_vec_to_string_vec(hash_table.get_row(0)),
@@ -761,7 +769,7 @@ mod tests {
             "item2".to_string(),
             "item3".to_string(),
         ];
-        list_table.dataset = DataSet::Filtered(list_filtered);
+        list_table.dataset = DataSet::Filtered(filtered_values(list_filtered));
         assert_eq!(list_table.data_len(), 3);
         assert_eq!(
             cow_vec_to_string_vec(list_table.get_row(0)),
@@ -774,7 +782,7 @@ mod tests {
 
         // Test with filtered data for set
         let set_filtered = vec!["member1".to_string(), "member2".to_string()];
-        set_table.dataset = DataSet::Filtered(set_filtered);
+        set_table.dataset = DataSet::Filtered(filtered_values(set_filtered));
         assert_eq!(set_table.data_len(), 2);
         assert_eq!(
             cow_vec_to_string_vec(set_table.get_row(0)),
@@ -788,7 +796,7 @@ mod tests {
             "player2".to_string(),
             "200.0".to_string(),
         ];
-        zset_table.dataset = DataSet::Filtered(zset_filtered);
+        zset_table.dataset = DataSet::Filtered(filtered_values(zset_filtered));
         assert_eq!(zset_table.data_len(), 2);
         assert_eq!(
             cow_vec_to_string_vec(zset_table.get_row(0)),
@@ -797,7 +805,7 @@ mod tests {
 
         // Test with filtered data for string (single value)
         let string_filtered = vec!["filtered_value".to_string()];
-        string_table.dataset = DataSet::Filtered(string_filtered);
+        string_table.dataset = DataSet::Filtered(filtered_values(string_filtered));
         assert_eq!(string_table.data_len(), 1);
         assert_eq!(
             cow_vec_to_string_vec(string_table.get_row(0)),
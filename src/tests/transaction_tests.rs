@@ -0,0 +1,395 @@
+#[cfg(any(test, feature = "pg_test"))]
+#[pgrx::pg_schema]
+mod tests {
+    use pgrx::prelude::*;
+
+    const TEST_DATABASE: &str = "15";
+    const FDW_NAME: &str = "redis_tx_fdw";
+    const SERVER_NAME: &str = "redis_tx_server";
+
+    fn setup_fdw() {
+        let _ = Spi::run(&format!(
+            "DROP FOREIGN DATA WRAPPER IF EXISTS {} CASCADE;",
+            FDW_NAME
+        ));
+        Spi::run(&format!(
+            "CREATE FOREIGN DATA WRAPPER {} HANDLER redis_fdw_handler VALIDATOR redis_fdw_validator;",
+            FDW_NAME
+        ))
+        .unwrap();
+        Spi::run(&format!(
+            "CREATE SERVER {} FOREIGN DATA WRAPPER {} OPTIONS (host_port '127.0.0.1:8899');",
+            SERVER_NAME, FDW_NAME
+        ))
+        .unwrap();
+    }
+
+    fn cleanup() {
+        let _ = Spi::run(&format!(
+            "DROP FOREIGN DATA WRAPPER IF EXISTS {} CASCADE;",
+            FDW_NAME
+        ));
+    }
+
+    fn redis_conn() -> redis::Connection {
+        redis::Client::open("redis://127.0.0.1:8899/15")
+            .unwrap()
+            .get_connection()
+            .unwrap()
+    }
+
+    fn key_exists(key: &str) -> bool {
+        redis::cmd("EXISTS")
+            .arg(key)
+            .query::<i64>(&mut redis_conn())
+            .unwrap()
+            == 1
+    }
+
+    fn cleanup_redis_key(key: &str) {
+        let _: Result<(), _> = redis::cmd("DEL").arg(key).query(&mut redis_conn());
+    }
+
+    #[pg_test]
+    fn test_transactional_insert_is_buffered_until_commit() {
+        setup_fdw();
+        let key = "tx_test:hash:buffered";
+        cleanup_redis_key(key);
+
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE tx_hash (field text, value text) SERVER {} OPTIONS (
+                database '{}', table_type 'hash', table_key_prefix '{}', transactional 'true'
+            );",
+            SERVER_NAME, TEST_DATABASE, key
+        ))
+        .unwrap();
+
+        Spi::run("INSERT INTO tx_hash VALUES ('a', '1'), ('b', '2');").unwrap();
+        Spi::run("UPDATE tx_hash SET value = '3' WHERE field = 'a';").unwrap();
+
+        // pg_test runs inside a transaction that never commits, so the
+        // writes must still be sitting in the buffer.
+        assert!(!key_exists(key));
+
+        cleanup_redis_key(key);
+        cleanup();
+    }
+
+    fn dump(key: &str) -> Option<Vec<u8>> {
+        redis::cmd("DUMP")
+            .arg(key)
+            .query(&mut redis_conn())
+            .unwrap()
+    }
+
+    /// Table type, columns, table_key_prefix, extra options, the key seeded
+    /// before the writes, its seed command and the write statements.
+    type WriteCase = (
+        &'static str,
+        &'static str,
+        &'static str,
+        &'static str,
+        &'static str,
+        redis::Cmd,
+        Vec<&'static str>,
+    );
+
+    #[pg_test]
+    fn test_transactional_writes_wait_for_commit_on_every_table_type() {
+        setup_fdw();
+        // Pub/sub tables store nothing; pubsub_tests checks that their
+        // messages wait for COMMIT.
+        let cases: Vec<WriteCase> = vec![
+            (
+                "string",
+                "value text",
+                "tx_test:all:string",
+                "",
+                "tx_test:all:string",
+                redis::cmd("SET")
+                    .arg("tx_test:all:string")
+                    .arg("old")
+                    .clone(),
+                vec![
+                    "INSERT INTO tx_all VALUES ('new')",
+                    "UPDATE tx_all SET value = 'x'",
+                    "DELETE FROM tx_all",
+                ],
+            ),
+            (
+                "hash",
+                "field text, value text",
+                "tx_test:all:hash",
+                "",
+                "tx_test:all:hash",
+                redis::cmd("HSET")
+                    .arg("tx_test:all:hash")
+                    .arg("f")
+                    .arg("old")
+                    .clone(),
+                vec![
+                    "INSERT INTO tx_all VALUES ('g', 'v')",
+                    "UPDATE tx_all SET value = 'x' WHERE field = 'f'",
+                    "DELETE FROM tx_all WHERE field = 'f'",
+                ],
+            ),
+            (
+                "list",
+                "element text",
+                "tx_test:all:list",
+                "",
+                "tx_test:all:list",
+                redis::cmd("RPUSH").arg("tx_test:all:list").arg("a").clone(),
+                vec![
+                    "INSERT INTO tx_all VALUES ('b')",
+                    "UPDATE tx_all SET element = 'x' WHERE element = 'a'",
+                    "DELETE FROM tx_all WHERE element = 'a'",
+                ],
+            ),
+            (
+                "set",
+                "member text",
+                "tx_test:all:set",
+                "",
+                "tx_test:all:set",
+                redis::cmd("SADD").arg("tx_test:all:set").arg("a").clone(),
+                vec![
+                    "INSERT INTO tx_all VALUES ('b')",
+                    "UPDATE tx_all SET member = 'x' WHERE member = 'a'",
+                    "DELETE FROM tx_all WHERE member = 'a'",
+                ],
+            ),
+            (
+                "zset",
+                "member text, score float8",
+                "tx_test:all:zset",
+                "",
+                "tx_test:all:zset",
+                redis::cmd("ZADD")
+                    .arg("tx_test:all:zset")
+                    .arg(1)
+                    .arg("a")
+                    .clone(),
+                vec![
+                    "INSERT INTO tx_all VALUES ('b', 2)",
+                    "UPDATE tx_all SET score = 5 WHERE member = 'a'",
+                    "DELETE FROM tx_all WHERE member = 'a'",
+                ],
+            ),
+            (
+                "stream",
+                "stream_id text, event text",
+                "tx_test:all:stream",
+                "",
+                "tx_test:all:stream",
+                redis::cmd("XADD")
+                    .arg("tx_test:all:stream")
+                    .arg("1-0")
+                    .arg("event")
+                    .arg("a")
+                    .clone(),
+                vec![
+                    "INSERT INTO tx_all VALUES ('2-0', 'b')",
+                    "DELETE FROM tx_all WHERE stream_id = '1-0'",
+                ],
+            ),
+            (
+                "hash_row",
+                "name text, email text",
+                "tx_test:all:hash_row",
+                "",
+                "tx_test:all:hash_row",
+                redis::cmd("HSET")
+                    .arg("tx_test:all:hash_row")
+                    .arg("name")
+                    .arg("a")
+                    .clone(),
+                vec![
+                    "INSERT INTO tx_all VALUES ('b', 'e')",
+                    "UPDATE tx_all SET email = 'x'",
+                    "DELETE FROM tx_all",
+                ],
+            ),
+            (
+                "json",
+                "key text, name text",
+                "tx_test:all:json:*",
+                "",
+                "tx_test:all:json:1",
+                redis::cmd("JSON.SET")
+                    .arg("tx_test:all:json:1")
+                    .arg("$")
+                    .arg(r#"{"name":"a"}"#)
+                    .clone(),
+                vec![
+                    "INSERT INTO tx_all VALUES ('tx_test:all:json:1', 'b')",
+                    "UPDATE tx_all SET name = 'x' WHERE key = 'tx_test:all:json:1'",
+                    "DELETE FROM tx_all WHERE key = 'tx_test:all:json:1'",
+                ],
+            ),
+            (
+                "geo",
+                "name text, longitude float8, latitude float8",
+                "tx_test:all:geo",
+                "",
+                "tx_test:all:geo",
+                redis::cmd("GEOADD")
+                    .arg("tx_test:all:geo")
+                    .arg(13.361389)
+                    .arg(38.115556)
+                    .arg("a")
+                    .clone(),
+                vec![
+                    "INSERT INTO tx_all VALUES ('b', 15, 37)",
+                    "UPDATE tx_all SET longitude = 14 WHERE name = 'a'",
+                    "DELETE FROM tx_all WHERE name = 'a'",
+                ],
+            ),
+            (
+                "bitmap",
+                "day bigint, active boolean",
+                "tx_test:all:bitmap",
+                "",
+                "tx_test:all:bitmap",
+                redis::cmd("SETBIT")
+                    .arg("tx_test:all:bitmap")
+                    .arg(3)
+                    .arg(1)
+                    .clone(),
+                vec![
+                    "INSERT INTO tx_all VALUES (5, true)",
+                    "UPDATE tx_all SET day = 7 WHERE day = 3",
+                    "DELETE FROM tx_all WHERE day = 3",
+                ],
+            ),
+            (
+                "hyperloglog",
+                "visitors bigint, visitor text",
+                "tx_test:all:hll",
+                "",
+                "tx_test:all:hll",
+                redis::cmd("PFADD").arg("tx_test:all:hll").arg("a").clone(),
+                vec![
+                    "INSERT INTO tx_all (visitor) VALUES ('b')",
+                    "UPDATE tx_all SET visitor = 'c'",
+                    "DELETE FROM tx_all",
+                ],
+            ),
+            (
+                "keyspace",
+                "key text, redis_type text",
+                "tx_test:all:keyspace:*",
+                "",
+                "tx_test:all:keyspace:1",
+                redis::cmd("SET")
+                    .arg("tx_test:all:keyspace:1")
+                    .arg("v")
+                    .clone(),
+                vec!["DELETE FROM tx_all WHERE key = 'tx_test:all:keyspace:1'"],
+            ),
+            (
+                "function",
+                "k text, arg text",
+                "tx_test:all:function",
+                ", read_script $$return {{'r1', 'x'}}$$,
+                 write_script $$return redis.call('RPUSH', KEYS[1], table.concat(ARGV, ':'))$$",
+                "tx_test:all:function",
+                redis::cmd("RPUSH")
+                    .arg("tx_test:all:function")
+                    .arg("old")
+                    .clone(),
+                vec![
+                    "INSERT INTO tx_all VALUES ('a', 'b')",
+                    "UPDATE tx_all SET arg = 'y' WHERE k = 'r1'",
+                    "DELETE FROM tx_all WHERE k = 'r1'",
+                ],
+            ),
+        ];
+
+        for (table_type, columns, prefix, options, key, seed, statements) in cases {
+            cleanup_redis_key(key);
+            let _: () = seed.query(&mut redis_conn()).unwrap();
+            let before = dump(key);
+
+            Spi::run(&format!(
+                "CREATE FOREIGN TABLE tx_all ({}) SERVER {} OPTIONS (
+                    database '{}', table_type '{}', table_key_prefix '{}',
+                    transactional 'true'{}
+                );",
+                columns, SERVER_NAME, TEST_DATABASE, table_type, prefix, options
+            ))
+            .unwrap();
+            for statement in statements {
+                Spi::run(statement).unwrap();
+                assert_eq!(dump(key), before, "{} table: {}", table_type, statement);
+            }
+            Spi::run("DROP FOREIGN TABLE tx_all;").unwrap();
+
+            cleanup_redis_key(key);
+        }
+        cleanup();
+    }
+
+    #[pg_test]
+    fn test_non_transactional_insert_is_immediate() {
+        setup_fdw();
+        let key = "tx_test:string:immediate";
+        cleanup_redis_key(key);
+
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE tx_str (value text) SERVER {} OPTIONS (
+                database '{}', table_type 'string', table_key_prefix '{}'
+            );",
+            SERVER_NAME, TEST_DATABASE, key
+        ))
+        .unwrap();
+
+        Spi::run("INSERT INTO tx_str VALUES ('now');").unwrap();
+        assert!(key_exists(key));
+
+        cleanup_redis_key(key);
+        cleanup();
+    }
+
+    #[pg_test]
+    #[should_panic(expected = "transactional must be")]
+    fn test_transactional_option_rejects_non_boolean() {
+        setup_fdw();
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE tx_bad (value text) SERVER {} OPTIONS (
+                table_type 'string', table_key_prefix 'tx_test:bad', transactional 'yes'
+            );",
+            SERVER_NAME
+        ))
+        .unwrap();
+    }
+
+    #[pg_test]
+    #[should_panic(expected = "ON CONFLICT DO NOTHING cannot be combined with transactional")]
+    fn test_transactional_rejects_on_conflict_do_nothing() {
+        setup_fdw();
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE tx_conflict (value text) SERVER {} OPTIONS (
+                table_type 'string', table_key_prefix 'tx_test:conflict', transactional 'true'
+            );",
+            SERVER_NAME
+        ))
+        .unwrap();
+
+        Spi::run("INSERT INTO tx_conflict VALUES ('v') ON CONFLICT DO NOTHING;").unwrap();
+    }
+
+    #[pg_test]
+    #[should_panic(expected = "cannot be combined with transactional")]
+    fn test_transactional_rejects_conditional_insert_mode() {
+        setup_fdw();
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE tx_nx (value text) SERVER {} OPTIONS (
+                table_type 'string', table_key_prefix 'tx_test:nx', transactional 'true',
+                insert_mode 'nx'
+            );",
+            SERVER_NAME
+        ))
+        .unwrap();
+    }
+}
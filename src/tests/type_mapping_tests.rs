@@ -0,0 +1,196 @@
+#[cfg(any(test, feature = "pg_test"))]
+#[pgrx::pg_schema]
+mod tests {
+    use pgrx::prelude::*;
+
+    const TEST_DATABASE: &str = "15";
+    const FDW_NAME: &str = "redis_type_mapping_fdw";
+    const SERVER_NAME: &str = "redis_type_mapping_server";
+
+    fn setup_fdw() {
+        let _ = Spi::run(&format!(
+            "DROP FOREIGN DATA WRAPPER IF EXISTS {} CASCADE;",
+            FDW_NAME
+        ));
+        Spi::run(&format!(
+            "CREATE FOREIGN DATA WRAPPER {} HANDLER redis_fdw_handler VALIDATOR redis_fdw_validator;",
+            FDW_NAME
+        ))
+        .unwrap();
+        Spi::run(&format!(
+            "CREATE SERVER {} FOREIGN DATA WRAPPER {} OPTIONS (host_port '127.0.0.1:8899');",
+            SERVER_NAME, FDW_NAME
+        ))
+        .unwrap();
+    }
+
+    fn cleanup() {
+        let _ = Spi::run(&format!(
+            "DROP FOREIGN DATA WRAPPER IF EXISTS {} CASCADE;",
+            FDW_NAME
+        ));
+    }
+
+    fn redis_conn() -> redis::Connection {
+        redis::Client::open("redis://127.0.0.1:8899/15")
+            .unwrap()
+            .get_connection()
+            .unwrap()
+    }
+
+    fn cleanup_redis_key(key: &str) {
+        let _: Result<(), _> = redis::cmd("DEL").arg(key).query(&mut redis_conn());
+    }
+
+    fn hset(key: &str, field: &str, value: &str) {
+        let _: () = redis::cmd("HSET")
+            .arg(key)
+            .arg(field)
+            .arg(value)
+            .query(&mut redis_conn())
+            .unwrap();
+    }
+
+    #[pg_test]
+    fn test_zset_float8_score_needs_no_cast() {
+        setup_fdw();
+        let key = "type_test:zset";
+        cleanup_redis_key(key);
+
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE tm_zset (member text, score float8) SERVER {} OPTIONS (
+                database '{}', table_type 'zset', table_key_prefix '{}'
+            );",
+            SERVER_NAME, TEST_DATABASE, key
+        ))
+        .unwrap();
+        Spi::run("INSERT INTO tm_zset VALUES ('a', 1.5), ('b', 2.25), ('c', 10);").unwrap();
+
+        let total = Spi::get_one::<f64>("SELECT sum(score) FROM tm_zset WHERE score < 5;")
+            .unwrap()
+            .unwrap();
+        assert_eq!(total, 3.75);
+
+        cleanup_redis_key(key);
+        cleanup();
+    }
+
+    #[pg_test]
+    fn test_typed_hash_values_round_trip() {
+        setup_fdw();
+        let key = "type_test:typed";
+        cleanup_redis_key(key);
+
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE tm_json (field text, value jsonb) SERVER {server} OPTIONS (
+                database '{db}', table_type 'hash', table_key_prefix '{key}'
+            );
+            CREATE FOREIGN TABLE tm_uuid (field text, value uuid) SERVER {server} OPTIONS (
+                database '{db}', table_type 'hash', table_key_prefix '{key}'
+            );
+            CREATE FOREIGN TABLE tm_ts (field text, value timestamptz) SERVER {server} OPTIONS (
+                database '{db}', table_type 'hash', table_key_prefix '{key}'
+            );",
+            server = SERVER_NAME,
+            db = TEST_DATABASE,
+            key = key
+        ))
+        .unwrap();
+
+        Spi::run(r#"INSERT INTO tm_json VALUES ('json', '{"hits": 3}');"#).unwrap();
+        Spi::run("INSERT INTO tm_uuid VALUES ('uuid', 'a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11');")
+            .unwrap();
+        Spi::run("INSERT INTO tm_ts VALUES ('ts', '2024-05-01 12:00:00+00');").unwrap();
+
+        let stored: String = redis::cmd("HGET")
+            .arg(key)
+            .arg("json")
+            .query(&mut redis_conn())
+            .unwrap();
+        assert_eq!(stored, r#"{"hits": 3}"#);
+
+        let hits =
+            Spi::get_one::<i32>("SELECT (value->>'hits')::int FROM tm_json WHERE field = 'json';")
+                .unwrap()
+                .unwrap();
+        assert_eq!(hits, 3);
+        let same_uuid = Spi::get_one::<bool>(
+            "SELECT value = 'a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11'::uuid FROM tm_uuid WHERE field = 'uuid';",
+        )
+        .unwrap()
+        .unwrap();
+        assert!(same_uuid);
+        let same_ts = Spi::get_one::<bool>(
+            "SELECT value = '2024-05-01 12:00:00+00'::timestamptz FROM tm_ts WHERE field = 'ts';",
+        )
+        .unwrap()
+        .unwrap();
+        assert!(same_ts);
+
+        cleanup_redis_key(key);
+        cleanup();
+    }
+
+    #[pg_test]
+    #[should_panic(expected = "is not a valid bigint for column \"value\"")]
+    fn test_malformed_value_names_column_and_type() {
+        setup_fdw();
+        let key = "type_test:malformed";
+        cleanup_redis_key(key);
+        hset(key, "hits", "not-a-number");
+
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE tm_bad (field text, value bigint) SERVER {} OPTIONS (
+                database '{}', table_type 'hash', table_key_prefix '{}'
+            );",
+            SERVER_NAME, TEST_DATABASE, key
+        ))
+        .unwrap();
+
+        Spi::get_one::<i64>("SELECT value FROM tm_bad;").unwrap();
+    }
+
+    #[pg_test]
+    fn test_on_conversion_error_null() {
+        setup_fdw();
+        let key = "type_test:lenient";
+        cleanup_redis_key(key);
+        hset(key, "good", "42");
+        hset(key, "bad", "forty-two");
+
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE tm_lenient (field text, value bigint) SERVER {} OPTIONS (
+                database '{}', table_type 'hash', table_key_prefix '{}',
+                on_conversion_error 'null'
+            );",
+            SERVER_NAME, TEST_DATABASE, key
+        ))
+        .unwrap();
+
+        let nulls = Spi::get_one::<i64>("SELECT count(*) FROM tm_lenient WHERE value IS NULL;")
+            .unwrap()
+            .unwrap();
+        assert_eq!(nulls, 1);
+        let good = Spi::get_one::<i64>("SELECT value FROM tm_lenient WHERE field = 'good';")
+            .unwrap()
+            .unwrap();
+        assert_eq!(good, 42);
+
+        cleanup_redis_key(key);
+        cleanup();
+    }
+
+    #[pg_test]
+    #[should_panic(expected = "on_conversion_error must be")]
+    fn test_invalid_on_conversion_error_rejected() {
+        setup_fdw();
+        Spi::run(&format!(
+            "CREATE FOREIGN TABLE tm_invalid (value text) SERVER {} OPTIONS (
+                database '{}', table_type 'string', table_key_prefix 'type_test:invalid',
+                on_conversion_error 'skip'
+            );",
+            SERVER_NAME, TEST_DATABASE
+        ))
+        .unwrap();
+    }
+}
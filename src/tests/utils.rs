@@ -0,0 +1,36 @@
+//! Helpers shared by the unit tests of several modules.
+
+use std::borrow::Cow;
+
+use crate::tables::types::RowValue;
+
+/// Arguments of `cmd`, name first, as raw bytes.
+pub fn cmd_arg_bytes(cmd: &redis::Cmd) -> Vec<Vec<u8>> {
+    cmd.args_iter()
+        .filter_map(|arg| match arg {
+            redis::Arg::Simple(bytes) => Some(bytes.to_vec()),
+            // Cursor placeholders, and whatever later redis versions add.
+            _ => None,
+        })
+        .collect()
+}
+
+/// Arguments of `cmd`, name first, as text.
+pub fn cmd_args(cmd: &redis::Cmd) -> Vec<String> {
+    cmd_arg_bytes(cmd)
+        .iter()
+        .map(|bytes| String::from_utf8_lossy(bytes).into_owned())
+        .collect()
+}
+
+/// Filtered-data values holding `values` as text, none of them NULL.
+pub fn row_values(values: &[&str]) -> Vec<RowValue> {
+    values.iter().map(|v| Some(v.as_bytes().to_vec())).collect()
+}
+
+/// Values of a row as text; panics on NULL or non-UTF-8 values.
+pub fn row_text<'a>(row: &'a [Option<Cow<'_, [u8]>>]) -> Vec<&'a str> {
+    row.iter()
+        .map(|v| std::str::from_utf8(v.as_deref().expect("NULL value")).unwrap())
+        .collect()
+}
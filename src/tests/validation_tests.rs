@@ -56,6 +56,45 @@ mod tests {
         .unwrap();
     }
 
+    #[pg_test]
+    fn test_validator_accepts_sentinel_server() {
+        setup_fdw();
+        Spi::run(&format!(
+            "CREATE SERVER {} FOREIGN DATA WRAPPER {} OPTIONS (
+                sentinel_nodes '127.0.0.1:26379,127.0.0.1:26380', sentinel_master_name 'mymaster',
+                sentinel_password 'secret', sentinel_replica_reads 'true'
+            );",
+            SERVER_NAME, FDW_NAME
+        ))
+        .unwrap();
+        cleanup();
+    }
+
+    #[pg_test]
+    #[should_panic(expected = "sentinel_nodes and sentinel_master_name must be set together")]
+    fn test_validator_rejects_sentinel_without_master_name() {
+        setup_fdw();
+        Spi::run(&format!(
+            "CREATE SERVER {} FOREIGN DATA WRAPPER {} OPTIONS (sentinel_nodes '127.0.0.1:26379');",
+            SERVER_NAME, FDW_NAME
+        ))
+        .unwrap();
+    }
+
+    #[pg_test]
+    #[should_panic(expected = "host_port and sentinel_nodes cannot be used together")]
+    fn test_validator_rejects_host_port_with_sentinel() {
+        setup_fdw();
+        Spi::run(&format!(
+            "CREATE SERVER {} FOREIGN DATA WRAPPER {} OPTIONS (
+                host_port '127.0.0.1:8899',
+                sentinel_nodes '127.0.0.1:26379', sentinel_master_name 'mymaster'
+            );",
+            SERVER_NAME, FDW_NAME
+        ))
+        .unwrap();
+    }
+
     #[pg_test]
     fn test_validator_accepts_valid_server_options() {
         setup_fdw();
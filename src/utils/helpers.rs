@@ -1,22 +1,38 @@
-use crate::utils::{cell::Cell, row::Row};
+use crate::utils::type_mapping::{ColumnInput, OnConversionError};
 use pgrx::{
     list::{self, List},
     memcx::{self, MemCx},
     pg_sys::{
-        self, defGetString, fmgr_info, getTypeInputInfo, list_concat, Datum, FmgrInfo,
-        FormData_pg_attribute, InputFunctionCall, MemoryContext, Oid, TupleDescData,
+        self, defGetString, list_concat, Datum, FormData_pg_attribute, MemoryContext, Oid,
+        TupleDescData,
     },
-    FromDatum, IntoDatum, PgBox, PgTupleDesc,
+    FromDatum, IntoDatum,
 };
 use std::{
     collections::HashMap,
-    ffi::{c_void, CStr, CString},
-    num::NonZeroUsize,
+    ffi::{c_void, CStr},
 };
 
 pub unsafe fn get_foreign_table_options(relid: pgrx::pg_sys::Oid) -> HashMap<String, String> {
+    def_elems_to_map(get_options_from_fdw(relid))
+}
+
+/// Options of column `attnum` (1-based) of a foreign table.
+pub unsafe fn get_foreign_column_options(relid: Oid, attnum: i16) -> HashMap<String, String> {
+    def_elems_to_map(pg_sys::GetForeignColumnOptions(relid, attnum))
+}
+
+/// Options of a foreign server, after those of its wrapper.
+pub unsafe fn get_foreign_server_options(
+    server: *mut pg_sys::ForeignServer,
+) -> HashMap<String, String> {
+    let wrapper = pg_sys::GetForeignDataWrapper((*server).fdwid);
+    let opts_list = list_concat(std::ptr::null_mut(), (*wrapper).options);
+    def_elems_to_map(list_concat(opts_list, (*server).options))
+}
+
+unsafe fn def_elems_to_map(opts_list: *mut pg_sys::List) -> HashMap<String, String> {
     let mut options = HashMap::new();
-    let opts_list = get_options_from_fdw(relid);
     if opts_list.is_null() {
         return options;
     }
@@ -51,24 +67,6 @@ unsafe fn get_options_from_fdw(relid: Oid) -> *mut pg_sys::List {
     opts_list
 }
 
-pub unsafe fn tuple_table_slot_to_row(slot: *mut pgrx::pg_sys::TupleTableSlot) -> Row {
-    let tup_desc = PgTupleDesc::from_pg_copy((*slot).tts_tupleDescriptor);
-
-    let mut should_free = false;
-    let htup = pgrx::pg_sys::ExecFetchSlotHeapTuple(slot, false, &mut should_free);
-    let htup = PgBox::from_pg(htup);
-    let mut row = Row::new();
-
-    for (att_idx, attr) in tup_desc.iter().filter(|a| !a.attisdropped).enumerate() {
-        let col = pgrx::name_data_to_str(&attr.attname);
-        let attno = NonZeroUsize::new(att_idx + 1).unwrap();
-        let cell: Option<Cell> = pgrx::htup::heap_getattr(&htup, attno, &tup_desc);
-        row.push(col, cell);
-    }
-
-    row
-}
-
 #[inline]
 pub fn string_from_cstr(c_str: *const i8) -> String {
     if c_str.is_null() {
@@ -82,21 +80,6 @@ pub fn string_from_cstr(c_str: *const i8) -> String {
     }
 }
 
-pub unsafe fn get_datum(value_str: &str, typid: Oid) -> Datum {
-    if value_str.is_empty() {
-        return Datum::null();
-    }
-
-    let c_value = CString::new(value_str).unwrap();
-    let mut typeinput = Oid::default();
-    let mut typeioparam = Oid::default();
-    let mut finfo = FmgrInfo::default();
-    getTypeInputInfo(typid, &mut typeinput, &mut typeioparam);
-    fmgr_info(typeinput, &mut finfo);
-
-    InputFunctionCall(&mut finfo, c_value.as_ptr().cast_mut(), typeioparam, -1)
-}
-
 pub unsafe fn pg_list_to_rust_list<'a, T: list::Enlist>(
     list: *mut pg_sys::List,
     mcx: &'a MemCx<'_>,
@@ -188,7 +171,8 @@ pub unsafe fn delete_wrappers_memctx(ctx: MemoryContext) {
     }
 }
 
-pub fn cell_to_string(cell: Option<&Cell>) -> String {
+#[cfg(any(test, feature = "pg_test"))]
+pub fn cell_to_string(cell: Option<&crate::utils::cell::Cell>) -> String {
     cell.map(|c| c.to_string())
         .unwrap_or_else(|| "NULL".to_string())
 }
@@ -197,12 +181,18 @@ pub unsafe fn write_datum_to_slot(
     slot: *mut pgrx::pg_sys::TupleTableSlot,
     tupdesc: pgrx::pg_sys::TupleDesc,
     colno: usize,
-    value: &str,
+    value: Option<&[u8]>,
 ) {
-    let pgtype = (*tuple_desc_attr(tupdesc, colno)).atttypid;
-    let datum = get_datum(value, pgtype);
-    (*slot).tts_values.add(colno).write(datum);
-    (*slot).tts_isnull.add(colno).write(false);
+    let input = ColumnInput::new(
+        tuple_desc_attr(tupdesc, colno),
+        pg_sys::CurrentMemoryContext,
+    );
+    let datum = input.to_datum(value, OnConversionError::Error);
+    (*slot)
+        .tts_values
+        .add(colno)
+        .write(datum.unwrap_or(Datum::null()));
+    (*slot).tts_isnull.add(colno).write(datum.is_none());
 }
 
 pub unsafe fn tuple_desc_attr(desc: *mut TupleDescData, i: usize) -> *mut FormData_pg_attribute {
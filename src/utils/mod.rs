@@ -5,4 +5,6 @@
 pub mod cell;
 pub mod helpers;
 pub mod memory;
+#[cfg(any(test, feature = "pg_test"))]
 pub mod row;
+pub mod type_mapping;
@@ -0,0 +1,242 @@
+//! Conversion of Redis values into column datums through each column type's
+//! input function, looked up once per scan.
+
+use crate::utils::helpers::{string_from_cstr, tuple_desc_attr};
+use pgrx::{
+    pg_sys::{self, panic::CaughtError, Datum, FmgrInfo, MemoryContext, Oid},
+    prelude::*,
+};
+use std::{ffi::CString, panic::AssertUnwindSafe};
+
+/// What to do with a Redis value that does not parse as its column's type.
+/// Set by the `on_conversion_error` table option.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OnConversionError {
+    /// Raise an error naming the column, type and value (default)
+    #[default]
+    Error,
+    /// Read the value as NULL
+    Null,
+}
+
+impl OnConversionError {
+    /// Parse the `on_conversion_error` option value (case-insensitive).
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "error" => Some(OnConversionError::Error),
+            "null" => Some(OnConversionError::Null),
+            _ => None,
+        }
+    }
+}
+
+/// Input function of one column.
+pub struct ColumnInput {
+    name: String,
+    typid: Oid,
+    typmod: i32,
+    ioparam: Oid,
+    finfo: *mut FmgrInfo,
+}
+
+impl ColumnInput {
+    /// Look up the input function of `attr`, keeping its cache in `mcxt`.
+    pub unsafe fn new(attr: *const pg_sys::FormData_pg_attribute, mcxt: MemoryContext) -> Self {
+        let typid = (*attr).atttypid;
+        let mut typeinput = Oid::default();
+        let mut ioparam = Oid::default();
+        pg_sys::getTypeInputInfo(typid, &mut typeinput, &mut ioparam);
+        let finfo =
+            pg_sys::MemoryContextAllocZero(mcxt, std::mem::size_of::<FmgrInfo>()) as *mut FmgrInfo;
+        pg_sys::fmgr_info_cxt(typeinput, finfo, mcxt);
+        ColumnInput {
+            name: pgrx::name_data_to_str(&(*attr).attname).to_string(),
+            typid,
+            typmod: (*attr).atttypmod,
+            ioparam,
+            finfo,
+        }
+    }
+
+    /// Whether an empty Redis value is an empty string rather than NULL.
+    fn is_string(&self) -> bool {
+        matches!(
+            self.typid,
+            pg_sys::TEXTOID | pg_sys::VARCHAROID | pg_sys::BPCHAROID | pg_sys::NAMEOID
+        )
+    }
+
+    /// Convert `value` to a datum; `None` is SQL NULL. Bytea columns take
+    /// the bytes as they are. Values that are not valid for the column
+    /// type raise an error naming the column, type and value or, with
+    /// [`OnConversionError::Null`], become NULL.
+    pub unsafe fn to_datum(
+        &self,
+        value: Option<&[u8]>,
+        on_error: OnConversionError,
+    ) -> Option<Datum> {
+        let value = value?;
+        if self.typid == pg_sys::BYTEAOID {
+            return value.into_datum();
+        }
+        if value.is_empty() && !self.is_string() {
+            return None;
+        }
+        let Ok(c_value) = CString::new(value) else {
+            return self.conversion_failed(value, on_error, "value contains a NUL byte");
+        };
+        let finfo = self.finfo;
+        let (ioparam, typmod) = (self.ioparam, self.typmod);
+        // The input function's error already reaches Rust as a panic at the
+        // FFI boundary, so catching it here only costs an unwind when a
+        // value is malformed.
+        PgTryBuilder::new(AssertUnwindSafe(move || {
+            Ok(pg_sys::InputFunctionCall(
+                finfo,
+                c_value.as_ptr().cast_mut(),
+                ioparam,
+                typmod,
+            ))
+        }))
+        .catch_others(|e| match e {
+            CaughtError::PostgresError(report) if is_data_exception(report.sql_error_code()) => {
+                Err(report.message().to_string())
+            }
+            other => other.rethrow(),
+        })
+        .execute()
+        .map_or_else(
+            |reason| self.conversion_failed(value, on_error, &reason),
+            Some,
+        )
+    }
+
+    unsafe fn conversion_failed(
+        &self,
+        value: &[u8],
+        on_error: OnConversionError,
+        reason: &str,
+    ) -> Option<Datum> {
+        if on_error == OnConversionError::Null {
+            return None;
+        }
+        let type_name = string_from_cstr(pg_sys::format_type_be(self.typid));
+        ereport!(
+            ERROR,
+            PgSqlErrorCode::ERRCODE_INVALID_TEXT_REPRESENTATION,
+            format!(
+                "redis_fdw: Redis value {} is not a valid {} for column \"{}\"",
+                quote_for_message(&String::from_utf8_lossy(value)),
+                type_name,
+                self.name
+            ),
+            format!(
+                "{}. Set on_conversion_error 'null' on the foreign table to read such values as NULL.",
+                reason
+            )
+        );
+    }
+}
+
+/// Per-column input functions for a tuple descriptor.
+pub struct ColumnInputs {
+    /// `None` for dropped columns
+    columns: Vec<Option<ColumnInput>>,
+    on_error: OnConversionError,
+}
+
+impl ColumnInputs {
+    pub unsafe fn new(
+        tupdesc: pg_sys::TupleDesc,
+        on_error: OnConversionError,
+        mcxt: MemoryContext,
+    ) -> Self {
+        let natts = (*tupdesc).natts as usize;
+        let columns = (0..natts)
+            .map(|i| {
+                let attr = tuple_desc_attr(tupdesc, i);
+                (!(*attr).attisdropped).then(|| ColumnInput::new(attr, mcxt))
+            })
+            .collect();
+        ColumnInputs { columns, on_error }
+    }
+
+    /// Datum for `value` in column `colno`; `None` is SQL NULL.
+    pub unsafe fn datum(&self, colno: usize, value: Option<&[u8]>) -> Option<Datum> {
+        match self.columns.get(colno) {
+            Some(Some(column)) => column.to_datum(value, self.on_error),
+            _ => None,
+        }
+    }
+
+    /// Store `value` in column `colno` of `slot`.
+    pub unsafe fn store(
+        &self,
+        slot: *mut pg_sys::TupleTableSlot,
+        colno: usize,
+        value: Option<&[u8]>,
+    ) {
+        let datum = self.datum(colno, value);
+        (*slot)
+            .tts_values
+            .add(colno)
+            .write(datum.unwrap_or(Datum::null()));
+        (*slot).tts_isnull.add(colno).write(datum.is_none());
+    }
+}
+
+/// SQLSTATE class 22: the input function rejected the value itself.
+fn is_data_exception(code: PgSqlErrorCode) -> bool {
+    const CLASS_MASK: isize = (1 << 12) - 1;
+    (code as isize & CLASS_MASK) == (PgSqlErrorCode::ERRCODE_DATA_EXCEPTION as isize & CLASS_MASK)
+}
+
+/// Quote a value for an error message, shortening long ones.
+fn quote_for_message(value: &str) -> String {
+    const MAX_CHARS: usize = 64;
+    if value.chars().count() <= MAX_CHARS {
+        format!("\"{}\"", value)
+    } else {
+        let head: String = value.chars().take(MAX_CHARS).collect();
+        format!("\"{}...\"", head)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_on_conversion_error_parse() {
+        assert_eq!(
+            OnConversionError::parse("NULL"),
+            Some(OnConversionError::Null)
+        );
+        assert_eq!(
+            OnConversionError::parse("error"),
+            Some(OnConversionError::Error)
+        );
+        assert_eq!(OnConversionError::parse("skip"), None);
+    }
+
+    #[test]
+    fn test_data_exception_class() {
+        assert!(is_data_exception(
+            PgSqlErrorCode::ERRCODE_INVALID_TEXT_REPRESENTATION
+        ));
+        assert!(is_data_exception(
+            PgSqlErrorCode::ERRCODE_NUMERIC_VALUE_OUT_OF_RANGE
+        ));
+        assert!(!is_data_exception(PgSqlErrorCode::ERRCODE_OUT_OF_MEMORY));
+    }
+
+    #[test]
+    fn test_quote_for_message_truncates() {
+        assert_eq!(quote_for_message("abc"), "\"abc\"");
+        let long = "x".repeat(100);
+        assert_eq!(
+            quote_for_message(&long),
+            format!("\"{}...\"", "x".repeat(64))
+        );
+    }
+}